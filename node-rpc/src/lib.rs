@@ -137,6 +137,8 @@ where
     C::Api: node_rpc::asset::AssetRuntimeApi<Block, AccountId>,
     C::Api: pallet_group_rpc::GroupRuntimeApi<Block>,
     C::Api: node_rpc::compliance_manager::ComplianceManagerRuntimeApi<Block, AccountId, Balance>,
+    C::Api: node_rpc::settlement::SettlementRuntimeApi<Block, AccountId>,
+    C::Api: node_rpc::sto::StoRuntimeApi<Block>,
     C::Api: BabeApi<Block>,
     C::Api: BlockBuilder<Block>,
     P: TransactionPool + 'static,
@@ -150,6 +152,8 @@ where
         asset::{Asset, AssetApi},
         identity::{Identity, IdentityApi},
         pips::{Pips, PipsApi},
+        settlement::{Settlement, SettlementApi},
+        sto::{Sto, StoApi},
         transaction_payment::{TransactionPayment, TransactionPaymentApi},
     };
     //use pallet_contracts_rpc::{Contracts, ContractsApi};
@@ -233,6 +237,8 @@ where
     )));
     io.extend_with(AssetApi::to_delegate(Asset::new(client.clone())));
     io.extend_with(GroupApi::to_delegate(Group::from(client.clone())));
+    io.extend_with(SettlementApi::to_delegate(Settlement::new(client.clone())));
+    io.extend_with(StoApi::to_delegate(Sto::new(client.clone())));
     io.extend_with(ComplianceManagerApi::to_delegate(ComplianceManager::new(
         client,
     )));
@@ -25,7 +25,10 @@ use polymesh_common_utilities::{
 };
 //use polymesh_contracts::ExtensionInfo;
 use polymesh_primitives::{
-    asset::AssetName, ticker::TICKER_LEN, AuthorizationData, Signatory, Ticker,
+    asset::AssetName,
+    asset_metadata::{AssetMetadataLockStatus, AssetMetadataValueType},
+    ticker::TICKER_LEN,
+    AuthorizationData, Signatory, Ticker,
 };
 //use polymesh_primitives::{ExtensionAttributes, SmartExtension};
 use sp_io::hashing::keccak_256;
@@ -36,6 +39,9 @@ const MAX_DOC_URI: usize = 1024;
 const MAX_DOC_NAME: usize = 1024;
 const MAX_DOC_TYPE: usize = 1024;
 const MAX_IDENTIFIERS_PER_ASSET: u32 = 512;
+const MAX_METADATA_NAME: u32 = 1024;
+const MAX_METADATA_SPEC: u32 = 2048;
+const MAX_METADATA_VALUE: u32 = 1024;
 
 pub fn make_document() -> Document {
     Document {
@@ -47,6 +53,35 @@ pub fn make_document() -> Document {
     }
 }
 
+/// Returns a metadata spec whose description and URL are `s` bytes long in total.
+fn make_metadata_spec(value_type: AssetMetadataValueType, s: u32) -> AssetMetadataSpec {
+    let half = (s / 2) as usize;
+    AssetMetadataSpec {
+        value_type,
+        description: Some(vec![b'd'; half]),
+        url: Some(vec![b'u'; s as usize - half]),
+    }
+}
+
+/// Registers a local bytes metadata key for a new asset, returning its owner, ticker and key.
+fn metadata_key_setup<T: Config + TestUtilsFn<AccountIdOf<T>>>(
+) -> (User<T>, Ticker, AssetMetadataKey) {
+    let (owner, ticker) = owned_ticker::<T>();
+    let spec = make_metadata_spec(AssetMetadataValueType::Bytes, MAX_METADATA_SPEC);
+    <Module<T>>::register_asset_metadata_local_type(
+        owner.origin().into(),
+        ticker,
+        AssetMetadataName::from(b"key"),
+        spec,
+    )
+    .unwrap();
+    (
+        owner,
+        ticker,
+        AssetMetadataKey::Local(AssetMetadataLocalKey(0)),
+    )
+}
+
 fn make_default_reg_config<T: Config>() -> TickerRegistrationConfig<T::Moment> {
     TickerRegistrationConfig {
         max_ticker_length: 8,
@@ -119,12 +154,45 @@ fn emulate_controller_transfer<T: Config>(
         <BalanceOfAtScope<T>>::insert(s_id, id, bal);
         <AggregateBalance<T>>::insert(ticker, id, bal);
         ScopeIdOf::insert(ticker, id, s_id);
-        Statistics::<T>::update_transfer_stats(&ticker, None, Some(bal), bal);
+        let party = pallet_statistics::TransferParty {
+            did: id,
+            scope_id: s_id,
+            balance: bal,
+        };
+        Statistics::<T>::update_transfer_stats(&ticker, None, Some(party), bal);
     };
     mock_storage(investor_did, 1000u32.into());
     mock_storage(pia, 5000u32.into());
 }
 
+fn forced_transfer_setup<T: Config + TestUtilsFn<AccountIdOf<T>>>(
+) -> (User<T>, Ticker, User<T>, DocumentId) {
+    let (owner, ticker) = owned_ticker::<T>();
+    let investor = UserBuilder::<T>::default().generate_did().build("investor");
+    emulate_controller_transfer::<T>(ticker, investor.did(), owner.did());
+    Portfolio::<T>::set_default_portfolio_balance(investor.did(), &ticker, 1000u32.into());
+    Module::<T>::add_documents(owner.origin().into(), vec![make_document()], ticker).unwrap();
+    (owner, ticker, investor, DocumentId(0))
+}
+
+fn schedule_forced_transfer<T: Config + TestUtilsFn<AccountIdOf<T>>>(
+    period: u32,
+) -> (User<T>, Ticker, User<T>) {
+    let (owner, ticker, investor, evidence) = forced_transfer_setup::<T>();
+    Module::<T>::force_transfer(
+        owner.origin().into(),
+        ticker,
+        PortfolioId::default_portfolio(investor.did()),
+        PortfolioId::default_portfolio(owner.did()),
+        500u32.into(),
+        ForcedTransferReason::CourtOrder,
+        evidence,
+        Some(period.into()),
+    )
+    .unwrap();
+    (owner, ticker, investor)
+}
+
 fn owner<T: Config + TestUtilsFn<AccountIdOf<T>>>() -> User<T> {
     UserBuilder::<T>::default().generate_did().build("owner")
 }
@@ -135,6 +203,18 @@ pub fn owned_ticker<T: Config + TestUtilsFn<AccountIdOf<T>>>() -> (User<T>, Tick
     (owner, ticker)
 }
 
+/// Creates a parent asset and a class asset of the same owner, linking them if `link`.
+fn asset_family_setup<T: Config + TestUtilsFn<AccountIdOf<T>>>(
+    link: bool,
+) -> (User<T>, Ticker, Ticker) {
+    let (owner, parent) = owned_ticker::<T>();
+    let class = make_asset::<T>(&owner, Some(b"CLASS"));
+    if link {
+        <Module<T>>::link_asset_class(owner.origin().into(), parent, class).unwrap();
+    }
+    (owner, parent, class)
+}
+
 fn verify_ownership<T: Config>(
     ticker: Ticker,
     old: IdentityId,
@@ -272,6 +352,53 @@ benchmarks! {
         assert_eq!(Module::<T>::frozen(&ticker), false);
     }
 
+    freeze_identity {
+        let (owner, ticker) = owned_ticker::<T>();
+        let did = IdentityId::from(42u128);
+    }: _(owner.origin, ticker, did)
+    verify {
+        assert_eq!(Module::<T>::frozen_identity(&ticker, &did), true);
+    }
+
+    unfreeze_identity {
+        let (owner, ticker) = owned_ticker::<T>();
+        let did = IdentityId::from(42u128);
+
+        Module::<T>::freeze_identity(owner.origin().into(), ticker, did)
+            .expect("Identity cannot be frozen");
+    }: _(owner.origin, ticker, did)
+    verify {
+        assert_eq!(Module::<T>::frozen_identity(&ticker, &did), false);
+    }
+
+    freeze_portfolio {
+        let (owner, ticker) = owned_ticker::<T>();
+        let portfolio = PortfolioId::default_portfolio(IdentityId::from(42u128));
+    }: _(owner.origin, ticker, portfolio)
+    verify {
+        assert_eq!(Module::<T>::frozen_portfolio(&ticker, &portfolio), true);
+    }
+
+    unfreeze_portfolio {
+        let (owner, ticker) = owned_ticker::<T>();
+        let portfolio = PortfolioId::default_portfolio(IdentityId::from(42u128));
+
+        Module::<T>::freeze_portfolio(owner.origin().into(), ticker, portfolio)
+            .expect("Portfolio cannot be frozen");
+    }: _(owner.origin, ticker, portfolio)
+    verify {
+        assert_eq!(Module::<T>::frozen_portfolio(&ticker, &portfolio), false);
+    }
+
+    set_frozen_balance {
+        let (owner, ticker) = owned_ticker::<T>();
+        let did = IdentityId::from(42u128);
+        let amount = T::Balance::from(1_000u32);
+    }: _(owner.origin, ticker, did, amount)
+    verify {
+        assert_eq!(Module::<T>::frozen_balance(&ticker, &did), amount);
+    }
+
     rename_asset {
         // New token name length.
         let n in 1 .. T::AssetNameMaxLength::get() as u32;
@@ -291,6 +418,15 @@ benchmarks! {
         assert_eq!(Module::<T>::token_details(ticker).total_supply, (2_000_000 * POLY).into());
     }
 
+    issue_with_vesting {
+        let (owner, ticker) = owned_ticker::<T>();
+        let portfolio = PortfolioId::default_portfolio(owner.did());
+        let vesting = VestingKind::Linear { start: 0, end: 1_000 };
+    }: _(owner.origin, ticker, (1_000_000 * POLY).into(), vesting)
+    verify {
+        assert_eq!(Portfolio::<T>::vesting_schedules(portfolio, ticker).len(), 1);
+    }
+
     redeem {
         let (owner, ticker) = owned_ticker::<T>();
     }: _(owner.origin, ticker, (600_000 * POLY).into())
@@ -446,4 +582,132 @@ benchmarks! {
     verify {
         assert_eq!(Module::<T>::balance_of(ticker, investor.did()), 500u32.into());
     }
+
+    force_transfer {
+        let (owner, ticker, investor, evidence) = forced_transfer_setup::<T>();
+        let from = PortfolioId::default_portfolio(investor.did());
+        let to = PortfolioId::default_portfolio(owner.did());
+    }: _(owner.origin, ticker, from, to, 500u32.into(), ForcedTransferReason::CourtOrder, evidence, None)
+    verify {
+        assert_eq!(Module::<T>::balance_of(ticker, investor.did()), 500u32.into());
+    }
+
+    execute_forced_transfer {
+        let (owner, ticker, investor) = schedule_forced_transfer::<T>(0);
+    }: _(owner.origin, ticker, 0)
+    verify {
+        assert_eq!(Module::<T>::balance_of(ticker, investor.did()), 500u32.into());
+    }
+
+    contest_forced_transfer {
+        let (owner, ticker, investor) = schedule_forced_transfer::<T>(1000);
+    }: _(investor.origin, ticker, 0)
+    verify {
+        assert_eq!(Module::<T>::forced_transfers(ticker, 0).unwrap().status, ForcedTransferStatus::Contested);
+    }
+
+    cancel_forced_transfer {
+        let (owner, ticker, investor) = schedule_forced_transfer::<T>(1000);
+    }: _(owner.origin, ticker, 0)
+    verify {
+        assert_eq!(Module::<T>::forced_transfers(ticker, 0).unwrap().status, ForcedTransferStatus::Cancelled);
+    }
+
+    register_asset_metadata_local_type {
+        let n in 1 .. MAX_METADATA_NAME;
+        let s in 1 .. MAX_METADATA_SPEC;
+
+        let (owner, ticker) = owned_ticker::<T>();
+        let name = AssetMetadataName::from(vec![b'n'; n as usize].as_slice());
+        let spec = make_metadata_spec(AssetMetadataValueType::Bytes, s);
+    }: _(owner.origin, ticker, name.clone(), spec)
+    verify {
+        assert!(Module::<T>::asset_metadata_local_name_to_key(ticker, name).is_some());
+    }
+
+    register_asset_metadata_global_type {
+        let n in 1 .. MAX_METADATA_NAME;
+        let s in 1 .. MAX_METADATA_SPEC;
+
+        let name = AssetMetadataName::from(vec![b'n'; n as usize].as_slice());
+        let spec = make_metadata_spec(AssetMetadataValueType::Bytes, s);
+    }: _(RawOrigin::Root, name.clone(), spec)
+    verify {
+        assert!(Module::<T>::asset_metadata_global_name_to_key(name).is_some());
+    }
+
+    set_asset_metadata {
+        let v in 1 .. MAX_METADATA_VALUE;
+
+        let (owner, ticker, key) = metadata_key_setup::<T>();
+        let value = AssetMetadataValue::Bytes(vec![b'v'; v as usize]);
+        // Replacing a previous value is the more complex code path.
+        <Module<T>>::set_asset_metadata(owner.origin().into(), ticker, key, value.clone(), None).unwrap();
+    }: _(owner.origin, ticker, key, value, None)
+    verify {
+        assert_eq!(Module::<T>::asset_metadata_values(ticker, key).unwrap().version, 2);
+    }
+
+    set_asset_metadata_details {
+        let (owner, ticker, key) = metadata_key_setup::<T>();
+        let value = AssetMetadataValue::Bytes(vec![b'v'; MAX_METADATA_VALUE as usize]);
+        <Module<T>>::set_asset_metadata(owner.origin().into(), ticker, key, value, None).unwrap();
+        let detail = AssetMetadataValueDetail {
+            expire: Some(1000u32.into()),
+            lock_status: AssetMetadataLockStatus::Locked,
+        };
+    }: _(owner.origin, ticker, key, detail.clone())
+    verify {
+        assert_eq!(Module::<T>::asset_metadata_values(ticker, key).unwrap().detail, detail);
+    }
+
+    link_asset_class {
+        let (owner, parent, class) = asset_family_setup::<T>(false);
+    }: _(owner.origin, parent, class)
+    verify {
+        assert_eq!(Module::<T>::asset_family_parent(class), Some(parent));
+    }
+
+    unlink_asset_class {
+        let (owner, parent, class) = asset_family_setup::<T>(true);
+        <Module<T>>::set_class_conversion(owner.origin().into(), parent, class, Some(ClassConversion {
+            numerator: 1,
+            denominator: 1,
+            start: None,
+            end: None,
+        })).unwrap();
+    }: _(owner.origin, class)
+    verify {
+        assert_eq!(Module::<T>::asset_family_parent(class), None);
+        assert_eq!(Module::<T>::class_conversion(parent, class), None);
+    }
+
+    set_class_conversion {
+        let (owner, parent, class) = asset_family_setup::<T>(true);
+        let conversion = ClassConversion {
+            numerator: 10,
+            denominator: 1,
+            start: Some(1000),
+            end: Some(2000),
+        };
+    }: _(owner.origin, class, parent, Some(conversion))
+    verify {
+        assert_eq!(Module::<T>::class_conversion(class, parent), Some(conversion));
+    }
+
+    convert_class {
+        let (owner, parent, class) = asset_family_setup::<T>(true);
+        let conversion = ClassConversion {
+            numerator: 10,
+            denominator: 1,
+            start: None,
+            end: None,
+        };
+        <Module<T>>::set_class_conversion(owner.origin().into(), parent, class, Some(conversion)).unwrap();
+        T::ComplianceManager::bench_allow_all_transfers(class);
+        let supply = Module::<T>::total_supply(class);
+    }: _(owner.origin, parent, class, 1000u32.into())
+    verify {
+        assert_eq!(Module::<T>::total_supply(class), supply + 10_000u32.into());
+    }
 }
@@ -40,8 +40,18 @@
 //! - `create_asset` - Initializes a new security token.
 //! - `freeze` - Freezes transfers and minting of a given token.
 //! - `unfreeze` - Unfreezes transfers and minting of a given token.
+//! - `freeze_identity` - Freezes transfers of a given token to and from an identity.
+//! - `unfreeze_identity` - Unfreezes transfers of a given token to and from an identity.
+//! - `freeze_portfolio` - Freezes transfers of a given token to and from a portfolio.
+//! - `unfreeze_portfolio` - Unfreezes transfers of a given token to and from a portfolio.
+//! - `set_frozen_balance` - Sets the amount of a given token that an identity can not transfer away.
 //! - `rename_asset` - Renames a given asset.
 //! - `controller_transfer` - Forces a transfer between two DID.
+//! - `force_transfer` - Forces a transfer between any two portfolios, with a reason and an evidence document,
+//!   either immediately or after a period during which the holder can contest it.
+//! - `execute_forced_transfer` - Executes a forced transfer whose contest period is over.
+//! - `contest_forced_transfer` - Contests a scheduled forced transfer from one of the caller's portfolios.
+//! - `cancel_forced_transfer` - Cancels a scheduled or contested forced transfer.
 //! - `issue` - Function is used to issue(or mint) new tokens to the primary issuance agent.
 //! - `redeem` - Redeems tokens from PIA's (Primary Issuance Agent) default portfolio.
 //! - `make_divisible` - Change the divisibility of the token to divisible. Only called by the token owner.
@@ -53,6 +63,14 @@
 //! - `add_extension` - It is used to permission the Smart-Extension address for a given ticker.
 //! - `archive_extension` - Extension gets archived meaning it is no longer used to verify compliance or any smart logic it possesses.
 //! - `unarchive_extension` - Extension gets unarchived meaning it is used again to verify compliance or any smart logic it possesses.
+//! - `register_asset_metadata_local_type` - Registers a typed metadata key for a given token.
+//! - `register_asset_metadata_global_type` - Registers a typed metadata key for every token. Only called by governance.
+//! - `set_asset_metadata` - Sets the value of a metadata key of a given token, optionally with an expiry and a lock.
+//! - `set_asset_metadata_details` - Changes the expiry and lock of a metadata value of a given token.
+//! - `link_asset_class` - Links a token as a class to the family of a parent token, e.g., of the same issuer.
+//! - `unlink_asset_class` - Unlinks a token from the family of its parent token.
+//! - `set_class_conversion` - Sets the ratio and window for converting one class of a family into another.
+//! - `convert_class` - Converts shares of one class of a family into another, redeeming and issuing atomically.
 //!
 //! ### Public Functions
 //!
@@ -66,11 +84,18 @@
 //! - `extension_details` - It provides the list of Smart extension added for the given tokens.
 //! - `extensions` - It provides the list of Smart extension added for the given tokens and for the given type.
 //! - `frozen` - It tells whether the given ticker is frozen or not.
+//! - `frozen_identity` - It tells whether transfers of the given ticker to and from an identity are frozen.
+//! - `frozen_portfolio` - It tells whether transfers of the given ticker to and from a portfolio are frozen.
+//! - `frozen_balance` - It provides the amount of the given ticker that an identity can not transfer away.
 //! - `is_ticker_available` - It checks whether the given ticker is available or not.
 //! - `is_ticker_registry_valid` - It checks whether the ticker is owned by a given IdentityId or not.
 //! - `is_ticker_available_or_registered_to` - It provides the status of a given ticker.
 //! - `total_supply` - It provides the total supply of a ticker.
 //! - `get_balance_at` - It provides the balance of a DID at a certain checkpoint.
+//! - `forced_transfers` - It provides the forced transfers of a ticker, including executed and cancelled ones.
+//! - `get_asset_metadata` - It provides the unexpired metadata values of a ticker with the specs of their keys.
+//! - `get_asset_metadata_value` - It provides the unexpired value of a metadata key of a ticker.
+//! - `asset_family_of` - It provides the parent ticker of the family of a ticker, if any.
 //! - `call_extension` - A helper function that is used to call the smart extension function.
 
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -94,6 +119,7 @@ use frame_support::{
 use frame_system::ensure_root;
 use pallet_base::{ensure_opt_string_limited, ensure_string_limited};
 use pallet_identity::{self as identity, PermissionedCallOriginData};
+use pallet_statistics::TransferParty;
 pub use polymesh_common_utilities::traits::asset::{Config, Event, RawEvent, WeightInfo};
 use polymesh_common_utilities::{
     asset::{AssetFnTrait, AssetMigrationError, AssetSubTrait},
@@ -101,20 +127,30 @@ use polymesh_common_utilities::{
     constants::*,
     protocol_fee::{ChargeProtocolFee, ProtocolOp},
     //traits::contracts::ContractsFn,
+    portfolio::PortfolioSubTrait,
     with_transaction,
     SystematicIssuers,
 };
 use polymesh_primitives::{
     agent::AgentGroup,
-    asset::{AssetName, AssetType, FundingRoundName, GranularCanTransferResult},
+    asset::{
+        AssetName, AssetType, ClassConversion, ForcedTransferReason, FundingRoundName,
+        GranularCanTransferResult,
+    },
+    asset_metadata::{
+        AssetMetadataEntry, AssetMetadataGlobalKey, AssetMetadataItem, AssetMetadataKey,
+        AssetMetadataLocalKey, AssetMetadataName, AssetMetadataSpec, AssetMetadataValue,
+        AssetMetadataValueDetail,
+    },
     calendar::CheckpointId,
     ethereum::{self, EcdsaSignature, EthereumAddress},
     extract_auth,
     statistics::TransferManagerResult,
     storage_migrate_on, storage_migration_ver, AssetIdentifier, Document, DocumentId, IdentityId,
-    MetaVersion as ExtVersion, PortfolioId, ScopeId, SmartExtension, SmartExtensionType, Ticker,
+    MetaVersion as ExtVersion, Moment, PortfolioId, ScopeId, SmartExtension, SmartExtensionType,
+    Ticker, VestingKind, VestingSchedule,
 };
-use sp_runtime::traits::{CheckedAdd, Saturating, Zero};
+use sp_runtime::traits::{CheckedAdd, CheckedMul, Saturating, Zero};
 #[cfg(feature = "std")]
 use sp_runtime::{Deserialize, Serialize};
 use sp_std::{convert::TryFrom, prelude::*};
@@ -124,6 +160,9 @@ type ExternalAgents<T> = pallet_external_agents::Module<T>;
 type Portfolio<T> = pallet_portfolio::Module<T>;
 type Statistics<T> = pallet_statistics::Module<T>;
 
+/// The maximum number of classes linked to the family of a parent asset.
+pub const MAX_ASSET_FAMILY_CLASSES: usize = 16;
+
 /// Ownership status of a ticker/token.
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum AssetOwnershipRelation {
@@ -186,6 +225,44 @@ mod migrate {
     }
 }
 
+/// The status of a forced transfer.
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForcedTransferStatus {
+    /// Waiting for its contest period to be over.
+    Pending,
+    /// Contested by the holder of the sending portfolio, so it can only be cancelled.
+    Contested,
+    /// The transfer took place.
+    Executed,
+    /// Cancelled by an agent of the asset.
+    Cancelled,
+}
+
+impl Default for ForcedTransferStatus {
+    fn default() -> Self {
+        Self::Pending
+    }
+}
+
+/// A transfer forced by the agents of an asset, kept in storage as an audit trail.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Debug)]
+pub struct ForcedTransfer<Balance, Moment> {
+    /// The portfolio the asset is transferred from.
+    pub from: PortfolioId,
+    /// The portfolio the asset is transferred to.
+    pub to: PortfolioId,
+    /// The amount transferred.
+    pub value: Balance,
+    /// Why the transfer is forced.
+    pub reason: ForcedTransferReason,
+    /// The document of the asset supporting the transfer, e.g., a court order.
+    pub evidence: DocumentId,
+    /// When the transfer can be executed; until then, the holder can contest it.
+    pub executable_at: Moment,
+    /// The status of the transfer.
+    pub status: ForcedTransferStatus,
+}
+
 /// struct to store the ticker registration details.
 #[derive(Encode, Decode, Clone, Default, PartialEq, Debug)]
 pub struct TickerRegistration<U> {
@@ -284,6 +361,21 @@ decl_storage! {
         /// The set of frozen assets implemented as a membership map.
         /// ticker -> bool
         pub Frozen get(fn frozen): map hasher(blake2_128_concat) Ticker => bool;
+        /// The identities whose transfers of an asset, both sending and receiving, are frozen.
+        /// (ticker, DID) -> bool
+        pub FrozenIdentities get(fn frozen_identity):
+            double_map hasher(blake2_128_concat) Ticker, hasher(blake2_128_concat) IdentityId => bool;
+        /// The portfolios whose transfers of an asset, both sending and receiving, are frozen.
+        /// (ticker, portfolio) -> bool
+        pub FrozenPortfolios get(fn frozen_portfolio):
+            double_map hasher(blake2_128_concat) Ticker, hasher(blake2_128_concat) PortfolioId => bool;
+        /// The amount of an asset that an identity can not transfer away.
+        /// (ticker, DID) -> frozen balance
+        pub FrozenBalances get(fn frozen_balance):
+            double_map hasher(blake2_128_concat) Ticker, hasher(blake2_128_concat) IdentityId => T::Balance;
+        /// The maturity date of a fixed income asset, from which on it can no longer be transferred.
+        /// (ticker) -> maturity date
+        pub Maturities get(fn maturity): map hasher(blake2_128_concat) Ticker => Option<Moment>;
         /// Tickers and token owned by a user
         /// (user, ticker) -> AssetOwnership
         pub AssetOwnershipRelations get(fn asset_ownership_relation):
@@ -292,6 +384,68 @@ decl_storage! {
         /// (ticker, doc_id) -> document
         pub AssetDocuments get(fn asset_documents):
             double_map hasher(blake2_128_concat) Ticker, hasher(blake2_128_concat) DocumentId => Document;
+        /// Forced transfers of an asset, including executed and cancelled ones.
+        /// (ticker, forced transfer ID) -> forced transfer
+        pub ForcedTransfers get(fn forced_transfers):
+            double_map hasher(blake2_128_concat) Ticker, hasher(twox_64_concat) u64 =>
+            Option<ForcedTransfer<T::Balance, T::Moment>>;
+        /// The number of forced transfers of an asset, i.e., the ID of the next one.
+        /// (ticker) -> count
+        pub ForcedTransferCount get(fn forced_transfer_count): map hasher(blake2_128_concat) Ticker => u64;
+        /// The scheduled or contested forced transfer of an asset from a portfolio, if any.
+        /// Its value is locked in the portfolio until it is executed or cancelled.
+        /// (ticker, portfolio) -> forced transfer ID
+        pub OpenForcedTransfers get(fn open_forced_transfer):
+            double_map hasher(blake2_128_concat) Ticker, hasher(twox_64_concat) PortfolioId => Option<u64>;
+        /// The parent of the family an asset is linked to as a class.
+        /// (class ticker) -> parent ticker
+        pub AssetFamilyParents get(fn asset_family_parent): map hasher(blake2_128_concat) Ticker => Option<Ticker>;
+        /// The classes linked to the family of a parent asset, excluding the parent itself.
+        /// (parent ticker) -> [class ticker]
+        pub AssetFamilyClasses get(fn asset_family_classes): map hasher(blake2_128_concat) Ticker => Vec<Ticker>;
+        /// The terms on which holders may convert one class of a family into another.
+        /// (class converted from, class converted into) -> conversion
+        pub ClassConversions get(fn class_conversion):
+            double_map hasher(blake2_128_concat) Ticker, hasher(blake2_128_concat) Ticker =>
+            Option<ClassConversion>;
+        /// The global metadata keys, by name.
+        /// (name) -> global key
+        pub AssetMetadataGlobalNameToKey get(fn asset_metadata_global_name_to_key):
+            map hasher(blake2_128_concat) AssetMetadataName => Option<AssetMetadataGlobalKey>;
+        /// The names of the global metadata keys.
+        /// (global key) -> name
+        pub AssetMetadataGlobalKeyToName get(fn asset_metadata_global_key_to_name):
+            map hasher(twox_64_concat) AssetMetadataGlobalKey => Option<AssetMetadataName>;
+        /// The specifications of the global metadata keys.
+        /// (global key) -> specification
+        pub AssetMetadataGlobalSpecs get(fn asset_metadata_global_specs):
+            map hasher(twox_64_concat) AssetMetadataGlobalKey => Option<AssetMetadataSpec>;
+        /// The next global metadata key.
+        pub AssetMetadataNextGlobalKey get(fn asset_metadata_next_global_key): AssetMetadataGlobalKey;
+        /// The metadata keys local to an asset, by name.
+        /// (ticker, name) -> local key
+        pub AssetMetadataLocalNameToKey get(fn asset_metadata_local_name_to_key):
+            double_map hasher(blake2_128_concat) Ticker, hasher(blake2_128_concat) AssetMetadataName =>
+            Option<AssetMetadataLocalKey>;
+        /// The names of the metadata keys local to an asset.
+        /// (ticker, local key) -> name
+        pub AssetMetadataLocalKeyToName get(fn asset_metadata_local_key_to_name):
+            double_map hasher(blake2_128_concat) Ticker, hasher(twox_64_concat) AssetMetadataLocalKey =>
+            Option<AssetMetadataName>;
+        /// The specifications of the metadata keys local to an asset.
+        /// (ticker, local key) -> specification
+        pub AssetMetadataLocalSpecs get(fn asset_metadata_local_specs):
+            double_map hasher(blake2_128_concat) Ticker, hasher(twox_64_concat) AssetMetadataLocalKey =>
+            Option<AssetMetadataSpec>;
+        /// The next metadata key local to an asset.
+        /// (ticker) -> local key
+        pub AssetMetadataNextLocalKey get(fn asset_metadata_next_local_key):
+            map hasher(blake2_128_concat) Ticker => AssetMetadataLocalKey;
+        /// The metadata values of an asset, along with their details and versions.
+        /// (ticker, key) -> value entry
+        pub AssetMetadataValues get(fn asset_metadata_values):
+            double_map hasher(blake2_128_concat) Ticker, hasher(blake2_128_concat) AssetMetadataKey =>
+            Option<AssetMetadataEntry<T::Moment>>;
         /// Per-ticker document ID counter.
         /// (ticker) -> doc_id
         pub AssetDocumentsIdSequence get(fn asset_documents_id_sequence): map hasher(blake2_128_concat) Ticker => DocumentId;
@@ -514,6 +668,96 @@ decl_module! {
             Self::set_freeze(origin, ticker, false)
         }
 
+        /// Freezes transfers of a given token to and from `did`,
+        /// while the rest of the market continues trading.
+        ///
+        /// # Arguments
+        /// * `origin` - the secondary key of the sender.
+        /// * `ticker` - the ticker of the token.
+        /// * `did` - the identity to freeze.
+        ///
+        /// ## Errors
+        /// - `AlreadyFrozen` if `did` is already frozen for `ticker`.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::freeze_identity()]
+        pub fn freeze_identity(origin, ticker: Ticker, did: IdentityId) -> DispatchResult {
+            Self::set_identity_freeze(origin, ticker, did, true)
+        }
+
+        /// Unfreezes transfers of a given token to and from `did`.
+        ///
+        /// # Arguments
+        /// * `origin` - the secondary key of the sender.
+        /// * `ticker` - the ticker of the token.
+        /// * `did` - the frozen identity.
+        ///
+        /// ## Errors
+        /// - `NotFrozen` if `did` is not frozen for `ticker`.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::unfreeze_identity()]
+        pub fn unfreeze_identity(origin, ticker: Ticker, did: IdentityId) -> DispatchResult {
+            Self::set_identity_freeze(origin, ticker, did, false)
+        }
+
+        /// Freezes transfers of a given token to and from `portfolio`,
+        /// while the other portfolios of its owner continue trading.
+        ///
+        /// # Arguments
+        /// * `origin` - the secondary key of the sender.
+        /// * `ticker` - the ticker of the token.
+        /// * `portfolio` - the portfolio to freeze.
+        ///
+        /// ## Errors
+        /// - `AlreadyFrozen` if `portfolio` is already frozen for `ticker`.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::freeze_portfolio()]
+        pub fn freeze_portfolio(origin, ticker: Ticker, portfolio: PortfolioId) -> DispatchResult {
+            Self::set_portfolio_freeze(origin, ticker, portfolio, true)
+        }
+
+        /// Unfreezes transfers of a given token to and from `portfolio`.
+        ///
+        /// # Arguments
+        /// * `origin` - the secondary key of the sender.
+        /// * `ticker` - the ticker of the token.
+        /// * `portfolio` - the frozen portfolio.
+        ///
+        /// ## Errors
+        /// - `NotFrozen` if `portfolio` is not frozen for `ticker`.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::unfreeze_portfolio()]
+        pub fn unfreeze_portfolio(origin, ticker: Ticker, portfolio: PortfolioId) -> DispatchResult {
+            Self::set_portfolio_freeze(origin, ticker, portfolio, false)
+        }
+
+        /// Sets the amount of a given token that `did` can not transfer away,
+        /// i.e., transfers are only allowed while the balance of `did` remains at least `amount`.
+        /// An `amount` of zero removes the freeze.
+        ///
+        /// The amount may exceed the current balance of `did`,
+        /// in which case no transfers away from `did` are allowed.
+        ///
+        /// # Arguments
+        /// * `origin` - the secondary key of the sender.
+        /// * `ticker` - the ticker of the token.
+        /// * `did` - the identity whose balance is frozen.
+        /// * `amount` - the frozen amount.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::set_frozen_balance()]
+        pub fn set_frozen_balance(origin, ticker: Ticker, did: IdentityId, amount: T::Balance) -> DispatchResult {
+            Self::base_set_frozen_balance(origin, ticker, did, amount)
+        }
+
         /// Renames a given token.
         ///
         /// # Arguments
@@ -550,6 +794,33 @@ decl_module! {
             Self::_mint(&ticker, did, amount, Some(ProtocolOp::AssetIssue))
         }
 
+        /// Issue new tokens to the caller, like `issue`,
+        /// and lock them in the caller's default portfolio under a vesting schedule.
+        ///
+        /// # Arguments
+        /// * `origin` must be the secondary key of token owner.
+        /// * `ticker` of the token.
+        /// * `amount` of tokens that get issued.
+        /// * `vesting` how the issued tokens are released.
+        ///
+        /// # Errors
+        /// - `InvalidVestingSchedule` if `vesting` is invalid.
+        /// - `TooManyVestingSchedules` if the default portfolio has too many active schedules for `ticker`.
+        ///
+        /// # Permissions
+        /// * Asset
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::issue_with_vesting()]
+        pub fn issue_with_vesting(origin, ticker: Ticker, amount: T::Balance, vesting: VestingKind) -> DispatchResult {
+            let did = Self::ensure_agent_with_custody_and_perms(origin, ticker)?;
+            let schedule = VestingSchedule { amount, kind: vesting };
+            let portfolio = PortfolioId::default_portfolio(did);
+            with_transaction(|| {
+                Portfolio::<T>::add_vesting_schedule(did, portfolio, ticker, schedule)?;
+                Self::_mint(&ticker, did, amount, Some(ProtocolOp::AssetIssue))
+            })
+        }
+
         /// Redeems existing tokens by reducing the balance of the PIA's default portfolio and the total supply of the token
         ///
         /// # Arguments
@@ -778,6 +1049,265 @@ decl_module! {
         pub fn controller_transfer(origin, ticker: Ticker, value: T::Balance, from_portfolio: PortfolioId) -> DispatchResult {
             Self::base_controller_transfer(origin, ticker, value, from_portfolio)
         }
+
+        /// Forces a transfer of `value` tokens from `from` to `to`, e.g., to recover the tokens
+        /// of a holder who lost their keys, or to enforce a court order.
+        /// Compliance rules and freezes do not apply.
+        ///
+        /// With a `contest_period`, the transfer is only scheduled, and the holder of `from`
+        /// can contest it until the period is over, after which an agent can execute it.
+        /// Meanwhile, `value` is locked in `from`, and no other transfer can be forced from it.
+        /// Otherwise, the transfer is executed immediately.
+        /// Either way, the transfer is kept in `ForcedTransfers`.
+        ///
+        /// # Arguments
+        /// * `origin` - the secondary key of the sender.
+        /// * `ticker` - the ticker of the token.
+        /// * `from` - the portfolio to transfer the tokens from.
+        /// * `to` - the portfolio to transfer the tokens to.
+        /// * `value` - the amount of tokens to transfer.
+        /// * `reason` - why the transfer is forced.
+        /// * `evidence` - the ID of the document of the asset supporting the transfer.
+        /// * `contest_period` - how long the holder has to contest the transfer, if at all.
+        ///
+        /// ## Errors
+        /// - `NoSuchDoc` if `evidence` is not a document of `ticker`.
+        /// - `SenderSameAsReceiver` if `from` and `to` belong to the same identity.
+        /// - `PortfolioDoesNotExist` if `from` or `to` does not exist.
+        /// - `ReceiverScopeClaimMissing` if the holder of `to` has no scope claim for `ticker`.
+        /// - `ForcedTransferOpen` if a transfer from `from` is already scheduled or contested.
+        /// - `InsufficientPortfolioBalance` if `from` does not have `value` free tokens.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::force_transfer()]
+        pub fn force_transfer(
+            origin,
+            ticker: Ticker,
+            from: PortfolioId,
+            to: PortfolioId,
+            value: T::Balance,
+            reason: ForcedTransferReason,
+            evidence: DocumentId,
+            contest_period: Option<T::Moment>,
+        ) -> DispatchResult {
+            Self::base_force_transfer(origin, ticker, from, to, value, reason, evidence, contest_period)
+        }
+
+        /// Executes the forced transfer `id` of `ticker` once its contest period is over.
+        ///
+        /// ## Errors
+        /// - `NoSuchForcedTransfer` if there is no forced transfer `id` of `ticker`.
+        /// - `ForcedTransferNotPending` if the transfer was contested, executed, or cancelled.
+        /// - `ContestPeriodNotOver` if the holder can still contest the transfer.
+        /// - `ReceiverScopeClaimMissing` if the receiver no longer has a scope claim for `ticker`.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::execute_forced_transfer()]
+        pub fn execute_forced_transfer(origin, ticker: Ticker, id: u64) -> DispatchResult {
+            Self::base_execute_forced_transfer(origin, ticker, id)
+        }
+
+        /// Contests the scheduled forced transfer `id` of `ticker`
+        /// from a portfolio of, or in the custody of, the caller.
+        /// A contested transfer can no longer be executed, only cancelled.
+        ///
+        /// ## Errors
+        /// - `NoSuchForcedTransfer` if there is no forced transfer `id` of `ticker`.
+        /// - `Unauthorized` if the caller neither owns nor holds the custody of the sending portfolio.
+        /// - `ForcedTransferNotPending` if the transfer was contested, executed, or cancelled.
+        /// - `ContestPeriodOver` if the contest period of the transfer is over.
+        #[weight = <T as Config>::WeightInfo::contest_forced_transfer()]
+        pub fn contest_forced_transfer(origin, ticker: Ticker, id: u64) -> DispatchResult {
+            Self::base_contest_forced_transfer(origin, ticker, id)
+        }
+
+        /// Cancels the scheduled or contested forced transfer `id` of `ticker`,
+        /// unlocking its value in the sending portfolio.
+        ///
+        /// ## Errors
+        /// - `NoSuchForcedTransfer` if there is no forced transfer `id` of `ticker`.
+        /// - `ForcedTransferClosed` if the transfer was already executed or cancelled.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::cancel_forced_transfer()]
+        pub fn cancel_forced_transfer(origin, ticker: Ticker, id: u64) -> DispatchResult {
+            Self::base_cancel_forced_transfer(origin, ticker, id)
+        }
+
+        /// Registers a metadata key local to `ticker`, whose values must conform to `spec`.
+        ///
+        /// * `ticker` - the asset to register the key for.
+        /// * `name` - the name of the key, unique among the global keys and the keys of `ticker`.
+        /// * `spec` - the type of the values of the key, along with a description and a URL.
+        ///
+        /// ## Errors
+        /// - `AssetMetadataNameAlreadyExists` if a global or local key is already named `name`.
+        /// - `TooLong` if `name`, the description, or the URL is too long.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::register_asset_metadata_local_type(
+            name.len() as u32,
+            spec.bytes_len() as u32,
+        )]
+        pub fn register_asset_metadata_local_type(
+            origin,
+            ticker: Ticker,
+            name: AssetMetadataName,
+            spec: AssetMetadataSpec,
+        ) -> DispatchResult {
+            Self::base_register_asset_metadata_local_type(origin, ticker, name, spec)
+        }
+
+        /// Registers a metadata key, whose values must conform to `spec`, for use by every asset.
+        ///
+        /// * `name` - the name of the key, unique among the global keys.
+        /// * `spec` - the type of the values of the key, along with a description and a URL.
+        ///
+        /// ## Errors
+        /// - `AssetMetadataNameAlreadyExists` if a global key is already named `name`.
+        /// - `TooLong` if `name`, the description, or the URL is too long.
+        ///
+        /// # Permissions
+        /// * Root
+        #[weight = <T as Config>::WeightInfo::register_asset_metadata_global_type(
+            name.len() as u32,
+            spec.bytes_len() as u32,
+        )]
+        pub fn register_asset_metadata_global_type(
+            origin,
+            name: AssetMetadataName,
+            spec: AssetMetadataSpec,
+        ) -> DispatchResult {
+            Self::base_register_asset_metadata_global_type(origin, name, spec)
+        }
+
+        /// Sets the value of the metadata `key` of `ticker`, bumping its version.
+        ///
+        /// * `ticker` - the asset whose metadata is set.
+        /// * `key` - the global key, or a key local to `ticker`.
+        /// * `value` - the new value, of the type given by the specification of `key`.
+        /// * `detail` - the expiry and lock status of the value, if any.
+        ///    Defaults to an unlocked value that never expires.
+        ///
+        /// ## Errors
+        /// - `AssetMetadataKeyIsMissing` if `key` was not registered.
+        /// - `AssetMetadataInvalidValue` if `value` is not of the type of `key` or is malformed.
+        /// - `AssetMetadataValueIsLocked` if the current value can not be changed.
+        /// - `TooLong` if `value` is too long.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::set_asset_metadata(
+            value.bytes().map_or(0, |bytes| bytes.len() as u32)
+        )]
+        pub fn set_asset_metadata(
+            origin,
+            ticker: Ticker,
+            key: AssetMetadataKey,
+            value: AssetMetadataValue,
+            detail: Option<AssetMetadataValueDetail<T::Moment>>,
+        ) -> DispatchResult {
+            Self::base_set_asset_metadata(origin, ticker, key, value, detail)
+        }
+
+        /// Changes the expiry and lock status of the current value of the metadata `key` of `ticker`.
+        ///
+        /// ## Errors
+        /// - `AssetMetadataValueIsMissing` if `key` has no value.
+        /// - `AssetMetadataValueIsLocked` if the current value can not be changed.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::set_asset_metadata_details()]
+        pub fn set_asset_metadata_details(
+            origin,
+            ticker: Ticker,
+            key: AssetMetadataKey,
+            detail: AssetMetadataValueDetail<T::Moment>,
+        ) -> DispatchResult {
+            Self::base_set_asset_metadata_details(origin, ticker, key, detail)
+        }
+
+        /// Links `class` to the family of `parent`, e.g., as another share class of the same issuer.
+        ///
+        /// * `parent` - the asset heading the family, which can not itself be a class of another.
+        /// * `class` - the asset to link, which can not be in a family already.
+        ///
+        /// ## Errors
+        /// - `AssetClassOfItself` if `parent == class`.
+        /// - `AssetAlreadyInFamily` if `parent` is a class, or `class` is a parent or a class.
+        /// - `TooManyAssetClasses` if the family of `parent` already has `MAX_ASSET_FAMILY_CLASSES`.
+        ///
+        /// # Permissions
+        /// * Asset, for both `parent` and `class`
+        #[weight = <T as Config>::WeightInfo::link_asset_class()]
+        pub fn link_asset_class(origin, parent: Ticker, class: Ticker) -> DispatchResult {
+            Self::base_link_asset_class(origin, parent, class)
+        }
+
+        /// Unlinks `class` from the family of its parent,
+        /// removing any conversions into or out of `class`.
+        ///
+        /// ## Errors
+        /// - `NotAnAssetClass` if `class` is not linked to a family.
+        ///
+        /// # Permissions
+        /// * Asset, for the parent of `class`
+        #[weight = <T as Config>::WeightInfo::unlink_asset_class()]
+        pub fn unlink_asset_class(origin, class: Ticker) -> DispatchResult {
+            Self::base_unlink_asset_class(origin, class)
+        }
+
+        /// Sets, or removes if `None`, the terms on which holders may convert `from` into `to`.
+        ///
+        /// * `from` - the class converted from.
+        /// * `to` - the class converted into, in the same family as `from`.
+        /// * `conversion` - the ratio of shares of `to` issued per shares of `from` redeemed,
+        ///    and the window in which conversions are open.
+        ///
+        /// ## Errors
+        /// - `NotSameAssetFamily` if `from == to` or they are not in the same family.
+        /// - `InvalidClassConversion` if the ratio is zero or infinite, or the window is empty.
+        ///
+        /// # Permissions
+        /// * Asset, for both `from` and `to`
+        #[weight = <T as Config>::WeightInfo::set_class_conversion()]
+        pub fn set_class_conversion(
+            origin,
+            from: Ticker,
+            to: Ticker,
+            conversion: Option<ClassConversion>,
+        ) -> DispatchResult {
+            Self::base_set_class_conversion(origin, from, to, conversion)
+        }
+
+        /// Converts `value` of `from` held in the caller's default portfolio into `to`,
+        /// redeeming the former and issuing the latter to the same portfolio, atomically.
+        ///
+        /// The caller must be allowed to receive `to` by its compliance rules and transfer managers,
+        /// as if it were issued to them by the owner of `to`.
+        /// Unlike issuance, no protocol fee is charged, as converting only exchanges shares of the same family
+        /// at a ratio set by its agents, akin to a transfer, and the supply of the family is not diluted.
+        ///
+        /// ## Errors
+        /// - `NoClassConversion` if conversions of `from` into `to` were not set.
+        /// - `ClassConversionClosed` if conversions are not open now.
+        /// - `InexactClassConversion` if `value` does not convert into a whole number of units of `to`.
+        /// - `InvalidTransfer` if `from` or `to` is frozen, for all or for the caller,
+        ///    or `value` exceeds the caller's unfrozen balance of `from`,
+        ///    or the caller may not receive `to` by its compliance rules or transfer managers.
+        /// - `InsufficientBalance` if the caller has less than `value` of `from` free.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::convert_class()]
+        pub fn convert_class(origin, from: Ticker, to: Ticker, value: T::Balance) -> DispatchResult {
+            Self::base_convert_class(origin, from, to, value)
+        }
     }
 }
 
@@ -843,6 +1373,48 @@ decl_error! {
         FundingRoundNameMaxLengthExceeded,
         /// Some `AssetIdentifier` was invalid.
         InvalidAssetIdentifier,
+        /// The given forced transfer does not exist.
+        NoSuchForcedTransfer,
+        /// The forced transfer was already contested, executed, or cancelled.
+        ForcedTransferNotPending,
+        /// The forced transfer was already executed or cancelled.
+        ForcedTransferClosed,
+        /// The holder can still contest the forced transfer.
+        ContestPeriodNotOver,
+        /// The holder can no longer contest the forced transfer.
+        ContestPeriodOver,
+        /// A forced transfer from the portfolio is already scheduled or contested.
+        ForcedTransferOpen,
+        /// The receiver of a forced transfer has no scope claim for the asset.
+        ReceiverScopeClaimMissing,
+        /// A metadata key with the given name already exists.
+        AssetMetadataNameAlreadyExists,
+        /// The metadata key does not exist.
+        AssetMetadataKeyIsMissing,
+        /// The metadata key has no value.
+        AssetMetadataValueIsMissing,
+        /// The metadata value does not match the specification of its key.
+        AssetMetadataInvalidValue,
+        /// The metadata value is locked.
+        AssetMetadataValueIsLocked,
+        /// An asset can not be linked as a class of its own family.
+        AssetClassOfItself,
+        /// The asset is already the parent or a class of a family.
+        AssetAlreadyInFamily,
+        /// The family already has the maximum number of classes.
+        TooManyAssetClasses,
+        /// The asset is not linked as a class of a family.
+        NotAnAssetClass,
+        /// The assets are not distinct classes of the same family.
+        NotSameAssetFamily,
+        /// The conversion ratio is zero or infinite, or its window is empty.
+        InvalidClassConversion,
+        /// No conversion between the classes was set.
+        NoClassConversion,
+        /// Conversions between the classes are not open.
+        ClassConversionClosed,
+        /// The value does not convert into a whole number of units.
+        InexactClassConversion,
     }
 }
 
@@ -960,6 +1532,14 @@ impl<T: Config> AssetSubTrait<T::Balance> for Module<T> {
     fn scope_id_of(ticker: &Ticker, did: &IdentityId) -> ScopeId {
         Self::scope_id_of(ticker, did)
     }
+
+    fn is_portfolio_frozen(ticker: &Ticker, portfolio: &PortfolioId) -> bool {
+        Self::frozen_portfolio(ticker, portfolio)
+    }
+
+    fn is_frozen_balance_exceeded(ticker: &Ticker, did: IdentityId, value: T::Balance) -> bool {
+        Self::frozen_balance_exceeded(ticker, did, value)
+    }
 }
 
 /// All functions in the decl_module macro become part of the public interface of the module
@@ -1032,6 +1612,13 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+    /// Returns an iterator over the identities holding a non-zero balance of `ticker`.
+    pub fn holders(ticker: &Ticker) -> impl Iterator<Item = IdentityId> {
+        <BalanceOf<T>>::iter_prefix(ticker)
+            .filter(|(_, balance)| !balance.is_zero())
+            .map(|(did, _)| did)
+    }
+
     pub fn is_owner(ticker: &Ticker, did: IdentityId) -> bool {
         match Self::asset_ownership_relation(&did, &ticker) {
             AssetOwnershipRelation::AssetOwned | AssetOwnershipRelation::TickerOwned => true,
@@ -1171,16 +1758,60 @@ impl<T: Config> Module<T> {
         from_portfolio: PortfolioId,
         to_portfolio: PortfolioId,
         value: T::Balance,
+    ) -> StdResult<u8, DispatchError> {
+        Self::transfer_status(ticker, from_portfolio, to_portfolio, value, true)
+    }
+
+    /// Returns the status code of a transfer settled through netting, i.e.,
+    /// without checking that the sender has the balance to send `value`,
+    /// as only the net balance changes are moved, with `unsafe_net_transfer`.
+    pub fn netted_transfer_status(
+        ticker: &Ticker,
+        from_portfolio: PortfolioId,
+        to_portfolio: PortfolioId,
+        value: T::Balance,
+    ) -> StdResult<u8, DispatchError> {
+        if !Self::check_granularity(ticker, value) {
+            return Ok(INVALID_GRANULARITY);
+        }
+        Self::transfer_status(ticker, from_portfolio, to_portfolio, value, false)
+    }
+
+    fn transfer_status(
+        ticker: &Ticker,
+        from_portfolio: PortfolioId,
+        to_portfolio: PortfolioId,
+        value: T::Balance,
+        check_balance: bool,
     ) -> StdResult<u8, DispatchError> {
         if Self::frozen(ticker) {
             return Ok(ERC1400_TRANSFERS_HALTED);
         }
 
+        if Self::holder_frozen(ticker, &from_portfolio)
+            || Self::holder_frozen(ticker, &to_portfolio)
+        {
+            return Ok(HOLDER_FROZEN);
+        }
+
+        if check_balance && Self::frozen_balance_exceeded(ticker, from_portfolio.did, value) {
+            return Ok(ERC1400_FUNDS_LOCKED);
+        }
+
+        if Self::matured(ticker) {
+            return Ok(ASSET_MATURED);
+        }
+
         if Self::missing_scope_claim(ticker, &to_portfolio, &from_portfolio) {
             return Ok(SCOPE_CLAIM_MISSING);
         }
 
-        if Self::portfolio_failure(&from_portfolio, &to_portfolio, ticker, &value) {
+        let portfolio_failure = if check_balance {
+            Self::portfolio_failure(&from_portfolio, &to_portfolio, ticker, &value)
+        } else {
+            Self::netted_portfolio_failure(&from_portfolio, &to_portfolio, ticker, &value)
+        };
+        if portfolio_failure {
             return Ok(PORTFOLIO_FAILURE);
         }
 
@@ -1268,12 +1899,9 @@ impl<T: Config> Module<T> {
 
         // Update statistic info.
         // Using the aggregate balance to update the unique investor count.
-        Statistics::<T>::update_transfer_stats(
-            ticker,
-            Some(Self::aggregate_balance_of(ticker, &from_scope_id)),
-            Some(Self::aggregate_balance_of(ticker, &to_scope_id)),
-            value,
-        );
+        let from_party = Self::transfer_party(ticker, from_portfolio.did, from_scope_id);
+        let to_party = Self::transfer_party(ticker, to_portfolio.did, to_scope_id);
+        Statistics::<T>::update_transfer_stats(ticker, Some(from_party), Some(to_party), value);
 
         Self::deposit_event(RawEvent::Transfer(
             from_portfolio.did,
@@ -1312,6 +1940,37 @@ impl<T: Config> Module<T> {
         to_did: IdentityId,
         value: T::Balance,
         protocol_fee_data: Option<ProtocolOp>,
+    ) -> DispatchResult {
+        Self::mint_to_default_portfolio(ticker, to_did, value, protocol_fee_data)?;
+
+        let round = Self::funding_round(ticker);
+        let ticker_round = (*ticker, round.clone());
+        // No check since the issued balance is always <= the total
+        // supply. The total supply was already checked when minting.
+        let issued_in_this_round = Self::issued_in_funding_round(&ticker_round) + value;
+        <IssuedInFundingRound<T>>::insert(&ticker_round, issued_in_this_round);
+
+        Self::deposit_event(Event::<T>::Issued(
+            to_did,
+            *ticker,
+            to_did,
+            value,
+            round,
+            issued_in_this_round,
+        ));
+
+        Ok(())
+    }
+
+    /// Increases the total supply of `ticker` and the balance of the default portfolio of `to_did` by `value`,
+    /// charging the protocol fee for `protocol_fee_data`, if any.
+    ///
+    /// Unlike `_mint`, this is not recorded as issuance in the current funding round.
+    fn mint_to_default_portfolio(
+        ticker: &Ticker,
+        to_did: IdentityId,
+        value: T::Balance,
+        protocol_fee_data: Option<ProtocolOp>,
     ) -> DispatchResult {
         Self::ensure_granular(ticker, value)?;
 
@@ -1352,23 +2011,20 @@ impl<T: Config> Module<T> {
         Portfolio::<T>::set_default_portfolio_balance(to_did, ticker, updated_to_def_balance);
         <Tokens<T>>::insert(ticker, token);
 
-        let updated_to_balance = if ScopeIdOf::contains_key(ticker, &to_did) {
+        let to_party = if ScopeIdOf::contains_key(ticker, &to_did) {
             let scope_id = Self::scope_id_of(ticker, &to_did);
             Self::update_scope_balance(&ticker, value, scope_id, to_did, updated_to_balance, false);
             // Using the aggregate balance to update the unique investor count.
-            Self::aggregate_balance_of(ticker, &scope_id)
+            Self::transfer_party(ticker, to_did, scope_id)
         } else {
             // Since the PIA does not have a scope claim yet, we assume this is their only identity
-            value
+            TransferParty {
+                did: to_did,
+                scope_id: to_did,
+                balance: value,
+            }
         };
-        Statistics::<T>::update_transfer_stats(&ticker, None, Some(updated_to_balance), value);
-
-        let round = Self::funding_round(ticker);
-        let ticker_round = (*ticker, round.clone());
-        // No check since the issued balance is always <= the total
-        // supply. The total supply is already checked above.
-        let issued_in_this_round = Self::issued_in_funding_round(&ticker_round) + value;
-        <IssuedInFundingRound<T>>::insert(&ticker_round, issued_in_this_round);
+        Statistics::<T>::update_transfer_stats(&ticker, None, Some(to_party), value);
 
         Self::deposit_event(Event::<T>::Transfer(
             to_did,
@@ -1377,15 +2033,42 @@ impl<T: Config> Module<T> {
             PortfolioId::default_portfolio(to_did),
             value,
         ));
-        Self::deposit_event(Event::<T>::Issued(
-            to_did,
-            *ticker,
-            to_did,
-            value,
-            round,
-            issued_in_this_round,
-        ));
+        Ok(())
+    }
+
+    /// Issues `value` of `ticker` to the default portfolio of `to_did`,
+    /// without checking permissions or charging protocol fees.
+    pub fn issue_to(ticker: &Ticker, to_did: IdentityId, value: T::Balance) -> DispatchResult {
+        Self::_mint(ticker, to_did, value, None)
+    }
 
+    /// Sets the balance of `did` in `ticker` to `new_balance`, e.g., when rescaled by a stock split,
+    /// adjusting the total supply, scoped balances and checkpoints accordingly.
+    ///
+    /// The balances of the portfolios of `did` must be updated separately.
+    pub fn set_rescaled_balance(
+        ticker: &Ticker,
+        did: IdentityId,
+        new_balance: T::Balance,
+    ) -> DispatchResult {
+        let balance = Self::balance_of(ticker, did);
+
+        // Record the balance before rescaling in any checkpoint due.
+        <Checkpoint<T>>::advance_update_balances(ticker, &[(did, balance)])?;
+
+        <Tokens<T>>::mutate(ticker, |token| {
+            token.total_supply = token.total_supply - balance + new_balance;
+        });
+        <BalanceOf<T>>::insert(ticker, did, new_balance);
+        if ScopeIdOf::contains_key(ticker, &did) {
+            let scope_id = Self::scope_id_of(ticker, &did);
+            <AggregateBalance<T>>::mutate(ticker, scope_id, |aggregate| {
+                *aggregate = aggregate
+                    .saturating_sub(balance)
+                    .saturating_add(new_balance)
+            });
+            <BalanceOfAtScope<T>>::insert(scope_id, did, new_balance);
+        }
         Ok(())
     }
 
@@ -1517,6 +2200,41 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+    /// Moves `value` of `ticker` from `from_portfolio` to `to_portfolio` as the net result
+    /// of legs that were each verified with `netted_transfer_status`,
+    /// so only the balance of the sender, which those checks skip, is checked here.
+    pub fn unsafe_net_transfer(
+        from_portfolio: PortfolioId,
+        to_portfolio: PortfolioId,
+        ticker: &Ticker,
+        value: T::Balance,
+    ) -> DispatchResult {
+        Portfolio::<T>::ensure_sufficient_balance(&from_portfolio, ticker, &value)?;
+        if from_portfolio.did != to_portfolio.did {
+            ensure!(
+                !Self::frozen_balance_exceeded(ticker, from_portfolio.did, value),
+                Error::<T>::InvalidTransfer
+            );
+            return Self::unsafe_transfer(from_portfolio, to_portfolio, ticker, value);
+        }
+
+        // Legs between other identities net to a move within a single identity.
+        Portfolio::<T>::unchecked_transfer_portfolio_balance(
+            &from_portfolio,
+            &to_portfolio,
+            ticker,
+            value,
+        );
+        Self::deposit_event(RawEvent::Transfer(
+            from_portfolio.did,
+            *ticker,
+            from_portfolio,
+            to_portfolio,
+            value,
+        ));
+        Ok(())
+    }
+
     /// Performs necessary checks on parameters of `create_asset`.
     fn ensure_create_asset_parameters(ticker: &Ticker) -> DispatchResult {
         Self::ensure_asset_fresh(&ticker)?;
@@ -1533,7 +2251,7 @@ impl<T: Config> Module<T> {
     }
 
     /// Ensure `supply <= MAX_SUPPLY`.
-    fn ensure_within_max_supply(supply: T::Balance) -> DispatchResult {
+    pub fn ensure_within_max_supply(supply: T::Balance) -> DispatchResult {
         ensure!(
             supply <= MAX_SUPPLY.into(),
             Error::<T>::TotalSupplyAboveLimit
@@ -1744,6 +2462,87 @@ impl<T: Config> Module<T> {
         Ok(did)
     }
 
+    fn set_identity_freeze(
+        origin: T::Origin,
+        ticker: Ticker,
+        did: IdentityId,
+        freeze: bool,
+    ) -> DispatchResult {
+        let caller = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+        Self::ensure_asset_exists(&ticker)?;
+
+        let (event, error) = match freeze {
+            true => (
+                RawEvent::IdentityFrozen(caller, ticker, did),
+                Error::<T>::AlreadyFrozen,
+            ),
+            false => (
+                RawEvent::IdentityUnfrozen(caller, ticker, did),
+                Error::<T>::NotFrozen,
+            ),
+        };
+
+        ensure!(Self::frozen_identity(&ticker, &did) != freeze, error);
+        if freeze {
+            FrozenIdentities::insert(&ticker, &did, true);
+        } else {
+            FrozenIdentities::remove(&ticker, &did);
+        }
+
+        Self::deposit_event(event);
+        Ok(())
+    }
+
+    fn set_portfolio_freeze(
+        origin: T::Origin,
+        ticker: Ticker,
+        portfolio: PortfolioId,
+        freeze: bool,
+    ) -> DispatchResult {
+        let caller = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+        Self::ensure_asset_exists(&ticker)?;
+
+        let (event, error) = match freeze {
+            true => (
+                RawEvent::PortfolioFrozen(caller, ticker, portfolio),
+                Error::<T>::AlreadyFrozen,
+            ),
+            false => (
+                RawEvent::PortfolioUnfrozen(caller, ticker, portfolio),
+                Error::<T>::NotFrozen,
+            ),
+        };
+
+        ensure!(Self::frozen_portfolio(&ticker, &portfolio) != freeze, error);
+        if freeze {
+            FrozenPortfolios::insert(&ticker, &portfolio, true);
+        } else {
+            FrozenPortfolios::remove(&ticker, &portfolio);
+        }
+
+        Self::deposit_event(event);
+        Ok(())
+    }
+
+    fn base_set_frozen_balance(
+        origin: T::Origin,
+        ticker: Ticker,
+        did: IdentityId,
+        amount: T::Balance,
+    ) -> DispatchResult {
+        let caller = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+        Self::ensure_asset_exists(&ticker)?;
+
+        if amount == Zero::zero() {
+            <FrozenBalances<T>>::remove(&ticker, &did);
+        } else {
+            <FrozenBalances<T>>::insert(&ticker, &did, amount);
+        }
+
+        Self::deposit_event(RawEvent::FrozenBalanceSet(caller, ticker, did, amount));
+        Ok(())
+    }
+
     fn set_freeze(origin: T::Origin, ticker: Ticker, freeze: bool) -> DispatchResult {
         let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
         Self::ensure_asset_exists(&ticker)?;
@@ -1780,7 +2579,16 @@ impl<T: Config> Module<T> {
     fn base_redeem(origin: T::Origin, ticker: Ticker, value: T::Balance) -> DispatchResult {
         // Ensure origin is agent with custody and permissions for default portfolio.
         let pia = Self::ensure_agent_with_custody_and_perms(origin, ticker)?;
+        Self::redeem_from_default_portfolio(pia, ticker, value)
+    }
 
+    /// Redeems `value` of `ticker` from the default portfolio of `pia`,
+    /// e.g., the agent redeeming, or tokens bought back by a corporate action.
+    pub fn redeem_from_default_portfolio(
+        pia: IdentityId,
+        ticker: Ticker,
+        value: T::Balance,
+    ) -> DispatchResult {
         Self::ensure_granular(&ticker, value)?;
 
         // Reduce PIA's portfolio balance. This makes sure that the PIA has enough unlocked tokens.
@@ -1807,8 +2615,8 @@ impl<T: Config> Module<T> {
 
         // Update statistic info.
         // Using the aggregate balance to update the unique investor count.
-        let updated_from_balance = Some(Self::aggregate_balance_of(ticker, &scope_id));
-        Statistics::<T>::update_transfer_stats(&ticker, updated_from_balance, None, value);
+        let from_party = Some(Self::transfer_party(&ticker, pia, scope_id));
+        Statistics::<T>::update_transfer_stats(&ticker, from_party, None, value);
 
         Self::deposit_event(RawEvent::Transfer(
             pia,
@@ -1822,6 +2630,12 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+    /// Sets the maturity date of `ticker`, e.g., of a bond,
+    /// from which on the asset can no longer be transferred.
+    pub fn set_maturity(ticker: Ticker, maturity: Moment) {
+        Maturities::insert(ticker, maturity);
+    }
+
     fn base_make_divisible(origin: T::Origin, ticker: Ticker) -> DispatchResult {
         let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
 
@@ -2097,6 +2911,512 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+    fn base_force_transfer(
+        origin: T::Origin,
+        ticker: Ticker,
+        from: PortfolioId,
+        to: PortfolioId,
+        value: T::Balance,
+        reason: ForcedTransferReason,
+        evidence: DocumentId,
+        contest_period: Option<T::Moment>,
+    ) -> DispatchResult {
+        let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+        Self::ensure_doc_exists(&ticker, &evidence)?;
+        ensure!(from.did != to.did, Error::<T>::SenderSameAsReceiver);
+        Portfolio::<T>::ensure_portfolio_validity(&from)?;
+        Portfolio::<T>::ensure_portfolio_validity(&to)?;
+        Self::ensure_receiver_scope_claim(ticker, to.did)?;
+        ensure!(
+            Self::open_forced_transfer(ticker, from).is_none(),
+            Error::<T>::ForcedTransferOpen
+        );
+
+        let id = Self::forced_transfer_count(ticker);
+        let now = <pallet_timestamp::Module<T>>::get();
+        let mut transfer = ForcedTransfer {
+            from,
+            to,
+            value,
+            reason,
+            evidence,
+            executable_at: now,
+            status: ForcedTransferStatus::Pending,
+        };
+        match contest_period {
+            Some(period) => {
+                // The holder can't move the value away while the transfer is open.
+                Portfolio::<T>::lock_tokens(&from, &ticker, &value)?;
+                OpenForcedTransfers::insert(ticker, from, id);
+                transfer.executable_at = now.saturating_add(period);
+                Self::deposit_event(RawEvent::ForcedTransferScheduled(
+                    did,
+                    ticker,
+                    id,
+                    from,
+                    to,
+                    value,
+                    reason,
+                    evidence,
+                    transfer.executable_at,
+                ));
+            }
+            None => Self::unsafe_execute_forced_transfer(did, ticker, id, &mut transfer)?,
+        }
+        ForcedTransferCount::insert(ticker, id + 1);
+        <ForcedTransfers<T>>::insert(ticker, id, transfer);
+        Ok(())
+    }
+
+    fn base_execute_forced_transfer(origin: T::Origin, ticker: Ticker, id: u64) -> DispatchResult {
+        let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+        let mut transfer = Self::ensure_forced_transfer(ticker, id)?;
+        ensure!(
+            transfer.status == ForcedTransferStatus::Pending,
+            Error::<T>::ForcedTransferNotPending
+        );
+        ensure!(
+            <pallet_timestamp::Module<T>>::get() >= transfer.executable_at,
+            Error::<T>::ContestPeriodNotOver
+        );
+
+        with_transaction(|| -> DispatchResult {
+            Self::close_forced_transfer(ticker, &transfer)?;
+            Self::unsafe_execute_forced_transfer(did, ticker, id, &mut transfer)
+        })?;
+        <ForcedTransfers<T>>::insert(ticker, id, transfer);
+        Ok(())
+    }
+
+    fn base_contest_forced_transfer(origin: T::Origin, ticker: Ticker, id: u64) -> DispatchResult {
+        let did = Identity::<T>::ensure_perms(origin)?;
+        let mut transfer = Self::ensure_forced_transfer(ticker, id)?;
+        ensure!(
+            did == transfer.from.did
+                || Portfolio::<T>::ensure_portfolio_custody(transfer.from, did).is_ok(),
+            Error::<T>::Unauthorized
+        );
+        ensure!(
+            transfer.status == ForcedTransferStatus::Pending,
+            Error::<T>::ForcedTransferNotPending
+        );
+        ensure!(
+            <pallet_timestamp::Module<T>>::get() < transfer.executable_at,
+            Error::<T>::ContestPeriodOver
+        );
+
+        transfer.status = ForcedTransferStatus::Contested;
+        <ForcedTransfers<T>>::insert(ticker, id, transfer);
+        Self::deposit_event(RawEvent::ForcedTransferContested(did, ticker, id));
+        Ok(())
+    }
+
+    fn base_cancel_forced_transfer(origin: T::Origin, ticker: Ticker, id: u64) -> DispatchResult {
+        let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+        let mut transfer = Self::ensure_forced_transfer(ticker, id)?;
+        ensure!(
+            matches!(
+                transfer.status,
+                ForcedTransferStatus::Pending | ForcedTransferStatus::Contested
+            ),
+            Error::<T>::ForcedTransferClosed
+        );
+
+        Self::close_forced_transfer(ticker, &transfer)?;
+        transfer.status = ForcedTransferStatus::Cancelled;
+        <ForcedTransfers<T>>::insert(ticker, id, transfer);
+        Self::deposit_event(RawEvent::ForcedTransferCancelled(did, ticker, id));
+        Ok(())
+    }
+
+    /// Returns the forced transfer `id` of `ticker`, if it exists.
+    fn ensure_forced_transfer(
+        ticker: Ticker,
+        id: u64,
+    ) -> Result<ForcedTransfer<T::Balance, T::Moment>, DispatchError> {
+        Self::forced_transfers(ticker, id).ok_or_else(|| Error::<T>::NoSuchForcedTransfer.into())
+    }
+
+    /// Unlocks the value of the open `transfer` of `ticker`, so that it can be executed or cancelled.
+    fn close_forced_transfer(
+        ticker: Ticker,
+        transfer: &ForcedTransfer<T::Balance, T::Moment>,
+    ) -> DispatchResult {
+        Portfolio::<T>::unlock_tokens(&transfer.from, &ticker, &transfer.value)?;
+        OpenForcedTransfers::remove(ticker, transfer.from);
+        Ok(())
+    }
+
+    /// Executes `transfer`, bypassing compliance rules and freezes, and marks it as executed.
+    /// Ensures that `did` has a scope claim for `ticker`, so that its balance is tracked by scope.
+    fn ensure_receiver_scope_claim(ticker: Ticker, did: IdentityId) -> DispatchResult {
+        ensure!(
+            ScopeIdOf::contains_key(ticker, did),
+            Error::<T>::ReceiverScopeClaimMissing
+        );
+        Ok(())
+    }
+
+    fn unsafe_execute_forced_transfer(
+        did: IdentityId,
+        ticker: Ticker,
+        id: u64,
+        transfer: &mut ForcedTransfer<T::Balance, T::Moment>,
+    ) -> DispatchResult {
+        // The claim may have been revoked while the transfer was scheduled.
+        Self::ensure_receiver_scope_claim(ticker, transfer.to.did)?;
+        Portfolio::<T>::ensure_sufficient_balance(&transfer.from, &ticker, &transfer.value)?;
+        Self::unsafe_transfer(transfer.from, transfer.to, &ticker, transfer.value)?;
+        transfer.status = ForcedTransferStatus::Executed;
+        Self::deposit_event(RawEvent::ForcedTransferExecuted(
+            did,
+            ticker,
+            id,
+            transfer.from,
+            transfer.to,
+            transfer.value,
+            transfer.reason,
+            transfer.evidence,
+        ));
+        Ok(())
+    }
+
+    fn base_register_asset_metadata_local_type(
+        origin: T::Origin,
+        ticker: Ticker,
+        name: AssetMetadataName,
+        spec: AssetMetadataSpec,
+    ) -> DispatchResult {
+        let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+        Self::ensure_asset_metadata_name_spec(&name, &spec)?;
+        ensure!(
+            !AssetMetadataLocalNameToKey::contains_key(ticker, &name)
+                && !AssetMetadataGlobalNameToKey::contains_key(&name),
+            Error::<T>::AssetMetadataNameAlreadyExists
+        );
+
+        let key = AssetMetadataNextLocalKey::get(ticker);
+        AssetMetadataNextLocalKey::insert(ticker, AssetMetadataLocalKey(key.0 + 1));
+        AssetMetadataLocalNameToKey::insert(ticker, &name, key);
+        AssetMetadataLocalKeyToName::insert(ticker, key, &name);
+        AssetMetadataLocalSpecs::insert(ticker, key, &spec);
+        Self::deposit_event(RawEvent::RegisterAssetMetadataLocalType(
+            did, ticker, name, key, spec,
+        ));
+        Ok(())
+    }
+
+    fn base_register_asset_metadata_global_type(
+        origin: T::Origin,
+        name: AssetMetadataName,
+        spec: AssetMetadataSpec,
+    ) -> DispatchResult {
+        ensure_root(origin)?;
+        Self::ensure_asset_metadata_name_spec(&name, &spec)?;
+        ensure!(
+            !AssetMetadataGlobalNameToKey::contains_key(&name),
+            Error::<T>::AssetMetadataNameAlreadyExists
+        );
+
+        let key = AssetMetadataNextGlobalKey::get();
+        AssetMetadataNextGlobalKey::put(AssetMetadataGlobalKey(key.0 + 1));
+        AssetMetadataGlobalNameToKey::insert(&name, key);
+        AssetMetadataGlobalKeyToName::insert(key, &name);
+        AssetMetadataGlobalSpecs::insert(key, &spec);
+        Self::deposit_event(RawEvent::RegisterAssetMetadataGlobalType(name, key, spec));
+        Ok(())
+    }
+
+    fn base_set_asset_metadata(
+        origin: T::Origin,
+        ticker: Ticker,
+        key: AssetMetadataKey,
+        value: AssetMetadataValue,
+        detail: Option<AssetMetadataValueDetail<T::Moment>>,
+    ) -> DispatchResult {
+        let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+        let spec =
+            Self::asset_metadata_spec(ticker, key).ok_or(Error::<T>::AssetMetadataKeyIsMissing)?;
+        ensure!(
+            value.value_type() == spec.value_type && value.is_valid(),
+            Error::<T>::AssetMetadataInvalidValue
+        );
+        ensure_opt_string_limited::<T>(value.bytes())?;
+
+        let current = Self::asset_metadata_values(ticker, key);
+        if let Some(current) = &current {
+            Self::ensure_asset_metadata_unlocked(current)?;
+        }
+        let entry = AssetMetadataEntry {
+            value,
+            detail: detail.unwrap_or_default(),
+            version: current.map_or(1, |current| current.version.saturating_add(1)),
+        };
+        <AssetMetadataValues<T>>::insert(ticker, key, &entry);
+        Self::deposit_event(RawEvent::SetAssetMetadataValue(
+            did,
+            ticker,
+            key,
+            entry.value,
+            entry.detail,
+            entry.version,
+        ));
+        Ok(())
+    }
+
+    fn base_set_asset_metadata_details(
+        origin: T::Origin,
+        ticker: Ticker,
+        key: AssetMetadataKey,
+        detail: AssetMetadataValueDetail<T::Moment>,
+    ) -> DispatchResult {
+        let did = <ExternalAgents<T>>::ensure_perms(origin, ticker)?;
+        let mut entry = Self::asset_metadata_values(ticker, key)
+            .ok_or(Error::<T>::AssetMetadataValueIsMissing)?;
+        Self::ensure_asset_metadata_unlocked(&entry)?;
+
+        entry.detail = detail.clone();
+        <AssetMetadataValues<T>>::insert(ticker, key, entry);
+        Self::deposit_event(RawEvent::SetAssetMetadataValueDetails(
+            did, ticker, key, detail,
+        ));
+        Ok(())
+    }
+
+    /// Ensures that `name` and the description and URL of `spec` are within the length limits.
+    fn ensure_asset_metadata_name_spec(
+        name: &AssetMetadataName,
+        spec: &AssetMetadataSpec,
+    ) -> DispatchResult {
+        ensure_string_limited::<T>(name)?;
+        ensure_opt_string_limited::<T>(spec.description.as_deref())?;
+        ensure_opt_string_limited::<T>(spec.url.as_deref())
+    }
+
+    /// Ensures that the metadata value of `entry` can be changed now.
+    fn ensure_asset_metadata_unlocked(entry: &AssetMetadataEntry<T::Moment>) -> DispatchResult {
+        let now = <pallet_timestamp::Module<T>>::get();
+        ensure!(
+            !entry.detail.lock_status.is_locked(&now),
+            Error::<T>::AssetMetadataValueIsLocked
+        );
+        Ok(())
+    }
+
+    /// Returns `true` if the metadata value of `entry` has not expired at `now`.
+    fn is_unexpired(entry: &AssetMetadataEntry<T::Moment>, now: T::Moment) -> bool {
+        entry.detail.expire.map_or(true, |expire| now < expire)
+    }
+
+    /// Returns the name and specification of the metadata `key` of `ticker`, if it exists.
+    fn asset_metadata_name_spec(
+        ticker: Ticker,
+        key: AssetMetadataKey,
+    ) -> Option<(AssetMetadataName, AssetMetadataSpec)> {
+        match key {
+            AssetMetadataKey::Global(key) => Self::asset_metadata_global_key_to_name(key)
+                .zip(Self::asset_metadata_global_specs(key)),
+            AssetMetadataKey::Local(key) => Self::asset_metadata_local_key_to_name(ticker, key)
+                .zip(Self::asset_metadata_local_specs(ticker, key)),
+        }
+    }
+
+    /// Returns the specification of the metadata `key` of `ticker`, if it exists.
+    pub fn asset_metadata_spec(ticker: Ticker, key: AssetMetadataKey) -> Option<AssetMetadataSpec> {
+        match key {
+            AssetMetadataKey::Global(key) => Self::asset_metadata_global_specs(key),
+            AssetMetadataKey::Local(key) => Self::asset_metadata_local_specs(ticker, key),
+        }
+    }
+
+    /// Returns the unexpired value of the metadata `key` of `ticker`, if any.
+    pub fn get_asset_metadata_value(
+        ticker: Ticker,
+        key: AssetMetadataKey,
+    ) -> Option<AssetMetadataEntry<T::Moment>> {
+        let now = <pallet_timestamp::Module<T>>::get();
+        Self::asset_metadata_values(ticker, key).filter(|entry| Self::is_unexpired(entry, now))
+    }
+
+    /// Returns the unexpired metadata values of `ticker`,
+    /// along with the names and specifications of their keys.
+    pub fn get_asset_metadata(ticker: Ticker) -> Vec<AssetMetadataItem<T::Moment>> {
+        let now = <pallet_timestamp::Module<T>>::get();
+        <AssetMetadataValues<T>>::iter_prefix(ticker)
+            .filter(|(_, entry)| Self::is_unexpired(entry, now))
+            .filter_map(|(key, entry)| {
+                let (name, spec) = Self::asset_metadata_name_spec(ticker, key)?;
+                Some(AssetMetadataItem {
+                    key,
+                    name,
+                    spec,
+                    entry,
+                })
+            })
+            .collect()
+    }
+
+    fn base_link_asset_class(origin: T::Origin, parent: Ticker, class: Ticker) -> DispatchResult {
+        let did = <ExternalAgents<T>>::ensure_perms(origin.clone(), parent)?;
+        <ExternalAgents<T>>::ensure_perms(origin, class)?;
+        ensure!(parent != class, Error::<T>::AssetClassOfItself);
+        // Families are flat, so neither asset may head or belong to another family.
+        ensure!(
+            !AssetFamilyParents::contains_key(parent)
+                && !AssetFamilyParents::contains_key(class)
+                && !AssetFamilyClasses::contains_key(class),
+            Error::<T>::AssetAlreadyInFamily
+        );
+
+        let mut classes = Self::asset_family_classes(parent);
+        ensure!(
+            classes.len() < MAX_ASSET_FAMILY_CLASSES,
+            Error::<T>::TooManyAssetClasses
+        );
+        classes.push(class);
+        AssetFamilyClasses::insert(parent, classes);
+        AssetFamilyParents::insert(class, parent);
+        Self::deposit_event(RawEvent::AssetClassLinked(did, parent, class));
+        Ok(())
+    }
+
+    fn base_unlink_asset_class(origin: T::Origin, class: Ticker) -> DispatchResult {
+        let parent = Self::asset_family_parent(class).ok_or(Error::<T>::NotAnAssetClass)?;
+        let did = <ExternalAgents<T>>::ensure_perms(origin, parent)?;
+
+        let mut classes = AssetFamilyClasses::take(parent);
+        classes.retain(|c| *c != class);
+        // Remove conversions out of and into `class`.
+        ClassConversions::remove_prefix(class);
+        ClassConversions::remove(parent, class);
+        for other in &classes {
+            ClassConversions::remove(other, class);
+        }
+        if !classes.is_empty() {
+            AssetFamilyClasses::insert(parent, classes);
+        }
+        AssetFamilyParents::remove(class);
+        Self::deposit_event(RawEvent::AssetClassUnlinked(did, parent, class));
+        Ok(())
+    }
+
+    fn base_set_class_conversion(
+        origin: T::Origin,
+        from: Ticker,
+        to: Ticker,
+        conversion: Option<ClassConversion>,
+    ) -> DispatchResult {
+        let did = <ExternalAgents<T>>::ensure_perms(origin.clone(), from)?;
+        <ExternalAgents<T>>::ensure_perms(origin, to)?;
+        ensure!(
+            from != to && Self::same_asset_family(from, to),
+            Error::<T>::NotSameAssetFamily
+        );
+
+        match conversion {
+            Some(conversion) => {
+                ensure!(conversion.is_valid(), Error::<T>::InvalidClassConversion);
+                ClassConversions::insert(from, to, conversion);
+            }
+            None => ClassConversions::remove(from, to),
+        }
+        Self::deposit_event(RawEvent::ClassConversionSet(did, from, to, conversion));
+        Ok(())
+    }
+
+    fn base_convert_class(
+        origin: T::Origin,
+        from: Ticker,
+        to: Ticker,
+        value: T::Balance,
+    ) -> DispatchResult {
+        let PermissionedCallOriginData {
+            primary_did: did,
+            secondary_key,
+            ..
+        } = Identity::<T>::ensure_origin_call_permissions(origin)?;
+        let portfolio = PortfolioId::default_portfolio(did);
+        Portfolio::<T>::ensure_portfolio_custody_and_permission(
+            portfolio,
+            did,
+            secondary_key.as_ref(),
+        )?;
+
+        let conversion = Self::class_conversion(from, to).ok_or(Error::<T>::NoClassConversion)?;
+        ensure!(
+            conversion.is_open(<Checkpoint<T>>::now_unix()),
+            Error::<T>::ClassConversionClosed
+        );
+        // Converting must not get around freezes of either class.
+        ensure!(
+            !Self::frozen(from)
+                && !Self::frozen(to)
+                && !Self::holder_frozen(&from, &portfolio)
+                && !Self::holder_frozen(&to, &portfolio)
+                && !Self::frozen_balance_exceeded(&from, did, value),
+            Error::<T>::InvalidTransfer
+        );
+
+        // Compute the value of `to` to issue, which must be exact.
+        let scaled = value
+            .checked_mul(&conversion.numerator.into())
+            .ok_or(Error::<T>::BalanceOverflow)?;
+        let denominator: T::Balance = conversion.denominator.into();
+        ensure!(
+            (scaled % denominator).is_zero(),
+            Error::<T>::InexactClassConversion
+        );
+        let issued = scaled / denominator;
+
+        // The caller must be allowed to receive `to`, as if it were issued to them.
+        Self::ensure_can_receive_issuance(&to, portfolio, issued)?;
+
+        // Redeem `from` and issue `to`, or neither.
+        // Conversions are not issuance in a funding round, so `ClassConverted` records them.
+        with_transaction(|| {
+            Self::redeem_from_default_portfolio(did, from, value)?;
+            Self::mint_to_default_portfolio(&to, did, issued, None)
+        })?;
+        Self::deposit_event(RawEvent::ClassConverted(did, from, to, value, issued));
+        Ok(())
+    }
+
+    /// Ensures that compliance rules and transfer managers of `ticker`
+    /// allow the holder of `to_portfolio` to receive `value` newly issued by the owner of `ticker`.
+    fn ensure_can_receive_issuance(
+        ticker: &Ticker,
+        to_portfolio: PortfolioId,
+        value: T::Balance,
+    ) -> DispatchResult {
+        let status_code =
+            T::ComplianceManager::verify_restriction(ticker, None, Some(to_portfolio.did), value)
+                .unwrap_or(COMPLIANCE_MANAGER_FAILURE);
+        ensure!(
+            status_code == ERC1400_TRANSFER_SUCCESS,
+            Error::<T>::InvalidTransfer
+        );
+
+        let issuer = PortfolioId::default_portfolio(Self::token_details(ticker).owner_did);
+        let (from, to, token) = Self::setup_statistics_failures(&issuer, &to_portfolio, ticker);
+        let total_supply = token
+            .total_supply
+            .checked_add(&value)
+            .ok_or(Error::<T>::TotalSupplyOverflow)?;
+        Statistics::<T>::verify_tm_restrictions(ticker, from, to, value, total_supply)
+            .map_err(|_| DispatchError::from(Error::<T>::InvalidTransfer))
+    }
+
+    /// Returns the parent of the family of `ticker`, which may be `ticker` itself, if any.
+    pub fn asset_family_of(ticker: Ticker) -> Option<Ticker> {
+        Self::asset_family_parent(ticker)
+            .or_else(|| AssetFamilyClasses::contains_key(ticker).then_some(ticker))
+    }
+
+    /// Returns `true` if `a` and `b` are in the same family.
+    pub fn same_asset_family(a: Ticker, b: Ticker) -> bool {
+        Self::asset_family_of(a).map_or(false, |family| Self::asset_family_of(b) == Some(family))
+    }
+
     pub fn unsafe_can_transfer_granular(
         from_custodian: Option<IdentityId>,
         from_portfolio: PortfolioId,
@@ -2123,6 +3443,13 @@ impl<T: Config> Module<T> {
             &value,
         );
         let asset_frozen = Self::frozen(ticker);
+        let sender_identity_frozen = Self::frozen_identity(ticker, from_portfolio.did);
+        let receiver_identity_frozen = Self::frozen_identity(ticker, to_portfolio.did);
+        let sender_portfolio_frozen = Self::frozen_portfolio(ticker, from_portfolio);
+        let receiver_portfolio_frozen = Self::frozen_portfolio(ticker, to_portfolio);
+        let sender_frozen_balance_exceeded =
+            Self::frozen_balance_exceeded(ticker, from_portfolio.did, value);
+        let asset_matured = Self::matured(ticker);
         let statistics_result =
             Self::statistics_failures_granular(&from_portfolio, &to_portfolio, ticker, value);
         let compliance_result = T::ComplianceManager::verify_restriction_granular(
@@ -2141,6 +3468,12 @@ impl<T: Config> Module<T> {
             sender_custodian_error,
             sender_insufficient_balance,
             asset_frozen,
+            sender_identity_frozen,
+            receiver_identity_frozen,
+            sender_portfolio_frozen,
+            receiver_portfolio_frozen,
+            sender_frozen_balance_exceeded,
+            asset_matured,
             result: !invalid_granularity
                 && !self_transfer
                 && !invalid_receiver_cdd
@@ -2151,6 +3484,12 @@ impl<T: Config> Module<T> {
                 && !sender_insufficient_balance
                 && portfolio_validity_result.result
                 && !asset_frozen
+                && !sender_identity_frozen
+                && !receiver_identity_frozen
+                && !sender_portfolio_frozen
+                && !receiver_portfolio_frozen
+                && !sender_frozen_balance_exceeded
+                && !asset_matured
                 && statistics_result.iter().all(|result| result.result)
                 && compliance_result.result,
             statistics_result,
@@ -2159,6 +3498,23 @@ impl<T: Config> Module<T> {
         }
     }
 
+    /// Returns `true` if transfers of `ticker` to and from `portfolio`, or its owner, are frozen.
+    fn holder_frozen(ticker: &Ticker, portfolio: &PortfolioId) -> bool {
+        Self::frozen_identity(ticker, portfolio.did) || Self::frozen_portfolio(ticker, portfolio)
+    }
+
+    /// Returns `true` if transferring `value` of `ticker` away from `did`
+    /// would leave less than its frozen balance.
+    fn frozen_balance_exceeded(ticker: &Ticker, did: IdentityId, value: T::Balance) -> bool {
+        let frozen = Self::frozen_balance(ticker, did);
+        frozen > Zero::zero() && Self::balance_of(ticker, did).saturating_sub(value) < frozen
+    }
+
+    /// Returns `true` if `ticker` has a maturity date which has been reached.
+    fn matured(ticker: &Ticker) -> bool {
+        Self::maturity(ticker).map_or(false, |at| at <= <Checkpoint<T>>::now_unix())
+    }
+
     fn invalid_granularity(ticker: &Ticker, value: T::Balance) -> bool {
         !Self::check_granularity(&ticker, value)
     }
@@ -2198,14 +3554,52 @@ impl<T: Config> Module<T> {
         .is_err()
     }
 
+    /// `portfolio_failure` without checking the free balance of `from_portfolio`.
+    fn netted_portfolio_failure(
+        from_portfolio: &PortfolioId,
+        to_portfolio: &PortfolioId,
+        ticker: &Ticker,
+        value: &T::Balance,
+    ) -> bool {
+        let validity = Portfolio::<T>::ensure_portfolio_transfer_validity_granular(
+            from_portfolio,
+            to_portfolio,
+            ticker,
+            value,
+        );
+        validity.receiver_is_same_portfolio
+            || validity.sender_portfolio_does_not_exist
+            || validity.receiver_portfolio_does_not_exist
+            || validity.receiver_policy_disallows_ticker
+            || validity.sender_policy_requires_affirmation
+    }
+
+    /// Returns `did` as a party of a transfer of `ticker`, with the aggregate balance of `scope_id`.
+    fn transfer_party(
+        ticker: &Ticker,
+        did: IdentityId,
+        scope_id: ScopeId,
+    ) -> TransferParty<T::Balance> {
+        TransferParty {
+            did,
+            scope_id,
+            balance: Self::aggregate_balance_of(ticker, &scope_id),
+        }
+    }
+
     fn setup_statistics_failures(
         from_portfolio: &PortfolioId,
         to_portfolio: &PortfolioId,
         ticker: &Ticker,
-    ) -> (ScopeId, ScopeId, SecurityToken<T::Balance>) {
+    ) -> (
+        TransferParty<T::Balance>,
+        TransferParty<T::Balance>,
+        SecurityToken<T::Balance>,
+    ) {
+        let party = |did| Self::transfer_party(ticker, did, Self::scope_id_of(ticker, &did));
         (
-            Self::scope_id_of(ticker, &from_portfolio.did),
-            Self::scope_id_of(ticker, &to_portfolio.did),
+            party(from_portfolio.did),
+            party(to_portfolio.did),
             <Tokens<T>>::get(ticker),
         )
     }
@@ -2216,18 +3610,10 @@ impl<T: Config> Module<T> {
         ticker: &Ticker,
         value: T::Balance,
     ) -> bool {
-        let (from_scope_id, to_scope_id, token) =
+        let (from, to, token) =
             Self::setup_statistics_failures(from_portfolio, to_portfolio, ticker);
-        Statistics::<T>::verify_tm_restrictions(
-            ticker,
-            from_scope_id,
-            to_scope_id,
-            value,
-            Self::aggregate_balance_of(ticker, &from_scope_id),
-            Self::aggregate_balance_of(ticker, &to_scope_id),
-            token.total_supply,
-        )
-        .is_err()
+        Statistics::<T>::verify_tm_restrictions(ticker, from, to, value, token.total_supply)
+            .is_err()
     }
 
     fn statistics_failures_granular(
@@ -2236,15 +3622,13 @@ impl<T: Config> Module<T> {
         ticker: &Ticker,
         value: T::Balance,
     ) -> Vec<TransferManagerResult> {
-        let (from_scope_id, to_scope_id, token) =
+        let (from, to, token) =
             Self::setup_statistics_failures(from_portfolio, to_portfolio, ticker);
         Statistics::<T>::verify_tm_restrictions_granular(
             ticker,
-            from_scope_id,
-            to_scope_id,
+            from,
+            to,
             value,
-            Self::aggregate_balance_of(ticker, &from_scope_id),
-            Self::aggregate_balance_of(ticker, &to_scope_id),
             token.total_supply,
         )
     }
@@ -34,12 +34,24 @@ pub mod did {
     pub const FIAT_TICKERS_RESERVATION_DID: &[u8; 32] = b"system:fiat_tickers_reservation\0";
 }
 
+/// Domain tags prefixed to payloads signed off-chain,
+/// so that a signature cannot be replayed for another purpose.
+pub mod signing_domain {
+    /// Tag of a `ClaimsBundle` signed by a key of its issuer.
+    pub const CLAIMS_BUNDLE: &[u8; 14] = b"CLAIMS_BUNDLE:";
+    /// Tag of a `ClaimAttestation` signed by a key of its issuer.
+    pub const CLAIM_ATTESTATION: &[u8; 18] = b"CLAIM_ATTESTATION:";
+}
+
 /// Priorities for the task that get scheduled.
 pub mod queue_priority {
     use frame_support::traits::schedule::Priority;
 
     /// Queue priority for the settlement instruction execution.
     pub const SETTLEMENT_INSTRUCTION_EXECUTION_PRIORITY: Priority = 100;
+    /// Queue priority for the settlement instruction expiry.
+    /// Lower than the execution priority, so an instruction due in the same block is executed first.
+    pub const SETTLEMENT_INSTRUCTION_EXPIRY_PRIORITY: Priority = 102;
     /// Queue priority for multisig action execution.
     pub const MULTISIG_PROPOSAL_EXECUTION_PRIORITY: Priority = 101;
 }
@@ -47,6 +59,7 @@ pub mod queue_priority {
 /// Prefixes for scheduled actions
 pub mod schedule_name_prefix {
     pub const SETTLEMENT_INSTRUCTION_EXECUTION: [u8; 27] = *b"SETTLEMENT_INSTRUCTION_EXEC";
+    pub const SETTLEMENT_INSTRUCTION_EXPIRY: [u8; 29] = *b"SETTLEMENT_INSTRUCTION_EXPIRY";
     pub const MULTISIG_PROPOSAL_EXECUTION: [u8; 22] = *b"MULTISIG_PROPOSAL_EXEC";
     pub const PIP_EXECUTION: [u8; 8] = *b"PIP_EXEC";
     pub const PIP_EXPIRY: [u8; 10] = *b"PIP_EXPIRY";
@@ -77,6 +90,8 @@ pub const PORTFOLIO_FAILURE: u8 = 0xa9;
 pub const CUSTODIAN_ERROR: u8 = 0xaa;
 pub const SCOPE_CLAIM_MISSING: u8 = 0xab;
 pub const TRANSFER_MANAGER_FAILURE: u8 = 0xac;
+pub const HOLDER_FROZEN: u8 = 0xad;
+pub const ASSET_MATURED: u8 = 0xae;
 
 // PIP pallet constants.
 pub const PIP_MAX_REPORTING_SIZE: usize = 1024;
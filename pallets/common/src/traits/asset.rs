@@ -20,7 +20,13 @@ use frame_support::decl_event;
 use frame_support::dispatch::DispatchResult;
 use frame_support::traits::{Currency, Get, UnixTime};
 use frame_support::weights::Weight;
-use polymesh_primitives::asset::{AssetName, AssetType, FundingRoundName};
+use polymesh_primitives::asset::{
+    AssetName, AssetType, ClassConversion, ForcedTransferReason, FundingRoundName,
+};
+use polymesh_primitives::asset_metadata::{
+    AssetMetadataGlobalKey, AssetMetadataKey, AssetMetadataLocalKey, AssetMetadataName,
+    AssetMetadataSpec, AssetMetadataValue, AssetMetadataValueDetail,
+};
 use polymesh_primitives::ethereum::EthereumAddress;
 use polymesh_primitives::migrate::MigrationError;
 use polymesh_primitives::{
@@ -49,6 +55,13 @@ pub trait AssetSubTrait<Balance> {
 
     /// Returns the `ScopeId` for a given `ticker` and `did`.
     fn scope_id_of(ticker: &Ticker, did: &IdentityId) -> ScopeId;
+
+    /// Returns `true` if transfers of `ticker` to and from `portfolio` are frozen.
+    fn is_portfolio_frozen(ticker: &Ticker, portfolio: &PortfolioId) -> bool;
+
+    /// Returns `true` if removing `value` of `ticker` from the balance of `did`
+    /// would leave less than its frozen balance.
+    fn is_frozen_balance_exceeded(ticker: &Ticker, did: IdentityId, value: Balance) -> bool;
 }
 
 pub trait AssetFnTrait<Balance, Account, Origin> {
@@ -91,8 +104,14 @@ pub trait WeightInfo {
     fn create_asset(n: u32, i: u32, f: u32) -> Weight;
     fn freeze() -> Weight;
     fn unfreeze() -> Weight;
+    fn freeze_identity() -> Weight;
+    fn unfreeze_identity() -> Weight;
+    fn freeze_portfolio() -> Weight;
+    fn unfreeze_portfolio() -> Weight;
+    fn set_frozen_balance() -> Weight;
     fn rename_asset(n: u32) -> Weight;
     fn issue() -> Weight;
+    fn issue_with_vesting() -> Weight;
     fn redeem() -> Weight;
     fn make_divisible() -> Weight;
     fn add_documents(d: u32) -> Weight;
@@ -106,6 +125,18 @@ pub trait WeightInfo {
     fn archive_extension() -> Weight;
     fn unarchive_extension() -> Weight;
     fn controller_transfer() -> Weight;
+    fn force_transfer() -> Weight;
+    fn execute_forced_transfer() -> Weight;
+    fn contest_forced_transfer() -> Weight;
+    fn cancel_forced_transfer() -> Weight;
+    fn register_asset_metadata_local_type(n: u32, s: u32) -> Weight;
+    fn register_asset_metadata_global_type(n: u32, s: u32) -> Weight;
+    fn set_asset_metadata(v: u32) -> Weight;
+    fn set_asset_metadata_details() -> Weight;
+    fn link_asset_class() -> Weight;
+    fn unlink_asset_class() -> Weight;
+    fn set_class_conversion() -> Weight;
+    fn convert_class() -> Weight;
 }
 
 /// The module's configuration trait.
@@ -201,6 +232,21 @@ decl_event! {
         /// An event emitted when an asset is unfrozen.
         /// Parameter: caller DID, ticker.
         AssetUnfrozen(IdentityId, Ticker),
+        /// An event emitted when transfers of an asset to and from an identity are frozen.
+        /// Parameters: caller DID, ticker, frozen DID.
+        IdentityFrozen(IdentityId, Ticker, IdentityId),
+        /// An event emitted when transfers of an asset to and from an identity are unfrozen.
+        /// Parameters: caller DID, ticker, unfrozen DID.
+        IdentityUnfrozen(IdentityId, Ticker, IdentityId),
+        /// An event emitted when transfers of an asset to and from a portfolio are frozen.
+        /// Parameters: caller DID, ticker, frozen portfolio.
+        PortfolioFrozen(IdentityId, Ticker, PortfolioId),
+        /// An event emitted when transfers of an asset to and from a portfolio are unfrozen.
+        /// Parameters: caller DID, ticker, unfrozen portfolio.
+        PortfolioUnfrozen(IdentityId, Ticker, PortfolioId),
+        /// An event emitted when the frozen balance of an identity in an asset is set.
+        /// Parameters: caller DID, ticker, DID, frozen balance.
+        FrozenBalanceSet(IdentityId, Ticker, IdentityId, Balance),
         /// An event emitted when a token is renamed.
         /// Parameters: caller DID, ticker, new token name.
         AssetRenamed(IdentityId, Ticker, AssetName),
@@ -230,5 +276,58 @@ decl_event! {
         /// Event for when a forced transfer takes place.
         /// caller DID/ controller DID, ticker, Portfolio of token holder, value.
         ControllerTransfer(IdentityId, Ticker, PortfolioId, Balance),
+        /// A forced transfer was scheduled, to be executed once its contest period is over.
+        /// caller DID, ticker, forced transfer ID, from portfolio, to portfolio, value,
+        /// reason, evidence document ID, executable at.
+        ForcedTransferScheduled(
+            IdentityId, Ticker, u64, PortfolioId, PortfolioId, Balance,
+            ForcedTransferReason, DocumentId, Moment
+        ),
+        /// A forced transfer took place.
+        /// caller DID, ticker, forced transfer ID, from portfolio, to portfolio, value,
+        /// reason, evidence document ID.
+        ForcedTransferExecuted(
+            IdentityId, Ticker, u64, PortfolioId, PortfolioId, Balance,
+            ForcedTransferReason, DocumentId
+        ),
+        /// The holder of the sending portfolio contested a scheduled forced transfer.
+        /// caller DID, ticker, forced transfer ID.
+        ForcedTransferContested(IdentityId, Ticker, u64),
+        /// A scheduled or contested forced transfer was cancelled.
+        /// caller DID, ticker, forced transfer ID.
+        ForcedTransferCancelled(IdentityId, Ticker, u64),
+        /// A metadata key was registered by governance for use by every asset.
+        /// metadata name, global key, specification.
+        RegisterAssetMetadataGlobalType(
+            AssetMetadataName, AssetMetadataGlobalKey, AssetMetadataSpec
+        ),
+        /// A metadata key was registered for an asset.
+        /// caller DID, ticker, metadata name, local key, specification.
+        RegisterAssetMetadataLocalType(
+            IdentityId, Ticker, AssetMetadataName, AssetMetadataLocalKey, AssetMetadataSpec
+        ),
+        /// A metadata value of an asset was set.
+        /// caller DID, ticker, key, value, details, version.
+        SetAssetMetadataValue(
+            IdentityId, Ticker, AssetMetadataKey, AssetMetadataValue,
+            AssetMetadataValueDetail<Moment>, u32
+        ),
+        /// The expiry or lock status of a metadata value of an asset was changed.
+        /// caller DID, ticker, key, details.
+        SetAssetMetadataValueDetails(
+            IdentityId, Ticker, AssetMetadataKey, AssetMetadataValueDetail<Moment>
+        ),
+        /// An asset was linked as a class to the family of a parent asset.
+        /// caller DID, parent ticker, class ticker.
+        AssetClassLinked(IdentityId, Ticker, Ticker),
+        /// An asset was unlinked from the family of its parent asset.
+        /// caller DID, parent ticker, class ticker.
+        AssetClassUnlinked(IdentityId, Ticker, Ticker),
+        /// The conversion of one class of an asset family into another was set or removed.
+        /// caller DID, class converted from, class converted into, conversion terms.
+        ClassConversionSet(IdentityId, Ticker, Ticker, Option<ClassConversion>),
+        /// A holder converted shares of one class of an asset family into another.
+        /// caller DID, class converted from, class converted into, value redeemed, value issued.
+        ClassConverted(IdentityId, Ticker, Ticker, Balance, Balance),
     }
 }
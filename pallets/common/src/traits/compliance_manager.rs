@@ -30,6 +30,10 @@ pub trait Config<Balance> {
         from_did_opt: Option<IdentityId>,
         to_did_opt: Option<IdentityId>,
     ) -> AssetComplianceResult;
+
+    #[cfg(feature = "runtime-benchmarks")]
+    /// Adds a compliance requirement without conditions to `ticker`, allowing all transfers.
+    fn bench_allow_all_transfers(ticker: Ticker);
 }
 
 pub trait WeightInfo {
@@ -33,8 +33,9 @@ use frame_support::{
     Parameter,
 };
 use polymesh_primitives::{
-    secondary_key::api::SecondaryKey, AuthorizationData, DispatchableName, IdentityClaim,
-    IdentityId, InvestorUid, PalletName, Permissions, Signatory, Ticker,
+    secondary_key::api::SecondaryKey, AuthorizationData, Claim, ClaimIssuerProfile,
+    DispatchableName, IdentityClaim, IdentityId, InvestorUid, PalletName, Permissions, Signatory,
+    Ticker,
 };
 use sp_core::H512;
 use sp_runtime::traits::{Dispatchable, IdentifyAccount, Member, Verify};
@@ -75,6 +76,20 @@ pub struct SecondaryKeyWithAuth<AccountId> {
     pub auth_signature: H512,
 }
 
+/// A batch of claims signed off-chain by a key of `issuer`,
+/// so that a relayer can submit it on behalf of the issuer.
+#[derive(codec::Encode, codec::Decode, Clone, PartialEq, Eq, Debug)]
+pub struct ClaimsBundle<Moment> {
+    /// Identity issuing the claims.
+    pub issuer: IdentityId,
+    /// Claims to add, as `(target, claim, expiry)`.
+    pub claims: Vec<(IdentityId, Claim, Option<Moment>)>,
+    /// It HAS TO be `issuer` claims bundle nonce: See `Identity::claims_bundle_nonce`
+    pub nonce: AuthorizationNonce,
+    /// The bundle cannot be submitted from this moment on.
+    pub expires_at: Moment,
+}
+
 pub trait WeightInfo {
     fn cdd_register_did(i: u32) -> Weight;
     fn invalidate_cdd_claims() -> Weight;
@@ -96,6 +111,14 @@ pub trait WeightInfo {
     fn add_investor_uniqueness_claim() -> Weight;
     fn add_investor_uniqueness_claim_v2() -> Weight;
     fn revoke_claim_by_index() -> Weight;
+    fn set_claim_issuer_profile(t: u32, j: u32) -> Weight;
+    fn remove_claim_issuer_profile() -> Weight;
+    fn suspend_claim_issuer() -> Weight;
+    fn resume_claim_issuer() -> Weight;
+    fn revoke_issued_claims(c: u32) -> Weight;
+    fn add_claims_batch(c: u32) -> Weight;
+    fn revoke_claims_batch(c: u32) -> Weight;
+    fn add_claims_batch_signed(c: u32) -> Weight;
 }
 
 /// The module's configuration trait.
@@ -224,6 +247,23 @@ decl_event!(
 
         /// Forwarded Call - (calling DID, target DID, pallet name, function name)
         ForwardedCall(IdentityId, IdentityId, PalletName, DispatchableName),
+
+        /// A claim issuer published or updated its profile. (issuer DID, profile)
+        ClaimIssuerProfileSet(IdentityId, ClaimIssuerProfile),
+
+        /// A claim issuer removed its profile. (issuer DID)
+        ClaimIssuerProfileRemoved(IdentityId),
+
+        /// A claim issuer was suspended network-wide. (caller DID, issuer DID)
+        ClaimIssuerSuspended(IdentityId, IdentityId),
+
+        /// A suspended claim issuer was resumed. (caller DID, issuer DID)
+        ClaimIssuerResumed(IdentityId, IdentityId),
+
+        /// A claim issuer revoked claims it issued in bulk.
+        /// Each revoked claim is also reported by a `ClaimRevoked` event.
+        /// (issuer DID, number of revoked claims, whether issued claims remain)
+        IssuedClaimsRevoked(IdentityId, u32, bool),
     }
 );
 
@@ -26,7 +26,8 @@ use frame_support::decl_event;
 use frame_support::dispatch::DispatchResult;
 use frame_support::weights::Weight;
 use polymesh_primitives::{
-    IdentityId, PortfolioId, PortfolioName, PortfolioNumber, SecondaryKey, Ticker,
+    IdentityId, PortfolioId, PortfolioName, PortfolioNumber, PortfolioPolicy, SecondaryKey, Ticker,
+    VestingSchedule,
 };
 use sp_std::vec::Vec;
 
@@ -77,6 +78,8 @@ pub trait WeightInfo {
     fn rename_portfolio(i: u32) -> Weight;
     fn quit_portfolio_custody() -> Weight;
     fn accept_portfolio_custody() -> Weight;
+    fn set_portfolio_policy(t: u32) -> Weight;
+    fn rebalance_portfolios(m: u32) -> Weight;
 }
 
 pub trait Config: CommonConfig + identity::Config + base::Config {
@@ -137,5 +140,20 @@ decl_event! {
         /// * portfolio id
         /// * portfolio custodian did
         PortfolioCustodianChanged(IdentityId, PortfolioId, IdentityId),
+        /// A vesting schedule has been attached to the balance of an asset in a portfolio.
+        ///
+        /// # Parameters
+        /// * DID that created the schedule
+        /// * portfolio id
+        /// * asset ticker
+        /// * vesting schedule
+        VestingScheduleAdded(IdentityId, PortfolioId, Ticker, VestingSchedule<Balance>),
+        /// The custodian of a portfolio has set its policy.
+        ///
+        /// # Parameters
+        /// * custodian DID
+        /// * portfolio id
+        /// * portfolio policy
+        PortfolioPolicySet(IdentityId, PortfolioId, PortfolioPolicy<Balance>),
     }
 }
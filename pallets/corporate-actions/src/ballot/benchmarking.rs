@@ -18,7 +18,7 @@ use crate::benchmarking::{set_ca_targets, setup_ca};
 use core::iter;
 use frame_benchmarking::benchmarks;
 use polymesh_common_utilities::{
-    benchs::{AccountIdOf, User},
+    benchs::{make_asset, AccountIdOf, User},
     TestUtilsFn,
 };
 
@@ -118,6 +118,22 @@ benchmarks! {
         assert!(!RCV::get(ca_id), "RCV not changed");
     }
 
+    set_class_weights {
+        let c in 1..(MAX_ASSET_FAMILY_CLASSES as u32 + 1);
+
+        // Weight the CA's asset and `c - 1` classes linked to its family.
+        let (owner, ca_id) = attach::<T>(0, 0);
+        let mut weights = vec![(ca_id.ticker, 1)];
+        for i in 1..c {
+            let class = make_asset::<T>(&owner, Some(&[b'C', b'A' + i as u8]));
+            <Asset<T>>::link_asset_class(owner.origin().into(), ca_id.ticker, class).unwrap();
+            weights.push((class, 10));
+        }
+    }: _(owner.origin(), ca_id, weights)
+    verify {
+        assert_eq!(ClassWeights::get(ca_id).len() as u32, c, "class weights not set");
+    }
+
     remove_ballot {
         let (owner, ca_id) = attach::<T>(0, 0);
     }: _(owner.origin(), ca_id)
@@ -31,6 +31,11 @@
 //! The record date is then translated into a checkpoint,
 //! and the holder's balance at that point is used as the available power.
 //!
+//! When the CA's asset belongs to a family of share classes,
+//! the CAA may instead weight voting power by class through `set_class_weights`.
+//! The available power is then the sum of the holder's balance of each weighted class
+//! at the record date, times the votes per share of that class.
+//!
 //! Eventually, the voting duration will be over.
 //! The interpretation of the vote results can then be interpreted off-chain,
 //! depending on the exact by-laws of the corporation.
@@ -72,6 +77,7 @@
 //! - `change_end(origin, ca_id, end)` changes the end date of the ballot for CA with `ca_id`.
 //! - `change_meta(origin, ca_id, meta)` changes the motions of the ballot for CA with `ca_id`.
 //! - `change_rcv(origin, ca_id, rcv)` changes the support for RCV to `rcv` in the ballot for CA with `ca_id`.
+//! - `set_class_weights(origin, ca_id, weights)` weights voting power by class in the ballot for CA with `ca_id`.
 //! - `remove_ballot(origin, ca_id)` removes the ballot for CA with `ca_id`.
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -89,19 +95,21 @@ use frame_support::{
     traits::Get,
     weights::Weight,
 };
-use pallet_asset::checkpoint;
+use pallet_asset::checkpoint::{self, ScheduleRefCount};
+use pallet_asset::MAX_ASSET_FAMILY_CLASSES;
 use pallet_base::ensure_string_limited;
 use pallet_identity as identity;
 use polymesh_common_utilities::protocol_fee::{ChargeProtocolFee, ProtocolOp};
-use polymesh_common_utilities::CommonConfig;
-use polymesh_primitives::{EventDid, IdentityId, Moment};
+use polymesh_common_utilities::{traits::checkpoint::ScheduleId, with_transaction, CommonConfig};
+use polymesh_primitives::{EventDid, IdentityId, Moment, Ticker};
 use polymesh_primitives_derive::VecU8StrongTyped;
-use sp_runtime::traits::{CheckedAdd, Zero};
+use sp_runtime::traits::{CheckedAdd, Saturating, Zero};
 #[cfg(feature = "std")]
 use sp_runtime::{Deserialize, Serialize};
 use sp_std::prelude::*;
 
 type Identity<T> = identity::Module<T>;
+type Asset<T> = pallet_asset::Module<T>;
 type Checkpoint<T> = checkpoint::Module<T>;
 type CA<T> = ca::Module<T>;
 type ExternalAgents<T> = pallet_external_agents::Module<T>;
@@ -231,6 +239,19 @@ pub struct BallotVote<Balance> {
     pub fallback: Option<u16>,
 }
 
+/// The voting power per share of a class in the family of a ballot's asset.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub struct ClassWeight {
+    /// The ticker of the class.
+    pub ticker: Ticker,
+    /// The votes per share of the class.
+    pub weight: u32,
+    /// The schedule snapshotting balances of the class at the record date,
+    /// or `None` for the CA's asset, which uses the CA's own record date checkpoint.
+    pub schedule: Option<ScheduleId>,
+}
+
 /// Weight abstraction for the corporate actions module.
 pub trait WeightInfo {
     fn attach_ballot(num_choices: u32) -> Weight;
@@ -238,6 +259,7 @@ pub trait WeightInfo {
     fn change_end() -> Weight;
     fn change_meta(num_choices: u32) -> Weight;
     fn change_rcv() -> Weight;
+    fn set_class_weights(classes: u32) -> Weight;
     fn remove_ballot() -> Weight;
 }
 
@@ -289,6 +311,12 @@ decl_storage! {
         pub Votes get(fn votes):
             double_map hasher(blake2_128_concat) CAId, hasher(blake2_128_concat) IdentityId =>
                 Vec<BallotVote<T::Balance>>;
+
+        /// The votes per share of each class weighted in a ballot, if any.
+        /// When empty, each share of the CA's asset counts as one vote.
+        ///
+        /// (CAId) => [class weight]
+        pub ClassWeights get(fn class_weights): map hasher(blake2_128_concat) CAId => Vec<ClassWeight>;
     }
 }
 
@@ -427,10 +455,9 @@ decl_module! {
                     .ok_or(Error::<T>::RCVNotAllowed)?;
             }
 
-            // Extract `did`'s balance at the record date.
+            // Extract `did`'s voting power at the record date.
             // Record date has passed by definition.
-            let cp_id = <CA<T>>::record_date_cp(&ca, ca_id);
-            let available_power = <CA<T>>::balance_at_cp(did, ca_id, cp_id);
+            let available_power = Self::voting_power(&ca, ca_id, did);
 
             // Ensure the total balance used in each motion doesn't exceed caller's voting power.
             motions
@@ -538,6 +565,78 @@ decl_module! {
             Self::deposit_event(Event::<T>::RCVChanged(caa, ca_id, rcv));
         }
 
+        /// Weight voting power by class in the ballot of the CA identified by `ca_id`.
+        ///
+        /// A holder's voting power becomes the sum, over each class in `weights`,
+        /// of their balance of the class at the record date, times the votes per share of the class.
+        /// Balances of classes other than the CA's asset are snapshotted
+        /// by a checkpoint scheduled at the record date.
+        /// Setting no weights restores one vote per share of the CA's asset.
+        ///
+        /// Changing the record date of the CA removes the weights,
+        /// as the snapshots would no longer match it.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be a signer for a CAA of `ca_id` and of each class weighted.
+        /// - `ca_id` identifies the attached ballot's CA.
+        /// - `weights` specifies the votes per share of each class,
+        ///    either the CA's asset or a class in its family.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker` or some class.
+        /// - `NoSuchBallot` if `ca_id` does not identify a ballot.
+        /// - `VotingAlreadyStarted` if `start >= now`, where `now` is the current time.
+        /// - `RecordDatePassed` if the CA's record date is not in the future.
+        /// - `TooManyClasses` if more classes are weighted than a family can have.
+        /// - `DuplicateClass` if some class is weighted more than once.
+        /// - `ClassNotInFamily` if some class is not in the family of the CA's asset.
+        /// - Other errors can occur when scheduling checkpoints.
+        #[weight = <T as Config>::BallotWeightInfo::set_class_weights(weights.len() as u32)]
+        pub fn set_class_weights(origin, ca_id: CAId, weights: Vec<(Ticker, u32)>) {
+            // Ensure origin is CAA, a ballot exists, start is in the future.
+            let caa = <ExternalAgents<T>>::ensure_perms(origin.clone(), ca_id.ticker)?;
+            Self::ensure_ballot_not_started(Self::ensure_ballot_exists(ca_id)?)?;
+
+            // Ensure the record date is in the future, so that classes can be snapshotted at it.
+            let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
+            let date = ca.record_date.ok_or(ca::Error::<T>::NoRecordDate)?.date;
+            ensure!(<Checkpoint<T>>::now_unix() < date, Error::<T>::RecordDatePassed);
+
+            // Ensure each class is weighted once, and is in the family, with origin as its agent.
+            ensure!(weights.len() <= MAX_ASSET_FAMILY_CLASSES + 1, Error::<T>::TooManyClasses);
+            for (idx, (class, _)) in weights.iter().enumerate() {
+                ensure!(weights[..idx].iter().all(|(c, _)| c != class), Error::<T>::DuplicateClass);
+                if *class != ca_id.ticker {
+                    ensure!(
+                        <Asset<T>>::same_asset_family(ca_id.ticker, *class),
+                        Error::<T>::ClassNotInFamily
+                    );
+                    <ExternalAgents<T>>::ensure_perms(origin.clone(), *class)?;
+                }
+            }
+
+            // Replace any previous weights, scheduling a checkpoint at the record date for other classes.
+            let weights = with_transaction(|| {
+                Self::remove_class_weights(ca_id);
+                weights
+                    .into_iter()
+                    .map(|(ticker, weight)| -> Result<_, DispatchError> {
+                        let schedule = (ticker != ca_id.ticker)
+                            .then(|| <Checkpoint<T>>::create_schedule_base(caa.for_event(), ticker, date.into(), 1))
+                            .transpose()?
+                            .map(|schedule| schedule.id);
+                        Ok(ClassWeight { ticker, weight, schedule })
+                    })
+                    .collect::<Result<Vec<_>, DispatchError>>()
+            })?;
+
+            // Commit to storage + emit event.
+            if !weights.is_empty() {
+                ClassWeights::insert(ca_id, weights.clone());
+            }
+            Self::deposit_event(Event::<T>::ClassWeightsSet(caa, ca_id, weights));
+        }
+
         /// Remove the ballot of the CA identified by `ca_id`.
         ///
         /// ## Arguments
@@ -587,6 +686,11 @@ decl_event! {
         /// (Ticker's CAA, CA's ID, New support)
         RCVChanged(IdentityId, CAId, bool),
 
+        /// A corporate ballot changed its voting power weights by class.
+        ///
+        /// (Ticker's CAA, CA's ID, New class weights)
+        ClassWeightsSet(IdentityId, CAId, Vec<ClassWeight>),
+
         /// A corporate ballot was removed.
         ///
         /// (Ticker's CAA, CA's ID)
@@ -623,7 +727,15 @@ decl_error! {
         /// The RCV fallback points to the origin choice.
         RCVSelfCycle,
         /// RCV is not allowed for this ballot.
-        RCVNotAllowed
+        RCVNotAllowed,
+        /// The record date has passed, so classes can no longer be snapshotted at it.
+        RecordDatePassed,
+        /// More classes were weighted than a family can have.
+        TooManyClasses,
+        /// A class was weighted more than once.
+        DuplicateClass,
+        /// A weighted class is not in the family of the CA's asset.
+        ClassNotInFamily,
     }
 }
 
@@ -641,12 +753,50 @@ impl<T: Config> Module<T> {
         Metas::remove(ca_id);
         MotionNumChoices::remove(ca_id);
         RCV::remove(ca_id);
+        Self::remove_class_weights(ca_id);
 
         // Emit event.
         Self::deposit_event(Event::<T>::Removed(caa, ca_id));
         Ok(())
     }
 
+    /// Remove the class weights of the ballot of `ca_id`, if any,
+    /// releasing the schedules snapshotting other classes.
+    crate fn remove_class_weights(ca_id: CAId) {
+        for ClassWeight {
+            ticker, schedule, ..
+        } in ClassWeights::take(ca_id)
+        {
+            if let Some(id) = schedule {
+                ScheduleRefCount::mutate(ticker, id, |c| *c = c.saturating_sub(1));
+            }
+        }
+    }
+
+    /// Returns the voting power of `did` in the ballot of `ca_id`,
+    /// i.e., their balance at the record date, weighted by class if so set.
+    fn voting_power(ca: &CorporateAction, ca_id: CAId, did: IdentityId) -> T::Balance {
+        let cp_id = <CA<T>>::record_date_cp(ca, ca_id);
+        let weights = ClassWeights::get(ca_id);
+        if weights.is_empty() {
+            return <CA<T>>::balance_at_cp(did, ca_id, cp_id);
+        }
+        weights
+            .into_iter()
+            .fold(Zero::zero(), |power: T::Balance, class| {
+                let balance = match class.schedule {
+                    // As in `balance_at_cp`, if no checkpoint was taken yet,
+                    // there were no balance changes since the record date.
+                    Some(id) => match <Checkpoint<T>>::schedule_points(class.ticker, id).first() {
+                        Some(&cp) => <Asset<T>>::get_balance_at(class.ticker, did, cp),
+                        None => <Asset<T>>::balance_of(class.ticker, did),
+                    },
+                    None => <CA<T>>::balance_at_cp(did, ca_id, cp_id),
+                };
+                power.saturating_add(balance.saturating_mul(class.weight.into()))
+            })
+    }
+
     /// Ensure that no string embedded within `meta` is too long.
     fn ensure_meta_lengths_limited(meta: &BallotMeta) -> DispatchResult {
         ensure_string_limited::<T>(&meta.title)?;
@@ -162,4 +162,30 @@ benchmarks! {
     verify {
         assert!(<Distributions<T>>::get(ca_id).is_none(), "not removed");
     }
+
+    set_scrip_option {
+        let (owner, ca_id, currency) = dist::<T>(0);
+        let scrip = Some(ScripOption {
+            reference_price: 1_000_000u32.into(),
+            election_ends_at: 3000,
+        });
+    }: _(owner.origin(), ca_id, scrip)
+    verify {
+        assert!(<ScripOptions<T>>::get(ca_id) == scrip, "scrip option not set");
+    }
+
+    elect_scrip {
+        let t in 0..MAX_TARGETS;
+
+        let (owner, ca_id, currency) = dist::<T>(t);
+        let scrip = ScripOption {
+            reference_price: 1_000_000u32.into(),
+            election_ends_at: 3000,
+        };
+        <ScripOptions<T>>::insert(ca_id, scrip);
+        let holder = user::<T>("holder", SEED);
+    }: _(holder.origin(), ca_id, true)
+    verify {
+        assert!(ScripElections::get(ca_id, holder.did()), "not elected");
+    }
 }
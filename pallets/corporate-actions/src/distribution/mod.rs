@@ -37,6 +37,15 @@
 //! Before `payment_at` is due, however,
 //! a planned distribution can be cancelled by calling `remove_distribution`.
 //!
+//! A planned distribution can also offer a scrip option through `set_scrip_option`,
+//! letting holders elect, via `elect_scrip` and before the election ends,
+//! to receive newly issued shares of the CA's asset instead of `currency`.
+//! Electing holders are issued their post-tax benefit divided by the reference price of a share,
+//! with any fraction of a share that cannot be issued paid in `currency`.
+//!
+//! A distribution attached to the redemption CA of a bond (see the fixed income module)
+//! pays par, and also redeems the shares of each holder paid.
+//!
 //! ## Overview
 //!
 //! The module provides functions for:
@@ -50,6 +59,7 @@
 //! - **Currency:** The ticker being distributed to holders as a benefit, e.g., USDC or some such.
 //! - **Payment-at date:** The date at which benefits may be claimed by or pushed to holders.
 //! - **Expires-at date:** The date at which benefits are forfeit, and may be reclaimed by the CAA.
+//! - **Scrip option:** The option for holders to reinvest their benefit in shares of the CA's asset.
 //!
 //! ## Interface
 //!
@@ -60,12 +70,14 @@
 //! - `push_benefit` pushes a benefit of an active capital distribution to a holder.
 //! - `reclaim` reclaims forfeited benefits of a capital distribution that has expired.
 //! - `remove_distribution` removes a capital distribution which hasn't reached its payment date yet.
+//! - `set_scrip_option` sets or removes the scrip option of a capital distribution which hasn't started yet.
+//! - `elect_scrip` elects, or not, to receive the benefit of a capital distribution in shares.
 
 #[cfg(feature = "runtime-benchmarks")]
 pub mod benchmarking;
 
 use crate as ca;
-use ca::{CAId, Config, Tax};
+use ca::{fixed_income::MAX_REDEEMED_PORTFOLIOS, CAId, Config, Tax};
 use codec::{Decode, Encode};
 use frame_support::{
     decl_error, decl_event, decl_module, decl_storage,
@@ -73,10 +85,12 @@ use frame_support::{
     ensure,
     traits::Get,
     weights::Weight,
+    IterableStorageMap,
 };
 use pallet_asset::{self as asset, checkpoint};
 use pallet_identity::{self as identity, PermissionedCallOriginData};
 use polymesh_common_utilities::{
+    constants::currency::ONE_UNIT,
     portfolio::PortfolioSubTrait,
     protocol_fee::{ChargeProtocolFee, ProtocolOp},
     with_transaction, CommonConfig,
@@ -94,6 +108,7 @@ type Asset<T> = asset::Module<T>;
 type Checkpoint<T> = checkpoint::Module<T>;
 type ExternalAgents<T> = pallet_external_agents::Module<T>;
 type CA<T> = ca::Module<T>;
+type FixedIncome<T> = ca::fixed_income::Module<T>;
 type Identity<T> = identity::Module<T>;
 type Portfolio<T> = pallet_portfolio::Module<T>;
 
@@ -125,6 +140,18 @@ pub struct Distribution<Balance> {
     pub expires_at: Option<Moment>,
 }
 
+/// The scrip option of a capital distribution,
+/// letting holders receive newly issued shares of the CA's asset instead of `currency`.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub struct ScripOption<Balance> {
+    /// Price of one share of the CA's asset, in per-million of `currency`,
+    /// i.e. `1 / 10^6`th of one `currency` token.
+    pub reference_price: Balance,
+    /// Until when holders may elect to receive shares; no later than `payment_at`.
+    pub election_ends_at: Moment,
+}
+
 /// Has the distribution expired?
 fn expired(expiry: Option<Moment>, now: Moment) -> bool {
     expiry.filter(|&e| e <= now).is_some()
@@ -137,6 +164,8 @@ pub trait WeightInfo {
     fn push_benefit(target_ids: u32, did_whts: u32) -> Weight;
     fn reclaim() -> Weight;
     fn remove_distribution() -> Weight;
+    fn set_scrip_option() -> Weight;
+    fn elect_scrip(target_ids: u32) -> Weight;
 }
 
 decl_storage! {
@@ -151,6 +180,16 @@ decl_storage! {
         /// (CAId, DID) -> Was DID paid in the CAId?
         HolderPaid get(fn holder_paid): map hasher(blake2_128_concat) (CAId, IdentityId) => bool;
 
+        /// Scrip options of capital distributions.
+        ///
+        /// (CAId) => ScripOption
+        ScripOptions get(fn scrip_option): map hasher(blake2_128_concat) CAId => Option<ScripOption<T::Balance>>;
+
+        /// Has an asset holder elected to receive their benefit in shares?
+        ///
+        /// (CAId) -> (DID) -> Did DID elect scrip in the CAId?
+        ScripElections get(fn scrip_elected): double_map hasher(blake2_128_concat) CAId, hasher(identity) IdentityId => bool;
+
         /// Storage version.
         StorageVersion get(fn storage_version) build(|_| Version::new(1).unwrap()): Version;
     }
@@ -221,15 +260,6 @@ decl_module! {
             payment_at: Moment,
             expires_at: Option<Moment>,
         ) {
-            // Ensure CA's asset is distinct from the distributed currency.
-            ensure!(ca_id.ticker != currency, Error::<T>::DistributingAsset);
-
-            // Ensure that any expiry date doesn't come before the payment date.
-            ensure!(!expired(expires_at, payment_at), Error::<T>::ExpiryBeforePayment);
-
-            // Ensure CA doesn't have a distribution yet.
-            ensure!(!<Distributions<T>>::contains_key(ca_id), Error::<T>::AlreadyExists);
-
             // Ensure origin is CAA and that they have custody over `from`.
             // Also ensure secondary key has perms for `from` + portfolio is valid.
             let PermissionedCallOriginData {
@@ -241,41 +271,16 @@ decl_module! {
             <Portfolio<T>>::ensure_portfolio_custody_and_permission(from, caa, secondary_key.as_ref())?;
             <Portfolio<T>>::ensure_portfolio_validity(&from)?;
 
-            // Ensure that `ca_id` exists, that its a benefit.
-            let caa = caa.for_event();
-            let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
-            ensure!(ca.kind.is_benefit(), Error::<T>::CANotBenefit);
-
-            // Ensure CA has a record `date <= payment_at`.
-            // If we cannot, deriving a checkpoint,
-            // used to determine each holder's allotment of the total `amount`,
-            // is not possible.
-            <CA<T>>::ensure_record_date_before_start(&ca, payment_at)?;
-
-            // Ensure `from` has at least `amount` to later lock (1).
-            <Portfolio<T>>::ensure_sufficient_balance(&from, &currency, &amount)?;
-
-            // Charge the protocol fee. Last check; we are in commit phase after this.
-            T::ProtocolFee::charge_fee(ProtocolOp::DistributionDistribute)?;
-
-            // (1) Lock `amount` in `from`.
-            <Portfolio<T>>::unchecked_lock_tokens(&from, &currency, &amount);
-
-            // Commit to storage.
-            let distribution = Distribution {
+            Self::distribute_base(
+                caa.for_event(),
+                ca_id,
                 from,
                 currency,
                 per_share,
                 amount,
-                remaining: amount,
-                reclaimed: false,
                 payment_at,
                 expires_at,
-            };
-            <Distributions<T>>::insert(ca_id, distribution);
-
-            // Emit event.
-            Self::deposit_event(Event::<T>::Created(caa, ca_id, distribution));
+            )?;
         }
 
         /// Claim a benefit of the capital distribution attached to `ca_id`.
@@ -299,7 +304,8 @@ decl_module! {
         /// - `BalanceAmountProductOverflowed` if `ba = balance * amount` would overflow.
         /// - `BalanceAmountProductSupplyDivisionFailed` if `ba * supply` would overflow.
         /// - Other errors can occur if the compliance manager rejects the transfer.
-        #[weight = <T as Config>::DistWeightInfo::claim(T::MaxTargetIds::get(), T::MaxDidWhts::get())]
+        #[weight = <T as Config>::DistWeightInfo::claim(T::MaxTargetIds::get(), T::MaxDidWhts::get())
+            .saturating_add(<T as Config>::FixedIncomeWeightInfo::redeem_holder(MAX_REDEEMED_PORTFOLIOS))]
         pub fn claim(origin, ca_id: CAId) {
             let did = <Identity<T>>::ensure_perms(origin)?;
             Self::transfer_benefit(did.for_event(), did, ca_id)?;
@@ -327,7 +333,8 @@ decl_module! {
         /// - `BalanceAmountProductOverflowed` if `ba = balance * amount` would overflow.
         /// - `BalanceAmountProductSupplyDivisionFailed` if `ba * supply` would overflow.
         /// - Other errors can occur if the compliance manager rejects the transfer.
-        #[weight = <T as Config>::DistWeightInfo::push_benefit(T::MaxTargetIds::get(), T::MaxDidWhts::get())]
+        #[weight = <T as Config>::DistWeightInfo::push_benefit(T::MaxTargetIds::get(), T::MaxDidWhts::get())
+            .saturating_add(<T as Config>::FixedIncomeWeightInfo::redeem_holder(MAX_REDEEMED_PORTFOLIOS))]
         pub fn push_benefit(origin, ca_id: CAId, holder: IdentityId) {
             let agent = <ExternalAgents<T>>::ensure_perms(origin, ca_id.ticker)?.for_event();
             Self::transfer_benefit(agent, holder, ca_id)?;
@@ -386,6 +393,71 @@ decl_module! {
             let dist = Self::ensure_distribution_exists(ca_id)?;
             Self::remove_distribution_base(caa, ca_id, &dist)?;
         }
+
+        /// Set, or remove if `None`, the scrip option of a distribution that hasn't started yet.
+        ///
+        /// Elections made under a previous scrip option are kept.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be a signer for a CAA of `ca_id`.
+        /// - `ca_id` identifies the CA with a not-yet-started capital distribution.
+        /// - `scrip` is the new scrip option, if any.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `NoSuchDistribution` if there's no capital distribution for `ca_id`.
+        /// - `DistributionStarted` if `payment_at <= now`.
+        /// - `InvalidScripOption` if the reference price is zero
+        ///    or the election ends after `payment_at`.
+        /// - `ScripOnRedemption` if `ca_id` redeems a bond at par.
+        #[weight = <T as Config>::DistWeightInfo::set_scrip_option()]
+        pub fn set_scrip_option(origin, ca_id: CAId, scrip: Option<ScripOption<T::Balance>>) {
+            let caa = <ExternalAgents<T>>::ensure_perms(origin, ca_id.ticker)?.for_event();
+            let dist = Self::ensure_distribution_exists(ca_id)?;
+            Self::ensure_distribution_not_started(&dist)?;
+            if let Some(scrip) = scrip {
+                ensure!(!<FixedIncome<T>>::is_redemption(ca_id), Error::<T>::ScripOnRedemption);
+                ensure!(
+                    scrip.reference_price > 0u32.into() && scrip.election_ends_at <= dist.payment_at,
+                    Error::<T>::InvalidScripOption
+                );
+            }
+
+            <ScripOptions<T>>::mutate(ca_id, |s| *s = scrip);
+            Self::deposit_event(Event::<T>::ScripOptionSet(caa, ca_id, scrip));
+        }
+
+        /// Elect, or not, to receive the benefit of the distribution attached to `ca_id`
+        /// in newly issued shares of the CA's asset, at the reference price of its scrip option.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be a holder targeted by `ca_id`.
+        /// - `ca_id` identifies the CA with a capital distribution offering a scrip option.
+        /// - `scrip` is whether to receive shares instead of `currency`.
+        ///
+        /// # Errors
+        /// - `NoScripOption` if the distribution attached to `ca_id` has no scrip option.
+        /// - `ScripElectionEnded` if `election_ends_at <= now`.
+        /// - `NoSuchCA` if `ca_id` does not identify an existing CA.
+        /// - `NotTargetedByCA` if the CA does not target `origin`'s DID.
+        #[weight = <T as Config>::DistWeightInfo::elect_scrip(T::MaxTargetIds::get())]
+        pub fn elect_scrip(origin, ca_id: CAId, scrip: bool) {
+            let did = <Identity<T>>::ensure_perms(origin)?;
+            let option = Self::scrip_option(ca_id).ok_or(Error::<T>::NoScripOption)?;
+            ensure!(
+                <Checkpoint<T>>::now_unix() < option.election_ends_at,
+                Error::<T>::ScripElectionEnded
+            );
+            let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
+            <CA<T>>::ensure_ca_targets(&ca, &did)?;
+
+            if scrip {
+                ScripElections::insert(ca_id, did, true);
+            } else {
+                ScripElections::remove(ca_id, did);
+            }
+            Self::deposit_event(Event::<T>::ScripElected(did.for_event(), ca_id, scrip));
+        }
     }
 }
 
@@ -414,6 +486,21 @@ decl_event! {
         ///
         /// (Ticker's CAA, CA's ID)
         Removed(EventDid, CAId),
+
+        /// The scrip option of a capital distribution was set or removed.
+        ///
+        /// (Ticker's CAA, CA's ID, new scrip option)
+        ScripOptionSet(EventDid, CAId, Option<ScripOption<Balance>>),
+
+        /// A token holder elected, or not, to receive their benefit in shares.
+        ///
+        /// (Holder DID, CA's ID, elected scrip?)
+        ScripElected(EventDid, CAId, bool),
+
+        /// A token holder's post-tax benefit was paid in newly issued shares.
+        ///
+        /// (Caller DID, Holder/Claimant DID, CA's ID, shares issued, `currency` paid for fractions of a share)
+        ScripIssued(EventDid, EventDid, CAId, Balance, Balance),
     }
 }
 
@@ -449,10 +536,87 @@ decl_error! {
         DistributionStarted,
         /// A distribution has insufficient remaining amount of currency to distribute.
         InsufficientRemainingAmount,
+        /// A scrip option's reference price is zero, or its election ends after the payment date.
+        InvalidScripOption,
+        /// The distribution has no scrip option.
+        NoScripOption,
+        /// The election period of the scrip option has ended.
+        ScripElectionEnded,
+        /// Multiplication of the benefit with the per-million precision overflowed.
+        ScripAmountOverflowed,
+        /// A scrip option was set for a distribution redeeming a bond at par.
+        ScripOnRedemption,
     }
 }
 
 impl<T: Config> Module<T> {
+    /// Starts and attaches a capital distribution to the CA identified by `ca_id`,
+    /// with `amount` funds in `currency` withdrawn from `from`, on behalf of `caa`.
+    ///
+    /// Permissions, custody, and validity of `from` are not checked here.
+    /// See `distribute` for the rest.
+    crate fn distribute_base(
+        caa: EventDid,
+        ca_id: CAId,
+        from: PortfolioId,
+        currency: Ticker,
+        per_share: T::Balance,
+        amount: T::Balance,
+        payment_at: Moment,
+        expires_at: Option<Moment>,
+    ) -> DispatchResult {
+        // Ensure CA's asset is distinct from the distributed currency.
+        ensure!(ca_id.ticker != currency, Error::<T>::DistributingAsset);
+
+        // Ensure that any expiry date doesn't come before the payment date.
+        ensure!(
+            !expired(expires_at, payment_at),
+            Error::<T>::ExpiryBeforePayment
+        );
+
+        // Ensure CA doesn't have a distribution yet.
+        ensure!(
+            !<Distributions<T>>::contains_key(ca_id),
+            Error::<T>::AlreadyExists
+        );
+
+        // Ensure that `ca_id` exists, that its a benefit.
+        let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
+        ensure!(ca.kind.is_benefit(), Error::<T>::CANotBenefit);
+
+        // Ensure CA has a record `date <= payment_at`.
+        // If we cannot, deriving a checkpoint,
+        // used to determine each holder's allotment of the total `amount`,
+        // is not possible.
+        <CA<T>>::ensure_record_date_before_start(&ca, payment_at)?;
+
+        // Ensure `from` has at least `amount` to later lock (1).
+        <Portfolio<T>>::ensure_sufficient_balance(&from, &currency, &amount)?;
+
+        // Charge the protocol fee. Last check; we are in commit phase after this.
+        T::ProtocolFee::charge_fee(ProtocolOp::DistributionDistribute)?;
+
+        // (1) Lock `amount` in `from`.
+        <Portfolio<T>>::unchecked_lock_tokens(&from, &currency, &amount);
+
+        // Commit to storage.
+        let distribution = Distribution {
+            from,
+            currency,
+            per_share,
+            amount,
+            remaining: amount,
+            reclaimed: false,
+            payment_at,
+            expires_at,
+        };
+        <Distributions<T>>::insert(ca_id, distribution);
+
+        // Emit event.
+        Self::deposit_event(Event::<T>::Created(caa, ca_id, distribution));
+        Ok(())
+    }
+
     /// Kill the distribution identified by `ca_id`.
     crate fn remove_distribution_base(
         caa: EventDid,
@@ -465,6 +629,8 @@ impl<T: Config> Module<T> {
         // Unlock and remove chain data.
         Self::unlock(&dist, dist.amount)?;
         <Distributions<T>>::remove(ca_id);
+        <ScripOptions<T>>::remove(ca_id);
+        ScripElections::remove_prefix(ca_id);
 
         // Emit event.
         Self::deposit_event(Event::<T>::Removed(caa, ca_id));
@@ -480,6 +646,12 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+    /// Returns whether a distribution still has `currency` locked to pay out.
+    crate fn has_pending_payouts(currency: Ticker) -> bool {
+        <Distributions<T>>::iter()
+            .any(|(_, dist)| dist.currency == currency && dist.remaining > 0u32.into())
+    }
+
     /// Transfer `holder`'s benefit in `ca_id` to them.
     fn transfer_benefit(actor: EventDid, holder: IdentityId, ca_id: CAId) -> DispatchResult {
         // Ensure holder not paid yet.
@@ -512,13 +684,36 @@ impl<T: Config> Module<T> {
         let tax = ca.tax_of(&holder);
         let gain = benefit - tax * benefit;
 
-        with_transaction(|| {
+        // Holders electing scrip are paid in shares, with the `currency` of their gain kept by the CAA.
+        let scrip = Self::scrip_option(ca_id)
+            .filter(|_| Self::scrip_elected(ca_id, holder))
+            .map(|scrip| Self::scrip_of(ca_id.ticker, gain, scrip.reference_price))
+            .transpose()?;
+
+        with_transaction(|| -> DispatchResult {
             // Unlock `benefit` of `currency` from CAAs portfolio.
             Self::unlock(&dist, benefit)?;
 
-            // Transfer remainder (`gain`) to DID.
-            let to = PortfolioId::default_portfolio(holder);
-            <Asset<T>>::base_transfer(dist.from, to, &dist.currency, gain)
+            // Issue the shares, and pay the remainder in `currency`, or else, transfer all of `gain`.
+            let cash = match scrip {
+                Some((shares, cash)) => {
+                    if shares > 0u32.into() {
+                        <Asset<T>>::issue_to(&ca_id.ticker, holder, shares)?;
+                    }
+                    cash
+                }
+                None => gain,
+            };
+            if scrip.is_none() || cash > 0u32.into() {
+                let to = PortfolioId::default_portfolio(holder);
+                <Asset<T>>::base_transfer(dist.from, to, &dist.currency, cash)?;
+            }
+
+            // Redeeming a bond at par also burns the holder's shares.
+            if balance > 0u32.into() && <FixedIncome<T>>::is_redemption(ca_id) {
+                <FixedIncome<T>>::redeem_holder(ca_id, holder, dist.from.did, balance)?;
+            }
+            Ok(())
         })?;
 
         // Note that DID was paid.
@@ -528,7 +723,10 @@ impl<T: Config> Module<T> {
         // Commit `dist` change to storage.
         <Distributions<T>>::insert(ca_id, dist);
 
-        // Emit event.
+        // Emit events.
+        if let Some((shares, cash)) = scrip {
+            Self::deposit_event(Event::<T>::ScripIssued(actor, holder, ca_id, shares, cash));
+        }
         Self::deposit_event(Event::<T>::BenefitClaimed(
             actor, holder, ca_id, dist, benefit, tax,
         ));
@@ -550,6 +748,27 @@ impl<T: Config> Module<T> {
             .ok_or_else(|| Error::<T>::BalancePerShareProductOverflowed.into())
     }
 
+    /// Compute the shares of `ticker` that `gain` buys at `reference_price`,
+    /// rounded down to whole units for indivisible assets,
+    /// and the remainder of `gain` to pay in `currency`.
+    fn scrip_of(
+        ticker: Ticker,
+        gain: T::Balance,
+        reference_price: T::Balance,
+    ) -> Result<(T::Balance, T::Balance), DispatchError> {
+        let precision = T::Balance::from(PER_SHARE_PRECISION);
+        let mut shares = gain
+            .checked_mul(&precision)
+            .map(|v| v / reference_price)
+            .ok_or(Error::<T>::ScripAmountOverflowed)?;
+        if !<Asset<T>>::token_details(ticker).divisible {
+            shares -= shares % ONE_UNIT.into();
+        }
+        // `shares * reference_price <= gain * precision`, so this cannot overflow.
+        let cost = shares * reference_price / precision;
+        Ok((shares, gain - cost))
+    }
+
     /// Ensure `ca_id` has some distribution and return it.
     fn ensure_distribution_exists(ca_id: CAId) -> Result<Distribution<T::Balance>, DispatchError> {
         <Distributions<T>>::get(ca_id).ok_or_else(|| Error::<T>::NoSuchDistribution.into())
@@ -0,0 +1,154 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymathNetwork/Polymesh).
+// Copyright (c) 2020 Polymath
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::benchmarking::{currency, SEED};
+use frame_benchmarking::benchmarks;
+use polymesh_common_utilities::{
+    benchs::{make_asset, user, AccountIdOf, User},
+    TestUtilsFn,
+};
+use polymesh_primitives::{
+    calendar::{CalendarPeriod, CalendarUnit},
+    PortfolioName,
+};
+
+// A coupon every second from `2000` to `MATURITY`, i.e., four coupons.
+const COUPON_SCHEDULE: CheckpointSchedule = CheckpointSchedule {
+    start: 2000,
+    period: CalendarPeriod {
+        unit: CalendarUnit::Second,
+        amount: 1,
+    },
+};
+const MATURITY: Moment = 5000;
+
+fn setup<T: Config + TestUtilsFn<AccountIdOf<T>>>() -> (User<T>, Ticker, Ticker) {
+    <pallet_timestamp::Now<T>>::set(1000u32.into());
+
+    let owner = user("owner", SEED);
+    let ticker = make_asset::<T>(&owner, None);
+    <asset::Tokens<T>>::mutate(ticker, |token| token.asset_type = AssetType::FixedIncome);
+
+    // Enough to pay every coupon and par.
+    let currency = currency::<T>(&owner);
+    <Asset<T>>::issue(owner.origin().into(), currency, 10_000_000u32.into()).unwrap();
+    (owner, ticker, currency)
+}
+
+fn set_up<T: Config + TestUtilsFn<AccountIdOf<T>>>() -> (User<T>, Ticker) {
+    let (owner, ticker, currency) = setup::<T>();
+    <Module<T>>::set_up_bond(
+        owner.origin().into(),
+        ticker,
+        None,
+        currency,
+        1u32.into(),
+        1u32.into(),
+        Some(COUPON_SCHEDULE),
+        MATURITY,
+    )
+    .unwrap();
+    (owner, ticker)
+}
+
+// A holder with one share in its default portfolio and in each of `p - 1` user portfolios.
+fn holder<T: Config + TestUtilsFn<AccountIdOf<T>>>(
+    owner: &User<T>,
+    ticker: Ticker,
+    p: u32,
+) -> (User<T>, Vec<PortfolioNumber>) {
+    let holder = user::<T>("holder", SEED);
+    let from = PortfolioId::default_portfolio(owner.did());
+    let nums = (1..p)
+        .map(|i| {
+            let name = PortfolioName(vec![b'P'; i as usize]);
+            <Portfolio<T>>::create_portfolio(holder.origin().into(), name).unwrap();
+            PortfolioNumber(i as u64)
+        })
+        .collect::<Vec<_>>();
+    let kinds =
+        iter::once(PortfolioKind::Default).chain(nums.iter().copied().map(PortfolioKind::User));
+    for kind in kinds {
+        let to = PortfolioId {
+            did: holder.did(),
+            kind,
+        };
+        <Asset<T>>::unsafe_transfer(from, to, &ticker, 1_000_000u32.into()).unwrap();
+    }
+    (holder, nums)
+}
+
+benchmarks! {
+    where_clause { where T: TestUtilsFn<AccountIdOf<T>> }
+
+    set_up_bond {
+        let (owner, ticker, currency) = setup::<T>();
+    }: _(owner.origin(), ticker, None, currency, 1u32.into(), 1u32.into(), Some(COUPON_SCHEDULE), MATURITY)
+    verify {
+        assert!(<Bonds<T>>::get(ticker).is_some(), "bond not set up");
+    }
+
+    process_bond {
+        let (_, ticker) = set_up::<T>();
+        // Every coupon and the redemption fall due at once, the first coupon being processed.
+        <pallet_timestamp::Now<T>>::set(5000u32.into());
+    }: {
+        <Module<T>>::process_bond(ticker, MATURITY);
+    }
+    verify {
+        assert_eq!(<Bonds<T>>::get(ticker).unwrap().coupons_due, 1, "coupon not processed");
+    }
+
+    redeem_holder {
+        let p in 1..MAX_REDEEMED_PORTFOLIOS;
+
+        let (owner, ticker) = set_up::<T>();
+        let (holder, _) = holder::<T>(&owner, ticker, p);
+        let ca_id = <Bonds<T>>::get(ticker).unwrap().redemption;
+        // One more share than held, so that the last one is pending.
+        let amount = T::Balance::from((p + 1) * 1_000_000);
+    }: {
+        <Module<T>>::redeem_holder(ca_id, holder.did(), owner.did(), amount).unwrap();
+    }
+    verify {
+        assert_eq!(<PendingRedemptions<T>>::get(ca_id, holder.did()), 1_000_000u32.into(), "shares not pending");
+    }
+
+    redeem_pending {
+        let p in 1..MAX_REDEEMED_PORTFOLIOS;
+
+        let (owner, ticker) = set_up::<T>();
+        let (holder, portfolios) = holder::<T>(&owner, ticker, p);
+        let redemption = <Bonds<T>>::get(ticker).unwrap().redemption;
+        <pallet_timestamp::Now<T>>::set(5000u32.into());
+        <Distribution<T>>::distribute_base(
+            owner.did().for_event(),
+            redemption,
+            PortfolioId::default_portfolio(owner.did()),
+            <Bonds<T>>::get(ticker).unwrap().currency,
+            1u32.into(),
+            1u32.into(),
+            MATURITY,
+            None,
+        )
+        .unwrap();
+        let amount = T::Balance::from(p * 1_000_000);
+        <PendingRedemptions<T>>::insert(redemption, holder.did(), amount);
+    }: _(owner.origin(), redemption, holder.did(), portfolios)
+    verify {
+        assert!(!<PendingRedemptions<T>>::contains_key(redemption, holder.did()), "shares still pending");
+    }
+}
@@ -0,0 +1,680 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymathNetwork/Polymesh).
+// Copyright (c) 2020 Polymath
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! # Fixed Income Module
+//!
+//! The fixed income module drives the lifecycle of fixed income assets, e.g., bonds,
+//! from coupon payments, through maturity, to redemption at par.
+//!
+//! The lifecycle of an asset of type `AssetType::FixedIncome` is set up through `set_up_bond`,
+//! provided with the portfolio of the CAA to pay from, the currency to pay in,
+//! the coupon and par value of one share, a coupon schedule, and a maturity date.
+//!
+//! The coupon schedule is a `CheckpointSchedule`, recurring until the maturity date,
+//! and a checkpoint schedule is created from it, recording the balances that coupons are paid on.
+//! Every time a coupon is due, a predictable benefit corporate action (CA) is automatically initiated,
+//! with the coupon's checkpoint as its record date, and a capital distribution paying the coupon
+//! on every share is attached to it, locking the funds needed in the paying portfolio.
+//! If that's not possible, e.g., as the portfolio has too little funds, the coupon is missed,
+//! and it's up to the CAA to pay it through `initiate_corporate_action` and `distribute`.
+//!
+//! From the maturity date on, the asset can no longer be transferred.
+//! When setting up the lifecycle, a redemption CA is also initiated, with the maturity date as its record date.
+//! At maturity, a capital distribution paying par on every share is automatically attached to it.
+//! Claiming or pushing the benefit of the redemption distribution
+//! also redeems the holder's free shares, which are moved to the default portfolio of the CAA and burnt there.
+//! Shares that are locked, e.g., by vesting, or held beyond the first `MAX_REDEEMED_PORTFOLIOS` portfolios,
+//! are recorded as pending, and the CAA redeems them later through `redeem_pending`.
+//! If the redemption distribution cannot be attached, the CAA may attach it through `distribute`.
+//!
+//! Coupons and redemptions due are processed at the start of each block,
+//! up to `MAX_BONDS_PER_BLOCK` assets per block, and one coupon or redemption per asset,
+//! so that overdue coupons are caught up on over the following blocks.
+//! As no one signs for these automatic distributions,
+//! their protocol fees are charged to the CAA upfront when setting up the lifecycle.
+//!
+//! ## Overview
+//!
+//! The module provides functions for:
+//!
+//! - Setting up the lifecycle of a fixed income asset.
+//! - Paying coupons and redeeming at par as they fall due.
+//!
+//! ### Terminology
+//!
+//! - **Currency:** The ticker that coupons and par are paid in, e.g., USDC or some such.
+//! - **Coupon:** The interest paid on one share every time the coupon schedule recurs.
+//! - **Par:** The face value of one share, paid back when the asset is redeemed at maturity.
+//! - **Maturity date:** The date at which the asset is redeemed, and from which on it cannot be transferred.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `set_up_bond` sets up the lifecycle of a fixed income asset.
+//! - `redeem_pending` redeems the shares of a holder that could not be redeemed when paid par.
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+use crate as ca;
+use ca::{
+    distribution::{self, PER_SHARE_PRECISION},
+    CADetails, CAId, CAKind, Config, RecordDateSpec,
+};
+use codec::{Decode, Encode};
+use core::iter;
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::{DispatchError, DispatchResult},
+    ensure,
+    traits::Get,
+    weights::Weight,
+    IterableStorageDoubleMap,
+};
+use pallet_asset::{self as asset, checkpoint};
+use pallet_identity::PermissionedCallOriginData;
+use polymesh_common_utilities::{
+    protocol_fee::{ChargeProtocolFee, ProtocolOp},
+    traits::checkpoint::ScheduleId,
+    with_transaction, CommonConfig,
+};
+use polymesh_primitives::{
+    asset::AssetType,
+    calendar::{CheckpointId, CheckpointSchedule},
+    EventDid, IdentityId, Moment, PortfolioId, PortfolioKind, PortfolioNumber, Ticker,
+};
+use sp_runtime::traits::CheckedMul as _;
+#[cfg(feature = "std")]
+use sp_runtime::{Deserialize, Serialize};
+use sp_std::prelude::*;
+
+type Asset<T> = asset::Module<T>;
+type Checkpoint<T> = checkpoint::Module<T>;
+type Distribution<T> = distribution::Module<T>;
+type ExternalAgents<T> = pallet_external_agents::Module<T>;
+type CA<T> = ca::Module<T>;
+type Portfolio<T> = pallet_portfolio::Module<T>;
+
+/// The maximum number of coupons a bond may pay until maturity.
+pub const MAX_COUPONS: u32 = 1_000;
+
+/// The maximum number of bonds whose coupons and redemptions are processed in one block.
+pub const MAX_BONDS_PER_BLOCK: u32 = 10;
+
+/// The maximum number of portfolios, the default one included,
+/// that a holder's shares are redeemed from at once.
+pub const MAX_REDEEMED_PORTFOLIOS: u32 = 16;
+
+/// The terms and lifecycle state of a fixed income asset.
+///
+/// All information contained is used by on-chain logic.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub struct Bond<Balance> {
+    /// The portfolio to pay coupons and par from.
+    pub from: PortfolioId,
+    /// The currency that coupons and par are paid in.
+    pub currency: Ticker,
+    /// Coupon paid on one share, in per-million,
+    /// i.e. `1 / 10^6`th of one `currency` token.
+    pub coupon: Balance,
+    /// Par value of one share, paid on redemption, in per-million,
+    /// i.e. `1 / 10^6`th of one `currency` token.
+    pub par: Balance,
+    /// The checkpoint schedule recording the balances that coupons are paid on, if any.
+    pub coupon_schedule: Option<ScheduleId>,
+    /// The number of coupons paid until maturity.
+    pub coupons: u32,
+    /// The number of coupons that have fallen due, whether paid or missed.
+    pub coupons_due: u32,
+    /// When the bond matures.
+    pub maturity: Moment,
+    /// The CA redeeming the bond at par, with the maturity date as its record date.
+    pub redemption: CAId,
+    /// Whether the bond has matured, and its redemption was either attached or missed.
+    pub matured: bool,
+}
+
+/// Weight abstraction for the fixed income module.
+pub trait WeightInfo {
+    fn set_up_bond() -> Weight;
+    fn process_bond() -> Weight;
+    fn redeem_holder(portfolios: u32) -> Weight;
+    fn redeem_pending(portfolios: u32) -> Weight;
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as FixedIncome {
+        /// The terms and lifecycle state of fixed income assets.
+        ///
+        /// (Ticker) => Bond
+        Bonds get(fn bonds): map hasher(blake2_128_concat) Ticker => Option<Bond<T::Balance>>;
+
+        /// Bonds with a coupon or redemption yet to fall due, keyed by when the next one does.
+        /// The due date is big-endian encoded, so that iteration is sorted by it.
+        ///
+        /// (due date, ticker) => queued
+        BondQueue: double_map hasher(identity) [u8; 8], hasher(blake2_128_concat) Ticker => bool;
+
+        /// Shares of a holder that could not be redeemed when it was paid par, e.g., as they were locked.
+        ///
+        /// (redemption CA's ID, holder DID) => shares pending redemption
+        PendingRedemptions get(fn pending_redemption):
+            double_map hasher(blake2_128_concat) CAId, hasher(blake2_128_concat) IdentityId => T::Balance;
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        fn on_initialize(_n: T::BlockNumber) -> Weight {
+            Self::process_due_bonds()
+        }
+
+        /// Set up the lifecycle of the fixed income asset `ticker`,
+        /// paying `coupon` on every share each time `coupon_schedule` recurs until `maturity`,
+        /// and `par` on every share at `maturity`, in `currency` from `portfolio` of `origin`'s DID.
+        ///
+        /// From `maturity` on, `ticker` can no longer be transferred.
+        ///
+        /// The protocol fee of distributing each coupon and the redemption is charged upfront,
+        /// as those distributions are attached automatically at the start of a block.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be a signer for a CAA of `ticker`.
+        /// - `ticker` of the fixed income asset.
+        /// - `portfolio` specifies the portfolio number of the CAA to pay from.
+        /// - `currency` to pay coupons and par in.
+        /// - `coupon` paid on one share, specified as a per-million, i.e. `1 / 10^6`th of one `currency` token.
+        /// - `par` paid on one share at maturity, specified as a per-million.
+        /// - `coupon_schedule`, if any, specifies when coupons are paid,
+        ///    starting in the future, and recurring no later than `maturity`.
+        ///    Must be provided if and only if `coupon` is not zero.
+        /// - `maturity` specifies when the asset is redeemed.
+        ///
+        /// # Errors
+        /// - `PayingWithAsset` if `ticker == currency`.
+        /// - `InvalidBondTerms` if `par` is zero, or `coupon` is zero but a coupon schedule is given, or vice versa.
+        /// - `AlreadyExists` if the lifecycle of `ticker` was already set up.
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `UnauthorizedCustodian` if CAA is not the custodian of `portfolio`.
+        /// - `NotFixedIncome` if `ticker` is not a fixed income asset.
+        /// - `InvalidMaturity` if `maturity` is not in the future.
+        /// - `InvalidCouponSchedule` if the schedule starts in the past or after `maturity`.
+        /// - `TooManyCoupons` if more than `MAX_COUPONS` coupons would be paid.
+        /// - Other errors can occur when scheduling checkpoints, initiating the redemption CA,
+        ///   or charging the protocol fees of the automatic distributions.
+        ///
+        /// # Permissions
+        /// * Asset
+        /// * Portfolio
+        #[weight = <T as Config>::FixedIncomeWeightInfo::set_up_bond()]
+        pub fn set_up_bond(
+            origin,
+            ticker: Ticker,
+            portfolio: Option<PortfolioNumber>,
+            currency: Ticker,
+            coupon: T::Balance,
+            par: T::Balance,
+            coupon_schedule: Option<CheckpointSchedule>,
+            maturity: Moment,
+        ) {
+            ensure!(ticker != currency, Error::<T>::PayingWithAsset);
+            ensure!(
+                par > 0u32.into() && (coupon > 0u32.into()) == coupon_schedule.is_some(),
+                Error::<T>::InvalidBondTerms
+            );
+
+            // Ensure the lifecycle wasn't set up yet.
+            ensure!(!<Bonds<T>>::contains_key(ticker), Error::<T>::AlreadyExists);
+
+            // Ensure origin is CAA and that they have custody over `from`.
+            let PermissionedCallOriginData {
+                primary_did: caa,
+                secondary_key,
+                ..
+            } = <ExternalAgents<T>>::ensure_agent_asset_perms(origin, ticker)?;
+            let from = PortfolioId { did: caa, kind: portfolio.into() };
+            <Portfolio<T>>::ensure_portfolio_custody_and_permission(from, caa, secondary_key.as_ref())?;
+            <Portfolio<T>>::ensure_portfolio_validity(&from)?;
+
+            // Ensure `ticker` is a fixed income asset maturing in the future.
+            ensure!(
+                <Asset<T>>::token_details(ticker).asset_type == AssetType::FixedIncome,
+                Error::<T>::NotFixedIncome
+            );
+            let now = <Checkpoint<T>>::now_unix();
+            ensure!(now < maturity, Error::<T>::InvalidMaturity);
+
+            // Count the coupons paid until maturity.
+            let coupons = match coupon_schedule {
+                Some(schedule) => {
+                    ensure!(
+                        now < schedule.start && schedule.start <= maturity,
+                        Error::<T>::InvalidCouponSchedule
+                    );
+                    let coupons = iter::successors(schedule.next_checkpoint(now), |&at| schedule.next_checkpoint(at))
+                        .take_while(|&at| at <= maturity)
+                        .take(MAX_COUPONS as usize + 1)
+                        .count() as u32;
+                    ensure!(coupons <= MAX_COUPONS, Error::<T>::TooManyCoupons);
+                    coupons
+                }
+                None => 0,
+            };
+
+            let event_caa = caa.for_event();
+            let bond = with_transaction(|| -> Result<_, DispatchError> {
+                // Schedule the coupon checkpoints.
+                // We hold a strong reference, so that the schedule cannot be removed.
+                let coupon_schedule = coupon_schedule
+                    .map(|CheckpointSchedule { start, period }| {
+                        let spec = checkpoint::ScheduleSpec { start: Some(start), period, remaining: coupons };
+                        <Checkpoint<T>>::create_schedule_base(event_caa, ticker, spec, 1)
+                    })
+                    .transpose()?
+                    .map(|schedule| schedule.id);
+
+                // Initiate the redemption CA, recording balances at maturity.
+                let redemption = <CA<T>>::initiate_corporate_action_base(
+                    event_caa,
+                    ticker,
+                    CAKind::PredictableBenefit,
+                    now,
+                    Some(RecordDateSpec::Scheduled(maturity)),
+                    CADetails::default(),
+                    None,
+                    None,
+                    None,
+                )?;
+
+                // Charge the fees of the automatic distributions. Last check; we commit after this.
+                T::ProtocolFee::batch_charge_fee(ProtocolOp::DistributionDistribute, coupons as usize + 1)?;
+
+                Ok(Bond {
+                    from,
+                    currency,
+                    coupon,
+                    par,
+                    coupon_schedule,
+                    coupons,
+                    coupons_due: 0,
+                    maturity,
+                    redemption,
+                    matured: false,
+                })
+            })?;
+
+            // Commit to storage, stopping transfers from `maturity` on.
+            <Asset<T>>::set_maturity(ticker, maturity);
+            if let Some(due) = Self::next_due(ticker, &bond) {
+                Self::enqueue(due, ticker);
+            }
+            <Bonds<T>>::insert(ticker, bond);
+
+            // Emit event.
+            Self::deposit_event(Event::<T>::BondSetUp(event_caa, ticker, bond));
+        }
+
+        /// Redeem the shares of `holder` pending redemption in the redemption CA `ca_id`,
+        /// i.e., those that could not be redeemed when `holder` was paid par, e.g., as they were locked.
+        ///
+        /// Free shares are moved from the default portfolio of `holder`, and then from `portfolios`,
+        /// to the default portfolio of the CAA that distributed par, and burnt there.
+        /// Any shares still not redeemed remain pending.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be a signer for a CAA of `ca_id.ticker`.
+        /// - `ca_id` of the redemption CA.
+        /// - `holder` whose pending shares to redeem.
+        /// - `portfolios` of `holder`, besides its default one, to redeem from.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `TooManyPortfolios` if `portfolios` has `MAX_REDEEMED_PORTFOLIOS` or more entries.
+        /// - `NoPendingRedemption` if `holder` has no shares pending redemption in `ca_id`.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::FixedIncomeWeightInfo::redeem_pending(portfolios.len() as u32 + 1)]
+        pub fn redeem_pending(origin, ca_id: CAId, holder: IdentityId, portfolios: Vec<PortfolioNumber>) {
+            <ExternalAgents<T>>::ensure_perms(origin, ca_id.ticker)?;
+            ensure!(
+                portfolios.len() < MAX_REDEEMED_PORTFOLIOS as usize,
+                Error::<T>::TooManyPortfolios
+            );
+            let amount = Self::pending_redemption(ca_id, holder);
+            ensure!(amount > 0u32.into(), Error::<T>::NoPendingRedemption);
+            let caa = <Distribution<T>>::distributions(ca_id)
+                .ok_or(distribution::Error::<T>::NoSuchDistribution)?
+                .from
+                .did;
+
+            let left = with_transaction(|| -> Result<_, DispatchError> {
+                Self::redeem_from(ca_id, holder, caa, portfolios.into_iter(), amount)
+            })?;
+            if left > 0u32.into() {
+                <PendingRedemptions<T>>::insert(ca_id, holder, left);
+            } else {
+                <PendingRedemptions<T>>::remove(ca_id, holder);
+            }
+        }
+    }
+}
+
+decl_event! {
+    pub enum Event<T>
+    where
+        Balance = <T as CommonConfig>::Balance,
+    {
+        /// The lifecycle of a fixed income asset was set up.
+        ///
+        /// (Ticker's CAA, ticker, bond details)
+        BondSetUp(EventDid, Ticker, Bond<Balance>),
+
+        /// A coupon fell due, and a capital distribution paying it was attached to a new CA.
+        ///
+        /// (Ticker's CAA, coupon CA's ID, coupon index, amount distributed)
+        CouponDistributed(EventDid, CAId, u32, Balance),
+
+        /// A coupon fell due, but could not be distributed, e.g., due to insufficient funds.
+        ///
+        /// (Ticker's CAA, ticker, coupon index)
+        CouponMissed(EventDid, Ticker, u32),
+
+        /// A bond matured, and a capital distribution paying par was attached to its redemption CA.
+        ///
+        /// (Ticker's CAA, redemption CA's ID, amount distributed)
+        RedemptionDistributed(EventDid, CAId, Balance),
+
+        /// A bond matured, but its redemption could not be distributed, e.g., due to insufficient funds.
+        ///
+        /// (Ticker's CAA, redemption CA's ID)
+        RedemptionMissed(EventDid, CAId),
+
+        /// A holder's shares were redeemed at par.
+        ///
+        /// (Holder DID, redemption CA's ID, shares redeemed)
+        SharesRedeemed(EventDid, CAId, Balance),
+
+        /// A holder was paid par, but some of its shares could not be redeemed, e.g., as they were locked,
+        /// and are pending redemption through `redeem_pending`.
+        ///
+        /// (Holder DID, redemption CA's ID, shares pending redemption)
+        RedemptionDeferred(EventDid, CAId, Balance),
+    }
+}
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// The lifecycle of the fixed income asset was already set up.
+        AlreadyExists,
+        /// The asset is not a fixed income asset.
+        NotFixedIncome,
+        /// The currency to pay with is the same as the asset.
+        PayingWithAsset,
+        /// The par value is zero, or a coupon schedule is given if and only if the coupon is zero.
+        InvalidBondTerms,
+        /// The maturity date is not in the future.
+        InvalidMaturity,
+        /// The coupon schedule starts in the past or after the maturity date.
+        InvalidCouponSchedule,
+        /// More than `MAX_COUPONS` coupons would be paid until maturity.
+        TooManyCoupons,
+        /// Multiplication of the supply with the coupon or par value overflowed.
+        FundsOverflowed,
+        /// The holder has no shares pending redemption.
+        NoPendingRedemption,
+        /// Too many portfolios were given to redeem from.
+        TooManyPortfolios,
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// Is `ca_id` the redemption CA of a bond?
+    crate fn is_redemption(ca_id: CAId) -> bool {
+        Self::bonds(ca_id.ticker).map_or(false, |bond| bond.redemption == ca_id)
+    }
+
+    /// Redeems `amount` shares of `holder`, paid par by `caa` in the redemption `ca_id`,
+    /// from its default portfolio and its first user portfolios, up to `MAX_REDEEMED_PORTFOLIOS` in all.
+    /// Shares that cannot be redeemed, e.g., as they are locked, are recorded as pending redemption.
+    crate fn redeem_holder(
+        ca_id: CAId,
+        holder: IdentityId,
+        caa: IdentityId,
+        amount: T::Balance,
+    ) -> DispatchResult {
+        let user_portfolios = pallet_portfolio::Portfolios::iter_prefix(holder)
+            .map(|(num, _)| num)
+            .take(MAX_REDEEMED_PORTFOLIOS as usize - 1);
+        let left = Self::redeem_from(ca_id, holder, caa, user_portfolios, amount)?;
+        if left > 0u32.into() {
+            <PendingRedemptions<T>>::insert(ca_id, holder, left);
+            Self::deposit_event(Event::<T>::RedemptionDeferred(
+                holder.for_event(),
+                ca_id,
+                left,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Redeems up to `amount` free shares of `holder`, moving them from its default portfolio,
+    /// and then from `user_portfolios`, to the default portfolio of `caa`, and burning them there.
+    /// Returns the shares left unredeemed.
+    fn redeem_from(
+        ca_id: CAId,
+        holder: IdentityId,
+        caa: IdentityId,
+        user_portfolios: impl Iterator<Item = PortfolioNumber>,
+        amount: T::Balance,
+    ) -> Result<T::Balance, DispatchError> {
+        let ticker = ca_id.ticker;
+        let to = PortfolioId::default_portfolio(caa);
+        let kinds =
+            iter::once(PortfolioKind::Default).chain(user_portfolios.map(PortfolioKind::User));
+
+        let mut left = amount;
+        for kind in kinds {
+            if left == 0u32.into() {
+                break;
+            }
+            let from = PortfolioId { did: holder, kind };
+            let moved = <Portfolio<T>>::portfolio_asset_balances(from, ticker)
+                .saturating_sub(<Portfolio<T>>::locked_assets(from, ticker))
+                .saturating_sub(<Portfolio<T>>::vesting_locked(&from, &ticker))
+                .min(left);
+            if moved == 0u32.into() {
+                continue;
+            }
+            if holder != caa {
+                <Asset<T>>::unsafe_transfer(from, to, &ticker, moved)?;
+            } else if from != to {
+                <Portfolio<T>>::unchecked_transfer_portfolio_balance(&from, &to, &ticker, moved);
+            }
+            left -= moved;
+        }
+
+        let redeemed = amount - left;
+        if redeemed > 0u32.into() {
+            <Asset<T>>::redeem_from_default_portfolio(caa, ticker, redeemed)?;
+            Self::deposit_event(Event::<T>::SharesRedeemed(
+                holder.for_event(),
+                ca_id,
+                redeemed,
+            ));
+        }
+        Ok(left)
+    }
+
+    /// Process the bonds with a coupon or redemption due, at most `MAX_BONDS_PER_BLOCK` of them.
+    fn process_due_bonds() -> Weight {
+        let now = <Checkpoint<T>>::now_unix();
+        // The queue is sorted by due date, so we stop at the first bond not yet due.
+        let due_bonds = BondQueue::iter()
+            .map(|(at, ticker, _)| (at, ticker))
+            .take_while(|(at, _)| Moment::from_be_bytes(*at) <= now)
+            .take(MAX_BONDS_PER_BLOCK as usize)
+            .collect::<Vec<_>>();
+        let due = due_bonds.len() as Weight;
+
+        for (at, ticker) in due_bonds {
+            BondQueue::remove(at, ticker);
+            if let Some(at) = Self::process_bond(ticker, now) {
+                Self::enqueue(at, ticker);
+            }
+        }
+
+        // Each due bond is read, removed, processed, and requeued, plus one read of the first bond not due.
+        <T as frame_system::Config>::DbWeight::get()
+            .reads_writes(due + 1, 2 * due)
+            .saturating_add(
+                <T as Config>::FixedIncomeWeightInfo::process_bond().saturating_mul(due),
+            )
+    }
+
+    /// Distribute the next coupon of `ticker` that is due at `now`,
+    /// or once no coupons are left due, the redemption, if due,
+    /// returning when the next one will be due, if ever.
+    crate fn process_bond(ticker: Ticker, now: Moment) -> Option<Moment> {
+        let mut bond = Self::bonds(ticker)?;
+        let caa = bond.from.did.for_event();
+
+        // Record the due coupon and maturity checkpoints.
+        // Should that fail, current balances are used for the redemption instead.
+        let _ = <Checkpoint<T>>::advance_update_balances(&ticker, &[]);
+
+        // Each new checkpoint of the coupon schedule is a coupon due.
+        if let Some(cp) = Self::next_coupon_cp(ticker, &bond) {
+            let idx = bond.coupons_due;
+            let distributed = with_transaction(|| -> Result<_, DispatchError> {
+                let date = <Checkpoint<T>>::timestamps(ticker, cp);
+                let ca_id = <CA<T>>::initiate_corporate_action_base(
+                    caa,
+                    ticker,
+                    CAKind::PredictableBenefit,
+                    date,
+                    Some(RecordDateSpec::Existing(cp)),
+                    CADetails::default(),
+                    None,
+                    None,
+                    None,
+                )?;
+                let supply = <Checkpoint<T>>::total_supply_at(ticker, cp);
+                let amount = Self::distribute(caa, ca_id, &bond, bond.coupon, supply, now)?;
+                Ok((ca_id, amount))
+            });
+            match distributed {
+                Ok((ca_id, amount)) => {
+                    Self::deposit_event(Event::<T>::CouponDistributed(caa, ca_id, idx, amount))
+                }
+                Err(_) => Self::deposit_event(Event::<T>::CouponMissed(caa, ticker, idx)),
+            }
+            bond.coupons_due += 1;
+        } else if !bond.matured && bond.maturity <= now {
+            // At maturity, pay par on every share recorded at the maturity checkpoint.
+            let ca_id = bond.redemption;
+            let distributed = with_transaction(|| -> Result<_, DispatchError> {
+                let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
+                <CA<T>>::ensure_record_date_before_start(&ca, now)?;
+                let supply = match <CA<T>>::record_date_cp(&ca, ca_id) {
+                    Some(cp) => <Checkpoint<T>>::total_supply_at(ticker, cp),
+                    None => <Asset<T>>::token_details(ticker).total_supply,
+                };
+                Self::distribute(caa, ca_id, &bond, bond.par, supply, now)
+            });
+            match distributed {
+                Ok(amount) => {
+                    Self::deposit_event(Event::<T>::RedemptionDistributed(caa, ca_id, amount))
+                }
+                Err(_) => Self::deposit_event(Event::<T>::RedemptionMissed(caa, ca_id)),
+            }
+            bond.matured = true;
+        }
+
+        let next = Self::next_due(ticker, &bond);
+        <Bonds<T>>::insert(ticker, bond);
+        next
+    }
+
+    /// Returns the checkpoint of the next coupon of `bond` that has fallen due but wasn't processed, if any.
+    fn next_coupon_cp(ticker: Ticker, bond: &Bond<T::Balance>) -> Option<CheckpointId> {
+        let id = bond.coupon_schedule?;
+        <Checkpoint<T>>::schedule_points(ticker, id)
+            .get(bond.coupons_due as usize)
+            .copied()
+    }
+
+    /// Attach a distribution paying `per_share` on every share of `supply` to `ca_id`,
+    /// payable from `now` on, returning the amount distributed.
+    fn distribute(
+        caa: EventDid,
+        ca_id: CAId,
+        bond: &Bond<T::Balance>,
+        per_share: T::Balance,
+        supply: T::Balance,
+        now: Moment,
+    ) -> Result<T::Balance, DispatchError> {
+        let amount = supply
+            .checked_mul(&per_share)
+            // `per_share` was entered as a multiple of 1_000_000.
+            .map(|v| v / T::Balance::from(PER_SHARE_PRECISION))
+            .ok_or(Error::<T>::FundsOverflowed)?;
+        <Distribution<T>>::distribute_base(
+            caa,
+            ca_id,
+            bond.from,
+            bond.currency,
+            per_share,
+            amount,
+            now,
+            None,
+        )?;
+        Ok(amount)
+    }
+
+    /// Returns when the next coupon or redemption of `bond` is due, if ever.
+    fn next_due(ticker: Ticker, bond: &Bond<T::Balance>) -> Option<Moment> {
+        // A coupon that has fallen due but wasn't processed yet comes first.
+        if let Some(cp) = Self::next_coupon_cp(ticker, bond) {
+            return Some(<Checkpoint<T>>::timestamps(ticker, cp));
+        }
+        let coupon = bond.coupon_schedule.and_then(|id| {
+            <Checkpoint<T>>::schedules(ticker)
+                .into_iter()
+                .find(|schedule| schedule.id == id)
+                .map(|schedule| schedule.at)
+        });
+        let maturity = (!bond.matured).then(|| bond.maturity);
+        coupon.into_iter().chain(maturity).min()
+    }
+
+    /// Insert `ticker` into the bond queue, due at `at`.
+    fn enqueue(at: Moment, ticker: Ticker) {
+        BondQueue::insert(at.to_be_bytes(), ticker, true);
+    }
+
+    /// Returns the bonds with a coupon or redemption yet to fall due, sorted by when the next one does.
+    pub fn queued_bonds() -> Vec<(Moment, Ticker)> {
+        BondQueue::iter()
+            .map(|(at, ticker, _)| (Moment::from_be_bytes(at), ticker))
+            .collect()
+    }
+}
@@ -51,10 +51,13 @@
 //! To do so, `link_ca_doc(ca_id, docs)` can be called,
 //! with the ID of the CA specified in `ca_id` as well the IDs of each document in `docs`.
 //!
-//! Beyond this module, two other modules exist dedicated to CAs. These are:
+//! Beyond this module, five other modules exist dedicated to CAs. These are:
 //!
 //! - The corporate ballots module, with which e.g., annual general meetings can be conducted on-chain.
 //! - The capital distributions module, with which e.g., dividends and other benefits may be distributed.
+//! - The stock split module, with which e.g., stock splits and share consolidations may be executed.
+//! - The tender offer module, with which e.g., tokens may be bought back from holders.
+//! - The fixed income module, with which e.g., bonds pay coupons and are redeemed at par at maturity.
 //!
 //! For more details, consult the documentation in those modules.
 //!
@@ -94,6 +97,9 @@ pub mod benchmarking;
 
 pub mod ballot;
 pub mod distribution;
+pub mod fixed_income;
+pub mod split;
+pub mod tender;
 
 use codec::{Decode, Encode};
 use core::convert::TryInto;
@@ -202,6 +208,9 @@ pub enum CAKind {
     /// Some generic uncategorized CA.
     /// In other words, none of the above.
     Other,
+    /// An offer to buy back tokens from the position holders.
+    /// Examples include tender offers and share buybacks.
+    TenderOffer,
 }
 
 impl CAKind {
@@ -322,11 +331,21 @@ pub trait WeightInfo {
 }
 
 /// The module's configuration trait.
-pub trait Config: frame_system::Config + BalancesConfig + IdentityConfig + asset::Config {
+pub trait Config:
+    frame_system::Config
+    + BalancesConfig
+    + IdentityConfig
+    + asset::Config
+    + pallet_settlement::Config
+    + pallet_sto::Config
+{
     /// The overarching event type.
     type Event: From<Event>
         + From<ballot::Event<Self>>
         + From<distribution::Event<Self>>
+        + From<fixed_income::Event<Self>>
+        + From<split::Event<Self>>
+        + From<tender::Event<Self>>
         + Into<<Self as frame_system::Config>::Event>;
 
     /// Max number of DID specified in `TargetIdentities`.
@@ -343,6 +362,15 @@ pub trait Config: frame_system::Config + BalancesConfig + IdentityConfig + asset
 
     /// Weight information for extrinsics in the capital distribution pallet.
     type DistWeightInfo: distribution::WeightInfo;
+
+    /// Weight information for extrinsics in the stock split pallet.
+    type SplitWeightInfo: split::WeightInfo;
+
+    /// Weight information for extrinsics in the tender offer pallet.
+    type TenderWeightInfo: tender::WeightInfo;
+
+    /// Weight information for extrinsics in the fixed income pallet.
+    type FixedIncomeWeightInfo: fixed_income::WeightInfo;
 }
 
 type Asset<T> = pallet_asset::Module<T>;
@@ -350,6 +378,8 @@ type Ballot<T> = ballot::Module<T>;
 type Checkpoint<T> = checkpoint::Module<T>;
 type Distribution<T> = distribution::Module<T>;
 type ExternalAgents<T> = pallet_external_agents::Module<T>;
+type Split<T> = split::Module<T>;
+type Tender<T> = tender::Module<T>;
 
 decl_storage! {
     trait Store for Module<T: Config> as CorporateAction {
@@ -579,64 +609,10 @@ decl_module! {
             default_withholding_tax: Option<Tax>,
             withholding_tax: Option<Vec<(IdentityId, Tax)>>,
         ) {
-            // Ensure that `details` is short enough.
-            details
-                .len()
-                .try_into()
-                .ok()
-                .filter(|&len: &u32| len <= Self::max_details_length())
-                .ok_or(Error::<T>::DetailsTooLong)?;
-
-            // Ensure that CAA is calling.
             let caa = <ExternalAgents<T>>::ensure_perms(origin, ticker)?.for_event();
-
-            // Ensure that the next local CA ID doesn't overflow.
-            let local_id = CAIdSequence::get(ticker);
-            let next_id = local_id.0.checked_add(1).map(LocalCAId).ok_or(Error::<T>::LocalCAIdOverflow)?;
-            let id = CAId { ticker, local_id };
-
-            // Ensure there are no duplicates in withholding tax overrides
-            // and that we're within the limit.
-            let mut withholding_tax = withholding_tax;
-            if let Some(wt) = &mut withholding_tax {
-                let before = wt.len();
-                Self::ensure_did_whts_limited(before)?;
-                wt.sort_unstable_by_key(|&(did, _)| did);
-                wt.dedup_by_key(|&mut (did, _)| did);
-                ensure!(before == wt.len(), Error::<T>::DuplicateDidTax);
-            }
-
-            // Ensure target ids are limited in number if provided.
-            if let Some(ref targets) = targets {
-                Self::ensure_target_ids_limited(targets)?;
-            }
-
-            // Declaration date must be <= now.
-            ensure!(decl_date <= <Checkpoint<T>>::now_unix(), Error::<T>::DeclDateInFuture);
-
-            // If provided, either use the existing CP ID or schedule one to be made.
-            let record_date = record_date
-                .map(|date| with_transaction(|| -> Result<_, DispatchError> {
-                    let rd = Self::handle_record_date(caa, ticker, date)?;
-                    ensure!(decl_date <= rd.date, Error::<T>::DeclDateAfterRecordDate);
-                    Ok(rd)
-                }))
-                .transpose()?;
-
-            // Commit the next local CA ID.
-            CAIdSequence::insert(ticker, next_id);
-
-            // Use asset level defaults if data not provided here.
-            let targets = targets
-                .map(|t| t.dedup())
-                .unwrap_or_else(|| Self::default_target_identities(ticker));
-            let default_withholding_tax = default_withholding_tax
-                .unwrap_or_else(|| Self::default_withholding_tax(ticker));
-            let withholding_tax = withholding_tax
-                .unwrap_or_else(|| Self::did_withholding_tax(ticker));
-
-            // Commit CA to storage.
-            let ca = CorporateAction {
+            Self::initiate_corporate_action_base(
+                caa,
+                ticker,
                 kind,
                 decl_date,
                 record_date,
@@ -644,11 +620,7 @@ decl_module! {
                 targets,
                 default_withholding_tax,
                 withholding_tax,
-            };
-            CorporateActions::insert(ticker, id.local_id, ca.clone());
-
-            // Emit event.
-            Self::deposit_event(Event::CAInitiated(caa, id, ca));
+            )?;
         }
 
         /// Link the given CA `id` to the given `docs`.
@@ -686,7 +658,7 @@ decl_module! {
         /// Removes the CA identified by `ca_id`.
         ///
         /// Associated data, such as document links, ballots,
-        /// and capital distributions are also removed.
+        /// capital distributions, splits, and tender offers are also removed.
         ///
         /// Any schedule associated with the record date will see
         /// `strong_ref_count(schedule_id)` decremented.
@@ -710,7 +682,17 @@ decl_module! {
 
             // Remove associated services.
             match ca.kind {
-                CAKind::Other | CAKind::Reorganization => {}
+                CAKind::Other => {}
+                CAKind::Reorganization => {
+                    if let Some(split) = <Split<T>>::splits(ca_id) {
+                        <Split<T>>::remove_split_base(caa, ca_id, &split)?;
+                    }
+                }
+                CAKind::TenderOffer => {
+                    if let Some(offer) = <Tender<T>>::tender_offers(ca_id) {
+                        <Tender<T>>::remove_tender_offer_base(caa, ca_id, &offer)?;
+                    }
+                }
                 CAKind::IssuerNotice => {
                     if let Some(range) = <Ballot<T>>::time_ranges(ca_id) {
                         <Ballot<T>>::remove_ballot_base(caa, ca_id, range)?;
@@ -761,11 +743,19 @@ decl_module! {
 
                 // Ensure associated services allow changing the date.
                 match ca.kind {
-                    CAKind::Other | CAKind::Reorganization => {}
+                    CAKind::Other | CAKind::TenderOffer => {}
+                    CAKind::Reorganization => {
+                        if let Some(split) = <Split<T>>::splits(ca_id) {
+                            ensure!(ca.record_date.is_some(), Error::<T>::NoRecordDate);
+                            <Split<T>>::ensure_split_not_started(&split)?;
+                        }
+                    }
                     CAKind::IssuerNotice => {
                         if let Some(range) = <Ballot<T>>::time_ranges(ca_id) {
                             Self::ensure_record_date_before_start(&ca, range.start)?;
                             <Ballot<T>>::ensure_ballot_not_started(range)?;
+                            // Classes were snapshotted at the old record date, so weights must be set anew.
+                            <Ballot<T>>::remove_class_weights(ca_id);
                         }
                     }
                     CAKind::PredictableBenefit | CAKind::UnpredictableBenefit => {
@@ -852,6 +842,98 @@ decl_error! {
 }
 
 impl<T: Config> Module<T> {
+    /// Initiates a CA for `ticker` of `kind` with `details` and other provided arguments,
+    /// on behalf of `caa`, returning the ID of the new CA.
+    ///
+    /// Permissions are not checked here. See `initiate_corporate_action` for the rest.
+    crate fn initiate_corporate_action_base(
+        caa: EventDid,
+        ticker: Ticker,
+        kind: CAKind,
+        decl_date: Moment,
+        record_date: Option<RecordDateSpec>,
+        details: CADetails,
+        targets: Option<TargetIdentities>,
+        default_withholding_tax: Option<Tax>,
+        mut withholding_tax: Option<Vec<(IdentityId, Tax)>>,
+    ) -> Result<CAId, DispatchError> {
+        // Ensure that `details` is short enough.
+        details
+            .len()
+            .try_into()
+            .ok()
+            .filter(|&len: &u32| len <= Self::max_details_length())
+            .ok_or(Error::<T>::DetailsTooLong)?;
+
+        // Ensure that the next local CA ID doesn't overflow.
+        let local_id = CAIdSequence::get(ticker);
+        let next_id = local_id
+            .0
+            .checked_add(1)
+            .map(LocalCAId)
+            .ok_or(Error::<T>::LocalCAIdOverflow)?;
+        let id = CAId { ticker, local_id };
+
+        // Ensure there are no duplicates in withholding tax overrides
+        // and that we're within the limit.
+        if let Some(wt) = &mut withholding_tax {
+            let before = wt.len();
+            Self::ensure_did_whts_limited(before)?;
+            wt.sort_unstable_by_key(|&(did, _)| did);
+            wt.dedup_by_key(|&mut (did, _)| did);
+            ensure!(before == wt.len(), Error::<T>::DuplicateDidTax);
+        }
+
+        // Ensure target ids are limited in number if provided.
+        if let Some(ref targets) = targets {
+            Self::ensure_target_ids_limited(targets)?;
+        }
+
+        // Declaration date must be <= now.
+        ensure!(
+            decl_date <= <Checkpoint<T>>::now_unix(),
+            Error::<T>::DeclDateInFuture
+        );
+
+        // If provided, either use the existing CP ID or schedule one to be made.
+        let record_date = record_date
+            .map(|date| {
+                with_transaction(|| -> Result<_, DispatchError> {
+                    let rd = Self::handle_record_date(caa, ticker, date)?;
+                    ensure!(decl_date <= rd.date, Error::<T>::DeclDateAfterRecordDate);
+                    Ok(rd)
+                })
+            })
+            .transpose()?;
+
+        // Commit the next local CA ID.
+        CAIdSequence::insert(ticker, next_id);
+
+        // Use asset level defaults if data not provided here.
+        let targets = targets
+            .map(|t| t.dedup())
+            .unwrap_or_else(|| Self::default_target_identities(ticker));
+        let default_withholding_tax =
+            default_withholding_tax.unwrap_or_else(|| Self::default_withholding_tax(ticker));
+        let withholding_tax = withholding_tax.unwrap_or_else(|| Self::did_withholding_tax(ticker));
+
+        // Commit CA to storage.
+        let ca = CorporateAction {
+            kind,
+            decl_date,
+            record_date,
+            details,
+            targets,
+            default_withholding_tax,
+            withholding_tax,
+        };
+        CorporateActions::insert(ticker, id.local_id, ca.clone());
+
+        // Emit event.
+        Self::deposit_event(Event::CAInitiated(caa, id, ca));
+        Ok(id)
+    }
+
     /// Ensure number of identities in `TargetIdentities` are limited.
     fn ensure_target_ids_limited(targets: &TargetIdentities) -> DispatchResult {
         ensure!(
@@ -0,0 +1,118 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymathNetwork/Polymesh).
+// Copyright (c) 2020 Polymath
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::benchmarking::{currency, setup_ca, SEED};
+use frame_benchmarking::benchmarks;
+use pallet_compliance_manager::Module as ComplianceManager;
+use polymesh_common_utilities::{
+    asset::AssetFnTrait,
+    benchs::{user, AccountIdOf, User},
+    TestUtilsFn,
+};
+
+const MAX_HOLDERS: u32 = 100;
+
+fn cash_in_lieu<T: Config>(owner: &User<T>) -> CashInLieu<T::Balance> {
+    CashInLieu {
+        from: PortfolioId::default_portfolio(owner.did()),
+        currency: currency::<T>(owner),
+        price: 1_000_000u32.into(),
+    }
+}
+
+fn split<T: Config + TestUtilsFn<AccountIdOf<T>>>() -> (User<T>, CAId) {
+    let (owner, ca_id) = setup_ca::<T>(CAKind::Reorganization);
+    let ratio = SplitRatio {
+        numerator: 1,
+        denominator: 3,
+    };
+    let cash = cash_in_lieu::<T>(&owner);
+    <Module<T>>::attach_split(owner.origin().into(), ca_id, ratio, Some(cash)).unwrap();
+    (owner, ca_id)
+}
+
+fn add_holders<T: Config + TestUtilsFn<AccountIdOf<T>>>(ticker: Ticker, n: u32) {
+    let balance = T::Balance::from(1_000_000u32);
+    for i in 0..n {
+        let did = user::<T>("holder", i).did();
+        <asset::BalanceOf<T>>::insert(ticker, did, balance);
+        <Portfolio<T>>::set_default_portfolio_balance(did, &ticker, balance);
+        <asset::Tokens<T>>::mutate(ticker, |token| token.total_supply += balance);
+    }
+}
+
+benchmarks! {
+    where_clause { where
+        T: pallet_compliance_manager::Config,
+        T: TestUtilsFn<AccountIdOf<T>>,
+    }
+
+    attach_split {
+        let (owner, ca_id) = setup_ca::<T>(CAKind::Reorganization);
+        let ratio = SplitRatio { numerator: 2, denominator: 1 };
+        let cash = Some(cash_in_lieu::<T>(&owner));
+    }: _(owner.origin(), ca_id, ratio, cash)
+    verify {
+        assert!(<Splits<T>>::get(ca_id).is_some(), "split not attached");
+    }
+
+    remove_split {
+        let (owner, ca_id) = split::<T>();
+    }: _(owner.origin(), ca_id)
+    verify {
+        assert!(<Splits<T>>::get(ca_id).is_none(), "split not removed");
+    }
+
+    execute_split {
+        let h in 1..MAX_HOLDERS;
+
+        let (owner, ca_id) = split::<T>();
+        add_holders::<T>(ca_id.ticker, h);
+        <pallet_timestamp::Now<T>>::set(3000u32.into());
+    }: _(owner.origin(), ca_id, h)
+    verify {
+        assert!(
+            <Splits<T>>::get(ca_id).unwrap().status != SplitStatus::Pending,
+            "split not executed"
+        );
+    }
+
+    claim_cash_in_lieu {
+        let (owner, ca_id) = split::<T>();
+        let cash = <Splits<T>>::get(ca_id).unwrap().cash_in_lieu.unwrap();
+
+        let holder = user::<T>("holder", SEED);
+        <T as pallet_compliance_manager::Config>::Asset::add_investor_uniqueness_claim(
+            owner.did(),
+            cash.currency,
+        );
+        <T as pallet_compliance_manager::Config>::Asset::add_investor_uniqueness_claim(
+            holder.did(),
+            cash.currency,
+        );
+        <ComplianceManager<T>>::add_compliance_requirement(
+            owner.origin().into(),
+            cash.currency,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        <CashInLieuOwed<T>>::insert(ca_id, holder.did(), T::Balance::from(1_000u32));
+    }: _(holder.origin(), ca_id)
+    verify {
+        assert!(<CashInLieuOwed<T>>::get(ca_id, holder.did()) == 0u32.into(), "not claimed");
+    }
+}
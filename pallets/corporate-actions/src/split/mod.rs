@@ -0,0 +1,636 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymathNetwork/Polymesh).
+// Copyright (c) 2020 Polymath
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! # Stock Split Module
+//!
+//! The stock split module provides functionality for stock splits, reverse splits,
+//! and share consolidations, i.e., for rescaling the balance of every holder of an asset by a ratio.
+//!
+//! The process works by first initiating a reorganization corporate action (CA)
+//! with a record date through `initiate_corporate_action`,
+//! and then attaching a split to it via `attach_split`.
+//!
+//! When attaching a split, the ratio `numerator / denominator` to rescale balances by is provided.
+//! A ratio above one is a split, while a ratio below one is a reverse split, or consolidation.
+//! Optionally, the portfolio and currency to pay holders in for fractions of a share
+//! lost to rounding ("cash-in-lieu") are also provided, as is the price of one share.
+//!
+//! Once the record date is due, the split is executed via `execute_split`,
+//! in batches of holders, so as to bound the weight of each call.
+//! Each batch resumes from the last holder rescaled by the previous one.
+//! While the split is executing, the asset is frozen,
+//! and it is unfrozen once every holder has been rescaled, unless it was frozen before.
+//! The first batch also rescales the STO fundraisers offering the asset,
+//! i.e., the amounts and prices of their tiers, bids and escrowed investments.
+//! The balances at the record date can be found in the CA's record date checkpoint.
+//!
+//! For each holder, the following are rescaled:
+//! - The balance of the holder, and thereby the total supply of the asset.
+//! - The balance of the asset in every portfolio of the holder, its locked amount and its vesting schedules.
+//!   The parts of the locked amount held by pending instructions and fundraisers
+//!   are set to what they lock after being rescaled, so that both agree.
+//! - The holder's balance at its scope, and the aggregate balance of the scope.
+//! - The amount of the holder's balance frozen by the asset's agents.
+//! - The amounts of the asset sent by the holder's portfolios in instructions pending execution.
+//!
+//! All amounts are rounded down, and for indivisible assets, down to a whole number of units.
+//! Any part of a holder's balance lost to rounding across their portfolios
+//! is credited to their default portfolio.
+//! Fractions of a share lost to rounding a holder's balance to whole units
+//! are owed to the holder in the cash-in-lieu currency, and may be claimed via `claim_cash_in_lieu`.
+//!
+//! Before execution starts, a split can be cancelled by calling `remove_split`.
+//!
+//! A split fails with `RescaleOverflow` if any rescaled amount would overflow,
+//! and with `TotalSupplyAboveLimit` if the rescaled total supply would exceed the maximum supply.
+//!
+//! Forced transfers, tender offers, fundraisers raising funds and distributions paying out
+//! lock or hold amounts of the asset that are not rescaled, so no batch executes while any of them is open.
+//!
+//! Note that pending instruction legs sent from portfolios of identities not holding the asset are not rescaled.
+//! The CAA should also refrain from issuing or redeeming the asset while a split is executing.
+//!
+//! ## Overview
+//!
+//! The module provides functions for:
+//!
+//! - Attaching a split to a reorganization CA.
+//! - Executing a split in batches of holders.
+//! - Claiming cash-in-lieu of fractional shares.
+//!
+//! ### Terminology
+//!
+//! - **Split ratio:** The ratio `numerator / denominator` by which balances are rescaled.
+//! - **Cash-in-lieu:** The `currency` paid to holders for fractions of a share lost to rounding.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `attach_split` attaches a split to a reorganization CA.
+//! - `remove_split` removes a split whose execution hasn't started yet.
+//! - `execute_split` rescales the balances of a batch of holders.
+//! - `claim_cash_in_lieu` claims the cash-in-lieu owed to a holder for their fractional shares.
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+use crate as ca;
+use ca::{distribution::PER_SHARE_PRECISION, CAId, CAKind, Config};
+use codec::{Decode, Encode};
+use core::iter;
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::{DispatchError, DispatchResult},
+    ensure,
+    storage::generator::StorageDoubleMap as _,
+    weights::Weight,
+    IterableStorageDoubleMap,
+};
+use pallet_asset::{self as asset, checkpoint};
+use pallet_identity::{self as identity, PermissionedCallOriginData};
+use polymesh_common_utilities::{constants::currency::ONE_UNIT, with_transaction, CommonConfig};
+use polymesh_primitives::{EventDid, IdentityId, PortfolioId, Ticker};
+use sp_runtime::traits::{CheckedMul as _, Saturating as _};
+#[cfg(feature = "std")]
+use sp_runtime::{Deserialize, Serialize};
+use sp_std::prelude::*;
+
+type Asset<T> = asset::Module<T>;
+type Checkpoint<T> = checkpoint::Module<T>;
+type ExternalAgents<T> = pallet_external_agents::Module<T>;
+type CA<T> = ca::Module<T>;
+type Distribution<T> = ca::distribution::Module<T>;
+type Identity<T> = identity::Module<T>;
+type Portfolio<T> = pallet_portfolio::Module<T>;
+type Settlement<T> = pallet_settlement::Module<T>;
+type Sto<T> = pallet_sto::Module<T>;
+type Tender<T> = ca::tender::Module<T>;
+
+/// The ratio `numerator / denominator` by which a split rescales balances.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Encode, Decode)]
+pub struct SplitRatio {
+    /// The number of shares after the split...
+    pub numerator: u32,
+    /// ...for this number of shares before the split.
+    pub denominator: u32,
+}
+
+impl SplitRatio {
+    /// Returns whether the ratio is non-zero, finite, and not one.
+    pub fn is_valid(&self) -> bool {
+        self.numerator > 0 && self.denominator > 0 && self.numerator != self.denominator
+    }
+
+    /// Returns the ratio `denominator / numerator`, e.g., to rescale prices per share.
+    pub fn inverse(&self) -> Self {
+        Self {
+            numerator: self.denominator,
+            denominator: self.numerator,
+        }
+    }
+}
+
+/// Cash paid to holders for fractions of a share lost to rounding.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub struct CashInLieu<Balance> {
+    /// The portfolio to pay from.
+    pub from: PortfolioId,
+    /// The currency that payouts happen in.
+    pub currency: Ticker,
+    /// Price of one share, after the split, in per-million of `currency`,
+    /// i.e. `1 / 10^6`th of one `currency` token.
+    pub price: Balance,
+}
+
+/// The progress of a split.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub enum SplitStatus {
+    /// The split hasn't started executing yet.
+    Pending,
+    /// Some, but not all, holders have been rescaled.
+    /// Records whether the asset was frozen before execution started.
+    Executing { was_frozen: bool },
+    /// Every holder has been rescaled.
+    Executed,
+}
+
+impl Default for SplitStatus {
+    fn default() -> Self {
+        Self::Pending
+    }
+}
+
+/// A split's various details.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub struct Split<Balance> {
+    /// The ratio to rescale balances by.
+    pub ratio: SplitRatio,
+    /// How fractions of a share are paid for, if at all.
+    pub cash_in_lieu: Option<CashInLieu<Balance>>,
+    /// The progress of the split.
+    pub status: SplitStatus,
+}
+
+/// Weight abstraction for the stock split module.
+pub trait WeightInfo {
+    fn attach_split() -> Weight;
+    fn remove_split() -> Weight;
+    fn execute_split(holders: u32) -> Weight;
+    fn claim_cash_in_lieu() -> Weight;
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as StockSplit {
+        /// All splits, tied to their respective corporate actions (CAs).
+        ///
+        /// (CAId) => Split
+        Splits get(fn splits): map hasher(blake2_128_concat) CAId => Option<Split<T::Balance>>;
+
+        /// The raw `BalanceOf` storage key of the last holder rescaled by an executing split,
+        /// from which its next batch resumes.
+        ///
+        /// (CAId) => Storage key of the last rescaled holder
+        SplitCursor get(fn split_cursor): map hasher(blake2_128_concat) CAId => Option<Vec<u8>>;
+
+        /// The amounts locked in a portfolio by STO fundraisers, before and after an executing split
+        /// rescaled the fundraisers, pending the rescaling of the portfolio itself.
+        ///
+        /// (CAId) -> (Portfolio) -> (Locked amount before, Locked amount after)
+        OfferingLocks get(fn offering_locks): double_map hasher(blake2_128_concat) CAId, hasher(blake2_128_concat) PortfolioId => (T::Balance, T::Balance);
+
+        /// Cash-in-lieu owed to asset holders for fractional shares.
+        ///
+        /// (CAId) -> (DID) -> Amount of `currency` owed to DID.
+        CashInLieuOwed get(fn cash_in_lieu_owed): double_map hasher(blake2_128_concat) CAId, hasher(identity) IdentityId => T::Balance;
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        /// Attach a split by `ratio` to the reorganization CA identified by `ca_id`.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be a signer for a CAA of `ca_id`.
+        /// - `ca_id` identifies the CA to attach a split to.
+        /// - `ratio` to rescale every holder's balance by.
+        /// - `cash_in_lieu` specifies, if provided, the portfolio of the CAA, currency and price
+        ///    to pay holders with for fractions of a share lost to rounding.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `InvalidSplitRatio` if either part of `ratio` is zero, or if they are equal.
+        /// - `CashInLieuInAsset` if the cash-in-lieu currency is the CA's ticker.
+        /// - `InvalidCashInLieuPrice` if the cash-in-lieu price is zero.
+        /// - `AlreadyExists` if the CA already has a split.
+        /// - `NoSuchCA` if `ca_id` does not identify an existing CA.
+        /// - `CANotReorganization` if the CA is not a reorganization.
+        /// - `NoRecordDate` if CA has no record date.
+        /// - `UnauthorizedCustodian` if CAA is not the custodian of the cash-in-lieu portfolio.
+        ///
+        /// # Permissions
+        /// * Asset
+        /// * Portfolio
+        #[weight = <T as Config>::SplitWeightInfo::attach_split()]
+        pub fn attach_split(
+            origin,
+            ca_id: CAId,
+            ratio: SplitRatio,
+            cash_in_lieu: Option<CashInLieu<T::Balance>>,
+        ) {
+            ensure!(ratio.is_valid(), Error::<T>::InvalidSplitRatio);
+            if let Some(cash) = cash_in_lieu {
+                ensure!(cash.currency != ca_id.ticker, Error::<T>::CashInLieuInAsset);
+                ensure!(cash.price > 0u32.into(), Error::<T>::InvalidCashInLieuPrice);
+            }
+
+            // Ensure CA doesn't have a split yet.
+            ensure!(!<Splits<T>>::contains_key(ca_id), Error::<T>::AlreadyExists);
+
+            // Ensure origin is CAA and that they have custody over the cash-in-lieu portfolio.
+            let PermissionedCallOriginData {
+                primary_did: caa,
+                secondary_key,
+                ..
+            } = <ExternalAgents<T>>::ensure_agent_asset_perms(origin, ca_id.ticker)?;
+            if let Some(cash) = cash_in_lieu {
+                <Portfolio<T>>::ensure_portfolio_custody_and_permission(cash.from, caa, secondary_key.as_ref())?;
+                <Portfolio<T>>::ensure_portfolio_validity(&cash.from)?;
+            }
+
+            // Ensure that `ca_id` exists, that it's a reorganization with a record date.
+            let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
+            ensure!(ca.kind == CAKind::Reorganization, Error::<T>::CANotReorganization);
+            ensure!(ca.record_date.is_some(), ca::Error::<T>::NoRecordDate);
+
+            // Commit to storage + emit event.
+            let split = Split {
+                ratio,
+                cash_in_lieu,
+                status: SplitStatus::Pending,
+            };
+            <Splits<T>>::insert(ca_id, split);
+            Self::deposit_event(Event::<T>::SplitAttached(caa.for_event(), ca_id, split));
+        }
+
+        /// Removes a split whose execution hasn't started yet.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be a signer for a CAA of `ca_id`.
+        /// - `ca_id` identifies the CA with a not-yet-started split to remove.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `NoSuchSplit` if there's no split for `ca_id`.
+        /// - `SplitStarted` if the execution of the split has started.
+        #[weight = <T as Config>::SplitWeightInfo::remove_split()]
+        pub fn remove_split(origin, ca_id: CAId) {
+            let caa = <ExternalAgents<T>>::ensure_perms(origin, ca_id.ticker)?.for_event();
+            let split = Self::ensure_split_exists(ca_id)?;
+            Self::remove_split_base(caa, ca_id, &split)?;
+        }
+
+        /// Rescale the balances of at most `max_holders` holders not yet rescaled
+        /// by the split attached to `ca_id`.
+        ///
+        /// The asset is frozen by the first call, which also rescales the fundraisers offering it,
+        /// and restored to its prior state by the call rescaling the last holder, which completes the split.
+        /// Each call resumes from the last holder rescaled by the previous one.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be a signer for a CAA of `ca_id`.
+        /// - `ca_id` identifies the CA with a split to execute.
+        /// - `max_holders` is the maximum number of holders to rescale.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `NoSuchSplit` if there's no split for `ca_id`.
+        /// - `SplitAlreadyExecuted` if every holder has already been rescaled.
+        /// - `NoSuchCA` if `ca_id` does not identify an existing CA.
+        /// - `NoRecordDate` if CA has no record date.
+        /// - `SplitBeforeRecordDate` if `now < record_date`.
+        /// - `AssetLocked` if a forced transfer, tender offer, fundraiser or distribution
+        ///    still locks or holds amounts of the asset.
+        /// - `RescaleOverflow` if a rescaled amount overflows.
+        /// - `TotalSupplyAboveLimit` if the rescaled total supply exceeds the maximum supply.
+        #[weight = <T as Config>::SplitWeightInfo::execute_split(*max_holders)]
+        pub fn execute_split(origin, ca_id: CAId, max_holders: u32) {
+            let caa = <ExternalAgents<T>>::ensure_perms(origin, ca_id.ticker)?.for_event();
+            let mut split = Self::ensure_split_exists(ca_id)?;
+            let ticker = ca_id.ticker;
+
+            // Ensure the record date has passed, so that the record date checkpoint
+            // holds the balances before the split.
+            let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
+            let record_date = ca.record_date.ok_or(ca::Error::<T>::NoRecordDate)?;
+            ensure!(
+                record_date.date <= <Checkpoint<T>>::now_unix(),
+                Error::<T>::SplitBeforeRecordDate
+            );
+
+            let (ratio, token) = (split.ratio, <Asset<T>>::token_details(ticker));
+            let rescale = |v| Self::rescale_units(ratio, token.divisible, v);
+            // Amounts these lock or hold in the asset would disagree with the rescaled balances.
+            ensure!(!Self::is_locked_elsewhere(ticker), Error::<T>::AssetLocked);
+
+            with_transaction(|| -> DispatchResult {
+                // Freeze the asset on the first batch, so that balances cannot move mid-split,
+                // and rescale the fundraisers offering it.
+                let was_frozen = match split.status {
+                    SplitStatus::Pending => {
+                        <Asset<T>>::ensure_within_max_supply(rescale(token.total_supply)?)?;
+                        let was_frozen = <Asset<T>>::frozen(ticker);
+                        asset::Frozen::insert(ticker, true);
+                        let rescale_price = |p| Self::rescale(ratio.inverse(), p);
+                        let locks = <Sto<T>>::rescale_offerings(ticker, &rescale, rescale_price)?;
+                        for (portfolio, locks) in locks {
+                            <OfferingLocks<T>>::insert(ca_id, portfolio, locks);
+                        }
+                        was_frozen
+                    }
+                    SplitStatus::Executing { was_frozen } => was_frozen,
+                    SplitStatus::Executed => return Err(Error::<T>::SplitAlreadyExecuted.into()),
+                };
+
+                // Take one more holder than requested to learn whether this is the last batch.
+                let max_holders = max_holders as usize;
+                let holders = Self::holders_after(ticker, Self::split_cursor(ca_id))
+                    .take(max_holders.saturating_add(1))
+                    .collect::<Vec<_>>();
+                let batch = &holders[..holders.len().min(max_holders)];
+                for (_, holder) in batch {
+                    Self::rescale_holder(ca_id, &split, &rescale, *holder)?;
+                }
+                if let Some((key, _)) = batch.last() {
+                    SplitCursor::insert(ca_id, key);
+                }
+                Self::deposit_event(Event::<T>::SplitProgressed(caa, ca_id, batch.len() as u32));
+
+                if holders.len() <= max_holders {
+                    // Every holder has been rescaled, so restore the frozen state and clean up.
+                    asset::Frozen::insert(ticker, was_frozen);
+                    SplitCursor::remove(ca_id);
+                    <OfferingLocks<T>>::remove_prefix(ca_id);
+                    split.status = SplitStatus::Executed;
+                    let supply = <Asset<T>>::token_details(ticker).total_supply;
+                    Self::deposit_event(Event::<T>::SplitExecuted(caa, ca_id, supply));
+                } else {
+                    split.status = SplitStatus::Executing { was_frozen };
+                }
+                <Splits<T>>::insert(ca_id, split);
+                Ok(())
+            })?;
+        }
+
+        /// Claim the cash-in-lieu owed to `origin`'s DID for fractional shares
+        /// lost to rounding by the split attached to `ca_id`.
+        ///
+        /// The cash is transferred to the default portfolio of `origin`'s DID.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be a holder rescaled by the split of `ca_id`.
+        /// - `ca_id` identifies the CA with a split owing cash-in-lieu.
+        ///
+        /// # Errors
+        /// - `NoSuchSplit` if there's no split for `ca_id`.
+        /// - `NoCashInLieu` if nothing is owed to `origin`'s DID.
+        /// - `InsufficientPortfolioBalance` if the cash-in-lieu portfolio has too little `currency`.
+        /// - Other errors can occur if the compliance manager rejects the transfer.
+        #[weight = <T as Config>::SplitWeightInfo::claim_cash_in_lieu()]
+        pub fn claim_cash_in_lieu(origin, ca_id: CAId) {
+            let did = <Identity<T>>::ensure_perms(origin)?;
+            let split = Self::ensure_split_exists(ca_id)?;
+            let amount = Self::cash_in_lieu_owed(ca_id, did);
+            let cash = split
+                .cash_in_lieu
+                .filter(|_| amount > 0u32.into())
+                .ok_or(Error::<T>::NoCashInLieu)?;
+
+            let to = PortfolioId::default_portfolio(did);
+            <Asset<T>>::base_transfer(cash.from, to, &cash.currency, amount)?;
+            <CashInLieuOwed<T>>::remove(ca_id, did);
+
+            Self::deposit_event(Event::<T>::CashInLieuClaimed(did.for_event(), ca_id, amount));
+        }
+    }
+}
+
+decl_event! {
+    pub enum Event<T>
+    where
+        Balance = <T as CommonConfig>::Balance,
+    {
+        /// A split, with details included,
+        /// was attached by the DID (the CAA) to the CA specified by the `CAId`.
+        ///
+        /// (CAA of CAId's ticker, CA's ID, split details)
+        SplitAttached(EventDid, CAId, Split<Balance>),
+
+        /// A split was removed.
+        ///
+        /// (Ticker's CAA, CA's ID)
+        SplitRemoved(EventDid, CAId),
+
+        /// A batch of holders was rescaled by a split.
+        ///
+        /// (Ticker's CAA, CA's ID, holders rescaled)
+        SplitProgressed(EventDid, CAId, u32),
+
+        /// Every holder was rescaled by a split.
+        ///
+        /// (Ticker's CAA, CA's ID, new total supply)
+        SplitExecuted(EventDid, CAId, Balance),
+
+        /// A holder claimed the cash-in-lieu owed for their fractional shares.
+        ///
+        /// (Holder DID, CA's ID, amount of `currency`)
+        CashInLieuClaimed(EventDid, CAId, Balance),
+    }
+}
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// A split was attached to a CA which is not a reorganization.
+        CANotReorganization,
+        /// A split already exists for this CA.
+        AlreadyExists,
+        /// A split doesn't exist for this CA.
+        NoSuchSplit,
+        /// Either part of the split ratio is zero, or they are equal.
+        InvalidSplitRatio,
+        /// The cash-in-lieu currency is the same as the CA's ticker.
+        CashInLieuInAsset,
+        /// The cash-in-lieu price is zero.
+        InvalidCashInLieuPrice,
+        /// The split cannot be executed as the current time is before the record date.
+        SplitBeforeRecordDate,
+        /// The execution of the split has started.
+        SplitStarted,
+        /// Every holder has already been rescaled by the split.
+        SplitAlreadyExecuted,
+        /// No cash-in-lieu is owed to the holder.
+        NoCashInLieu,
+        /// Rescaling an amount by the split ratio overflows.
+        RescaleOverflow,
+        /// A forced transfer, tender offer, fundraiser or distribution locks or holds amounts of the asset.
+        AssetLocked,
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// Kill the split identified by `ca_id`.
+    crate fn remove_split_base(
+        caa: EventDid,
+        ca_id: CAId,
+        split: &Split<T::Balance>,
+    ) -> DispatchResult {
+        // Cannot remove once execution has started.
+        Self::ensure_split_not_started(split)?;
+
+        <Splits<T>>::remove(ca_id);
+        Self::deposit_event(Event::<T>::SplitRemoved(caa, ca_id));
+        Ok(())
+    }
+
+    /// Ensure that the execution of `split` hasn't started.
+    crate fn ensure_split_not_started(split: &Split<T::Balance>) -> DispatchResult {
+        ensure!(
+            split.status == SplitStatus::Pending,
+            Error::<T>::SplitStarted
+        );
+        Ok(())
+    }
+
+    /// Returns whether amounts of `ticker` are locked or held by a forced transfer,
+    /// a tender offer buying it or paying in it, a fundraiser raising it, or a distribution paying it out,
+    /// none of which a split rescales.
+    fn is_locked_elsewhere(ticker: Ticker) -> bool {
+        asset::OpenForcedTransfers::iter_prefix(ticker)
+            .next()
+            .is_some()
+            || <Tender<T>>::has_open_offers(ticker)
+            || <Sto<T>>::raises_funds_in(ticker)
+            || <Distribution<T>>::has_pending_payouts(ticker)
+    }
+
+    /// Rescale the balances of `holder` in `ca_id.ticker` by `split`,
+    /// noting any cash-in-lieu owed to them.
+    fn rescale_holder(
+        ca_id: CAId,
+        split: &Split<T::Balance>,
+        rescale: impl Fn(T::Balance) -> Result<T::Balance, DispatchError>,
+        holder: IdentityId,
+    ) -> DispatchResult {
+        let ticker = ca_id.ticker;
+
+        // Rescale every portfolio of `holder`, and the legs they send,
+        // carrying over what the legs and fundraisers lock after being rescaled.
+        let default = PortfolioId::default_portfolio(holder);
+        let portfolios = iter::once(default).chain(
+            pallet_portfolio::Portfolios::iter_prefix(holder)
+                .map(|(num, _)| PortfolioId::user_portfolio(holder, num)),
+        );
+        let mut rescaled = T::Balance::from(0u32);
+        for portfolio in portfolios {
+            let (old_legs, new_legs) =
+                <Settlement<T>>::rescale_pending_legs(portfolio, ticker, &rescale)?;
+            let (old_offerings, new_offerings) = <OfferingLocks<T>>::take(ca_id, portfolio);
+            let locks = (old_legs + old_offerings, new_legs + new_offerings);
+            rescaled +=
+                <Portfolio<T>>::rescale_portfolio_asset(&portfolio, &ticker, &rescale, locks)?;
+        }
+
+        // Rounding each portfolio down loses at most what rounding the sum does.
+        // Credit the difference to the default portfolio.
+        let balance = <Asset<T>>::balance_of(ticker, holder);
+        let new_balance = rescale(balance)?;
+        let dust = new_balance.saturating_sub(rescaled);
+        if dust > 0u32.into() {
+            let default_balance = <Portfolio<T>>::portfolio_asset_balances(default, ticker);
+            <Portfolio<T>>::set_default_portfolio_balance(holder, &ticker, default_balance + dust);
+        }
+        <Asset<T>>::set_rescaled_balance(&ticker, holder, new_balance)?;
+        if <asset::FrozenBalances<T>>::contains_key(ticker, holder) {
+            let frozen =
+                Self::rescale(split.ratio, <asset::FrozenBalances<T>>::get(ticker, holder))?;
+            <asset::FrozenBalances<T>>::insert(ticker, holder, frozen);
+        }
+
+        // Note what is owed for the fraction of a share lost to rounding to whole units.
+        if let Some(cash) = split.cash_in_lieu {
+            let fraction = Self::rescale(split.ratio, balance)? - new_balance;
+            let owed = fraction
+                .checked_mul(&cash.price)
+                .ok_or(Error::<T>::RescaleOverflow)?
+                / PER_SHARE_PRECISION.into();
+            if owed > 0u32.into() {
+                <CashInLieuOwed<T>>::insert(ca_id, holder, owed);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the holders of `ticker`, with their raw `BalanceOf` storage keys,
+    /// in storage order, starting after the holder with the key `cursor`, if any.
+    fn holders_after(
+        ticker: Ticker,
+        cursor: Option<Vec<u8>>,
+    ) -> impl Iterator<Item = (Vec<u8>, IdentityId)> {
+        let prefix = <asset::BalanceOf<T>>::storage_double_map_final_key1(ticker);
+        let mut key = cursor.unwrap_or_else(|| prefix.clone());
+        iter::from_fn(move || {
+            let next = sp_io::storage::next_key(&key).filter(|next| next.starts_with(&prefix))?;
+            // Skip the `blake2_128` part of the `blake2_128_concat` hash of the DID.
+            let did = IdentityId::decode(&mut &next[prefix.len() + 16..]).ok()?;
+            key = next.clone();
+            Some((next, did))
+        })
+    }
+
+    /// Computes `value * ratio`, rounded down.
+    fn rescale(ratio: SplitRatio, value: T::Balance) -> Result<T::Balance, DispatchError> {
+        value
+            .checked_mul(&ratio.numerator.into())
+            .map(|v| v / ratio.denominator.into())
+            .ok_or_else(|| Error::<T>::RescaleOverflow.into())
+    }
+
+    /// Computes `value * ratio`, rounded down, and for indivisible assets, down to whole units.
+    fn rescale_units(
+        ratio: SplitRatio,
+        divisible: bool,
+        value: T::Balance,
+    ) -> Result<T::Balance, DispatchError> {
+        let v = Self::rescale(ratio, value)?;
+        Ok(if divisible {
+            v
+        } else {
+            v - v % ONE_UNIT.into()
+        })
+    }
+
+    /// Ensure `ca_id` has some split and return it.
+    fn ensure_split_exists(ca_id: CAId) -> Result<Split<T::Balance>, DispatchError> {
+        <Splits<T>>::get(ca_id).ok_or_else(|| Error::<T>::NoSuchSplit.into())
+    }
+}
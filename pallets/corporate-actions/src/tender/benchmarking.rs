@@ -0,0 +1,123 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymathNetwork/Polymesh).
+// Copyright (c) 2020 Polymath
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::benchmarking::{currency, setup_ca, SEED};
+use frame_benchmarking::benchmarks;
+use pallet_compliance_manager::Module as ComplianceManager;
+use polymesh_common_utilities::{
+    asset::AssetFnTrait,
+    benchs::{user, AccountIdOf, User},
+    TestUtilsFn,
+};
+
+const MAX_TENDERS: u32 = 100;
+
+fn launch<T: Config + TestUtilsFn<AccountIdOf<T>>>(start: Moment) -> (User<T>, CAId, Ticker) {
+    let (owner, ca_id) = setup_ca::<T>(CAKind::TenderOffer);
+    let currency = currency::<T>(&owner);
+    <Module<T>>::launch_tender_offer(
+        owner.origin().into(),
+        ca_id,
+        None,
+        currency,
+        1_000_000u32.into(),
+        1_000u32.into(),
+        start,
+        4000,
+        TenderDisposal::Redeem,
+        false,
+    )
+    .unwrap();
+    (owner, ca_id, currency)
+}
+
+fn holder<T: Config + TestUtilsFn<AccountIdOf<T>>>(ticker: Ticker, u: u32) -> User<T> {
+    let holder = user::<T>("holder", u);
+    let balance = T::Balance::from(1_000_000u32);
+    <asset::BalanceOf<T>>::insert(ticker, holder.did(), balance);
+    <Portfolio<T>>::set_default_portfolio_balance(holder.did(), &ticker, balance);
+    <asset::Tokens<T>>::mutate(ticker, |token| token.total_supply += balance);
+    holder
+}
+
+fn allow_transfers<T: Config + pallet_compliance_manager::Config>(
+    owner: &User<T>,
+    ticker: Ticker,
+    holders: &[IdentityId],
+) {
+    for did in holders.iter().chain(core::iter::once(&owner.did())) {
+        <T as pallet_compliance_manager::Config>::Asset::add_investor_uniqueness_claim(
+            *did, ticker,
+        );
+    }
+    <ComplianceManager<T>>::add_compliance_requirement(
+        owner.origin().into(),
+        ticker,
+        vec![],
+        vec![],
+    )
+    .unwrap();
+}
+
+benchmarks! {
+    where_clause { where
+        T: pallet_compliance_manager::Config,
+        T: TestUtilsFn<AccountIdOf<T>>,
+    }
+
+    launch_tender_offer {
+        let (owner, ca_id) = setup_ca::<T>(CAKind::TenderOffer);
+        let currency = currency::<T>(&owner);
+        let treasury = TenderDisposal::Treasury(PortfolioKind::Default);
+    }: _(owner.origin(), ca_id, None, currency, 1_000_000u32.into(), 1_000u32.into(), 2000, 4000, treasury, false)
+    verify {
+        assert!(<TenderOffers<T>>::get(ca_id).is_some(), "tender offer not launched");
+    }
+
+    tender {
+        let (owner, ca_id, _) = launch::<T>(1000);
+        let holder = holder::<T>(ca_id.ticker, SEED);
+        // Replacing a previous tender is the more complex code path.
+        <Module<T>>::tender(holder.origin().into(), ca_id, None, 1_000u32.into()).unwrap();
+    }: _(holder.origin(), ca_id, None, 2_000u32.into())
+    verify {
+        assert!(<Tenders<T>>::get(ca_id, holder.did()).unwrap().amount == 2_000u32.into(), "not tendered");
+    }
+
+    remove_tender_offer {
+        let (owner, ca_id, _) = launch::<T>(2000);
+    }: _(owner.origin(), ca_id)
+    verify {
+        assert!(<TenderOffers<T>>::get(ca_id).is_none(), "tender offer not removed");
+    }
+
+    settle_tender_offer {
+        let t in 1..MAX_TENDERS;
+
+        let (owner, ca_id, currency) = launch::<T>(1000);
+        let holders = (0..t).map(|u| holder::<T>(ca_id.ticker, u)).collect::<Vec<_>>();
+        for holder in &holders {
+            <Module<T>>::tender(holder.origin().into(), ca_id, None, 1_000u32.into()).unwrap();
+        }
+        let dids = holders.iter().map(|h| h.did()).collect::<Vec<_>>();
+        allow_transfers::<T>(&owner, ca_id.ticker, &dids);
+        allow_transfers::<T>(&owner, currency, &dids);
+        <pallet_timestamp::Now<T>>::set(4000u32.into());
+    }: _(owner.origin(), ca_id, t)
+    verify {
+        assert!(<TenderOffers<T>>::get(ca_id).unwrap().settled, "tender offer not settled");
+    }
+}
@@ -0,0 +1,606 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymathNetwork/Polymesh).
+// Copyright (c) 2020 Polymath
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! # Tender Offer Module
+//!
+//! The tender offer module provides functionality for buying back tokens from their holders,
+//! e.g., through voluntary or mandatory tender offers and share buybacks.
+//!
+//! The process works by first initiating a tender offer corporate action (CA)
+//! through `initiate_corporate_action`, and then launching the offer via `launch_tender_offer`.
+//!
+//! When launching an offer, the portfolio to pay from is provided,
+//! as is the currency, the price of one share, and the maximum number of shares to buy.
+//! The funds needed to buy the maximum number of shares are locked in the portfolio.
+//! Additionally, a window (`start` to `end`) is provided during which holders may tender,
+//! and whether bought shares are redeemed or held in a treasury portfolio of the CAA.
+//! A mandatory offer, e.g., one required by regulation upon a change of control,
+//! must be extended to every holder, and so its CA must target everyone.
+//!
+//! During the window, holders targeted by the CA tender shares from one of their portfolios,
+//! or change or withdraw their tender, via `tender`. Tendered shares are locked in the portfolio.
+//!
+//! Once the window has closed, the offer is settled via `settle_tender_offer`,
+//! in batches of tenders, so as to bound the weight of each call.
+//! If more shares were tendered than the offer buys, shares are bought pro-rata,
+//! rounded down, and for indivisible assets, down to a whole number of units.
+//! Shares which are not bought are unlocked, as are tenders which cannot be settled,
+//! e.g., as the compliance manager rejects the transfer.
+//! Once every tender is settled, the funds not spent are unlocked.
+//!
+//! Before the window opens, a planned offer can be cancelled by calling `remove_tender_offer`.
+//!
+//! ## Overview
+//!
+//! The module provides functions for:
+//!
+//! - Launching a tender offer.
+//! - Tendering shares to an offer.
+//! - Settling an offer in batches of tenders.
+//!
+//! ### Terminology
+//!
+//! - **Currency:** The ticker paid to holders for their shares, e.g., USDC or some such.
+//! - **Tender:** The shares a holder offers to sell.
+//! - **Treasury portfolio:** A portfolio of the CAA holding bought shares, if they are not redeemed.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `launch_tender_offer` launches a tender offer.
+//! - `tender` tenders shares to an offer, or changes or withdraws a tender.
+//! - `remove_tender_offer` removes a tender offer whose window hasn't opened yet.
+//! - `settle_tender_offer` settles a batch of tenders of an offer whose window has closed.
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+use crate as ca;
+use ca::{distribution::PER_SHARE_PRECISION, CAId, CAKind, Config};
+use codec::{Decode, Encode};
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::{DispatchError, DispatchResult},
+    ensure,
+    weights::Weight,
+    IterableStorageDoubleMap, IterableStorageMap,
+};
+use pallet_asset::{self as asset, checkpoint};
+use pallet_identity::{self as identity, PermissionedCallOriginData};
+use polymesh_common_utilities::{constants::currency::ONE_UNIT, with_transaction, CommonConfig};
+use polymesh_primitives::{
+    EventDid, IdentityId, Moment, PortfolioId, PortfolioKind, PortfolioNumber, Ticker,
+};
+use sp_runtime::traits::{CheckedMul as _, Saturating as _};
+#[cfg(feature = "std")]
+use sp_runtime::{Deserialize, Serialize};
+use sp_std::prelude::*;
+
+type Asset<T> = asset::Module<T>;
+type Checkpoint<T> = checkpoint::Module<T>;
+type ExternalAgents<T> = pallet_external_agents::Module<T>;
+type CA<T> = ca::Module<T>;
+type Identity<T> = identity::Module<T>;
+type Portfolio<T> = pallet_portfolio::Module<T>;
+
+/// What happens to the shares bought by a tender offer.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub enum TenderDisposal {
+    /// The shares are redeemed, reducing the total supply.
+    Redeem,
+    /// The shares are held in the given portfolio of the CAA.
+    Treasury(PortfolioKind),
+}
+
+impl Default for TenderDisposal {
+    fn default() -> Self {
+        Self::Redeem
+    }
+}
+
+/// A tender offer's various details.
+///
+/// All information contained is used by on-chain logic.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub struct TenderOffer<Balance> {
+    /// The portfolio to pay from.
+    pub from: PortfolioId,
+    /// The currency that payments happen in.
+    pub currency: Ticker,
+    /// Price of one share, in per-million of `currency`,
+    /// i.e. `1 / 10^6`th of one `currency` token.
+    pub price: Balance,
+    /// The maximum number of shares to buy.
+    pub max_shares: Balance,
+    /// When holders may first tender.
+    pub start: Moment,
+    /// When tendering ends and the offer may be settled.
+    pub end: Moment,
+    /// What happens to the shares bought.
+    pub disposal: TenderDisposal,
+    /// Whether the offer is mandatory, and so must be extended to every holder.
+    pub mandatory: bool,
+    /// The number of shares tendered.
+    pub tendered: Balance,
+    /// The number of shares bought so far.
+    pub bought: Balance,
+    /// The amount of `currency` paid so far.
+    pub paid: Balance,
+    /// Whether every tender has been settled.
+    pub settled: bool,
+}
+
+/// The shares a holder tendered to an offer.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub struct Tender<Balance> {
+    /// The portfolio the shares are tendered from.
+    pub portfolio: PortfolioId,
+    /// The number of shares tendered.
+    pub amount: Balance,
+}
+
+/// Weight abstraction for the tender offer module.
+pub trait WeightInfo {
+    fn launch_tender_offer() -> Weight;
+    fn tender() -> Weight;
+    fn remove_tender_offer() -> Weight;
+    fn settle_tender_offer(tenders: u32) -> Weight;
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as TenderOffer {
+        /// All tender offers, tied to their respective corporate actions (CAs).
+        ///
+        /// (CAId) => TenderOffer
+        TenderOffers get(fn tender_offers): map hasher(blake2_128_concat) CAId => Option<TenderOffer<T::Balance>>;
+
+        /// The shares tendered by holders to offers, and not settled yet.
+        ///
+        /// (CAId) -> (DID) -> The shares DID tendered to the CAId.
+        Tenders get(fn tenders): double_map hasher(blake2_128_concat) CAId, hasher(identity) IdentityId => Option<Tender<T::Balance>>;
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        /// Launch a tender offer, attached to the CA identified by `ca_id`,
+        /// buying at most `max_shares` at `price` with `currency` from `portfolio` of `origin`'s DID.
+        ///
+        /// The funds needed to buy `max_shares` are locked in `portfolio`.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be a signer for a CAA of `ca_id`.
+        /// - `ca_id` identifies the CA to launch a tender offer for.
+        /// - `portfolio` specifies the portfolio number of the CAA to pay from.
+        /// - `currency` to pay holders in.
+        /// - `price` of one share, specified as a per-million, i.e. `1 / 10^6`th of one `currency` token.
+        /// - `max_shares` is the maximum number of shares to buy.
+        /// - `start` specifies when holders may first tender.
+        /// - `end` specifies when tendering ends and the offer may be settled.
+        /// - `disposal` specifies whether bought shares are redeemed or held in a treasury portfolio.
+        /// - `mandatory` specifies whether the offer must be extended to every holder.
+        ///
+        /// # Errors
+        /// - `BuyingWithAsset` if `ca_id.ticker == currency`.
+        /// - `InvalidTenderWindow` if `end <= start`.
+        /// - `InvalidTenderTerms` if `price` or `max_shares` is zero.
+        /// - `AlreadyExists` if the CA already has a tender offer.
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `UnauthorizedCustodian` if CAA is not the custodian of `portfolio` or the treasury portfolio.
+        /// - `NoSuchCA` if `ca_id` does not identify an existing CA.
+        /// - `CANotTenderOffer` if the CA is not a tender offer.
+        /// - `MandatoryOfferNotToEveryone` if the offer is mandatory but the CA doesn't target everyone.
+        /// - `FundsOverflowed` if `price * max_shares` overflows.
+        /// - `InsufficientPortfolioBalance` if `portfolio` has too little `currency`.
+        ///
+        /// # Permissions
+        /// * Asset
+        /// * Portfolio
+        #[weight = <T as Config>::TenderWeightInfo::launch_tender_offer()]
+        pub fn launch_tender_offer(
+            origin,
+            ca_id: CAId,
+            portfolio: Option<PortfolioNumber>,
+            currency: Ticker,
+            price: T::Balance,
+            max_shares: T::Balance,
+            start: Moment,
+            end: Moment,
+            disposal: TenderDisposal,
+            mandatory: bool,
+        ) {
+            ensure!(ca_id.ticker != currency, Error::<T>::BuyingWithAsset);
+            ensure!(start < end, Error::<T>::InvalidTenderWindow);
+            ensure!(
+                price > 0u32.into() && max_shares > 0u32.into(),
+                Error::<T>::InvalidTenderTerms
+            );
+
+            // Ensure CA doesn't have a tender offer yet.
+            ensure!(!<TenderOffers<T>>::contains_key(ca_id), Error::<T>::AlreadyExists);
+
+            // Ensure origin is CAA and that they have custody over `from` and the treasury.
+            let PermissionedCallOriginData {
+                primary_did: caa,
+                secondary_key,
+                ..
+            } = <ExternalAgents<T>>::ensure_agent_asset_perms(origin, ca_id.ticker)?;
+            let from = PortfolioId { did: caa, kind: portfolio.into() };
+            <Portfolio<T>>::ensure_portfolio_custody_and_permission(from, caa, secondary_key.as_ref())?;
+            <Portfolio<T>>::ensure_portfolio_validity(&from)?;
+            if let TenderDisposal::Treasury(kind) = disposal {
+                let treasury = PortfolioId { did: caa, kind };
+                <Portfolio<T>>::ensure_portfolio_custody_and_permission(treasury, caa, secondary_key.as_ref())?;
+                <Portfolio<T>>::ensure_portfolio_validity(&treasury)?;
+            }
+
+            // Ensure that `ca_id` exists, that it's a tender offer,
+            // which targets everyone if it is mandatory.
+            let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
+            ensure!(ca.kind == CAKind::TenderOffer, Error::<T>::CANotTenderOffer);
+            ensure!(
+                !mandatory || (!ca.targets.treatment.is_include() && ca.targets.identities.is_empty()),
+                Error::<T>::MandatoryOfferNotToEveryone
+            );
+
+            // Ensure `from` has the funds to buy `max_shares`, and lock them.
+            let funds = Self::payment_of(max_shares, price)?;
+            <Portfolio<T>>::ensure_sufficient_balance(&from, &currency, &funds)?;
+            <Portfolio<T>>::unchecked_lock_tokens(&from, &currency, &funds);
+
+            // Commit to storage.
+            let offer = TenderOffer {
+                from,
+                currency,
+                price,
+                max_shares,
+                start,
+                end,
+                disposal,
+                mandatory,
+                tendered: 0u32.into(),
+                bought: 0u32.into(),
+                paid: 0u32.into(),
+                settled: false,
+            };
+            <TenderOffers<T>>::insert(ca_id, offer);
+
+            // Emit event.
+            Self::deposit_event(Event::<T>::TenderOfferLaunched(caa.for_event(), ca_id, offer));
+        }
+
+        /// Tender `amount` shares from `portfolio` of `origin`'s DID
+        /// to the tender offer attached to `ca_id`, replacing any previous tender.
+        ///
+        /// The shares are locked in `portfolio` until the offer is settled.
+        /// Tendering zero shares withdraws the tender.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be a holder targeted by `ca_id`.
+        /// - `ca_id` identifies the CA with a tender offer.
+        /// - `portfolio` specifies the portfolio number of `origin`'s DID to tender from.
+        /// - `amount` of shares to tender.
+        ///
+        /// # Errors
+        /// - `NoSuchTenderOffer` if there's no tender offer for `ca_id`.
+        /// - `NotInTenderWindow` if `now < start` or `end <= now`.
+        /// - `NoSuchCA` if `ca_id` does not identify an existing CA.
+        /// - `NotTargetedByCA` if the CA does not target `origin`'s DID.
+        /// - `UnauthorizedCustodian` if `origin`'s DID is not the custodian of `portfolio`.
+        /// - `InsufficientPortfolioBalance` if `portfolio` has less than `amount` of unlocked shares.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::TenderWeightInfo::tender()]
+        pub fn tender(origin, ca_id: CAId, portfolio: Option<PortfolioNumber>, amount: T::Balance) {
+            let PermissionedCallOriginData {
+                primary_did: did,
+                secondary_key,
+                ..
+            } = <Identity<T>>::ensure_origin_call_permissions(origin)?;
+            let mut offer = Self::ensure_tender_offer_exists(ca_id)?;
+            let now = <Checkpoint<T>>::now_unix();
+            ensure!(offer.start <= now && now < offer.end, Error::<T>::NotInTenderWindow);
+            let ca = <CA<T>>::ensure_ca_exists(ca_id)?;
+            <CA<T>>::ensure_ca_targets(&ca, &did)?;
+            let portfolio = PortfolioId { did, kind: portfolio.into() };
+            <Portfolio<T>>::ensure_portfolio_custody_and_permission(portfolio, did, secondary_key.as_ref())?;
+
+            let ticker = ca_id.ticker;
+            let previous = Self::tenders(ca_id, did);
+            with_transaction(|| -> DispatchResult {
+                // Release any previous tender, and lock the new one.
+                if let Some(previous) = previous {
+                    <Portfolio<T>>::unlock_tokens(&previous.portfolio, &ticker, &previous.amount)?;
+                    offer.tendered -= previous.amount;
+                }
+                if amount > 0u32.into() {
+                    <Portfolio<T>>::ensure_sufficient_balance(&portfolio, &ticker, &amount)?;
+                    <Portfolio<T>>::unchecked_lock_tokens(&portfolio, &ticker, &amount);
+                    offer.tendered = offer.tendered.saturating_add(amount);
+                }
+                Ok(())
+            })?;
+
+            // Commit to storage + emit event.
+            if amount > 0u32.into() {
+                <Tenders<T>>::insert(ca_id, did, Tender { portfolio, amount });
+            } else {
+                <Tenders<T>>::remove(ca_id, did);
+            }
+            <TenderOffers<T>>::insert(ca_id, offer);
+            Self::deposit_event(Event::<T>::Tendered(did.for_event(), ca_id, portfolio, amount));
+        }
+
+        /// Removes a tender offer whose window hasn't opened yet,
+        /// unlocking its funds in the paying portfolio.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be a signer for a CAA of `ca_id`.
+        /// - `ca_id` identifies the CA with a not-yet-started tender offer to remove.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `NoSuchTenderOffer` if there's no tender offer for `ca_id`.
+        /// - `TenderOfferStarted` if `start <= now`.
+        #[weight = <T as Config>::TenderWeightInfo::remove_tender_offer()]
+        pub fn remove_tender_offer(origin, ca_id: CAId) {
+            let caa = <ExternalAgents<T>>::ensure_perms(origin, ca_id.ticker)?.for_event();
+            let offer = Self::ensure_tender_offer_exists(ca_id)?;
+            Self::remove_tender_offer_base(caa, ca_id, &offer)?;
+        }
+
+        /// Settle at most `max_tenders` tenders of the tender offer attached to `ca_id`,
+        /// buying the shares accepted, pro-rata if the offer is oversubscribed,
+        /// and unlocking the rest.
+        ///
+        /// The call settling the last tender unlocks the funds not spent.
+        ///
+        /// ## Arguments
+        /// - `origin` which must be a signer for a CAA of `ca_id`.
+        /// - `ca_id` identifies the CA with a tender offer to settle.
+        /// - `max_tenders` is the maximum number of tenders to settle.
+        ///
+        /// # Errors
+        /// - `UnauthorizedAgent` if `origin` is not agent-permissioned for `ticker`.
+        /// - `NoSuchTenderOffer` if there's no tender offer for `ca_id`.
+        /// - `TenderOfferNotEnded` if `now < end`.
+        /// - `TenderOfferSettled` if every tender has already been settled.
+        #[weight = <T as Config>::TenderWeightInfo::settle_tender_offer(*max_tenders)]
+        pub fn settle_tender_offer(origin, ca_id: CAId, max_tenders: u32) {
+            let caa = <ExternalAgents<T>>::ensure_perms(origin, ca_id.ticker)?.for_event();
+            let mut offer = Self::ensure_tender_offer_exists(ca_id)?;
+            ensure!(offer.end <= <Checkpoint<T>>::now_unix(), Error::<T>::TenderOfferNotEnded);
+            ensure!(!offer.settled, Error::<T>::TenderOfferSettled);
+
+            // Take one more tender than requested to learn whether this is the last batch.
+            let max_tenders = max_tenders as usize;
+            let tenders = <Tenders<T>>::iter_prefix(ca_id)
+                .take(max_tenders.saturating_add(1))
+                .collect::<Vec<_>>();
+            let done = tenders.len() <= max_tenders;
+
+            let divisible = <Asset<T>>::token_details(ca_id.ticker).divisible;
+            // Settle the whole batch or none of it, so that no tender is paid without being removed.
+            with_transaction(|| -> DispatchResult {
+                for (holder, tender) in tenders.into_iter().take(max_tenders) {
+                    Self::settle_tender(ca_id, &mut offer, divisible, holder, tender)?;
+                    <Tenders<T>>::remove(ca_id, holder);
+                }
+
+                if done {
+                    // Every tender is settled, so release the funds not spent.
+                    let funds = Self::payment_of(offer.max_shares, offer.price)?;
+                    <Portfolio<T>>::unlock_tokens(&offer.from, &offer.currency, &(funds - offer.paid))?;
+                    offer.settled = true;
+                    Self::deposit_event(Event::<T>::TenderOfferSettled(caa, ca_id, offer.bought, offer.paid));
+                }
+                <TenderOffers<T>>::insert(ca_id, offer);
+                Ok(())
+            })?;
+        }
+    }
+}
+
+decl_event! {
+    pub enum Event<T>
+    where
+        Balance = <T as CommonConfig>::Balance,
+    {
+        /// A tender offer, with details included,
+        /// was launched by the DID (the CAA) for the CA specified by the `CAId`.
+        ///
+        /// (CAA of CAId's ticker, CA's ID, tender offer details)
+        TenderOfferLaunched(EventDid, CAId, TenderOffer<Balance>),
+
+        /// A holder tendered shares to a tender offer, replacing any previous tender.
+        ///
+        /// (Holder DID, CA's ID, portfolio tendered from, shares tendered)
+        Tendered(EventDid, CAId, PortfolioId, Balance),
+
+        /// A tender offer was removed.
+        ///
+        /// (Ticker's CAA, CA's ID)
+        TenderOfferRemoved(EventDid, CAId),
+
+        /// Shares tendered by a holder were bought.
+        ///
+        /// (Holder DID, CA's ID, shares bought, `currency` paid)
+        TenderAccepted(EventDid, CAId, Balance, Balance),
+
+        /// Shares tendered by a holder could not be bought, and were unlocked.
+        ///
+        /// (Holder DID, CA's ID, shares tendered)
+        TenderRejected(EventDid, CAId, Balance),
+
+        /// Every tender of a tender offer was settled.
+        ///
+        /// (Ticker's CAA, CA's ID, shares bought, `currency` paid)
+        TenderOfferSettled(EventDid, CAId, Balance, Balance),
+    }
+}
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// A tender offer was launched for a CA which is not a tender offer.
+        CANotTenderOffer,
+        /// A tender offer already exists for this CA.
+        AlreadyExists,
+        /// A tender offer doesn't exist for this CA.
+        NoSuchTenderOffer,
+        /// The currency to pay with is the same as the CA's ticker.
+        BuyingWithAsset,
+        /// The tender window ends before it starts.
+        InvalidTenderWindow,
+        /// The price or the maximum number of shares is zero.
+        InvalidTenderTerms,
+        /// A mandatory tender offer's CA doesn't target every holder.
+        MandatoryOfferNotToEveryone,
+        /// Multiplication of the number of shares with the price overflowed.
+        FundsOverflowed,
+        /// The tender window has opened, as `start <= now` holds.
+        TenderOfferStarted,
+        /// The current time is outside the tender window.
+        NotInTenderWindow,
+        /// The tender offer cannot be settled as the current time is before the end of the window.
+        TenderOfferNotEnded,
+        /// Every tender of the tender offer has already been settled.
+        TenderOfferSettled,
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// Kill the tender offer identified by `ca_id`.
+    crate fn remove_tender_offer_base(
+        caa: EventDid,
+        ca_id: CAId,
+        offer: &TenderOffer<T::Balance>,
+    ) -> DispatchResult {
+        // Cannot remove once holders may tender.
+        ensure!(
+            <Checkpoint<T>>::now_unix() < offer.start,
+            Error::<T>::TenderOfferStarted
+        );
+
+        // Unlock and remove chain data.
+        let funds = Self::payment_of(offer.max_shares, offer.price)?;
+        <Portfolio<T>>::unlock_tokens(&offer.from, &offer.currency, &funds)?;
+        <TenderOffers<T>>::remove(ca_id);
+
+        // Emit event.
+        Self::deposit_event(Event::<T>::TenderOfferRemoved(caa, ca_id));
+        Ok(())
+    }
+
+    /// Settle `holder`'s `tender` to `offer`, buying the accepted shares, if possible,
+    /// and unlocking the tendered shares.
+    fn settle_tender(
+        ca_id: CAId,
+        offer: &mut TenderOffer<T::Balance>,
+        divisible: bool,
+        holder: IdentityId,
+        tender: Tender<T::Balance>,
+    ) -> DispatchResult {
+        let ticker = ca_id.ticker;
+        let accepted = Self::accepted_of(offer, divisible, tender.amount);
+        // `accepted <= max_shares`, so this cannot overflow.
+        let payment = Self::payment_of(accepted, offer.price)?;
+
+        // Release the tendered shares; those accepted are sold right after.
+        <Portfolio<T>>::unlock_tokens(&tender.portfolio, &ticker, &tender.amount)?;
+
+        let sold = with_transaction(|| -> DispatchResult {
+            if accepted > 0u32.into() {
+                let buyer = offer.from.did;
+                let to = match offer.disposal {
+                    TenderDisposal::Redeem => PortfolioId::default_portfolio(buyer),
+                    TenderDisposal::Treasury(kind) => PortfolioId { did: buyer, kind },
+                };
+                <Asset<T>>::base_transfer(tender.portfolio, to, &ticker, accepted)?;
+                if offer.disposal == TenderDisposal::Redeem {
+                    <Asset<T>>::redeem_from_default_portfolio(buyer, ticker, accepted)?;
+                }
+            }
+            if payment > 0u32.into() {
+                <Portfolio<T>>::unlock_tokens(&offer.from, &offer.currency, &payment)?;
+                let to = PortfolioId::default_portfolio(holder);
+                <Asset<T>>::base_transfer(offer.from, to, &offer.currency, payment)?;
+            }
+            Ok(())
+        });
+
+        let holder = holder.for_event();
+        match sold {
+            Ok(()) => {
+                offer.bought += accepted;
+                offer.paid += payment;
+                Self::deposit_event(Event::<T>::TenderAccepted(holder, ca_id, accepted, payment));
+            }
+            Err(_) => {
+                Self::deposit_event(Event::<T>::TenderRejected(holder, ca_id, tender.amount));
+            }
+        }
+        Ok(())
+    }
+
+    /// Compute the part of `amount` tendered that `offer` buys,
+    /// i.e., pro-rata if oversubscribed, rounded down,
+    /// and to whole units for indivisible assets.
+    fn accepted_of(
+        offer: &TenderOffer<T::Balance>,
+        divisible: bool,
+        amount: T::Balance,
+    ) -> T::Balance {
+        let accepted = if offer.tendered > offer.max_shares {
+            amount.saturating_mul(offer.max_shares) / offer.tendered
+        } else {
+            amount
+        };
+        if divisible {
+            accepted
+        } else {
+            accepted - accepted % ONE_UNIT.into()
+        }
+    }
+
+    /// Compute `shares * price`, i.e., what buying `shares` costs.
+    fn payment_of(shares: T::Balance, price: T::Balance) -> Result<T::Balance, DispatchError> {
+        shares
+            .checked_mul(&price)
+            // `price` was entered as a multiple of 1_000_000.
+            .map(|v| v / T::Balance::from(PER_SHARE_PRECISION))
+            .ok_or_else(|| Error::<T>::FundsOverflowed.into())
+    }
+
+    /// Returns whether an unsettled tender offer buys `ticker`, or pays for shares in it.
+    crate fn has_open_offers(ticker: Ticker) -> bool {
+        <TenderOffers<T>>::iter().any(|(ca_id, offer)| {
+            !offer.settled && (ca_id.ticker == ticker || offer.currency == ticker)
+        })
+    }
+
+    /// Ensure `ca_id` has some tender offer and return it.
+    fn ensure_tender_offer_exists(ca_id: CAId) -> Result<TenderOffer<T::Balance>, DispatchError> {
+        <TenderOffers<T>>::get(ca_id).ok_or_else(|| Error::<T>::NoSuchTenderOffer.into())
+    }
+}
@@ -18,6 +18,7 @@ use crate::*;
 use confidential_identity::mocked::make_investor_uid as make_investor_uid_v2;
 use confidential_identity_v1::mocked::make_investor_uid as make_investor_uid_v1;
 use frame_benchmarking::{account, benchmarks};
+use frame_support::{traits::UnfilteredDispatchable, IterableStorageDoubleMap, StorageMap};
 use frame_system::RawOrigin;
 use polymesh_common_utilities::{
     benchs::{AccountIdOf, User, UserBuilder},
@@ -25,12 +26,32 @@ use polymesh_common_utilities::{
 };
 use polymesh_primitives::{
     investor_zkproof_data::{v1, v2},
-    AuthorizationData, Claim, CountryCode, IdentityId, Permissions, Scope, ScopeId, SecondaryKey,
-    Signatory,
+    AuthorizationData, Claim, ClaimIssuerName, ClaimIssuerUri, CountryCode, IdentityId,
+    Permissions, Scope, ScopeId, SecondaryKey, Signatory,
 };
 use sp_std::prelude::*;
 
 const SEED: u32 = 0;
+const MAX_PROFILE_ITEMS: u32 = 100;
+const MAX_ISSUED_CLAIMS: u32 = 100;
+const MAX_BATCHED_CLAIMS: u32 = 100;
+
+/// Returns `c` jurisdiction claims, issued by `issuer`, for freshly created identities.
+fn batched_claims<T: Config + TestUtilsFn<AccountIdOf<T>>>(
+    issuer: &User<T>,
+    c: u32,
+) -> Vec<(IdentityId, Claim)> {
+    (0..c)
+        .map(|i| {
+            let target = UserBuilder::<T>::default()
+                .generate_did()
+                .seed(i)
+                .build("target");
+            let claim = Claim::Jurisdiction(CountryCode::BB, Scope::Identity(issuer.did()));
+            (target.did(), claim)
+        })
+        .collect()
+}
 
 fn setup_investor_uniqueness_claim_common<T, P, IF, CF, SF, IUF, PF>(
     name: &'static str,
@@ -375,4 +396,95 @@ benchmarks! {
     add_investor_uniqueness_claim_v2 {
         let (caller, scope, claim, proof) = setup_investor_uniqueness_claim_v2::<T>("caller");
     }: _(caller.origin, caller.did(), scope, claim, proof.0, Some(666u32.into()))
+
+    set_claim_issuer_profile {
+        let t in 0 .. MAX_PROFILE_ITEMS;
+        let j in 0 .. MAX_PROFILE_ITEMS;
+
+        let caller = UserBuilder::<T>::default().generate_did().build("caller");
+        let profile = ClaimIssuerProfile {
+            name: ClaimIssuerName(vec![b'n'; 64]),
+            uri: ClaimIssuerUri(vec![b'u'; 64]),
+            claim_types: vec![ClaimType::Accredited; t as usize],
+            jurisdictions: vec![CountryCode::BB; j as usize],
+        };
+    }: _(caller.origin, profile)
+    verify {
+        assert!(ClaimIssuers::contains_key(caller.did()), "profile not set");
+    }
+
+    remove_claim_issuer_profile {
+        let caller = UserBuilder::<T>::default().generate_did().build("caller");
+        ClaimIssuers::insert(caller.did(), ClaimIssuerProfile::default());
+    }: _(caller.origin)
+    verify {
+        assert!(!ClaimIssuers::contains_key(caller.did()), "profile not removed");
+    }
+
+    suspend_claim_issuer {
+        let issuer = UserBuilder::<T>::default().generate_did().build("issuer");
+        let origin = T::GCVotingMajorityOrigin::successful_origin();
+        let call = Call::<T>::suspend_claim_issuer(issuer.did());
+    }: {
+        call.dispatch_bypass_filter(origin).unwrap();
+    }
+    verify {
+        assert!(Module::<T>::is_issuer_suspended(issuer.did()), "issuer not suspended");
+    }
+
+    resume_claim_issuer {
+        let issuer = UserBuilder::<T>::default().generate_did().build("issuer");
+        SuspendedIssuers::insert(issuer.did(), true);
+        let origin = T::GCVotingMajorityOrigin::successful_origin();
+        let call = Call::<T>::resume_claim_issuer(issuer.did());
+    }: {
+        call.dispatch_bypass_filter(origin).unwrap();
+    }
+    verify {
+        assert!(!Module::<T>::is_issuer_suspended(issuer.did()), "issuer not resumed");
+    }
+
+    revoke_issued_claims {
+        let c in 0 .. MAX_ISSUED_CLAIMS;
+
+        let caller = UserBuilder::<T>::default().generate_did().build("caller");
+        for i in 0..c {
+            let target = UserBuilder::<T>::default().generate_did().seed(i).build("target");
+            let claim = Claim::Jurisdiction(CountryCode::BB, Scope::Identity(caller.did()));
+            Module::<T>::base_add_claim(target.did(), claim, caller.did(), None);
+        }
+    }: _(caller.origin, c)
+    verify {
+        assert!(IssuedClaims::iter_prefix(caller.did()).next().is_none(), "claims not revoked");
+    }
+
+    add_claims_batch {
+        let c in 0 .. MAX_BATCHED_CLAIMS;
+
+        let caller = UserBuilder::<T>::default().generate_did().build("caller");
+        let claims = batched_claims::<T>(&caller, c)
+            .into_iter()
+            .map(|(target, claim)| (target, claim, Some(666u32.into())))
+            .collect::<Vec<_>>();
+    }: _(caller.origin, claims)
+    verify {
+        assert!(IssuedClaims::iter_prefix(caller.did()).count() == c as usize, "claims not added");
+    }
+
+    revoke_claims_batch {
+        let c in 0 .. MAX_BATCHED_CLAIMS;
+
+        let caller = UserBuilder::<T>::default().generate_did().build("caller");
+        let claims = batched_claims::<T>(&caller, c);
+        for (target, claim) in claims.iter().cloned() {
+            Module::<T>::base_add_claim(target, claim, caller.did(), None);
+        }
+    }: _(caller.origin, claims)
+    verify {
+        assert!(IssuedClaims::iter_prefix(caller.did()).next().is_none(), "claims not revoked");
+    }
+
+    // `add_claims_batch_signed` is not benchmarked for the same reason as
+    // `add_secondary_keys_with_authorization`: its weight adds a defensive
+    // signature verification cost to `add_claims_batch`.
 }
@@ -79,6 +79,14 @@
 //! - `revoke_offchain_authorization` - Revokes the `auth` off-chain authorization of `signer`.
 //! - `add_investor_uniqueness_claim` - Adds InvestorUniqueness claim for a given target identity.
 //! - `add_investor_uniqueness_claim_v2` - Adds InvestorUniqueness claim V2 for a given target identity.
+//! - `set_claim_issuer_profile` - Publishes or updates the claim issuer profile of the caller.
+//! - `remove_claim_issuer_profile` - Removes the claim issuer profile of the caller.
+//! - `suspend_claim_issuer` - Suspends a claim issuer network-wide.
+//! - `resume_claim_issuer` - Resumes a suspended claim issuer.
+//! - `revoke_issued_claims` - Revokes, in bulk, claims issued by the caller.
+//! - `add_claims_batch` - Adds or edits many claims issued by the caller.
+//! - `revoke_claims_batch` - Revokes many claims issued by the caller.
+//! - `add_claims_batch_signed` - Adds or edits many claims signed off-chain by their issuer.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![recursion_limit = "256"]
@@ -106,26 +114,30 @@ use frame_support::{
         DispatchClass::{Normal, Operational},
         Pays, Weight,
     },
-    StorageDoubleMap,
+    IterableStorageDoubleMap, StorageDoubleMap,
 };
 use frame_system::{self as system, ensure_root, ensure_signed};
 use pallet_base::{ensure_length_ok, ensure_string_limited};
 pub use polymesh_common_utilities::traits::identity::WeightInfo;
 use polymesh_common_utilities::{
-    constants::did::{SECURITY_TOKEN, USER},
+    constants::{
+        did::{SECURITY_TOKEN, USER},
+        signing_domain,
+    },
     protocol_fee::{ChargeProtocolFee, ProtocolOp},
     traits::{
         asset::AssetSubTrait,
         group::{GroupTrait, InactiveMember},
         identity::{
-            AuthorizationNonce, Config, IdentityFnTrait, RawEvent, SecondaryKeyWithAuth,
-            TargetIdAuthorization,
+            AuthorizationNonce, ClaimsBundle, Config, IdentityFnTrait, RawEvent,
+            SecondaryKeyWithAuth, TargetIdAuthorization,
         },
         multisig::MultiSigSubTrait,
         transaction_payment::CddAndFeeDetails,
         AccountCallPermissionsData, CheckAccountCallPermissions,
     },
-    Context, SystematicIssuers, GC_DID, SYSTEMATIC_ISSUERS,
+    with_each_transaction, with_transaction, Context, SystematicIssuers, GC_DID,
+    SYSTEMATIC_ISSUERS,
 };
 use polymesh_primitives::identity_id::GenesisIdentityRecord;
 use polymesh_primitives::{
@@ -133,9 +145,10 @@ use polymesh_primitives::{
     investor_zkproof_data::{v1::InvestorZKProofData, InvestorZKProofData as InvestorZKProof},
     secondary_key::{self, api::LegacyPermissions},
     storage_migrate_on, storage_migration_ver, valid_proof_of_investor, Authorization,
-    AuthorizationData, AuthorizationError, AuthorizationType, CddId, Claim, ClaimType,
-    DispatchableName, ExtrinsicPermissions, Identity as DidRecord, IdentityClaim, IdentityId,
-    InvestorUid, PalletName, Permissions, Scope, ScopeId, SecondaryKey, Signatory, Ticker,
+    AuthorizationData, AuthorizationError, AuthorizationType, CddId, Claim, ClaimIssuerProfile,
+    ClaimType, DispatchableName, ExtrinsicPermissions, Identity as DidRecord, IdentityClaim,
+    IdentityId, InvestorUid, PalletName, Permissions, Scope, ScopeId, SecondaryKey, Signatory,
+    Ticker,
 };
 use sp_core::sr25519::Signature;
 use sp_io::hashing::blake2_256;
@@ -152,7 +165,7 @@ type CallPermissions<T> = pallet_permissions::Module<T>;
 
 // A value placed in storage that represents the current version of the this storage. This value
 // is used by the `on_runtime_upgrade` logic to determine whether we run storage migration logic.
-storage_migration_ver!(3);
+storage_migration_ver!(4);
 
 decl_storage! {
     trait Store for Module<T: Config> as identity {
@@ -183,6 +196,9 @@ decl_storage! {
         /// Authorization nonce per Identity. Initially is 0.
         pub OffChainAuthorizationNonce get(fn offchain_authorization_nonce): map hasher(twox_64_concat) IdentityId => AuthorizationNonce;
 
+        /// Nonce of the next `ClaimsBundle` of each issuer. Initially is 0.
+        pub ClaimsBundleNonce get(fn claims_bundle_nonce): map hasher(twox_64_concat) IdentityId => AuthorizationNonce;
+
         /// Inmediate revoke of any off-chain authorization.
         pub RevokeOffChainAuthorization get(fn is_offchain_authorization_revoked):
             map hasher(blake2_128_concat) (Signatory<T::AccountId>, TargetIdAuthorization<T::Moment>) => bool;
@@ -203,8 +219,22 @@ decl_storage! {
         /// change the primary key of an identity.
         pub CddAuthForPrimaryKeyRotation get(fn cdd_auth_for_primary_key_rotation): bool;
 
+        /// Issuer DID -> Profile published by the claim issuer.
+        pub ClaimIssuers get(fn claim_issuer_profile):
+            map hasher(twox_64_concat) IdentityId => Option<ClaimIssuerProfile>;
+
+        /// Issuer DID -> Whether the claim issuer is suspended network-wide.
+        /// Claims of suspended issuers are ignored by compliance evaluation.
+        pub SuspendedIssuers get(fn is_issuer_suspended):
+            map hasher(twox_64_concat) IdentityId => bool;
+
+        /// (Issuer DID, (claim first key, claim second key)) -> Whether the claim exists.
+        /// Index of the claims issued by each issuer, used for bulk revocation.
+        pub IssuedClaims get(fn issued_claims): double_map hasher(twox_64_concat) IdentityId,
+            hasher(blake2_128_concat) (Claim1stKey, Claim2ndKey) => bool;
+
         /// Storage version.
-        StorageVersion get(fn storage_version) build(|_| Version::new(3).unwrap()): Version;
+        StorageVersion get(fn storage_version) build(|_| Version::new(4).unwrap()): Version;
     }
     add_extra_genesis {
         // Identities at genesis.
@@ -273,6 +303,11 @@ decl_module! {
             let storage_ver = StorageVersion::get();
 
             storage_migrate_on!(storage_ver, 3, { Claims::translate(migration::migrate_claim); });
+            storage_migrate_on!(storage_ver, 4, {
+                for (pk, sk, _) in Claims::iter() {
+                    IssuedClaims::insert(sk.issuer, (pk, sk), true);
+                }
+            });
 
             // It's gonna be alot, so lets pretend its 0 anyways.
             0
@@ -443,6 +478,7 @@ decl_module! {
                 Claim::CustomerDueDiligence(..) => Self::base_add_cdd_claim(target, claim, issuer, expiry),
                 Claim::InvestorUniqueness(..) | Claim::InvestorUniquenessV2(..) => Err(Error::<T>::ClaimVariantNotAllowed.into()),
                 _ => {
+                    ensure!(!Self::is_issuer_suspended(issuer), Error::<T>::IssuerSuspended);
                     T::ProtocolFee::charge_fee(ProtocolOp::IdentityAddClaim)?;
                     Self::base_add_claim(target, claim, issuer, expiry);
                     Ok(())
@@ -748,6 +784,154 @@ decl_module! {
             let issuer = Self::ensure_perms(origin)?;
             Self::base_revoke_claim(target, claim_type, issuer, scope)
         }
+
+        /// Publishes or updates the claim issuer profile of the caller's identity.
+        ///
+        /// # Errors
+        /// - `TooLong` if any of the fields of `profile` is too long.
+        #[weight = <T as Config>::WeightInfo::set_claim_issuer_profile(
+            profile.claim_types.len() as u32,
+            profile.jurisdictions.len() as u32,
+        )]
+        pub fn set_claim_issuer_profile(origin, profile: ClaimIssuerProfile) -> DispatchResult {
+            let issuer = Self::ensure_perms(origin)?;
+            ensure_string_limited::<T>(&profile.name)?;
+            ensure_string_limited::<T>(&profile.uri)?;
+            ensure_length_ok::<T>(profile.claim_types.len())?;
+            ensure_length_ok::<T>(profile.jurisdictions.len())?;
+
+            <ClaimIssuers>::insert(issuer, profile.clone());
+            Self::deposit_event(RawEvent::ClaimIssuerProfileSet(issuer, profile));
+            Ok(())
+        }
+
+        /// Removes the claim issuer profile of the caller's identity.
+        ///
+        /// # Errors
+        /// - `NoClaimIssuerProfile` if the caller has not published a profile.
+        #[weight = <T as Config>::WeightInfo::remove_claim_issuer_profile()]
+        pub fn remove_claim_issuer_profile(origin) -> DispatchResult {
+            let issuer = Self::ensure_perms(origin)?;
+            ensure!(<ClaimIssuers>::contains_key(issuer), Error::<T>::NoClaimIssuerProfile);
+
+            <ClaimIssuers>::remove(issuer);
+            Self::deposit_event(RawEvent::ClaimIssuerProfileRemoved(issuer));
+            Ok(())
+        }
+
+        /// Assuming this is executed by the GC voting majority, suspends `issuer` network-wide.
+        /// Suspended issuers cannot add claims and their claims are ignored by compliance evaluation.
+        ///
+        /// # Errors
+        /// - `IssuerSuspended` if `issuer` is already suspended.
+        #[weight = (<T as Config>::WeightInfo::suspend_claim_issuer(), Operational, Pays::Yes)]
+        pub fn suspend_claim_issuer(origin, issuer: IdentityId) -> DispatchResult {
+            T::GCVotingMajorityOrigin::ensure_origin(origin)?;
+            ensure!(!Self::is_issuer_suspended(issuer), Error::<T>::IssuerSuspended);
+
+            <SuspendedIssuers>::insert(issuer, true);
+            Self::deposit_event(RawEvent::ClaimIssuerSuspended(GC_DID, issuer));
+            Ok(())
+        }
+
+        /// Assuming this is executed by the GC voting majority, resumes the suspended `issuer`.
+        ///
+        /// # Errors
+        /// - `IssuerNotSuspended` if `issuer` is not suspended.
+        #[weight = (<T as Config>::WeightInfo::resume_claim_issuer(), Operational, Pays::Yes)]
+        pub fn resume_claim_issuer(origin, issuer: IdentityId) -> DispatchResult {
+            T::GCVotingMajorityOrigin::ensure_origin(origin)?;
+            ensure!(Self::is_issuer_suspended(issuer), Error::<T>::IssuerNotSuspended);
+
+            <SuspendedIssuers>::remove(issuer);
+            Self::deposit_event(RawEvent::ClaimIssuerResumed(GC_DID, issuer));
+            Ok(())
+        }
+
+        /// Revokes up to `max` claims issued by the caller's identity.
+        /// `InvestorUniqueness*` claims are not revoked, as they are bound to the target's balance.
+        ///
+        /// A `ClaimRevoked` event is emitted for each revoked claim, followed by an
+        /// `IssuedClaimsRevoked` event stating whether more claims remain to be revoked.
+        #[weight = <T as Config>::WeightInfo::revoke_issued_claims(*max)]
+        pub fn revoke_issued_claims(origin, max: u32) -> DispatchResult {
+            let issuer = Self::ensure_perms(origin)?;
+            Self::base_revoke_issued_claims(issuer, max)
+        }
+
+        /// Adds or edits many claims, each given as `(target, claim, expiry)`, issued by the caller.
+        /// The protocol fee of `add_claim` is charged once per claim, except for CDD claims.
+        /// Either all claims are added or none is.
+        ///
+        /// # Errors
+        /// - `DidMustAlreadyExist` if any target does not exist.
+        /// - `ClaimVariantNotAllowed` if any claim is an `InvestorUniqueness*` claim.
+        /// - `IssuerSuspended` if the caller is suspended as a claim issuer.
+        #[weight = <T as Config>::WeightInfo::add_claims_batch(claims.len() as u32)]
+        pub fn add_claims_batch(
+            origin,
+            claims: Vec<(IdentityId, Claim, Option<T::Moment>)>,
+        ) -> DispatchResult {
+            let issuer = Self::ensure_perms(origin)?;
+            Self::base_add_claims_batch(issuer, claims)
+        }
+
+        /// Revokes many claims, each given as `(target, claim)`, issued by the caller.
+        /// Either all claims are revoked or none is.
+        ///
+        /// # Errors
+        /// - See `revoke_claim`.
+        #[weight = <T as Config>::WeightInfo::revoke_claims_batch(claims.len() as u32)]
+        pub fn revoke_claims_batch(origin, claims: Vec<(IdentityId, Claim)>) -> DispatchResult {
+            let issuer = Self::ensure_perms(origin)?;
+            with_each_transaction(claims, |(target, claim)| {
+                let scope = claim.as_scope().cloned();
+                Self::base_revoke_claim(target, claim.claim_type(), issuer, scope)
+            })
+        }
+
+        /// Adds or edits the claims of `bundle`, signed off-chain by `signer`,
+        /// which must be a key of the issuer of the bundle permissioned to call `add_claims_batch`.
+        /// This allows claim issuers to keep their keys without POLYX,
+        /// as the caller, acting as a relayer, pays all fees.
+        ///
+        /// `signature` must be over `claims_bundle_payload(bundle)`,
+        /// i.e., `bundle` tagged with its domain and the genesis hash of the chain.
+        ///
+        /// # Errors
+        /// - `AuthorizationExpired` if `bundle` has expired.
+        /// - `InvalidAuthorizationNonce` if the nonce of `bundle` is not the current
+        /// claims bundle nonce of its issuer.
+        /// - `NotASigner` if `signer` is not a key of the issuer of `bundle`.
+        /// - `Unauthorized` if `signer` is a secondary key not permissioned to call `add_claims_batch`.
+        /// - `InvalidAuthorizationSignature` if `signature` does not match `bundle` and `signer`.
+        /// - See `add_claims_batch`.
+        #[weight = <T as Config>::WeightInfo::add_claims_batch_signed(bundle.claims.len() as u32)]
+        pub fn add_claims_batch_signed(
+            origin,
+            bundle: ClaimsBundle<T::Moment>,
+            signer: T::AccountId,
+            signature: T::OffChainSignature,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let issuer = bundle.issuer;
+            let nonce = Self::claims_bundle_nonce(issuer);
+            ensure!(
+                <pallet_timestamp::Module<T>>::get() < bundle.expires_at,
+                Error::<T>::AuthorizationExpired
+            );
+            ensure!(bundle.nonce == nonce, Error::<T>::InvalidAuthorizationNonce);
+            Self::ensure_key_call_permissions(issuer, &signer, "add_claims_batch")?;
+            ensure!(
+                signature.verify(Self::claims_bundle_payload(&bundle).as_slice(), &signer),
+                Error::<T>::InvalidAuthorizationSignature
+            );
+
+            Self::base_add_claims_batch(issuer, bundle.claims)?;
+            ClaimsBundleNonce::insert(issuer, nonce + 1);
+            Ok(())
+        }
     }
 }
 
@@ -816,7 +1000,15 @@ decl_error! {
         /// Do not allow forwarded call to be called recursively
         RecursionNotAllowed,
         /// Claim and Proof versions are different.
-        ClaimAndProofVersionsDoNotMatch
+        ClaimAndProofVersionsDoNotMatch,
+        /// The claim issuer is suspended.
+        IssuerSuspended,
+        /// The claim issuer is not suspended.
+        IssuerNotSuspended,
+        /// The identity has not published a claim issuer profile.
+        NoClaimIssuerProfile,
+        /// The nonce of the off-chain authorization is not the expected one.
+        InvalidAuthorizationNonce,
     }
 }
 
@@ -1121,6 +1313,42 @@ impl<T: Config> Module<T> {
         }
     }
 
+    /// Ensures that `key`, which signed a payload off-chain on behalf of `did`,
+    /// is either its primary key or a secondary key permissioned to call `dispatchable` of this pallet.
+    pub fn ensure_key_call_permissions(
+        did: IdentityId,
+        key: &T::AccountId,
+        dispatchable: &str,
+    ) -> DispatchResult {
+        let record = <DidRecords<T>>::get(did);
+        if record.primary_key == *key {
+            return Ok(());
+        }
+        let signer = Signatory::Account(key.clone());
+        let sk = record
+            .secondary_keys
+            .into_iter()
+            .find(|sk| sk.signer == signer)
+            .ok_or(Error::<T>::NotASigner)?;
+        ensure!(
+            !Self::is_did_frozen(did)
+                && sk.has_extrinsic_permission(
+                    &PalletName::from("Identity"),
+                    &DispatchableName::from(dispatchable)
+                ),
+            Error::<T>::Unauthorized
+        );
+        Ok(())
+    }
+
+    /// Returns the payload to sign off-chain for `bundle` to be accepted by `add_claims_batch_signed`,
+    /// i.e., `bundle` prefixed with its domain tag and the genesis hash of this chain.
+    pub fn claims_bundle_payload(bundle: &ClaimsBundle<T::Moment>) -> Vec<u8> {
+        let genesis_hash =
+            <system::Module<T>>::block_hash(<T as system::Config>::BlockNumber::zero());
+        (signing_domain::CLAIMS_BUNDLE, genesis_hash, bundle).encode()
+    }
+
     /// It checks if `key` is a secondary key of `did` identity.
     pub fn is_signer(did: IdentityId, signer: &Signatory<T::AccountId>) -> bool {
         let record = <DidRecords<T>>::get(did);
@@ -1158,6 +1386,20 @@ impl<T: Config> Module<T> {
             .find(|c| Self::is_identity_claim_not_expired_at(c, now))
     }
 
+    /// It iterates over all non-expired `claim_type` claims for target identity `id`, which were
+    /// issued by `issuer` under any scope.
+    pub fn fetch_claims_by_issuer(
+        id: IdentityId,
+        claim_type: ClaimType,
+        issuer: IdentityId,
+    ) -> impl Iterator<Item = IdentityClaim> {
+        let now = <pallet_timestamp::Module<T>>::get();
+
+        Self::fetch_base_claims(id, claim_type).filter(move |c| {
+            c.claim_issuer == issuer && Self::is_identity_claim_not_expired_at(c, now)
+        })
+    }
+
     /// See `Self::fetch_cdd`.
     #[inline]
     pub fn has_valid_cdd(claim_for: IdentityId) -> bool {
@@ -1497,6 +1739,7 @@ impl<T: Config> Module<T> {
         };
 
         Claims::insert(&pk, &sk, id_claim.clone());
+        IssuedClaims::insert(issuer, (pk, sk), true);
         Self::deposit_event(RawEvent::ClaimAdded(target, id_claim));
     }
 
@@ -1686,11 +1929,80 @@ impl<T: Config> Module<T> {
             );
         }
 
+        IssuedClaims::remove(issuer, (pk.clone(), sk.clone()));
         let claim = Claims::take(&pk, &sk);
         Self::deposit_event(RawEvent::ClaimRevoked(target, claim));
         Ok(())
     }
 
+    /// Adds or edits `claims`, issued by `issuer`, charging the protocol fee for them.
+    /// Either all claims are added or none is.
+    fn base_add_claims_batch(
+        issuer: IdentityId,
+        claims: Vec<(IdentityId, Claim, Option<T::Moment>)>,
+    ) -> DispatchResult {
+        let charged = claims
+            .iter()
+            .filter(|(_, claim, _)| !matches!(claim, Claim::CustomerDueDiligence(..)))
+            .count();
+        with_transaction(|| {
+            T::ProtocolFee::batch_charge_fee(ProtocolOp::IdentityAddClaim, charged)?;
+            claims
+                .into_iter()
+                .try_for_each(|(target, claim, expiry)| -> DispatchResult {
+                    ensure!(
+                        Self::is_identity_exists(&target),
+                        Error::<T>::DidMustAlreadyExist
+                    );
+                    match &claim {
+                        Claim::CustomerDueDiligence(..) => {
+                            Self::base_add_cdd_claim(target, claim, issuer, expiry)
+                        }
+                        Claim::InvestorUniqueness(..) | Claim::InvestorUniquenessV2(..) => {
+                            Err(Error::<T>::ClaimVariantNotAllowed.into())
+                        }
+                        _ => {
+                            ensure!(
+                                !Self::is_issuer_suspended(issuer),
+                                Error::<T>::IssuerSuspended
+                            );
+                            Self::base_add_claim(target, claim, issuer, expiry);
+                            Ok(())
+                        }
+                    }
+                })
+        })
+    }
+
+    /// Returns the keys of the claims issued by `issuer` that can be revoked in bulk,
+    /// i.e., all but `InvestorUniqueness*` claims.
+    fn revocable_issued_claims(
+        issuer: IdentityId,
+    ) -> impl Iterator<Item = (Claim1stKey, Claim2ndKey)> {
+        IssuedClaims::iter_prefix(issuer)
+            .map(|(keys, _)| keys)
+            .filter(|(pk, _)| {
+                !matches!(
+                    pk.claim_type,
+                    ClaimType::InvestorUniqueness | ClaimType::InvestorUniquenessV2
+                )
+            })
+    }
+
+    /// Revokes up to `max` claims issued by `issuer`, without any previous security check.
+    fn base_revoke_issued_claims(issuer: IdentityId, max: u32) -> DispatchResult {
+        let keys = Self::revocable_issued_claims(issuer)
+            .take(max as usize)
+            .collect::<Vec<_>>();
+        let count = keys.len() as u32;
+        for (pk, sk) in keys {
+            Self::base_revoke_claim(pk.target, pk.claim_type, issuer, sk.scope)?;
+        }
+        let remaining = Self::revocable_issued_claims(issuer).next().is_some();
+        Self::deposit_event(RawEvent::IssuedClaimsRevoked(issuer, count, remaining));
+        Ok(())
+    }
+
     /// Returns an auth id if it is present and not expired.
     pub fn get_non_expired_auth(
         target: &Signatory<T::AccountId>,
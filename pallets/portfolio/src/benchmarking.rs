@@ -20,7 +20,7 @@ use polymesh_common_utilities::{
     benchs::{user, AccountIdOf, User},
     TestUtilsFn,
 };
-use polymesh_primitives::{AuthorizationData, PortfolioName, Signatory};
+use polymesh_primitives::{AuthorizationData, PortfolioName, PortfolioPolicy, Signatory, Ticker};
 use sp_std::prelude::*;
 
 const PORTFOLIO_NAME_LEN: usize = 500;
@@ -115,6 +115,36 @@ benchmarks! {
         assert_eq!(<PortfolioAssetBalances<T>>::get(&user_portfolio, &first_ticker), amount);
     }
 
+    rebalance_portfolios {
+        // Number of moves
+        let m in 1 .. 500;
+        let target = user::<T>("target", 0);
+        let amount = T::Balance::from(10u32);
+        let next_portfolio_num = NextPortfolioNumber::get(&target.did());
+        let default_portfolio = PortfolioId::default_portfolio(target.did());
+        let user_portfolio = PortfolioId::user_portfolio(target.did(), next_portfolio_num);
+        Module::<T>::create_portfolio(target.origin.clone().into(), PortfolioName(vec![65u8; 5])).unwrap();
+
+        let moves = (0..m as u64)
+            .map(|x| {
+                let ticker = Ticker::generate_into(x);
+                <PortfolioAssetBalances<T>>::insert(&default_portfolio, &ticker, amount);
+                PortfolioMove {
+                    from: default_portfolio,
+                    to: user_portfolio,
+                    ticker,
+                    amount,
+                    memo: make_worst_memo(),
+                }
+            })
+            .collect::<Vec<_>>();
+        let first_ticker = Ticker::generate_into(0u64);
+    }: _(target.origin, moves)
+    verify {
+        assert_eq!(<PortfolioAssetBalances<T>>::get(&default_portfolio, &first_ticker), 0u32.into());
+        assert_eq!(<PortfolioAssetBalances<T>>::get(&user_portfolio, &first_ticker), amount);
+    }
+
     rename_portfolio {
         // Length of portfolio name
         let i in 1 .. PORTFOLIO_NAME_LEN.try_into().unwrap();
@@ -156,4 +186,20 @@ benchmarks! {
     verify {
         assert_custodian::<T>(user_portfolio, &custodian, true);
     }
+
+    set_portfolio_policy {
+        let t in 0 .. MAX_ALLOWED_TICKERS as u32;
+
+        let (owner, user_portfolio) = owner_portfolio::<T>();
+        let allowed_tickers = (0..t)
+            .map(|i| Ticker::generate_into(i.into()))
+            .collect::<Vec<_>>();
+        let policy = PortfolioPolicy {
+            allowed_tickers: Some(allowed_tickers),
+            affirmation_threshold: Some(1_000u32.into()),
+        };
+    }: _(owner.origin, user_portfolio, policy.clone())
+    verify {
+        assert_eq!(PortfolioPolicies::<T>::get(&user_portfolio), policy);
+    }
 }
@@ -28,7 +28,11 @@
 //! - `delete_portfolio`: Deletes an existing user portfolio.
 //! - `move_portfolio_funds`: Moves specified amounts of assets from one portfolio to another portfolio
 //!   of the same DID.
+//! - `rebalance_portfolios`: Atomically applies a set of moves between portfolios of the same DID,
+//!   across all the identities whose portfolios are in the custody of the caller.
 //! - `rename_portfolio`: Renames a user portfolio.
+//! - `set_portfolio_policy`: Sets the assets a portfolio can receive and the amount above which
+//!   outgoing transfers must be affirmed by its custodian.
 //!
 //! ### Public Functions
 //!
@@ -38,7 +42,13 @@
 //! - `unchecked_transfer_portfolio_balance`: Transfers funds from one portfolio to another.
 //! - `ensure_portfolio_custody`: Makes sure that the given identity has custodian access over the portfolio.
 //! - `ensure_portfolio_transfer_validity`: Makes sure that a transfer between two portfolios is valid.
+//! - `add_vesting_schedule`: Attaches a vesting schedule to the balance of an asset in a portfolio.
+//! - `vesting_locked`: Returns the amount of an asset in a portfolio that is still locked by vesting schedules.
+//! - `rescale_portfolio_asset`: Rescales the balance, locked amount and vesting schedules of an asset in a portfolio.
 //! - `quit_portfolio_custody`: Returns the custody of the portfolio to the owner unilaterally.
+//! - `ensure_receivable`: Makes sure that the policy of a portfolio allows it to receive an asset.
+//! - `with_custodian_affirmation`: Executes transfers affirmed by the custodians of their portfolios.
+//! - `create_custodied_portfolio`: Creates a user portfolio in the custody of another identity.
 
 #![feature(const_option)]
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -48,25 +58,36 @@ pub mod benchmarking;
 
 use codec::{Decode, Encode};
 use frame_support::{
-    decl_error, decl_module, decl_storage, dispatch::DispatchResult, ensure, storage::StorageValue,
-    weights::Weight, IterableStorageDoubleMap,
+    decl_error, decl_module, decl_storage,
+    dispatch::{DispatchError, DispatchResult},
+    ensure,
+    storage::StorageValue,
+    weights::Weight,
+    IterableStorageDoubleMap,
 };
 use pallet_identity::{self as identity, PermissionedCallOriginData};
-use polymesh_common_utilities::traits::balances::Memo;
+use polymesh_common_utilities::traits::{asset::AssetSubTrait, balances::Memo};
 use polymesh_common_utilities::traits::portfolio::PortfolioSubTrait;
 pub use polymesh_common_utilities::traits::portfolio::{Config, Event, RawEvent, WeightInfo};
 use polymesh_common_utilities::CommonConfig;
 use polymesh_primitives::{
     extract_auth, identity_id::PortfolioValidityResult, storage_migration_ver, IdentityId,
-    PortfolioId, PortfolioKind, PortfolioName, PortfolioNumber, SecondaryKey, Ticker,
+    PortfolioId, PortfolioKind, PortfolioName, PortfolioNumber, PortfolioPolicy, SecondaryKey,
+    Ticker, VestingSchedule,
 };
-use sp_arithmetic::traits::{CheckedSub, Saturating};
-use sp_std::{iter, mem, prelude::Vec};
+use sp_arithmetic::traits::{CheckedSub, SaturatedConversion, Saturating};
+use sp_std::{collections::btree_map::BTreeMap, iter, mem, prelude::Vec};
 
 storage_migration_ver!(1);
 
 type Identity<T> = identity::Module<T>;
 
+/// Maximum number of vesting schedules on the balance of an asset in a portfolio.
+pub const MAX_VESTING_SCHEDULES: usize = 10;
+
+/// Maximum number of assets in the allowlist of a portfolio policy.
+pub const MAX_ALLOWED_TICKERS: usize = 100;
+
 /// The ticker and balance of an asset to be moved from one portfolio to another.
 #[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct MovePortfolioItem<Balance> {
@@ -79,6 +100,22 @@ pub struct MovePortfolioItem<Balance> {
     pub memo: Option<Memo>,
 }
 
+/// A move of an asset between two portfolios of the same identity, as part of a rebalancing.
+#[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PortfolioMove<Balance> {
+    /// The portfolio the asset is moved from.
+    pub from: PortfolioId,
+    /// The portfolio the asset is moved to.
+    pub to: PortfolioId,
+    /// The ticker of the asset to be moved.
+    pub ticker: Ticker,
+    /// The balance of the asset to be moved.
+    pub amount: Balance,
+    /// The memo of the move.
+    /// Currently only used in events.
+    pub memo: Option<Memo>,
+}
+
 decl_storage! {
     trait Store for Module<T: Config> as Portfolio {
         /// The set of existing portfolios with their names. If a certain pair of a DID and
@@ -107,6 +144,17 @@ decl_storage! {
         /// `false` values are never explicitly stored in the map, and are instead inferred by the absence of a key.
         pub PortfoliosInCustody get(fn portfolios_in_custody):
             double_map hasher(twox_64_concat) IdentityId, hasher(twox_64_concat) PortfolioId => bool;
+        /// Vesting schedules on the balance of an asset in a portfolio.
+        /// The amounts still locked by the schedules show up in portfolio balance but can not be transferred away.
+        pub VestingSchedules get(fn vesting_schedules):
+            double_map hasher(twox_64_concat) PortfolioId, hasher(blake2_128_concat) Ticker =>
+            Vec<VestingSchedule<T::Balance>>;
+        /// Restrictions set by the custodian of a portfolio on the transfers involving it.
+        pub PortfolioPolicies get(fn portfolio_policy):
+            map hasher(twox_64_concat) PortfolioId => PortfolioPolicy<T::Balance>;
+        /// Whether the transfers being executed have been affirmed by the custodians of their portfolios.
+        /// Only `true` while the settlement engine executes affirmed instructions.
+        CustodianAffirmed get(fn custodian_affirmed): bool;
         /// Storage version.
         StorageVersion get(fn storage_version) build(|_| Version::new(1).unwrap()): Version;
     }
@@ -131,7 +179,21 @@ decl_error! {
         /// The portfolio still has some asset balance left
         PortfolioNotEmpty,
         /// The portfolios belong to different identities
-        DifferentIdentityPortfolios
+        DifferentIdentityPortfolios,
+        /// The vesting schedule has no amount or its release parameters are invalid.
+        InvalidVestingSchedule,
+        /// The balance of the asset in the portfolio already has `MAX_VESTING_SCHEDULES` active vesting schedules.
+        TooManyVestingSchedules,
+        /// The policy of the portfolio allows more than `MAX_ALLOWED_TICKERS` assets.
+        TooManyAllowedTickers,
+        /// The policy of the receiving portfolio does not allow the asset.
+        TickerNotAllowedByPolicy,
+        /// The policy of the sending portfolio requires the transfer to be affirmed by its custodian.
+        CustodianAffirmationRequired,
+        /// Transfers of the asset to and from the portfolio are frozen.
+        PortfolioFrozen,
+        /// The move would take funds out of the custody of the identity beyond its unfrozen balance.
+        FrozenBalanceExceeded
     }
 }
 
@@ -207,6 +269,7 @@ decl_module! {
         /// * `DifferentIdentityPortfolios` if the sender and receiver portfolios belong to different identities
         /// * `UnauthorizedCustodian` if the caller is not the custodian of the from portfolio
         /// * `InsufficientPortfolioBalance` if the sender does not have enough free balance
+        /// * See `ensure_moves_unfrozen`.
         ///
         /// # Permissions
         /// * Portfolio
@@ -233,6 +296,10 @@ decl_module! {
 
             // Ensure the receiving portfolio exists.
             Self::ensure_portfolio_validity(&to)?;
+            // Ensure the receiving portfolio's policy allows the assets.
+            for item in &items {
+                Self::ensure_receivable(&to, &item.ticker)?;
+            }
             // Ensure that the secondary key has access to the receiver's portfolio.
             Self::ensure_user_portfolio_permission(secondary_key.as_ref(), to)?;
 
@@ -240,6 +307,8 @@ decl_module! {
             for item in &items {
                 Self::ensure_sufficient_balance(&from, &item.ticker, &item.amount)?;
             }
+            // Ensure freezes allow the moves.
+            Self::ensure_moves_unfrozen(items.iter().map(|item| (from, to, item.ticker, item.amount)))?;
 
             // Commit changes.
             for item in items {
@@ -255,6 +324,80 @@ decl_module! {
             }
         }
 
+        /// Atomically applies a set of moves between portfolios, e.g., to rebalance the sub-portfolios
+        /// of a fund. Each move is between two portfolios of the same identity, and its sending
+        /// portfolio must be in the custody of the caller, so a custodian can rebalance the portfolios
+        /// of several identities at once.
+        ///
+        /// Balances are checked against the net amount moved out of each portfolio,
+        /// so an asset can be moved out of a portfolio that only receives it within the same call.
+        ///
+        /// # Errors
+        /// * `PortfolioDoesNotExist` if a receiving portfolio does not exist.
+        /// * `DestinationIsSamePortfolio` if a move has the same sending and receiving portfolio.
+        /// * `DifferentIdentityPortfolios` if a move is between portfolios of different identities.
+        /// * `UnauthorizedCustodian` if the caller is not the custodian of a sending portfolio.
+        /// * `TickerNotAllowedByPolicy` if the policy of a receiving portfolio does not allow the asset.
+        /// * `InsufficientPortfolioBalance` if a portfolio does not have enough free balance
+        ///    for the net amount moved out of it.
+        /// * See `ensure_moves_unfrozen`.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::rebalance_portfolios(moves.len() as u32)]
+        pub fn rebalance_portfolios(
+            origin,
+            moves: Vec<PortfolioMove<<T as CommonConfig>::Balance>>,
+        ) {
+            let PermissionedCallOriginData {
+                primary_did,
+                secondary_key,
+                ..
+            } = Identity::<T>::ensure_origin_call_permissions(origin)?;
+
+            // Incoming and outgoing amounts of each asset in each portfolio.
+            let mut flows = BTreeMap::<_, (T::Balance, T::Balance)>::new();
+            for mv in &moves {
+                ensure!(mv.from != mv.to, Error::<T>::DestinationIsSamePortfolio);
+                ensure!(mv.from.did == mv.to.did, Error::<T>::DifferentIdentityPortfolios);
+                Self::ensure_portfolio_custody_and_permission(mv.from, primary_did, secondary_key.as_ref())?;
+                Self::ensure_portfolio_validity(&mv.to)?;
+                Self::ensure_receivable(&mv.to, &mv.ticker)?;
+                Self::ensure_user_portfolio_permission(secondary_key.as_ref(), mv.to)?;
+
+                let (_, out) = flows.entry((mv.from, mv.ticker)).or_default();
+                *out = out.saturating_add(mv.amount);
+                let (inc, _) = flows.entry((mv.to, mv.ticker)).or_default();
+                *inc = inc.saturating_add(mv.amount);
+            }
+
+            // Ensure there are sufficient funds for the net amount moved out of each portfolio.
+            for ((portfolio, ticker), (inc, out)) in &flows {
+                if out > inc {
+                    Self::ensure_sufficient_balance(portfolio, ticker, &(*out - *inc))?;
+                }
+            }
+            // Ensure freezes allow the moves.
+            Self::ensure_moves_unfrozen(moves.iter().map(|mv| (mv.from, mv.to, mv.ticker, mv.amount)))?;
+
+            // Commit changes.
+            for ((portfolio, ticker), (inc, out)) in flows {
+                <PortfolioAssetBalances<T>>::mutate(portfolio, ticker, |balance| {
+                    *balance = balance.saturating_add(inc).saturating_sub(out)
+                });
+            }
+            for mv in moves {
+                Self::deposit_event(RawEvent::MovedBetweenPortfolios(
+                    primary_did,
+                    mv.from,
+                    mv.to,
+                    mv.ticker,
+                    mv.amount,
+                    mv.memo
+                ));
+            }
+        }
+
         /// Renames a non-default portfolio.
         ///
         /// # Errors
@@ -311,6 +454,46 @@ decl_module! {
         pub fn accept_portfolio_custody(origin, auth_id: u64) -> DispatchResult {
             Self::base_accept_portfolio_custody(origin, auth_id)
         }
+
+        /// Sets the `policy` of `portfolio`, restricting the assets it can receive
+        /// and requiring outgoing transfers above a threshold to be affirmed by its custodian,
+        /// i.e., to be part of an affirmed settlement instruction.
+        /// A default `policy` removes all restrictions.
+        ///
+        /// # Errors
+        /// * `UnauthorizedCustodian` if the caller is not the custodian of `portfolio`.
+        /// * `PortfolioDoesNotExist` if `portfolio` doesn't exist.
+        /// * `TooManyAllowedTickers` if `policy` allows more than `MAX_ALLOWED_TICKERS` assets.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::set_portfolio_policy(
+            policy.allowed_tickers.as_ref().map_or(0, |t| t.len() as u32)
+        )]
+        pub fn set_portfolio_policy(
+            origin,
+            portfolio: PortfolioId,
+            policy: PortfolioPolicy<<T as CommonConfig>::Balance>,
+        ) {
+            let PermissionedCallOriginData {
+                primary_did,
+                secondary_key,
+                ..
+            } = Identity::<T>::ensure_origin_call_permissions(origin)?;
+            Self::ensure_portfolio_custody_and_permission(portfolio, primary_did, secondary_key.as_ref())?;
+            Self::ensure_portfolio_validity(&portfolio)?;
+            ensure!(
+                policy.allowed_tickers.as_ref().map_or(true, |t| t.len() <= MAX_ALLOWED_TICKERS),
+                Error::<T>::TooManyAllowedTickers
+            );
+
+            if policy == PortfolioPolicy::default() {
+                <PortfolioPolicies<T>>::remove(portfolio);
+            } else {
+                <PortfolioPolicies<T>>::insert(portfolio, policy.clone());
+            }
+            Self::deposit_event(RawEvent::PortfolioPolicySet(primary_did, portfolio, policy));
+        }
     }
 }
 
@@ -341,11 +524,70 @@ impl<T: Config> Module<T> {
         <PortfolioAssetBalances<T>>::insert(PortfolioId::default_portfolio(did), ticker, balance);
     }
 
+    /// Rescales the balance of `ticker` in `portfolio`, its locked amount and its vesting schedules,
+    /// e.g., for a stock split, returning the new balance.
+    ///
+    /// `locks` are the amounts locked by other pallets, before and after those pallets rescaled them.
+    /// They are carried over as is, so that the locked amount agrees with what the pallets later unlock,
+    /// and only the rest of the locked amount is rescaled.
+    ///
+    /// `rescale` must be monotonic and round down, so that the locked amount never exceeds the balance.
+    pub fn rescale_portfolio_asset(
+        portfolio: &PortfolioId,
+        ticker: &Ticker,
+        rescale: impl Fn(T::Balance) -> Result<T::Balance, DispatchError>,
+        locks: (T::Balance, T::Balance),
+    ) -> Result<T::Balance, DispatchError> {
+        let balance = rescale(Self::portfolio_asset_balances(portfolio, ticker))?;
+        let (old_locks, new_locks) = locks;
+        let other = Self::locked_assets(portfolio, ticker).saturating_sub(old_locks);
+        let locked = new_locks + rescale(other)?;
+        let schedules = Self::vesting_schedules(portfolio, ticker)
+            .into_iter()
+            .map(|schedule| {
+                Ok(VestingSchedule {
+                    amount: rescale(schedule.amount)?,
+                    ..schedule
+                })
+            })
+            .collect::<Result<Vec<_>, DispatchError>>()?;
+
+        <PortfolioAssetBalances<T>>::insert(portfolio, ticker, balance);
+        <PortfolioLockedAssets<T>>::insert(portfolio, ticker, locked);
+        if !schedules.is_empty() {
+            <VestingSchedules<T>>::insert(portfolio, ticker, schedules);
+        }
+        Ok(balance)
+    }
+
     /// Returns the next portfolio number of a given identity and increments the stored number.
     fn get_next_portfolio_number(did: &IdentityId) -> PortfolioNumber {
         NextPortfolioNumber::mutate(did, |num| mem::replace(num, PortfolioNumber(num.0 + 1)))
     }
 
+    /// Creates a user portfolio of `did` named `name`, in the custody of `custodian` and restricted by `policy`.
+    ///
+    /// Used by other pallets to hold assets of `did` on its behalf,
+    /// so `name` isn't required to be unique among the portfolios of `did`.
+    pub fn create_custodied_portfolio(
+        did: IdentityId,
+        name: PortfolioName,
+        custodian: IdentityId,
+        policy: PortfolioPolicy<T::Balance>,
+    ) -> PortfolioId {
+        let num = Self::get_next_portfolio_number(&did);
+        let pid = PortfolioId::user_portfolio(did, num);
+        Portfolios::insert(&did, &num, name.clone());
+        PortfolioCustodian::insert(&pid, custodian);
+        PortfoliosInCustody::insert(&custodian, &pid, true);
+        <PortfolioPolicies<T>>::insert(&pid, policy);
+        Self::deposit_event(RawEvent::PortfolioCreated(did, num, name));
+        Self::deposit_event(RawEvent::PortfolioCustodianChanged(
+            custodian, pid, custodian,
+        ));
+        pid
+    }
+
     /// Ensures that there is no portfolio with the desired `name` yet.
     fn ensure_name_unique(did: &IdentityId, name: &PortfolioName) -> DispatchResult {
         pallet_base::ensure_string_limited::<T>(name)?;
@@ -440,7 +682,90 @@ impl<T: Config> Module<T> {
         Self::ensure_portfolio_validity(to_portfolio)?;
 
         // 3. Ensure sender has enough free balance
-        Self::ensure_sufficient_balance(&from_portfolio, ticker, amount)
+        Self::ensure_sufficient_balance(&from_portfolio, ticker, amount)?;
+
+        // 4. Ensure the policies of the portfolios allow the transfer
+        Self::ensure_receivable(to_portfolio, ticker)?;
+        ensure!(
+            !Self::requires_custodian_affirmation(from_portfolio, amount),
+            Error::<T>::CustodianAffirmationRequired
+        );
+        Ok(())
+    }
+
+    /// Ensures that asset freezes allow `moves`, given as `(from, to, ticker, amount)`,
+    /// between portfolios of the same identity.
+    ///
+    /// # Errors
+    /// * `PortfolioFrozen` if `ticker` is frozen for the sending or receiving portfolio of a move.
+    /// * `FrozenBalanceExceeded` if, after the moves, the balance of `ticker` of an identity
+    ///    does not cover its frozen balance, counting the funds it moves out of its own custody,
+    ///    i.e., into portfolios in the custody of someone else, as transferred away.
+    pub fn ensure_moves_unfrozen(
+        moves: impl Iterator<Item = (PortfolioId, PortfolioId, Ticker, T::Balance)>,
+    ) -> DispatchResult {
+        let custodian = |p: &PortfolioId| Self::portfolio_custodian(p).unwrap_or(p.did);
+        // Amounts of each asset moved out of the custody of each identity.
+        let mut released = BTreeMap::<_, T::Balance>::new();
+        for (from, to, ticker, amount) in moves {
+            ensure!(
+                !T::AssetSubTraitTarget::is_portfolio_frozen(&ticker, &from)
+                    && !T::AssetSubTraitTarget::is_portfolio_frozen(&ticker, &to),
+                Error::<T>::PortfolioFrozen
+            );
+            if custodian(&from) == from.did && custodian(&to) != to.did {
+                let total = released.entry((from.did, ticker)).or_default();
+                *total = total.saturating_add(amount);
+            }
+        }
+        for ((did, ticker), amount) in released {
+            ensure!(
+                !T::AssetSubTraitTarget::is_frozen_balance_exceeded(&ticker, did, amount),
+                Error::<T>::FrozenBalanceExceeded
+            );
+        }
+        Ok(())
+    }
+
+    /// Ensures that the policy of `portfolio` allows it to receive `ticker`.
+    pub fn ensure_receivable(portfolio: &PortfolioId, ticker: &Ticker) -> DispatchResult {
+        ensure!(
+            Self::portfolio_policy(portfolio).allows_ticker(ticker),
+            Error::<T>::TickerNotAllowedByPolicy
+        );
+        Ok(())
+    }
+
+    /// Returns `true` if sending `amount` from `portfolio` requires the affirmation of its custodian,
+    /// which it does not have in the current context.
+    fn requires_custodian_affirmation(portfolio: &PortfolioId, amount: &T::Balance) -> bool {
+        !Self::custodian_affirmed()
+            && Self::portfolio_policy(portfolio).requires_affirmation(amount)
+    }
+
+    /// Executes `f`, which transfers assets from `senders` affirmed by the custodians of those portfolios,
+    /// so that the affirmation thresholds of portfolio policies are satisfied.
+    /// The affirmation is only recorded, for the duration of `f`,
+    /// if the policy of one of `senders` has an affirmation threshold.
+    ///
+    /// NB - Use this function only in the settlement engine to execute affirmed instructions.
+    pub fn with_custodian_affirmation<'a, R>(
+        senders: impl IntoIterator<Item = &'a PortfolioId>,
+        f: impl FnOnce() -> R,
+    ) -> R {
+        let needs_affirmation = !Self::custodian_affirmed()
+            && senders.into_iter().any(|portfolio| {
+                Self::portfolio_policy(portfolio)
+                    .affirmation_threshold
+                    .is_some()
+            });
+        if !needs_affirmation {
+            return f();
+        }
+        CustodianAffirmed::put(true);
+        let result = f();
+        CustodianAffirmed::kill();
+        result
     }
 
     /// Granular `ensure_portfolio_transfer_validity`.
@@ -457,15 +782,23 @@ impl<T: Config> Module<T> {
             Self::ensure_portfolio_validity(to_portfolio).is_err();
         let sender_insufficient_balance =
             Self::ensure_sufficient_balance(&from_portfolio, ticker, amount).is_err();
+        let receiver_policy_disallows_ticker =
+            Self::ensure_receivable(to_portfolio, ticker).is_err();
+        let sender_policy_requires_affirmation =
+            Self::requires_custodian_affirmation(from_portfolio, amount);
         PortfolioValidityResult {
             receiver_is_same_portfolio,
             sender_portfolio_does_not_exist,
             receiver_portfolio_does_not_exist,
             sender_insufficient_balance,
+            receiver_policy_disallows_ticker,
+            sender_policy_requires_affirmation,
             result: !receiver_is_same_portfolio
                 && !sender_portfolio_does_not_exist
                 && !receiver_portfolio_does_not_exist
-                && !sender_insufficient_balance,
+                && !sender_insufficient_balance
+                && !receiver_policy_disallows_ticker
+                && !sender_policy_requires_affirmation,
         }
     }
 
@@ -478,7 +811,8 @@ impl<T: Config> Module<T> {
     ) -> DispatchResult {
         // Ensure portfolio has enough free balance
         let total_balance = Self::portfolio_asset_balances(&portfolio, ticker);
-        let locked_balance = Self::locked_assets(&portfolio, ticker);
+        let locked_balance = Self::locked_assets(&portfolio, ticker)
+            .saturating_add(Self::vesting_locked(portfolio, ticker));
         let remaining_balance = total_balance
             .checked_sub(amount)
             .filter(|rb| rb >= &locked_balance)
@@ -509,6 +843,7 @@ impl<T: Config> Module<T> {
     ) -> DispatchResult {
         Self::portfolio_asset_balances(portfolio, ticker)
             .saturating_sub(Self::locked_assets(portfolio, ticker))
+            .saturating_sub(Self::vesting_locked(portfolio, ticker))
             .checked_sub(&amount)
             .ok_or_else(|| Error::<T>::InsufficientPortfolioBalance.into())
             .map(drop)
@@ -521,6 +856,60 @@ impl<T: Config> Module<T> {
         <PortfolioLockedAssets<T>>::mutate(portfolio, ticker, |l| *l = l.saturating_add(*amount));
     }
 
+    /// Returns the amount of `ticker` in `portfolio` that is still locked by vesting schedules.
+    pub fn vesting_locked(portfolio: &PortfolioId, ticker: &Ticker) -> T::Balance {
+        let now = <pallet_timestamp::Module<T>>::get().saturated_into::<u64>();
+        Self::vesting_schedules(portfolio, ticker)
+            .iter()
+            .fold(0u32.into(), |locked: T::Balance, schedule| {
+                locked.saturating_add(schedule.locked_at(now))
+            })
+    }
+
+    /// Attaches `schedule` to the balance of `ticker` in `portfolio`,
+    /// pruning the schedules that have been fully released.
+    ///
+    /// A schedule with the same release parameters as an active one is merged into it,
+    /// so repeated acquisitions under the same terms only take up one schedule.
+    /// The balance locked by the schedule is not required to be in the portfolio yet,
+    /// so that the schedule can be attached before the tokens are transferred in.
+    ///
+    /// # Errors
+    /// * `InvalidVestingSchedule` if the schedule has no amount or its release parameters are invalid.
+    /// * `TooManyVestingSchedules` if `MAX_VESTING_SCHEDULES` schedules with distinct release parameters
+    ///   are still active.
+    pub fn add_vesting_schedule(
+        did: IdentityId,
+        portfolio: PortfolioId,
+        ticker: Ticker,
+        schedule: VestingSchedule<T::Balance>,
+    ) -> DispatchResult {
+        ensure!(
+            schedule.amount > 0u32.into() && schedule.kind.is_valid(),
+            Error::<T>::InvalidVestingSchedule
+        );
+        let now = <pallet_timestamp::Module<T>>::get().saturated_into::<u64>();
+        <VestingSchedules<T>>::try_mutate(portfolio, ticker, |schedules| {
+            schedules.retain(|s| s.locked_at(now) > 0u32.into());
+            // Schedules with the same release parameters lock the same fraction of their amount at any time.
+            match schedules.iter_mut().find(|s| s.kind == schedule.kind) {
+                Some(merged) => merged.amount = merged.amount.saturating_add(schedule.amount),
+                None => {
+                    ensure!(
+                        schedules.len() < MAX_VESTING_SCHEDULES,
+                        Error::<T>::TooManyVestingSchedules
+                    );
+                    schedules.push(schedule.clone());
+                }
+            }
+            Ok::<_, Error<T>>(())
+        })?;
+        Self::deposit_event(RawEvent::VestingScheduleAdded(
+            did, portfolio, ticker, schedule,
+        ));
+        Ok(())
+    }
+
     fn base_accept_portfolio_custody(origin: T::Origin, auth_id: u64) -> DispatchResult {
         let to = Identity::<T>::ensure_perms(origin)?;
         Identity::<T>::accept_auth_with(&to.into(), auth_id, |data, from| {
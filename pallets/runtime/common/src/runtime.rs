@@ -324,6 +324,9 @@ macro_rules! misc_pallet_impls {
             type WeightInfo = polymesh_weights::pallet_corporate_actions::WeightInfo;
             type BallotWeightInfo = polymesh_weights::pallet_corporate_ballot::WeightInfo;
             type DistWeightInfo = polymesh_weights::pallet_capital_distribution::WeightInfo;
+            type SplitWeightInfo = polymesh_weights::pallet_stock_split::WeightInfo;
+            type TenderWeightInfo = polymesh_weights::pallet_tender_offer::WeightInfo;
+            type FixedIncomeWeightInfo = polymesh_weights::pallet_fixed_income::WeightInfo;
         }
 
         impl pallet_statistics::Config for Runtime {
@@ -831,6 +834,23 @@ macro_rules! runtime_apis {
                 {
                     Asset::unsafe_can_transfer_granular(from_custodian, from_portfolio, to_custodian, to_portfolio, ticker, value)
                 }
+
+                #[inline]
+                fn asset_metadata(
+                    ticker: Ticker
+                ) -> Vec<polymesh_primitives::asset_metadata::AssetMetadataItem<Moment>>
+                {
+                    Asset::get_asset_metadata(ticker)
+                }
+
+                #[inline]
+                fn asset_metadata_value(
+                    ticker: Ticker,
+                    key: polymesh_primitives::asset_metadata::AssetMetadataKey
+                ) -> Option<polymesh_primitives::asset_metadata::AssetMetadataEntry<Moment>>
+                {
+                    Asset::get_asset_metadata_value(ticker, key)
+                }
             }
 
             impl node_rpc_runtime_api::compliance_manager::ComplianceManagerApi<Block, polymesh_primitives::AccountId, Balance>
@@ -846,6 +866,52 @@ macro_rules! runtime_apis {
                     use polymesh_common_utilities::compliance_manager::Config;
                     ComplianceManager::verify_restriction_granular(&ticker, from_did, to_did)
                 }
+
+                #[inline]
+                fn simulate_compliance(
+                    ticker: Ticker,
+                    requirements: Vec<polymesh_primitives::compliance_manager::ComplianceRequirement>,
+                    start: u32,
+                    count: u32,
+                ) -> polymesh_primitives::compliance_manager::ComplianceSimulationResult
+                {
+                    let holders = Asset::holders(&ticker);
+                    ComplianceManager::simulate_compliance(&ticker, &requirements, holders, start, count)
+                }
+            }
+
+            impl node_rpc_runtime_api::settlement::SettlementApi<Block, polymesh_primitives::AccountId>
+                for Runtime
+            {
+                #[inline]
+                fn get_instruction(
+                    instruction_id: u64
+                ) -> Option<pallet_settlement::InstructionReport<polymesh_primitives::AccountId, Moment, BlockNumber, Balance>>
+                {
+                    Settlement::get_instruction_report(instruction_id)
+                }
+
+                #[inline]
+                fn pending_for_portfolio(portfolio: PortfolioId) -> Vec<(u64, pallet_settlement::AffirmationStatus)> {
+                    Settlement::pending_instructions_for_portfolio(portfolio)
+                }
+
+                #[inline]
+                fn can_execute(instruction_id: u64) -> pallet_settlement::InstructionExecutionReport {
+                    Settlement::dry_run_instruction(instruction_id)
+                }
+            }
+
+            impl node_rpc_runtime_api::sto::StoApi<Block> for Runtime {
+                #[inline]
+                fn remaining_allowance(
+                    offering_asset: Ticker,
+                    fundraiser_id: u64,
+                    investor: IdentityId
+                ) -> Option<Balance>
+                {
+                    Sto::remaining_allowance(offering_asset, fundraiser_id, investor)
+                }
             }
 
             impl pallet_group_rpc_runtime_api::GroupApi<Block> for Runtime {
@@ -13,6 +13,9 @@ use pallet_asset::checkpoint as pallet_checkpoint;
 //use pallet_contracts::weights::WeightInfo;
 use pallet_corporate_actions::ballot as pallet_corporate_ballot;
 use pallet_corporate_actions::distribution as pallet_capital_distribution;
+use pallet_corporate_actions::split as pallet_stock_split;
+use pallet_corporate_actions::fixed_income as pallet_fixed_income;
+use pallet_corporate_actions::tender as pallet_tender_offer;
 use pallet_session::historical as pallet_session_historical;
 pub use pallet_transaction_payment::{Multiplier, RuntimeDispatchInfo, TargetedFeeAdjustment};
 use polymesh_common_utilities::{
@@ -392,6 +395,9 @@ construct_runtime!(
         TestUtils: pallet_test_utils::{Module, Call, Storage, Event<T> } = 50,
         Base: pallet_base::{Module, Call, Event} = 51,
         ExternalAgents: pallet_external_agents::{Module, Call, Storage, Event} = 52,
+        StockSplit: pallet_stock_split::{Module, Call, Storage, Event<T>} = 53,
+        TenderOffer: pallet_tender_offer::{Module, Call, Storage, Event<T>} = 54,
+        FixedIncome: pallet_fixed_income::{Module, Call, Storage, Event<T>} = 55,
     }
 );
 
@@ -442,6 +448,9 @@ polymesh_runtime_common::runtime_apis! {
             add_benchmark!(params, batches, pallet_corporate_actions, CorporateAction);
             add_benchmark!(params, batches, pallet_corporate_ballot, CorporateBallot);
             add_benchmark!(params, batches, pallet_capital_distribution, CapitalDistribution);
+            add_benchmark!(params, batches, pallet_stock_split, StockSplit);
+            add_benchmark!(params, batches, pallet_tender_offer, TenderOffer);
+            add_benchmark!(params, batches, pallet_fixed_income, FixedIncome);
             add_benchmark!(params, batches, pallet_external_agents, ExternalAgents);
             //add_benchmark!(params, batches, polymesh_contracts, Contracts);
             add_benchmark!(params, batches, pallet_committee, PolymeshCommittee);
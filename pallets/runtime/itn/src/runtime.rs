@@ -11,6 +11,9 @@ use frame_support::{
 use pallet_asset::checkpoint as pallet_checkpoint;
 use pallet_corporate_actions::ballot as pallet_corporate_ballot;
 use pallet_corporate_actions::distribution as pallet_capital_distribution;
+use pallet_corporate_actions::split as pallet_stock_split;
+use pallet_corporate_actions::fixed_income as pallet_fixed_income;
+use pallet_corporate_actions::tender as pallet_tender_offer;
 use pallet_session::historical as pallet_session_historical;
 pub use pallet_transaction_payment::{Multiplier, RuntimeDispatchInfo, TargetedFeeAdjustment};
 use polymesh_common_utilities::{constants::currency::*, protocol_fee::ProtocolOp};
@@ -369,6 +372,9 @@ construct_runtime!(
         Utility: pallet_utility::{Module, Call, Storage, Event} = 44,
         Base: pallet_base::{Module, Call, Event} = 45,
         ExternalAgents: pallet_external_agents::{Module, Call, Storage, Event} = 46,
+        StockSplit: pallet_stock_split::{Module, Call, Storage, Event<T>} = 47,
+        TenderOffer: pallet_tender_offer::{Module, Call, Storage, Event<T>} = 48,
+        FixedIncome: pallet_fixed_income::{Module, Call, Storage, Event<T>} = 49,
     }
 );
 
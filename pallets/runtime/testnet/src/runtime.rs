@@ -15,6 +15,9 @@ use pallet_asset::checkpoint as pallet_checkpoint;
 //use pallet_contracts::weights::WeightInfo;
 use pallet_corporate_actions::ballot as pallet_corporate_ballot;
 use pallet_corporate_actions::distribution as pallet_capital_distribution;
+use pallet_corporate_actions::split as pallet_stock_split;
+use pallet_corporate_actions::fixed_income as pallet_fixed_income;
+use pallet_corporate_actions::tender as pallet_tender_offer;
 use pallet_session::historical as pallet_session_historical;
 pub use pallet_transaction_payment::{Multiplier, RuntimeDispatchInfo, TargetedFeeAdjustment};
 use polymesh_common_utilities::{constants::currency::*, protocol_fee::ProtocolOp};
@@ -382,6 +385,9 @@ construct_runtime!(
         TestUtils: pallet_test_utils::{Module, Call, Storage, Event<T> } = 50,
         Base: pallet_base::{Module, Call, Event} = 51,
         ExternalAgents: pallet_external_agents::{Module, Call, Storage, Event} = 52,
+        StockSplit: pallet_stock_split::{Module, Call, Storage, Event<T>} = 53,
+        TenderOffer: pallet_tender_offer::{Module, Call, Storage, Event<T>} = 54,
+        FixedIncome: pallet_fixed_income::{Module, Call, Storage, Event<T>} = 55,
     }
 );
 
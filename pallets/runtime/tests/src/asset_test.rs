@@ -19,8 +19,8 @@ use ink_primitives::hash as FunctionSelectorHasher;
 use pallet_asset::checkpoint::ScheduleSpec;
 use pallet_asset::{
     self as asset, AssetOwnershipRelation, ClassicTickerImport, ClassicTickerRegistration,
-    ClassicTickers, Config as AssetConfig, ScopeIdOf, SecurityToken, TickerRegistration,
-    TickerRegistrationConfig, Tickers,
+    ClassicTickers, Config as AssetConfig, ForcedTransferStatus, ScopeIdOf, SecurityToken,
+    TickerRegistration, TickerRegistrationConfig, Tickers,
 };
 use pallet_balances as balances;
 use pallet_compliance_manager as compliance_manager;
@@ -37,13 +37,19 @@ use polymesh_common_utilities::{
 use polymesh_primitives::ethereum;
 use polymesh_primitives::{
     agent::AgentGroup,
-    asset::{AssetName, AssetType, FundingRoundName},
+    asset::{AssetName, AssetType, ClassConversion, ForcedTransferReason, FundingRoundName},
+    asset_metadata::{
+        AssetMetadataGlobalKey, AssetMetadataKey, AssetMetadataLocalKey, AssetMetadataLockStatus,
+        AssetMetadataName, AssetMetadataSpec, AssetMetadataValue, AssetMetadataValueDetail,
+        AssetMetadataValueType,
+    },
     calendar::{
         CalendarPeriod, CalendarUnit, CheckpointId, CheckpointSchedule, FixedOrVariableCalendarUnit,
     },
+    statistics::TransferManager,
     AccountId, AssetIdentifier, AssetPermissions, AuthorizationData, AuthorizationError, Document,
     DocumentId, IdentityId, InvestorUid, Moment, Permissions, PortfolioId, PortfolioName,
-    SecondaryKey, Signatory, Ticker,
+    PortfolioNumber, SecondaryKey, Signatory, Ticker,
 };
 use rand::Rng;
 use sp_io::hashing::keccak_256;
@@ -641,6 +647,440 @@ fn controller_transfer() {
         })
 }
 
+#[test]
+fn forced_transfers() {
+    let eve = AccountKeyring::Eve.to_account_id();
+    ExtBuilder::default()
+        .cdd_providers(vec![eve.clone()])
+        .build()
+        .execute_with(|| {
+            set_time_to_now();
+
+            let owner = User::new(AccountKeyring::Dave);
+            let alice = User::new(AccountKeyring::Alice);
+            let bob = User::new(AccountKeyring::Bob);
+            let (ticker, token) = a_token(owner.did);
+            assert_ok!(basic_asset(owner, ticker, &token));
+            provide_scope_claim_to_multiple_parties(&[alice.did, bob.did, owner.did], ticker, eve);
+            allow_all_transfers(ticker, owner);
+            assert_ok!(transfer(ticker, owner, alice, 500));
+
+            // The court order supporting the transfers.
+            assert_ok!(Asset::add_documents(
+                owner.origin(),
+                vec![Document {
+                    name: b"Court order".into(),
+                    uri: b"www.court.gov".into(),
+                    content_hash: [1u8; 64][..].try_into().unwrap(),
+                    doc_type: None,
+                    filing_date: None,
+                }],
+                ticker
+            ));
+            let evidence = DocumentId(0);
+            let alice_portfolio = PortfolioId::default_portfolio(alice.did);
+            let bob_portfolio = PortfolioId::default_portfolio(bob.did);
+            let force = |user: User, value, evidence, period| {
+                Asset::force_transfer(
+                    user.origin(),
+                    ticker,
+                    alice_portfolio,
+                    bob_portfolio,
+                    value,
+                    ForcedTransferReason::LostKeyRecovery,
+                    evidence,
+                    period,
+                )
+            };
+            let status = |id| Asset::forced_transfers(ticker, id).unwrap().status;
+            let balance_of = |did| Asset::balance_of(&ticker, did);
+
+            // Only agents can force transfers, and only with a document of the asset as evidence.
+            assert_noop!(force(bob, 100, evidence, None), EAError::UnauthorizedAgent);
+            assert_noop!(
+                force(owner, 100, DocumentId(1), None),
+                AssetError::NoSuchDoc
+            );
+            assert_noop!(
+                Asset::force_transfer(
+                    owner.origin(),
+                    ticker,
+                    PortfolioId::user_portfolio(alice.did, PortfolioNumber(42)),
+                    bob_portfolio,
+                    100,
+                    ForcedTransferReason::LostKeyRecovery,
+                    evidence,
+                    None,
+                ),
+                PortfolioError::PortfolioDoesNotExist
+            );
+
+            // The receiver needs a scope claim for the asset.
+            let charlie = User::new(AccountKeyring::Charlie);
+            assert_noop!(
+                Asset::force_transfer(
+                    owner.origin(),
+                    ticker,
+                    alice_portfolio,
+                    PortfolioId::default_portfolio(charlie.did),
+                    100,
+                    ForcedTransferReason::LostKeyRecovery,
+                    evidence,
+                    Some(1000),
+                ),
+                AssetError::ReceiverScopeClaimMissing
+            );
+            let locked = || Portfolio::locked_assets(alice_portfolio, ticker);
+
+            // Without a contest period, the transfer is immediate, and still recorded.
+            assert_ok!(force(owner, 100, evidence, None));
+            assert_eq!(balance_of(alice.did), 400);
+            assert_eq!(balance_of(bob.did), 100);
+            assert_eq!(status(0), ForcedTransferStatus::Executed);
+            assert_eq!(Asset::forced_transfer_count(ticker), 1);
+
+            // Alice can contest a scheduled transfer, which can then only be cancelled.
+            // Meanwhile, its value is locked, and no other transfer can be forced from her portfolio.
+            assert_noop!(
+                force(owner, 401, evidence, Some(1000)),
+                PortfolioError::InsufficientPortfolioBalance
+            );
+            assert_ok!(force(owner, 100, evidence, Some(1000)));
+            assert_eq!(locked(), 100);
+            assert_noop!(
+                force(owner, 100, evidence, None),
+                AssetError::ForcedTransferOpen
+            );
+            assert_noop!(
+                Asset::execute_forced_transfer(owner.origin(), ticker, 1),
+                AssetError::ContestPeriodNotOver
+            );
+            assert_noop!(
+                Asset::contest_forced_transfer(bob.origin(), ticker, 1),
+                AssetError::Unauthorized
+            );
+            assert_ok!(Asset::contest_forced_transfer(alice.origin(), ticker, 1));
+            assert_eq!(status(1), ForcedTransferStatus::Contested);
+            Timestamp::set_timestamp(Timestamp::get() + 1000);
+            assert_noop!(
+                Asset::execute_forced_transfer(owner.origin(), ticker, 1),
+                AssetError::ForcedTransferNotPending
+            );
+            assert_eq!(locked(), 100);
+            assert_ok!(Asset::cancel_forced_transfer(owner.origin(), ticker, 1));
+            assert_eq!(status(1), ForcedTransferStatus::Cancelled);
+            assert_eq!(locked(), 0);
+            assert_noop!(
+                Asset::cancel_forced_transfer(owner.origin(), ticker, 1),
+                AssetError::ForcedTransferClosed
+            );
+
+            // Once the contest period is over, the transfer can no longer be contested, only executed.
+            assert_ok!(force(owner, 100, evidence, Some(1000)));
+            Timestamp::set_timestamp(Timestamp::get() + 1000);
+            assert_noop!(
+                Asset::contest_forced_transfer(alice.origin(), ticker, 2),
+                AssetError::ContestPeriodOver
+            );
+            assert_eq!(locked(), 100);
+            assert_ok!(Asset::execute_forced_transfer(owner.origin(), ticker, 2));
+            assert_eq!(status(2), ForcedTransferStatus::Executed);
+            assert_eq!(locked(), 0);
+            assert_eq!(balance_of(alice.did), 300);
+            assert_eq!(balance_of(bob.did), 200);
+            assert_noop!(
+                Asset::execute_forced_transfer(owner.origin(), ticker, 3),
+                AssetError::NoSuchForcedTransfer
+            );
+        });
+}
+
+#[test]
+fn asset_metadata() {
+    ExtBuilder::default().build().execute_with(|| {
+        set_time_to_now();
+
+        let owner = User::new(AccountKeyring::Dave);
+        let alice = User::new(AccountKeyring::Alice);
+        let (ticker, token) = a_token(owner.did);
+        assert_ok!(basic_asset(owner, ticker, &token));
+
+        let spec = |value_type| AssetMetadataSpec {
+            value_type,
+            description: Some(b"A description".to_vec()),
+            url: None,
+        };
+        let isin = AssetMetadataKey::Local(AssetMetadataLocalKey(0));
+        let maturity = AssetMetadataKey::Global(AssetMetadataGlobalKey(0));
+        let set = |key, value, detail| {
+            Asset::set_asset_metadata(owner.origin(), ticker, key, value, detail)
+        };
+        let text = |text: &[u8]| AssetMetadataValue::Text(text.to_vec());
+
+        // Only agents can register local keys, and only governance can register global ones.
+        assert_noop!(
+            Asset::register_asset_metadata_local_type(
+                alice.origin(),
+                ticker,
+                "ISIN".into(),
+                spec(AssetMetadataValueType::Text)
+            ),
+            EAError::UnauthorizedAgent
+        );
+        assert_ok!(Asset::register_asset_metadata_local_type(
+            owner.origin(),
+            ticker,
+            "ISIN".into(),
+            spec(AssetMetadataValueType::Text)
+        ));
+        assert_noop!(
+            Asset::register_asset_metadata_global_type(
+                alice.origin(),
+                "Maturity".into(),
+                spec(AssetMetadataValueType::Moment)
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(Asset::register_asset_metadata_global_type(
+            root(),
+            "Maturity".into(),
+            spec(AssetMetadataValueType::Moment)
+        ));
+
+        // Names are unique among the global keys and the keys of the asset.
+        assert_noop!(
+            Asset::register_asset_metadata_local_type(
+                owner.origin(),
+                ticker,
+                "ISIN".into(),
+                spec(AssetMetadataValueType::Bytes)
+            ),
+            AssetError::AssetMetadataNameAlreadyExists
+        );
+        assert_noop!(
+            Asset::register_asset_metadata_local_type(
+                owner.origin(),
+                ticker,
+                "Maturity".into(),
+                spec(AssetMetadataValueType::Moment)
+            ),
+            AssetError::AssetMetadataNameAlreadyExists
+        );
+
+        // Values must be of the type of their registered key.
+        assert_noop!(
+            set(
+                AssetMetadataKey::Local(AssetMetadataLocalKey(1)),
+                text(b"US0378331005"),
+                None
+            ),
+            AssetError::AssetMetadataKeyIsMissing
+        );
+        assert_noop!(
+            set(isin, AssetMetadataValue::Bool(true), None),
+            AssetError::AssetMetadataInvalidValue
+        );
+        assert_noop!(
+            set(isin, text(&[0xff, 0xfe]), None),
+            AssetError::AssetMetadataInvalidValue
+        );
+        assert_noop!(
+            Asset::set_asset_metadata_details(owner.origin(), ticker, isin, Default::default()),
+            AssetError::AssetMetadataValueIsMissing
+        );
+
+        // Every change of a value bumps its version.
+        assert_ok!(set(isin, text(b"US0378331004"), None));
+        assert_ok!(set(isin, text(b"US0378331005"), None));
+        let entry = Asset::get_asset_metadata_value(ticker, isin).unwrap();
+        assert_eq!(entry.value, text(b"US0378331005"));
+        assert_eq!(entry.version, 2);
+
+        // A locked value can not be changed until the lock is over.
+        let now = Timestamp::get();
+        let locked = AssetMetadataValueDetail {
+            expire: None,
+            lock_status: AssetMetadataLockStatus::LockedUntil(now + 1000),
+        };
+        assert_ok!(Asset::set_asset_metadata_details(
+            owner.origin(),
+            ticker,
+            isin,
+            locked
+        ));
+        assert_noop!(
+            set(isin, text(b"US0378331006"), None),
+            AssetError::AssetMetadataValueIsLocked
+        );
+        assert_noop!(
+            Asset::set_asset_metadata_details(owner.origin(), ticker, isin, Default::default()),
+            AssetError::AssetMetadataValueIsLocked
+        );
+        Timestamp::set_timestamp(now + 1000);
+        assert_ok!(set(isin, text(b"US0378331006"), None));
+        assert_eq!(
+            Asset::get_asset_metadata_value(ticker, isin)
+                .unwrap()
+                .version,
+            3
+        );
+
+        // Expired values are no longer reported.
+        let now = Timestamp::get();
+        let expiring = AssetMetadataValueDetail {
+            expire: Some(now + 1000),
+            lock_status: AssetMetadataLockStatus::Unlocked,
+        };
+        assert_ok!(set(
+            maturity,
+            AssetMetadataValue::Moment(now + 5000),
+            Some(expiring)
+        ));
+        let keys = |ticker| {
+            Asset::get_asset_metadata(ticker)
+                .into_iter()
+                .map(|item| (item.key, item.name))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(keys(ticker).len(), 2);
+        assert!(keys(ticker).contains(&(maturity, "Maturity".into())));
+        Timestamp::set_timestamp(now + 1000);
+        assert_eq!(Asset::get_asset_metadata_value(ticker, maturity), None);
+        assert_eq!(keys(ticker), vec![(isin, AssetMetadataName::from("ISIN"))]);
+    });
+}
+
+#[test]
+fn asset_families() {
+    let eve = AccountKeyring::Eve.to_account_id();
+    ExtBuilder::default()
+        .cdd_providers(vec![eve.clone()])
+        .build()
+        .execute_with(|| {
+            Timestamp::set_timestamp(1000);
+
+            let owner = User::new(AccountKeyring::Dave);
+            let alice = User::new(AccountKeyring::Alice);
+            let create = |name: &[u8], user: User| {
+                let (ticker, token) = token(name, user.did);
+                assert_ok!(basic_asset(user, ticker, &token));
+                provide_scope_claim_to_multiple_parties(
+                    &[alice.did, owner.did],
+                    ticker,
+                    eve.clone(),
+                );
+                allow_all_transfers(ticker, user);
+                ticker
+            };
+            let a = create(b"A", owner);
+            let b = create(b"B", owner);
+            let c = create(b"C", owner);
+            let other = create(b"OTHER", owner);
+            let alices = create(b"ALICE", alice);
+            assert_ok!(transfer(a, owner, alice, 500));
+
+            // Agents of both assets link a class to a family of a parent.
+            let link =
+                |user: User, parent, class| Asset::link_asset_class(user.origin(), parent, class);
+            assert_noop!(link(alice, a, b), EAError::UnauthorizedAgent);
+            assert_noop!(link(owner, a, alices), EAError::UnauthorizedAgent);
+            assert_noop!(link(owner, a, a), AssetError::AssetClassOfItself);
+            assert_ok!(link(owner, a, b));
+            assert_ok!(link(owner, a, c));
+            // Families are flat.
+            assert_noop!(link(owner, b, other), AssetError::AssetAlreadyInFamily);
+            assert_noop!(link(owner, other, a), AssetError::AssetAlreadyInFamily);
+            assert_noop!(link(owner, other, c), AssetError::AssetAlreadyInFamily);
+            assert_eq!(Asset::asset_family_classes(a), vec![b, c]);
+            assert_eq!(Asset::asset_family_of(a), Some(a));
+            assert_eq!(Asset::asset_family_of(c), Some(a));
+            assert_eq!(Asset::asset_family_of(other), None);
+            assert!(Asset::same_asset_family(b, c));
+
+            // Conversions are set between distinct classes of the same family.
+            let conversion = |numerator, denominator, start, end| ClassConversion {
+                numerator,
+                denominator,
+                start,
+                end,
+            };
+            let set = |from, to, conversion| {
+                Asset::set_class_conversion(owner.origin(), from, to, conversion)
+            };
+            let a_to_b = conversion(3, 2, Some(2000), Some(3000));
+            assert_noop!(set(a, a, Some(a_to_b)), AssetError::NotSameAssetFamily);
+            assert_noop!(set(a, other, Some(a_to_b)), AssetError::NotSameAssetFamily);
+            assert_noop!(
+                set(a, b, Some(conversion(0, 2, None, None))),
+                AssetError::InvalidClassConversion
+            );
+            assert_noop!(
+                set(a, b, Some(conversion(3, 2, Some(3000), Some(3000)))),
+                AssetError::InvalidClassConversion
+            );
+            assert_ok!(set(a, b, Some(a_to_b)));
+            assert_ok!(set(c, a, Some(conversion(1, 1, None, None))));
+            assert_eq!(Asset::class_conversion(a, b), Some(a_to_b));
+
+            // Holders convert within the window, into whole units only.
+            let convert = |from, to, value| Asset::convert_class(alice.origin(), from, to, value);
+            assert_noop!(convert(a, c, 100), AssetError::NoClassConversion);
+            assert_noop!(convert(a, b, 100), AssetError::ClassConversionClosed);
+            Timestamp::set_timestamp(2000);
+            assert_noop!(convert(a, b, 101), AssetError::InexactClassConversion);
+            assert_noop!(
+                convert(a, b, 1000),
+                PortfolioError::InsufficientPortfolioBalance
+            );
+            assert_ok!(Asset::freeze(owner.origin(), b));
+            assert_noop!(convert(a, b, 100), AssetError::InvalidTransfer);
+            assert_ok!(Asset::unfreeze(owner.origin(), b));
+
+            // The caller must be allowed to receive `to`, by compliance rules and transfer managers.
+            assert_ok!(ComplianceManager::reset_asset_compliance(owner.origin(), b));
+            assert_noop!(convert(a, b, 100), AssetError::InvalidTransfer);
+            allow_all_transfers(b, owner);
+            let ctm = TransferManager::CountTransferManager(0);
+            assert_ok!(Statistics::add_transfer_manager(
+                owner.origin(),
+                b,
+                ctm.clone()
+            ));
+            assert_noop!(convert(a, b, 100), AssetError::InvalidTransfer);
+            assert_ok!(Statistics::remove_transfer_manager(owner.origin(), b, ctm));
+
+            // Conversions are not issuance in a funding round.
+            let round = (b, Asset::funding_round(b));
+            let issued_in_round = Asset::issued_in_funding_round(&round);
+            assert_ok!(convert(a, b, 100));
+            assert_eq!(Asset::issued_in_funding_round(&round), issued_in_round);
+            assert_eq!(Asset::balance_of(&a, alice.did), 400);
+            assert_eq!(Asset::balance_of(&b, alice.did), 150);
+            assert_eq!(Asset::total_supply(a), TOTAL_SUPPLY - 100);
+            assert_eq!(Asset::total_supply(b), TOTAL_SUPPLY + 150);
+            Timestamp::set_timestamp(3000);
+            assert_noop!(convert(a, b, 100), AssetError::ClassConversionClosed);
+
+            // Unlinking a class removes its conversions.
+            assert_noop!(
+                Asset::unlink_asset_class(owner.origin(), a),
+                AssetError::NotAnAssetClass
+            );
+            assert_noop!(
+                Asset::unlink_asset_class(alice.origin(), b),
+                EAError::UnauthorizedAgent
+            );
+            assert_ok!(Asset::unlink_asset_class(owner.origin(), b));
+            assert_eq!(Asset::asset_family_of(b), None);
+            assert_eq!(Asset::asset_family_classes(a), vec![c]);
+            assert_eq!(Asset::class_conversion(a, b), None);
+            assert!(Asset::class_conversion(c, a).is_some());
+            assert_ok!(Asset::unlink_asset_class(owner.origin(), c));
+            assert_eq!(Asset::asset_family_of(a), None);
+            assert_eq!(Asset::class_conversion(c, a), None);
+        });
+}
+
 #[test]
 fn transfer_token_ownership() {
     ExtBuilder::default().build().execute_with(|| {
@@ -1075,6 +1515,106 @@ fn freeze_unfreeze_asset() {
     });
 }
 
+#[test]
+fn freeze_holders() {
+    let eve = AccountKeyring::Eve.to_account_id();
+    ExtBuilder::default()
+        .cdd_providers(vec![eve.clone()])
+        .build()
+        .execute_with(|| {
+            set_time_to_now();
+
+            let owner = User::new(AccountKeyring::Dave);
+            let alice = User::new(AccountKeyring::Alice);
+            let bob = User::new(AccountKeyring::Bob);
+            let (ticker, token) = a_token(owner.did);
+            assert_ok!(basic_asset(owner, ticker, &token));
+            provide_scope_claim_to_multiple_parties(&[alice.did, owner.did], ticker, eve);
+            allow_all_transfers(ticker, owner);
+            assert_ok!(transfer(ticker, owner, alice, 500));
+
+            let alice_portfolio = PortfolioId::default_portfolio(alice.did);
+            let owner_portfolio = PortfolioId::default_portfolio(owner.did);
+            let granular = |from, to, amount| {
+                Asset::unsafe_can_transfer_granular(None, from, None, to, &ticker, amount)
+            };
+
+            // Only agents can freeze holders.
+            assert_noop!(
+                Asset::freeze_identity(bob.origin(), ticker, alice.did),
+                EAError::UnauthorizedAgent
+            );
+            assert_noop!(
+                Asset::set_frozen_balance(bob.origin(), ticker, alice.did, 100),
+                EAError::UnauthorizedAgent
+            );
+
+            // A frozen identity can neither send nor receive the asset.
+            assert_ok!(Asset::freeze_identity(owner.origin(), ticker, alice.did));
+            assert_noop!(
+                Asset::freeze_identity(owner.origin(), ticker, alice.did),
+                AssetError::AlreadyFrozen
+            );
+            assert_noop!(
+                transfer(ticker, owner, alice, 100),
+                AssetError::InvalidTransfer
+            );
+            assert_noop!(
+                transfer(ticker, alice, owner, 100),
+                AssetError::InvalidTransfer
+            );
+            let result = granular(alice_portfolio, owner_portfolio, 100);
+            assert!(result.sender_identity_frozen && !result.receiver_identity_frozen);
+            assert!(!result.result);
+            assert_ok!(Asset::unfreeze_identity(owner.origin(), ticker, alice.did));
+            assert_noop!(
+                Asset::unfreeze_identity(owner.origin(), ticker, alice.did),
+                AssetError::NotFrozen
+            );
+
+            // The same goes for a frozen portfolio.
+            assert_ok!(Asset::freeze_portfolio(
+                owner.origin(),
+                ticker,
+                alice_portfolio
+            ));
+            assert_noop!(
+                transfer(ticker, owner, alice, 100),
+                AssetError::InvalidTransfer
+            );
+            assert!(granular(owner_portfolio, alice_portfolio, 100).receiver_portfolio_frozen);
+            assert_ok!(Asset::unfreeze_portfolio(
+                owner.origin(),
+                ticker,
+                alice_portfolio
+            ));
+            assert_ok!(transfer(ticker, owner, alice, 100));
+
+            // With part of her balance frozen, Alice can only transfer the rest.
+            assert_ok!(Asset::set_frozen_balance(
+                owner.origin(),
+                ticker,
+                alice.did,
+                400
+            ));
+            assert_eq!(Asset::frozen_balance(ticker, alice.did), 400);
+            assert_noop!(
+                transfer(ticker, alice, owner, 201),
+                AssetError::InvalidTransfer
+            );
+            assert!(granular(alice_portfolio, owner_portfolio, 201).sender_frozen_balance_exceeded);
+            assert_ok!(transfer(ticker, alice, owner, 200));
+            assert_ok!(Asset::set_frozen_balance(
+                owner.origin(),
+                ticker,
+                alice.did,
+                0
+            ));
+            assert_ok!(transfer(ticker, alice, owner, 400));
+            assert_eq!(Asset::balance_of(&ticker, alice.did), 0);
+        });
+}
+
 #[test]
 fn frozen_secondary_keys_create_asset() {
     ExtBuilder::default()
@@ -1,12 +1,13 @@
 use super::{
     asset_test::{allow_all_transfers, create_token},
+    committee_test::gc_vmo,
     storage::{
         create_cdd_id, create_investor_uid, provide_scope_claim_to_multiple_parties, set_curr_did,
         TestStorage, User,
     },
     ExtBuilder,
 };
-use chrono::prelude::Utc;
+use chrono::prelude::{NaiveDate, Utc};
 use frame_support::{assert_noop, assert_ok, traits::Currency};
 use pallet_balances as balances;
 use pallet_compliance_manager::{self as compliance_manager, Error as CMError};
@@ -19,10 +20,11 @@ use polymesh_common_utilities::{
 use polymesh_primitives::{
     agent::AgentGroup,
     compliance_manager::{
-        AssetComplianceResult, ComplianceRequirement, ComplianceRequirementResult,
+        AssetComplianceResult, ComplianceRequirement, ComplianceRequirementResult, ConditionResult,
     },
-    AuthorizationData, Claim, ClaimType, Condition, ConditionType, CountryCode, IdentityId,
-    PortfolioId, Scope, Signatory, TargetIdentity, Ticker, TrustedFor,
+    AttributeKind, AttributePredicate, AttributeValue, AuthorizationData, Claim, ClaimType,
+    Condition, ConditionType, CountryCode, IdentityId, PortfolioId, Scope, Signatory,
+    TargetIdentity, Ticker, TrustedFor,
 };
 use sp_std::prelude::*;
 use test_client::AccountKeyring;
@@ -1318,3 +1320,379 @@ fn check_new_return_type_of_rpc() {
         assert_invalid_transfer!(ticker, owner.did, receiver.did, 100);
     });
 }
+
+#[test]
+fn nested_conditions_asset_compliance() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(nested_conditions_asset_compliance_we);
+}
+fn nested_conditions_asset_compliance_we() {
+    // 0. Create accounts
+    let owner = User::new(AccountKeyring::Alice);
+    let cdd = User::new(AccountKeyring::Bob);
+    let charlie = User::new(AccountKeyring::Charlie);
+    let dave = User::new(AccountKeyring::Dave);
+
+    // 1. Create a token.
+    let (ticker, _) = create_token(owner);
+    provide_scope_claim_to_multiple_parties(
+        &[owner.did, charlie.did, dave.did],
+        ticker,
+        AccountKeyring::Eve.to_account_id(),
+    );
+
+    // 2. Receivers must be "(Accredited AND US) OR (NOT US AND KYC)".
+    // Nested conditions trust the issuers of the root condition.
+    let scope = Scope::Identity(Identity::get_token_did(&ticker).unwrap());
+    let present = |claim| Condition::from(ConditionType::IsPresent(claim));
+    let us = Claim::Jurisdiction(CountryCode::US, scope.clone());
+    let condition = Condition::from_dids(
+        ConditionType::Any(vec![
+            ConditionType::All(vec![
+                present(Claim::Accredited(scope.clone())),
+                present(us.clone()),
+            ])
+            .into(),
+            ConditionType::All(vec![
+                ConditionType::Not(Box::new(present(us.clone()))).into(),
+                present(Claim::KnowYourCustomer(scope.clone())),
+            ])
+            .into(),
+        ]),
+        &[cdd.did],
+    );
+    // Complexity = 1 + (1 + 1 + 1) + (1 + (1 + 1) + 1) = 8
+    assert_eq!(condition.claim_reads(0), 8);
+    assert_noop!(
+        ComplianceManager::add_compliance_requirement(
+            owner.origin(),
+            ticker,
+            vec![],
+            vec![condition.clone(); 7]
+        ),
+        CMError::<TestStorage>::ComplianceRequirementTooComplex
+    );
+    assert_ok!(ComplianceManager::add_compliance_requirement(
+        owner.origin(),
+        ticker,
+        vec![],
+        vec![condition]
+    ));
+
+    // 3. Validate behaviour.
+    let add_claim = |target, claim| assert_add_claim!(cdd.origin(), target, claim, None);
+    // 3.1. Charlie is in the US, so he must be accredited.
+    add_claim(charlie.did, us.clone());
+    assert_invalid_transfer!(ticker, owner.did, charlie.did, 10);
+    add_claim(charlie.did, Claim::Accredited(scope.clone()));
+    assert_valid_transfer!(ticker, owner.did, charlie.did, 10);
+
+    // 3.2. Dave is not in the US, so KYC is enough, until he moves to the US.
+    add_claim(dave.did, Claim::KnowYourCustomer(scope.clone()));
+    assert_valid_transfer!(ticker, owner.did, dave.did, 10);
+    add_claim(dave.did, us);
+    assert_invalid_transfer!(ticker, owner.did, dave.did, 10);
+
+    // 3.3. Granular results explain which nested conditions failed.
+    let result =
+        ComplianceManager::verify_restriction_granular(&ticker, Some(owner.did), Some(dave.did));
+    assert!(!result.result);
+    let root = &result.requirements[0].receiver_conditions[0];
+    let results = |res: &ConditionResult| res.nested.iter().map(|r| r.result).collect::<Vec<_>>();
+    assert!(!root.result);
+    assert_eq!(results(root), vec![false, false]);
+    assert_eq!(results(&root.nested[0]), vec![false, true]);
+    assert_eq!(results(&root.nested[1]), vec![false, true]);
+    assert_eq!(results(&root.nested[1].nested[0]), vec![true]);
+    // Nested results carry the issuers they were evaluated with.
+    assert_eq!(
+        root.nested[1].nested[1].condition.issuers,
+        vec![cdd.issuer()]
+    );
+}
+
+#[test]
+fn attribute_conditions_asset_compliance() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(attribute_conditions_asset_compliance_we);
+}
+fn attribute_conditions_asset_compliance_we() {
+    let ms = |y, m, d| NaiveDate::from_ymd(y, m, d).and_hms(0, 0, 0).timestamp() as u64 * 1000;
+    const DAY: u64 = 24 * 60 * 60 * 1000;
+
+    // 0. Create accounts
+    let owner = User::new(AccountKeyring::Alice);
+    let cdd = User::new(AccountKeyring::Bob);
+    let charlie = User::new(AccountKeyring::Charlie);
+    let dave = User::new(AccountKeyring::Dave);
+
+    // 1. Create a token.
+    let (ticker, _) = create_token(owner);
+    provide_scope_claim_to_multiple_parties(
+        &[owner.did, charlie.did, dave.did],
+        ticker,
+        AccountKeyring::Eve.to_account_id(),
+    );
+    Timestamp::set_timestamp(ms(2021, 6, 15));
+
+    // 2. Receivers must be accredited within the last 90 days and at least 18 years old.
+    let scope = Scope::Identity(Identity::get_token_did(&ticker).unwrap());
+    let has_attribute = |kind, predicate| {
+        Condition::from_dids(
+            ConditionType::HasAttribute(kind, scope.clone(), predicate),
+            &[cdd.did],
+        )
+    };
+    assert_ok!(ComplianceManager::add_compliance_requirement(
+        owner.origin(),
+        ticker,
+        vec![],
+        vec![
+            has_attribute(
+                AttributeKind::AccreditationDate,
+                AttributePredicate::WithinDays(90)
+            ),
+            has_attribute(
+                AttributeKind::DateOfBirth,
+                AttributePredicate::AtLeastYearsAgo(18)
+            ),
+        ]
+    ));
+
+    // 3. Validate behaviour.
+    let add_claim = |target, kind, value, scope| {
+        assert_add_claim!(
+            cdd.origin(),
+            target,
+            Claim::Attribute(kind, value, scope),
+            None
+        )
+    };
+    let date = AttributeValue::Date;
+
+    // 3.1. Charlie turned 18 today and was accredited 30 days ago.
+    add_claim(
+        charlie.did,
+        AttributeKind::DateOfBirth,
+        date(ms(2003, 6, 15)),
+        scope.clone(),
+    );
+    assert_invalid_transfer!(ticker, owner.did, charlie.did, 10);
+    // Attributes in another scope do not count.
+    add_claim(
+        charlie.did,
+        AttributeKind::AccreditationDate,
+        date(ms(2021, 5, 16)),
+        Scope::Identity(charlie.did),
+    );
+    assert_invalid_transfer!(ticker, owner.did, charlie.did, 10);
+    add_claim(
+        charlie.did,
+        AttributeKind::AccreditationDate,
+        date(ms(2021, 5, 16)),
+        scope.clone(),
+    );
+    assert_valid_transfer!(ticker, owner.did, charlie.did, 10);
+
+    // 3.2. Dave turns 18 tomorrow. A number is not a valid date of birth.
+    add_claim(
+        dave.did,
+        AttributeKind::AccreditationDate,
+        date(ms(2021, 6, 1)),
+        scope.clone(),
+    );
+    add_claim(
+        dave.did,
+        AttributeKind::DateOfBirth,
+        AttributeValue::Number(0),
+        scope.clone(),
+    );
+    assert_invalid_transfer!(ticker, owner.did, dave.did, 10);
+    add_claim(
+        dave.did,
+        AttributeKind::DateOfBirth,
+        date(ms(2003, 6, 16)),
+        scope.clone(),
+    );
+    assert_invalid_transfer!(ticker, owner.did, dave.did, 10);
+    Timestamp::set_timestamp(ms(2021, 6, 16));
+    assert_valid_transfer!(ticker, owner.did, dave.did, 10);
+
+    // 3.3. Charlie's accreditation lapses after 90 days.
+    Timestamp::set_timestamp(ms(2021, 5, 16) + 90 * DAY);
+    assert_valid_transfer!(ticker, owner.did, charlie.did, 10);
+    Timestamp::set_timestamp(ms(2021, 5, 16) + 90 * DAY + 1);
+    assert_invalid_transfer!(ticker, owner.did, charlie.did, 10);
+
+    // 4. Numeric predicates compare values of the same kind only.
+    let now = ms(2021, 6, 15);
+    let net_worth = AttributeValue::Number(1_000_000);
+    let gt = AttributePredicate::GreaterThan(AttributeValue::Number(999_999));
+    assert!(gt.holds(&net_worth, now));
+    assert!(!gt.holds(&date(1_000_000), now));
+    assert!(AttributePredicate::EqualTo(net_worth).holds(&net_worth, now));
+    assert!(!AttributePredicate::LessThan(net_worth).holds(&net_worth, now));
+    // Those born on a leap day are of age on the 28th of February.
+    let leap = AttributePredicate::AtLeastYearsAgo(1);
+    assert!(leap.holds(&date(ms(2020, 2, 29)), ms(2021, 2, 28)));
+    assert!(!leap.holds(&date(ms(2020, 2, 29)), ms(2021, 2, 27)));
+}
+
+#[test]
+fn simulate_compliance_over_holders() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(simulate_compliance_over_holders_we);
+}
+fn simulate_compliance_over_holders_we() {
+    // 0. Create accounts
+    let owner = User::new(AccountKeyring::Alice);
+    let cdd = User::new(AccountKeyring::Bob);
+    let charlie = User::new(AccountKeyring::Charlie);
+    let dave = User::new(AccountKeyring::Dave);
+    let ferdie = User::new(AccountKeyring::Ferdie);
+
+    // 1. Create a token and distribute it, bypassing compliance.
+    let (ticker, _) = create_token(owner);
+    provide_scope_claim_to_multiple_parties(
+        &[owner.did, charlie.did, dave.did, ferdie.did],
+        ticker,
+        AccountKeyring::Eve.to_account_id(),
+    );
+    let transfer = |from: User, to: User, amount| {
+        assert_ok!(Asset::unsafe_transfer(
+            PortfolioId::default_portfolio(from.did),
+            PortfolioId::default_portfolio(to.did),
+            &ticker,
+            amount
+        ))
+    };
+    for holder in &[charlie, dave, ferdie] {
+        transfer(owner, *holder, 100);
+    }
+    // Ferdie no longer holds the asset.
+    transfer(ferdie, owner, 100);
+
+    // 2. Senders must pass KYC and receivers must be accredited.
+    let scope = Scope::Identity(Identity::get_token_did(&ticker).unwrap());
+    let kyc = Claim::KnowYourCustomer(scope.clone());
+    let accredited = Claim::Accredited(scope.clone());
+    let requirement = ComplianceRequirement {
+        sender_conditions: vec![Condition::from_dids(
+            ConditionType::IsPresent(kyc.clone()),
+            &[cdd.did],
+        )],
+        receiver_conditions: vec![Condition::from_dids(
+            ConditionType::IsPresent(accredited.clone()),
+            &[cdd.did],
+        )],
+        id: 1,
+    };
+    let add_claim = |target, claim| assert_add_claim!(cdd.origin(), target, claim, None);
+    add_claim(owner.did, kyc.clone());
+    add_claim(owner.did, accredited);
+    add_claim(charlie.did, kyc);
+
+    // 3. Only Charlie and Dave would fail, the former only as a receiver.
+    let simulate = |start, count| {
+        ComplianceManager::simulate_compliance(
+            &ticker,
+            &[requirement.clone()],
+            Asset::holders(&ticker),
+            start,
+            count,
+        )
+    };
+    let mut result = simulate(0, 10);
+    assert_eq!(result.next, None);
+    result.failures.sort_by_key(|res| res.did);
+    let mut expected = vec![(charlie.did, true, false), (dave.did, false, false)];
+    expected.sort();
+    assert_eq!(
+        result
+            .failures
+            .iter()
+            .map(|res| (res.did, res.sender, res.receiver))
+            .collect::<Vec<_>>(),
+        expected
+    );
+    // The results explain which conditions failed.
+    let charlie_res = result
+        .failures
+        .iter()
+        .find(|res| res.did == charlie.did)
+        .unwrap();
+    let req = &charlie_res.requirements[0];
+    assert!(!req.result);
+    assert!(req.sender_conditions[0].result);
+    assert!(!req.receiver_conditions[0].result);
+
+    // 4. Paging covers all holders exactly once.
+    let (mut start, mut failures) = (0, Vec::new());
+    loop {
+        let page = simulate(start, 1);
+        failures.extend(page.failures.into_iter().map(|res| res.did));
+        match page.next {
+            Some(next) => start = next,
+            None => break,
+        }
+    }
+    assert_eq!(start, 2);
+    failures.sort();
+    assert_eq!(
+        failures,
+        expected.iter().map(|(did, ..)| *did).collect::<Vec<_>>()
+    );
+
+    // 5. The simulation did not change the rules of the asset.
+    assert_eq!(
+        ComplianceManager::asset_compliance(ticker).requirements,
+        vec![]
+    );
+}
+
+#[test]
+fn suspended_issuer_claims_are_ignored() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(suspended_issuer_claims_are_ignored_we);
+}
+fn suspended_issuer_claims_are_ignored_we() {
+    // 0. Create accounts
+    let owner = User::new(AccountKeyring::Alice);
+    let issuer = User::new(AccountKeyring::Bob);
+    let charlie = User::new(AccountKeyring::Charlie);
+
+    // 1. Create a token whose receivers must be accredited by `issuer`.
+    let (ticker, _) = create_token(owner);
+    provide_scope_claim_to_multiple_parties(
+        &[owner.did, charlie.did],
+        ticker,
+        AccountKeyring::Eve.to_account_id(),
+    );
+    let scope = Scope::Identity(Identity::get_token_did(&ticker).unwrap());
+    let accredited = Claim::Accredited(scope);
+    assert_ok!(ComplianceManager::add_compliance_requirement(
+        owner.origin(),
+        ticker,
+        vec![],
+        vec![Condition::from_dids(
+            ConditionType::IsPresent(accredited.clone()),
+            &[issuer.did]
+        )]
+    ));
+    assert_add_claim!(issuer.origin(), charlie.did, accredited, None);
+    assert_valid_transfer!(ticker, owner.did, charlie.did, 10);
+
+    // 2. Claims of a suspended issuer are ignored until it is resumed.
+    assert_ok!(Identity::suspend_claim_issuer(gc_vmo(), issuer.did));
+    assert_invalid_transfer!(ticker, owner.did, charlie.did, 10);
+    assert_ok!(Identity::resume_claim_issuer(gc_vmo(), issuer.did));
+    assert_valid_transfer!(ticker, owner.did, charlie.did, 10);
+}
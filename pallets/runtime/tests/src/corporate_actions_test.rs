@@ -11,23 +11,32 @@ use core::iter;
 use frame_support::{
     assert_noop, assert_ok,
     dispatch::{DispatchError, DispatchResult},
+    traits::OnInitialize,
     IterableStorageDoubleMap, StorageDoubleMap, StorageMap,
 };
 use pallet_corporate_actions::{
     ballot::{self, BallotMeta, BallotTimeRange, BallotVote, Motion},
-    distribution::{self, Distribution, PER_SHARE_PRECISION},
+    distribution::{self, Distribution, ScripOption, PER_SHARE_PRECISION},
+    fixed_income::{self, MAX_REDEEMED_PORTFOLIOS},
+    split::{self, CashInLieu, SplitRatio, SplitStatus},
+    tender::{self, TenderDisposal},
     CACheckpoint, CADetails, CAId, CAIdSequence, CAKind, CorporateAction, CorporateActions,
     LocalCAId, RecordDate, RecordDateSpec, TargetIdentities, TargetTreatment,
     TargetTreatment::{Exclude, Include},
     Tax,
 };
+use pallet_settlement::{Leg, SettlementType, VenueDetails, VenueType};
+use pallet_sto::{FundraiserName, PriceTier};
 use polymesh_common_utilities::asset::AssetFnTrait;
+use polymesh_common_utilities::constants::{currency::ONE_UNIT, ASSET_MATURED};
 use polymesh_common_utilities::traits::checkpoint::{ScheduleId, StoredSchedule};
+use polymesh_common_utilities::traits::portfolio::PortfolioSubTrait;
 use polymesh_primitives::{
     agent::AgentGroup,
-    calendar::{CheckpointId, CheckpointSchedule},
-    AuthorizationData, Document, DocumentId, IdentityId, Moment, PortfolioId, PortfolioNumber,
-    Signatory, Ticker,
+    asset::AssetType,
+    calendar::{CalendarPeriod, CalendarUnit, CheckpointId, CheckpointSchedule},
+    AuthorizationData, Document, DocumentId, IdentityId, Moment, PortfolioId, PortfolioKind,
+    PortfolioNumber, Signatory, Ticker,
 };
 use sp_arithmetic::Permill;
 use std::convert::TryInto;
@@ -45,10 +54,18 @@ type ComplianceManager = pallet_compliance_manager::Module<TestStorage>;
 type CA = pallet_corporate_actions::Module<TestStorage>;
 type Ballot = ballot::Module<TestStorage>;
 type Dist = distribution::Module<TestStorage>;
+type Split = split::Module<TestStorage>;
+type Tender = tender::Module<TestStorage>;
+type FixedIncome = fixed_income::Module<TestStorage>;
 type Portfolio = pallet_portfolio::Module<TestStorage>;
+type Settlement = pallet_settlement::Module<TestStorage>;
+type Sto = pallet_sto::Module<TestStorage>;
 type Error = pallet_corporate_actions::Error<TestStorage>;
 type BallotError = ballot::Error<TestStorage>;
 type DistError = distribution::Error<TestStorage>;
+type SplitError = split::Error<TestStorage>;
+type TenderError = tender::Error<TestStorage>;
+type FIError = fixed_income::Error<TestStorage>;
 type PError = pallet_portfolio::Error<TestStorage>;
 type CPError = pallet_asset::checkpoint::Error<TestStorage>;
 type EAError = pallet_external_agents::Error<TestStorage>;
@@ -563,6 +580,7 @@ const ALL_CA_KINDS: &[CAKind] = &[
     CAKind::IssuerNotice,
     CAKind::Reorganization,
     CAKind::Other,
+    CAKind::TenderOffer,
 ];
 
 #[test]
@@ -1729,6 +1747,64 @@ fn vote_scheduled_checkpoint() {
     vote_cp_test(|ticker, owner| notice_ca(owner, ticker, Some(2000)).unwrap());
 }
 
+#[test]
+fn vote_class_weights_works() {
+    test(|ticker, [owner, foo, bar]| {
+        set_schedule_complexity();
+
+        // `class` is in the family of `ticker`, unlike `other`.
+        let class = create_asset(b"CLASS", owner);
+        let other = create_asset(b"OTHER", owner);
+        assert_ok!(Asset::link_asset_class(owner.origin(), ticker, class));
+        transfer(&ticker, owner, foo);
+        transfer(&class, owner, bar);
+
+        let id = notice_ca(owner, ticker, Some(2000)).unwrap();
+        let set = |user: User, weights| Ballot::set_class_weights(user.origin(), id, weights);
+        assert_noop!(set(owner, vec![(ticker, 1)]), BallotError::NoSuchBallot);
+        assert_ok!(attach(owner, id, false));
+        assert_noop!(set(foo, vec![(ticker, 1)]), EAError::UnauthorizedAgent);
+        assert_noop!(
+            set(owner, vec![(ticker, 1), (ticker, 2)]),
+            BallotError::DuplicateClass
+        );
+        assert_noop!(
+            set(owner, vec![(ticker, 1), (other, 2)]),
+            BallotError::ClassNotInFamily
+        );
+
+        // Each share of `class` counts as ten votes, snapshotted by a schedule at the record date.
+        let weights = vec![(ticker, 1), (class, 10)];
+        assert_ok!(set(owner, weights.clone()));
+        let schedule = |id| Ballot::class_weights(id)[1].schedule.unwrap();
+        let first = schedule(id);
+        assert_eq!(Ballot::class_weights(id)[0].schedule, None);
+        assert_eq!(Checkpoint::schedule_ref_count(class, first), 1);
+
+        // Changing the record date removes the weights, releasing the schedule.
+        let rd = Some(RecordDateSpec::Scheduled(2000));
+        assert_ok!(CA::change_record_date(owner.origin(), id, rd));
+        assert_eq!(Ballot::class_weights(id), vec![]);
+        assert_eq!(Checkpoint::schedule_ref_count(class, first), 0);
+        assert_ok!(set(owner, weights));
+
+        // Changes in balances after the record date are not counted.
+        Timestamp::set_timestamp(2000);
+        transfer(&class, owner, foo);
+        assert_noop!(set(owner, vec![]), BallotError::RecordDatePassed);
+        assert_eq!(Checkpoint::schedule_points(class, schedule(id)).len(), 1);
+
+        // `foo` had 500 of `ticker`, and `bar` 500 of `class`, at the record date.
+        Timestamp::set_timestamp(T_RANGE.start);
+        let vote = |user: User, vs| Ballot::vote(user.origin(), id, votes(vs));
+        assert_noop!(vote(foo, &[501, 0, 0, 0]), BallotError::InsufficientVotes);
+        assert_ok!(vote(foo, &[500, 0, 0, 500]));
+        assert_noop!(vote(bar, &[5001, 0, 0, 0]), BallotError::InsufficientVotes);
+        assert_ok!(vote(bar, &[0, 5000, 0, 5000]));
+        assert_eq!(Ballot::results(id), vec![500, 5000, 0, 5500]);
+    });
+}
+
 fn dist_ca(owner: User, ticker: Ticker, rd: Option<Moment>) -> Result<CAId, DispatchError> {
     let id = next_ca_id(ticker);
     moment_ca(owner, ticker, CAKind::UnpredictableBenefit, rd)?;
@@ -2148,6 +2224,98 @@ fn dist_claim_no_remaining() {
     });
 }
 
+#[test]
+fn dist_scrip_works() {
+    currency_test(|ticker, currency, [owner, foo, bar]| {
+        // Transfer 500 to `foo` & `bar`.
+        transfer(&ticker, owner, foo);
+        transfer(&ticker, owner, bar);
+
+        // Create the dist.
+        let id = dist_ca(owner, ticker, Some(1)).unwrap();
+        let per_share = 2 * PER_SHARE_PRECISION;
+        assert_ok!(Dist::distribute(
+            owner.origin(),
+            id,
+            None,
+            currency,
+            per_share,
+            200_000,
+            5,
+            None,
+        ));
+
+        // Set the scrip option, which must be priced and end by the payment date.
+        let scrip = |reference_price, election_ends_at| {
+            Some(ScripOption {
+                reference_price,
+                election_ends_at,
+            })
+        };
+        let reference_price = 3 * PER_SHARE_PRECISION;
+        assert_noop!(
+            Dist::elect_scrip(foo.origin(), id, true),
+            DistError::NoScripOption
+        );
+        assert_noop!(
+            Dist::set_scrip_option(foo.origin(), id, scrip(reference_price, 4)),
+            EAError::UnauthorizedAgent
+        );
+        assert_noop!(
+            Dist::set_scrip_option(owner.origin(), id, scrip(0, 4)),
+            DistError::InvalidScripOption
+        );
+        assert_noop!(
+            Dist::set_scrip_option(owner.origin(), id, scrip(reference_price, 6)),
+            DistError::InvalidScripOption
+        );
+        assert_ok!(Dist::set_scrip_option(
+            owner.origin(),
+            id,
+            scrip(reference_price, 4)
+        ));
+        assert_eq!(Dist::scrip_option(id), scrip(reference_price, 4));
+
+        // `foo` elects scrip, `bar` changes their mind.
+        assert_ok!(Dist::elect_scrip(foo.origin(), id, true));
+        assert_ok!(Dist::elect_scrip(bar.origin(), id, true));
+        assert_ok!(Dist::elect_scrip(bar.origin(), id, false));
+        assert!(Dist::scrip_elected(id, foo.did));
+        assert!(!Dist::scrip_elected(id, bar.did));
+
+        // Elections end before payment.
+        Timestamp::set_timestamp(4);
+        assert_noop!(
+            Dist::elect_scrip(bar.origin(), id, true),
+            DistError::ScripElectionEnded
+        );
+        assert_noop!(
+            Dist::set_scrip_option(owner.origin(), id, None),
+            DistError::DistributionStarted
+        );
+        Timestamp::set_timestamp(5);
+
+        // `foo` is issued shares, with the fraction of a share paid in `currency`.
+        let supply = Asset::total_supply(ticker);
+        let benefit = 500 * per_share / PER_SHARE_PRECISION;
+        let shares = benefit * PER_SHARE_PRECISION / reference_price;
+        let cash = benefit - shares * reference_price / PER_SHARE_PRECISION;
+        assert_ok!(Dist::claim(foo.origin(), id));
+        assert_eq!(Asset::balance(&ticker, foo.did), 500 + shares);
+        assert_eq!(Asset::total_supply(ticker), supply + shares);
+        assert_eq!(Asset::balance(&currency, foo.did), cash);
+
+        // `bar` is paid in `currency`.
+        assert_ok!(Dist::push_benefit(owner.origin(), id, bar.did));
+        assert_eq!(Asset::balance(&ticker, bar.did), 500);
+        assert_eq!(Asset::balance(&currency, bar.did), benefit);
+        assert_eq!(
+            Dist::distributions(id).unwrap().remaining,
+            200_000 - 2 * benefit
+        );
+    });
+}
+
 fn dist_claim_cp_test(mk_ca: impl FnOnce(Ticker, User) -> CAId) {
     currency_test(|ticker, currency, [owner, other, claimant]| {
         // Owner ==[500]==> Voter.
@@ -2204,3 +2372,699 @@ fn dist_claim_existing_checkpoint() {
 fn dist_claim_scheduled_checkpoint() {
     dist_claim_cp_test(|ticker, owner| dist_ca(owner, ticker, Some(2000)).unwrap());
 }
+
+fn split_ca(owner: User, ticker: Ticker, rd: Option<Moment>) -> Result<CAId, DispatchError> {
+    let id = next_ca_id(ticker);
+    moment_ca(owner, ticker, CAKind::Reorganization, rd)?;
+    Ok(id)
+}
+
+#[test]
+fn split_attach_bad() {
+    currency_test(|ticker, currency, [owner, foo, _]| {
+        let ratio = |numerator, denominator| SplitRatio {
+            numerator,
+            denominator,
+        };
+        let cash = |currency| {
+            Some(CashInLieu {
+                from: PortfolioId::default_portfolio(owner.did),
+                currency,
+                price: PER_SHARE_PRECISION,
+            })
+        };
+
+        // Only reorganizations with a record date can be split.
+        let id = dist_ca(owner, ticker, Some(2)).unwrap();
+        assert_noop!(
+            Split::attach_split(owner.origin(), id, ratio(2, 1), None),
+            SplitError::CANotReorganization
+        );
+        let id = split_ca(owner, ticker, None).unwrap();
+        assert_noop!(
+            Split::attach_split(owner.origin(), id, ratio(2, 1), None),
+            Error::NoRecordDate
+        );
+
+        // Ratios and cash-in-lieu must be sane.
+        let id = split_ca(owner, ticker, Some(2)).unwrap();
+        for bad in &[ratio(0, 1), ratio(1, 0), ratio(3, 3)] {
+            assert_noop!(
+                Split::attach_split(owner.origin(), id, *bad, None),
+                SplitError::InvalidSplitRatio
+            );
+        }
+        assert_noop!(
+            Split::attach_split(owner.origin(), id, ratio(2, 1), cash(ticker)),
+            SplitError::CashInLieuInAsset
+        );
+        assert_noop!(
+            Split::attach_split(foo.origin(), id, ratio(2, 1), cash(currency)),
+            EAError::UnauthorizedAgent
+        );
+        assert_ok!(Split::attach_split(
+            owner.origin(),
+            id,
+            ratio(2, 1),
+            cash(currency)
+        ));
+        assert_noop!(
+            Split::attach_split(owner.origin(), id, ratio(2, 1), None),
+            SplitError::AlreadyExists
+        );
+
+        // Pending splits can be removed, also along with their CA.
+        assert_ok!(Split::remove_split(owner.origin(), id));
+        assert_eq!(Split::splits(id), None);
+        assert_ok!(Split::attach_split(owner.origin(), id, ratio(2, 1), None));
+        assert_ok!(CA::remove_ca(owner.origin(), id));
+        assert_eq!(Split::splits(id), None);
+    });
+}
+
+#[test]
+fn split_works() {
+    currency_test(|ticker, _, [owner, foo, bar]| {
+        // Transfer 500 to `foo` & `bar`, and lock 101 of `foo`'s.
+        transfer(&ticker, owner, foo);
+        transfer(&ticker, owner, bar);
+        let foo_pid = PortfolioId::default_portfolio(foo.did);
+        Portfolio::unchecked_lock_tokens(&foo_pid, &ticker, &101);
+        let supply = Asset::total_supply(ticker);
+
+        // Attach a 3:2 split.
+        let id = split_ca(owner, ticker, Some(2)).unwrap();
+        let ratio = SplitRatio {
+            numerator: 3,
+            denominator: 2,
+        };
+        assert_ok!(Split::attach_split(owner.origin(), id, ratio, None));
+
+        // Execution waits for the record date.
+        assert_noop!(
+            Split::execute_split(owner.origin(), id, 10),
+            SplitError::SplitBeforeRecordDate
+        );
+        Timestamp::set_timestamp(2);
+
+        // The asset is frozen while the split is executing.
+        assert_ok!(Split::execute_split(owner.origin(), id, 1));
+        assert!(matches!(
+            Split::splits(id).unwrap().status,
+            SplitStatus::Executing { was_frozen: false }
+        ));
+        assert!(Asset::frozen(ticker));
+        assert_noop!(
+            Split::remove_split(owner.origin(), id),
+            SplitError::SplitStarted
+        );
+        assert_noop!(
+            CA::change_record_date(owner.origin(), id, None),
+            Error::NoRecordDate
+        );
+
+        // Finish the split, which unfreezes the asset.
+        assert_ok!(Split::execute_split(owner.origin(), id, 10));
+        assert_eq!(Split::splits(id).unwrap().status, SplitStatus::Executed);
+        assert!(!Asset::frozen(ticker));
+        assert_noop!(
+            Split::execute_split(owner.origin(), id, 10),
+            SplitError::SplitAlreadyExecuted
+        );
+
+        // Balances, locks, and the supply were rescaled, rounding down.
+        assert_eq!(Asset::balance(&ticker, foo.did), 750);
+        assert_eq!(Asset::balance(&ticker, bar.did), 750);
+        assert_eq!(Portfolio::locked_assets(foo_pid, ticker), 151);
+        assert_eq!(Asset::total_supply(ticker), supply * 3 / 2);
+        assert_eq!(Portfolio::default_portfolio_balance(foo.did, &ticker), 750);
+
+        // The record date checkpoint holds the balances before the split.
+        let cp = Checkpoint::checkpoint_id_sequence(ticker);
+        assert_eq!(Asset::get_balance_at(ticker, foo.did, cp), 500);
+        assert_eq!(Checkpoint::total_supply_at(ticker, cp), supply);
+    });
+}
+
+#[test]
+fn split_cash_in_lieu_works() {
+    currency_test(|_, currency, [owner, foo, bar]| {
+        // Create an indivisible asset, and transfer 5 units of it to `foo`.
+        let (ticker, mut token) = token(b"GAMMA", owner.did);
+        token.divisible = false;
+        token.total_supply = 1_000 * ONE_UNIT;
+        assert_ok!(basic_asset(owner, ticker, &token));
+        allow_all_transfers(ticker, owner);
+        provide_scope_claim_to_multiple_parties(
+            &[owner.did, foo.did],
+            ticker,
+            CDDP.to_account_id(),
+        );
+        assert_ok!(crate::asset_test::transfer(
+            ticker,
+            owner,
+            foo,
+            5 * ONE_UNIT
+        ));
+
+        // Attach a 1:3 reverse split, paying 3 `currency` per share for fractions.
+        let id = split_ca(owner, ticker, Some(2)).unwrap();
+        let ratio = SplitRatio {
+            numerator: 1,
+            denominator: 3,
+        };
+        let price = 3 * PER_SHARE_PRECISION;
+        let cash = CashInLieu {
+            from: PortfolioId::default_portfolio(owner.did),
+            currency,
+            price,
+        };
+        assert_ok!(Split::attach_split(owner.origin(), id, ratio, Some(cash)));
+        Timestamp::set_timestamp(2);
+        assert_ok!(Split::execute_split(owner.origin(), id, 10));
+
+        // `foo` is left with whole units, and is owed for the fraction of a share.
+        let fraction = 5 * ONE_UNIT / 3 - ONE_UNIT;
+        let owed = fraction * price / PER_SHARE_PRECISION;
+        assert_eq!(Asset::balance(&ticker, foo.did), ONE_UNIT);
+        assert_eq!(Asset::balance(&ticker, owner.did), 331 * ONE_UNIT);
+        assert_eq!(Asset::total_supply(ticker), 332 * ONE_UNIT);
+        assert_eq!(Split::cash_in_lieu_owed(id, foo.did), owed);
+
+        // `foo` claims their cash, but only once; `bar` is owed nothing.
+        assert_ok!(Split::claim_cash_in_lieu(foo.origin(), id));
+        assert_eq!(Asset::balance(&currency, foo.did), owed);
+        assert_noop!(
+            Split::claim_cash_in_lieu(foo.origin(), id),
+            SplitError::NoCashInLieu
+        );
+        assert_noop!(
+            Split::claim_cash_in_lieu(bar.origin(), id),
+            SplitError::NoCashInLieu
+        );
+    });
+}
+
+#[test]
+fn split_rescales_locks_and_offerings() {
+    currency_test(|ticker, currency, [owner, foo, bar]| {
+        // `foo` locks 101 and sends another 101 to `bar` in an affirmed instruction.
+        transfer(&ticker, owner, foo);
+        let foo_pid = PortfolioId::default_portfolio(foo.did);
+        Portfolio::unchecked_lock_tokens(&foo_pid, &ticker, &101);
+        let venue = Settlement::venue_counter();
+        assert_ok!(Settlement::create_venue(
+            foo.origin(),
+            VenueDetails::default(),
+            vec![],
+            VenueType::Other
+        ));
+        let instruction = Settlement::instruction_counter();
+        let leg = Leg {
+            from: foo_pid,
+            to: PortfolioId::default_portfolio(bar.did),
+            asset: ticker,
+            amount: 101,
+        };
+        assert_ok!(Settlement::add_and_affirm_instruction(
+            foo.origin(),
+            venue,
+            SettlementType::SettleOnAffirmation,
+            None,
+            None,
+            vec![leg],
+            vec![foo_pid]
+        ));
+
+        // `owner` offers 1_000 at a price of 2 `currency`.
+        let owner_pid = PortfolioId::default_portfolio(owner.did);
+        let venue = Settlement::venue_counter();
+        assert_ok!(Settlement::create_venue(
+            owner.origin(),
+            VenueDetails::default(),
+            vec![],
+            VenueType::Sto
+        ));
+        let fundraiser_id = Sto::fundraiser_count(ticker);
+        assert_ok!(Sto::create_fundraiser(
+            owner.origin(),
+            owner_pid,
+            ticker,
+            owner_pid,
+            currency,
+            vec![PriceTier {
+                total: 1_000,
+                price: 2_000_000
+            }],
+            venue,
+            None,
+            None,
+            0,
+            FundraiserName::default(),
+        ));
+
+        // A split whose supply would exceed the maximum supply fails.
+        let id = split_ca(owner, ticker, Some(2)).unwrap();
+        let ratio = |numerator| SplitRatio {
+            numerator,
+            denominator: 2,
+        };
+        assert_ok!(Split::attach_split(
+            owner.origin(),
+            id,
+            ratio(u32::MAX),
+            None
+        ));
+        Timestamp::set_timestamp(2);
+        assert_noop!(
+            Split::execute_split(owner.origin(), id, 10),
+            AssetError::TotalSupplyAboveLimit
+        );
+        assert_ok!(Split::remove_split(owner.origin(), id));
+
+        // Execute a 3:2 split one holder at a time, resuming after the last holder rescaled.
+        assert_ok!(Split::attach_split(owner.origin(), id, ratio(3), None));
+        assert_ok!(Split::execute_split(owner.origin(), id, 1));
+        assert!(Split::split_cursor(id).is_some());
+        assert_ok!(Split::execute_split(owner.origin(), id, 1));
+        assert_eq!(Split::splits(id).unwrap().status, SplitStatus::Executed);
+        assert_eq!(Split::split_cursor(id), None);
+
+        // What the leg locks is rescaled along with the leg, and the rest on its own.
+        assert_eq!(Asset::balance(&ticker, foo.did), 750);
+        assert_eq!(Settlement::instruction_legs(instruction, 0).amount, 151);
+        assert_eq!(Portfolio::locked_assets(foo_pid, ticker), 151 + 151);
+
+        // The offering is rescaled for the same funds, and stays fully locked until stopped.
+        let tier = &Sto::fundraisers(ticker, fundraiser_id).unwrap().tiers[0];
+        assert_eq!((tier.total, tier.remaining), (1_500, 1_500));
+        assert_eq!(tier.price, 1_333_333);
+        assert_eq!(Portfolio::locked_assets(owner_pid, ticker), 1_500);
+        assert_ok!(Sto::stop(owner.origin(), ticker, fundraiser_id));
+        assert_eq!(Portfolio::locked_assets(owner_pid, ticker), 0);
+    });
+}
+
+#[test]
+fn split_waits_for_other_locks() {
+    currency_test(|ticker, currency, [owner, ..]| {
+        // A tender offer for `currency` locks `ticker` to pay for the shares.
+        let offer_id = tender_ca(owner, currency, None);
+        assert_ok!(Tender::launch_tender_offer(
+            owner.origin(),
+            offer_id,
+            None,
+            ticker,
+            PER_SHARE_PRECISION,
+            600,
+            3,
+            5,
+            TenderDisposal::Redeem,
+            false,
+        ));
+
+        // The split can't execute until the offer is removed.
+        let id = split_ca(owner, ticker, Some(2)).unwrap();
+        let ratio = SplitRatio {
+            numerator: 3,
+            denominator: 2,
+        };
+        assert_ok!(Split::attach_split(owner.origin(), id, ratio, None));
+        Timestamp::set_timestamp(2);
+        assert_noop!(
+            Split::execute_split(owner.origin(), id, 10),
+            SplitError::AssetLocked
+        );
+        assert_ok!(Tender::remove_tender_offer(owner.origin(), offer_id));
+        assert_ok!(Split::execute_split(owner.origin(), id, 10));
+        assert_eq!(Split::splits(id).unwrap().status, SplitStatus::Executed);
+    });
+}
+
+fn tender_ca(owner: User, ticker: Ticker, targets: Option<TargetIdentities>) -> CAId {
+    let id = next_ca_id(ticker);
+    let kind = CAKind::TenderOffer;
+    init_ca(
+        owner,
+        ticker,
+        kind,
+        None,
+        <_>::default(),
+        targets,
+        None,
+        None,
+    )
+    .unwrap();
+    id
+}
+
+#[test]
+fn tender_offer_launch_bad() {
+    currency_test(|ticker, currency, [owner, foo, _]| {
+        let id = tender_ca(owner, ticker, None);
+        let launch = |id, currency, price, start, end, mandatory| {
+            Tender::launch_tender_offer(
+                owner.origin(),
+                id,
+                None,
+                currency,
+                price,
+                600,
+                start,
+                end,
+                TenderDisposal::Redeem,
+                mandatory,
+            )
+        };
+
+        assert_noop!(
+            launch(id, ticker, PER_SHARE_PRECISION, 2, 5, false),
+            TenderError::BuyingWithAsset
+        );
+        assert_noop!(
+            launch(id, currency, PER_SHARE_PRECISION, 5, 5, false),
+            TenderError::InvalidTenderWindow
+        );
+        assert_noop!(
+            launch(id, currency, 0, 2, 5, false),
+            TenderError::InvalidTenderTerms
+        );
+        let dist_id = dist_ca(owner, ticker, Some(2)).unwrap();
+        assert_noop!(
+            launch(dist_id, currency, PER_SHARE_PRECISION, 2, 5, false),
+            TenderError::CANotTenderOffer
+        );
+
+        // Mandatory offers must target every holder.
+        let targets = TargetIdentities {
+            identities: vec![foo.did],
+            treatment: Include,
+        };
+        let partial_id = tender_ca(owner, ticker, Some(targets));
+        assert_noop!(
+            launch(partial_id, currency, PER_SHARE_PRECISION, 2, 5, true),
+            TenderError::MandatoryOfferNotToEveryone
+        );
+        assert_ok!(launch(id, currency, PER_SHARE_PRECISION, 2, 5, true));
+        assert_noop!(
+            launch(id, currency, PER_SHARE_PRECISION, 2, 5, true),
+            TenderError::AlreadyExists
+        );
+
+        // Planned offers can be removed, unlocking their funds, also along with their CA.
+        let owner_pid = PortfolioId::default_portfolio(owner.did);
+        assert_eq!(Portfolio::locked_assets(owner_pid, currency), 600);
+        assert_ok!(Tender::remove_tender_offer(owner.origin(), id));
+        assert_eq!(Portfolio::locked_assets(owner_pid, currency), 0);
+        assert_ok!(launch(id, currency, PER_SHARE_PRECISION, 2, 5, false));
+        assert_ok!(CA::remove_ca(owner.origin(), id));
+        assert_eq!(Tender::tender_offers(id), None);
+        assert_eq!(Portfolio::locked_assets(owner_pid, currency), 0);
+    });
+}
+
+#[test]
+fn tender_offer_works() {
+    currency_test(|ticker, currency, [owner, foo, bar]| {
+        // Transfer 500 to `foo` & `bar`.
+        transfer(&ticker, owner, foo);
+        transfer(&ticker, owner, bar);
+        let supply = Asset::total_supply(ticker);
+        let owner_pid = PortfolioId::default_portfolio(owner.did);
+        let foo_pid = PortfolioId::default_portfolio(foo.did);
+
+        // Buy back at most 600 shares, at 2 `currency` each, redeeming them.
+        let id = tender_ca(owner, ticker, None);
+        let price = 2 * PER_SHARE_PRECISION;
+        assert_ok!(Tender::launch_tender_offer(
+            owner.origin(),
+            id,
+            None,
+            currency,
+            price,
+            600,
+            2,
+            5,
+            TenderDisposal::Redeem,
+            false,
+        ));
+        assert_eq!(Portfolio::locked_assets(owner_pid, currency), 1200);
+
+        // Tendering happens within the window.
+        assert_noop!(
+            Tender::tender(foo.origin(), id, None, 400),
+            TenderError::NotInTenderWindow
+        );
+        Timestamp::set_timestamp(2);
+        assert_noop!(
+            Tender::remove_tender_offer(owner.origin(), id),
+            TenderError::TenderOfferStarted
+        );
+        assert_noop!(
+            Tender::tender(bar.origin(), id, None, 600),
+            PError::InsufficientPortfolioBalance
+        );
+
+        // `foo` revises their tender, which oversubscribes the offer.
+        assert_ok!(Tender::tender(foo.origin(), id, None, 400));
+        assert_ok!(Tender::tender(foo.origin(), id, None, 500));
+        assert_ok!(Tender::tender(bar.origin(), id, None, 300));
+        assert_eq!(Portfolio::locked_assets(foo_pid, ticker), 500);
+        assert_eq!(Tender::tender_offers(id).unwrap().tendered, 800);
+
+        // Settlement waits for the window to close.
+        assert_noop!(
+            Tender::settle_tender_offer(owner.origin(), id, 10),
+            TenderError::TenderOfferNotEnded
+        );
+        Timestamp::set_timestamp(5);
+        assert_noop!(
+            Tender::tender(foo.origin(), id, None, 100),
+            TenderError::NotInTenderWindow
+        );
+        assert_ok!(Tender::settle_tender_offer(owner.origin(), id, 1));
+        assert!(!Tender::tender_offers(id).unwrap().settled);
+        assert_ok!(Tender::settle_tender_offer(owner.origin(), id, 10));
+        assert_noop!(
+            Tender::settle_tender_offer(owner.origin(), id, 10),
+            TenderError::TenderOfferSettled
+        );
+
+        // Shares were bought pro-rata and redeemed, and every lock was released.
+        let offer = Tender::tender_offers(id).unwrap();
+        assert!(offer.settled);
+        assert_eq!((offer.bought, offer.paid), (600, 1200));
+        assert_eq!(Asset::balance(&ticker, foo.did), 500 - 375);
+        assert_eq!(Asset::balance(&ticker, bar.did), 500 - 225);
+        assert_eq!(Asset::balance(&currency, foo.did), 750);
+        assert_eq!(Asset::balance(&currency, bar.did), 450);
+        assert_eq!(Asset::total_supply(ticker), supply - 600);
+        assert_eq!(Portfolio::locked_assets(foo_pid, ticker), 0);
+        assert_eq!(Portfolio::locked_assets(owner_pid, currency), 0);
+
+        // An undersubscribed offer buys every share tendered, into the treasury.
+        let id = tender_ca(owner, ticker, None);
+        assert_ok!(Tender::launch_tender_offer(
+            owner.origin(),
+            id,
+            None,
+            currency,
+            price,
+            600,
+            6,
+            7,
+            TenderDisposal::Treasury(PortfolioKind::Default),
+            false,
+        ));
+        Timestamp::set_timestamp(6);
+        assert_ok!(Tender::tender(foo.origin(), id, None, 100));
+        Timestamp::set_timestamp(7);
+        let owner_balance = Asset::balance(&ticker, owner.did);
+        assert_ok!(Tender::settle_tender_offer(owner.origin(), id, 10));
+        assert_eq!(Asset::balance(&ticker, foo.did), 25);
+        assert_eq!(Asset::balance(&ticker, owner.did), owner_balance + 100);
+        assert_eq!(Asset::total_supply(ticker), supply - 600);
+        assert_eq!(Portfolio::locked_assets(owner_pid, currency), 0);
+    });
+}
+
+fn create_bond(owner: User) -> Ticker {
+    let (ticker, mut token) = token(b"BOND", owner.did);
+    token.asset_type = AssetType::FixedIncome;
+    assert_ok!(basic_asset(owner, ticker, &token));
+    allow_all_transfers(ticker, owner);
+    ticker
+}
+
+#[test]
+fn fixed_income_works() {
+    currency_test(|equity, currency, [owner, foo, bar]| {
+        let ticker = create_bond(owner);
+        transfer(&ticker, owner, foo);
+        transfer(&ticker, owner, bar);
+        let supply = Asset::total_supply(ticker);
+        let owner_pid = PortfolioId::default_portfolio(owner.did);
+        let foo_pid = PortfolioId::default_portfolio(foo.did);
+        let bar_pid = PortfolioId::default_portfolio(bar.did);
+
+        // A coupon of 1% of par every second, from 1s until maturity at 3s.
+        let schedule = CheckpointSchedule {
+            start: 1000,
+            period: CalendarPeriod {
+                unit: CalendarUnit::Second,
+                amount: 1,
+            },
+        };
+        let coupon = PER_SHARE_PRECISION / 100;
+        let par = PER_SHARE_PRECISION;
+        let set_up = |user: User, ticker, coupon, schedule, maturity| {
+            FixedIncome::set_up_bond(
+                user.origin(),
+                ticker,
+                None,
+                currency,
+                coupon,
+                par,
+                schedule,
+                maturity,
+            )
+        };
+
+        assert_noop!(
+            set_up(foo, ticker, coupon, Some(schedule), 3000),
+            EAError::UnauthorizedAgent
+        );
+        assert_noop!(
+            set_up(owner, equity, coupon, Some(schedule), 3000),
+            FIError::NotFixedIncome
+        );
+        assert_noop!(
+            set_up(owner, ticker, 0, Some(schedule), 3000),
+            FIError::InvalidBondTerms
+        );
+        assert_noop!(
+            set_up(owner, ticker, coupon, None, 3000),
+            FIError::InvalidBondTerms
+        );
+        assert_noop!(
+            set_up(owner, ticker, coupon, Some(schedule), 1),
+            FIError::InvalidMaturity
+        );
+        assert_noop!(
+            set_up(owner, ticker, coupon, Some(schedule), 500),
+            FIError::InvalidCouponSchedule
+        );
+        assert_ok!(set_up(owner, ticker, coupon, Some(schedule), 3000));
+        assert_noop!(
+            set_up(owner, ticker, coupon, Some(schedule), 3000),
+            FIError::AlreadyExists
+        );
+        let bond = FixedIncome::bonds(ticker).unwrap();
+        assert_eq!(bond.coupons, 3);
+        assert_eq!(Asset::maturity(ticker), Some(3000));
+
+        // The first coupon is distributed once due, on the balances at its checkpoint.
+        let coupon_id = next_ca_id(ticker);
+        FixedIncome::on_initialize(1);
+        assert_eq!(Dist::distributions(coupon_id), None);
+        Timestamp::set_timestamp(1000);
+        FixedIncome::on_initialize(2);
+        let dist = Dist::distributions(coupon_id).unwrap();
+        assert_eq!((dist.per_share, dist.amount), (coupon, supply / 100));
+        assert_ok!(Dist::claim(foo.origin(), coupon_id));
+        assert_eq!(Asset::balance(&currency, foo.did), 5);
+        assert_eq!(FixedIncome::bonds(ticker).unwrap().coupons_due, 1);
+
+        // Overdue coupons are caught up on, one per block, and then at maturity,
+        // the redemption is missed for lack of funds, and transfers stop.
+        Timestamp::set_timestamp(3000);
+        FixedIncome::on_initialize(3);
+        assert_eq!(FixedIncome::bonds(ticker).unwrap().coupons_due, 2);
+        assert_eq!(FixedIncome::queued_bonds(), vec![(3000, ticker)]);
+        FixedIncome::on_initialize(4);
+        let bond = FixedIncome::bonds(ticker).unwrap();
+        assert_eq!(bond.coupons_due, 3);
+        assert!(!bond.matured);
+        FixedIncome::on_initialize(5);
+        let bond = FixedIncome::bonds(ticker).unwrap();
+        assert!(bond.matured);
+        assert_eq!(Dist::distributions(bond.redemption), None);
+        assert_eq!(FixedIncome::queued_bonds(), vec![]);
+        assert_noop!(
+            crate::asset_test::transfer(ticker, foo, bar, 1),
+            AssetError::InvalidTransfer
+        );
+        assert_eq!(
+            Asset::_is_valid_transfer(&ticker, foo_pid, bar_pid, 1),
+            Ok(ASSET_MATURED)
+        );
+
+        // The CAA funds and attaches the redemption themselves, which cannot offer scrip.
+        assert_ok!(Asset::issue(owner.origin(), currency, supply));
+        assert_ok!(Dist::distribute(
+            owner.origin(),
+            bond.redemption,
+            None,
+            currency,
+            par,
+            supply,
+            4000,
+            None,
+        ));
+        let scrip = ScripOption {
+            reference_price: par,
+            election_ends_at: 4000,
+        };
+        assert_noop!(
+            Dist::set_scrip_option(owner.origin(), bond.redemption, Some(scrip)),
+            DistError::ScripOnRedemption
+        );
+
+        // Holders are paid par, and their free shares are redeemed,
+        // while locked ones are pending until the CAA redeems them.
+        Timestamp::set_timestamp(4000);
+        Portfolio::unchecked_lock_tokens(&bar_pid, &ticker, &100);
+        let foo_currency = Asset::balance(&currency, foo.did);
+        assert_ok!(Dist::claim(foo.origin(), bond.redemption));
+        assert_ok!(Dist::push_benefit(owner.origin(), bond.redemption, bar.did));
+        assert_eq!(Asset::balance(&currency, foo.did), foo_currency + 500);
+        assert_eq!(Asset::balance(&ticker, foo.did), 0);
+        assert_eq!(Asset::balance(&ticker, bar.did), 100);
+        assert_eq!(
+            FixedIncome::pending_redemption(bond.redemption, bar.did),
+            100
+        );
+        assert_eq!(Asset::total_supply(ticker), supply - 900);
+
+        let redeem_pending = |user: User, holder: User, portfolios| {
+            FixedIncome::redeem_pending(user.origin(), bond.redemption, holder.did, portfolios)
+        };
+        assert_noop!(redeem_pending(foo, bar, vec![]), EAError::UnauthorizedAgent);
+        assert_noop!(
+            redeem_pending(owner, foo, vec![]),
+            FIError::NoPendingRedemption
+        );
+        assert_noop!(
+            redeem_pending(
+                owner,
+                bar,
+                vec![PortfolioNumber(1); MAX_REDEEMED_PORTFOLIOS as usize]
+            ),
+            FIError::TooManyPortfolios
+        );
+        assert_ok!(redeem_pending(owner, bar, vec![]));
+        assert_eq!(
+            FixedIncome::pending_redemption(bond.redemption, bar.did),
+            100
+        );
+        assert_ok!(Portfolio::unlock_tokens(&bar_pid, &ticker, &100));
+        assert_ok!(redeem_pending(owner, bar, vec![]));
+        assert_eq!(FixedIncome::pending_redemption(bond.redemption, bar.did), 0);
+        assert_eq!(Asset::balance(&ticker, bar.did), 0);
+        assert_eq!(
+            Portfolio::portfolio_asset_balances(owner_pid, ticker),
+            supply - 1000
+        );
+        assert_eq!(Asset::total_supply(ticker), supply - 1000);
+    });
+}
@@ -1,11 +1,12 @@
 use super::{
+    assert_last_event,
     asset_test::{an_asset, basic_asset, max_len, max_len_bytes, token},
     committee_test::gc_vmo,
     ext_builder::PROTOCOL_OP_BASE_FEE,
     storage::{
         add_secondary_key, create_cdd_id_and_investor_uid, get_identity_id, get_last_auth_id,
         provide_scope_claim, register_keyring_account, register_keyring_account_with_balance,
-        GovernanceCommittee, TestStorage, User,
+        EventTest, GovernanceCommittee, TestStorage, User,
     },
     ExtBuilder,
 };
@@ -13,9 +14,12 @@ use codec::Encode;
 use confidential_identity::mocked::make_investor_uid as make_investor_uid_v2;
 use core::iter;
 use frame_support::{
-    assert_noop, assert_ok, dispatch::DispatchResult, traits::Currency, StorageDoubleMap,
-    StorageMap,
+    assert_noop, assert_ok,
+    dispatch::{DispatchError, DispatchResult},
+    traits::Currency,
+    IterableStorageDoubleMap, StorageDoubleMap, StorageMap,
 };
+use frame_system::EventRecord;
 use pallet_asset::SecurityToken;
 use pallet_balances as balances;
 use pallet_identity::types::DidRecords as RpcDidRecords;
@@ -24,21 +28,23 @@ use polymesh_common_utilities::{
     protocol_fee::ProtocolOp,
     traits::{
         group::GroupTrait,
-        identity::{Config as IdentityConfig, SecondaryKeyWithAuth, TargetIdAuthorization},
+        identity::{
+            ClaimsBundle, Config as IdentityConfig, SecondaryKeyWithAuth, TargetIdAuthorization,
+        },
         transaction_payment::CddAndFeeDetails,
     },
     SystematicIssuers, GC_DID,
 };
 use polymesh_primitives::{
     investor_zkproof_data::v2, AccountId, AssetPermissions, AuthorizationData, AuthorizationError,
-    AuthorizationType, CddId, Claim, ClaimType, DispatchableName, ExtrinsicPermissions,
-    IdentityClaim, IdentityId, InvestorUid, PalletName, PalletPermissions, Permissions,
-    PortfolioId, PortfolioNumber, Scope, SecondaryKey, Signatory, SubsetRestriction, Ticker,
-    TransactionError,
+    AuthorizationType, CddId, Claim, ClaimIssuerName, ClaimIssuerProfile, ClaimIssuerUri,
+    ClaimType, CountryCode, DispatchableName, ExtrinsicPermissions, IdentityClaim, IdentityId,
+    InvestorUid, PalletName, PalletPermissions, Permissions, PortfolioId, PortfolioNumber, Scope,
+    SecondaryKey, Signatory, SubsetRestriction, Ticker, TransactionError,
 };
 use polymesh_runtime_develop::{fee_details::CddHandler, runtime::Call};
 use sp_core::H512;
-use sp_runtime::transaction_validity::InvalidTransaction;
+use sp_runtime::{transaction_validity::InvalidTransaction, MultiSignature};
 use std::convert::{From, TryFrom};
 use test_client::AccountKeyring;
 
@@ -2290,3 +2296,297 @@ fn ext_leave_identity_as_identity() {
         assert_ok!(leave(bob));
     });
 }
+
+#[test]
+fn claim_issuer_registry() {
+    ExtBuilder::default()
+        .monied(true)
+        .build()
+        .execute_with(claim_issuer_registry_we);
+}
+
+fn claim_issuer_registry_we() {
+    let issuer = User::new(AccountKeyring::Alice);
+    let bob = User::new(AccountKeyring::Bob);
+    let charlie = User::new(AccountKeyring::Charlie);
+
+    // 1. Publish, update and remove a profile.
+    let profile = ClaimIssuerProfile {
+        name: ClaimIssuerName(b"Accreditation Inc.".to_vec()),
+        uri: ClaimIssuerUri(b"https://accreditation.example".to_vec()),
+        claim_types: vec![ClaimType::Accredited, ClaimType::Jurisdiction],
+        jurisdictions: vec![CountryCode::US, CountryCode::CA],
+    };
+    let long_profile = ClaimIssuerProfile {
+        name: max_len_bytes(1),
+        ..profile.clone()
+    };
+    assert_too_long!(Identity::set_claim_issuer_profile(
+        issuer.origin(),
+        long_profile
+    ));
+    assert_ok!(Identity::set_claim_issuer_profile(
+        issuer.origin(),
+        profile.clone()
+    ));
+    assert_eq!(Identity::claim_issuer_profile(issuer.did), Some(profile));
+    assert_ok!(Identity::remove_claim_issuer_profile(issuer.origin()));
+    assert_eq!(Identity::claim_issuer_profile(issuer.did), None);
+    assert_noop!(
+        Identity::remove_claim_issuer_profile(issuer.origin()),
+        Error::NoClaimIssuerProfile
+    );
+
+    // 2. Only governance can suspend and resume issuers.
+    let scope = Scope::Identity(issuer.did);
+    let claim = |cc| Claim::Jurisdiction(cc, scope.clone());
+    assert_ok!(Identity::add_claim(
+        issuer.origin(),
+        bob.did,
+        claim(CountryCode::US),
+        None
+    ));
+    assert_noop!(
+        Identity::suspend_claim_issuer(bob.origin(), issuer.did),
+        DispatchError::BadOrigin
+    );
+    assert_ok!(Identity::suspend_claim_issuer(gc_vmo(), issuer.did));
+    assert!(Identity::is_issuer_suspended(issuer.did));
+    assert_noop!(
+        Identity::suspend_claim_issuer(gc_vmo(), issuer.did),
+        Error::IssuerSuspended
+    );
+    // Suspended issuers cannot add claims.
+    assert_noop!(
+        Identity::add_claim(issuer.origin(), charlie.did, claim(CountryCode::US), None),
+        Error::IssuerSuspended
+    );
+    assert_ok!(Identity::resume_claim_issuer(gc_vmo(), issuer.did));
+    assert!(!Identity::is_issuer_suspended(issuer.did));
+    assert_noop!(
+        Identity::resume_claim_issuer(gc_vmo(), issuer.did),
+        Error::IssuerNotSuspended
+    );
+
+    // 3. Revoke issued claims in bulk, in pages.
+    assert_ok!(Identity::add_claim(
+        issuer.origin(),
+        charlie.did,
+        claim(CountryCode::CA),
+        None
+    ));
+    assert_ok!(Identity::add_claim(
+        issuer.origin(),
+        charlie.did,
+        Claim::Accredited(scope.clone()),
+        None
+    ));
+    // Claims issued by others are not affected.
+    assert_ok!(Identity::add_claim(
+        bob.origin(),
+        charlie.did,
+        Claim::Accredited(scope.clone()),
+        None
+    ));
+    let issued = || {
+        identity::IssuedClaims::iter_prefix(issuer.did)
+            .map(|((pk, _), _)| (pk.target, pk.claim_type))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(issued().len(), 3);
+
+    System::reset_events();
+    assert_ok!(Identity::revoke_issued_claims(issuer.origin(), 2));
+    assert_eq!(issued().len(), 1);
+    let revoked = System::events()
+        .iter()
+        .filter(|e| {
+            matches!(
+                e.event,
+                EventTest::pallet_identity(identity::RawEvent::ClaimRevoked(..))
+            )
+        })
+        .count();
+    assert_eq!(revoked, 2);
+    assert_last_event!(
+        EventTest::pallet_identity(identity::RawEvent::IssuedClaimsRevoked(did, 2, true)),
+        *did == issuer.did
+    );
+
+    assert_ok!(Identity::revoke_issued_claims(issuer.origin(), 2));
+    assert_eq!(issued(), vec![]);
+    assert_last_event!(EventTest::pallet_identity(
+        identity::RawEvent::IssuedClaimsRevoked(_, 1, false)
+    ));
+    for (target, ty) in &[
+        (bob.did, ClaimType::Jurisdiction),
+        (charlie.did, ClaimType::Jurisdiction),
+        (charlie.did, ClaimType::Accredited),
+    ] {
+        assert_eq!(
+            Identity::fetch_claim(*target, *ty, issuer.did, Some(scope.clone())),
+            None
+        );
+    }
+    assert!(
+        Identity::fetch_claim(charlie.did, ClaimType::Accredited, bob.did, Some(scope)).is_some()
+    );
+}
+
+#[test]
+fn batch_claims() {
+    ExtBuilder::default()
+        .monied(true)
+        .build()
+        .execute_with(batch_claims_we);
+}
+
+fn batch_claims_we() {
+    let issuer = User::new(AccountKeyring::Alice);
+    let bob = User::new(AccountKeyring::Bob);
+    let charlie = User::new(AccountKeyring::Charlie);
+    let relayer = User::new(AccountKeyring::Dave);
+    let scope = Scope::Identity(issuer.did);
+    let claims = vec![
+        (bob.did, Claim::Accredited(scope.clone()), None),
+        (charlie.did, Claim::Accredited(scope.clone()), Some(1000)),
+        (charlie.did, Claim::Affiliate(scope.clone()), None),
+    ];
+    let count_added = || {
+        System::events()
+            .iter()
+            .filter(|e| {
+                matches!(
+                    e.event,
+                    EventTest::pallet_identity(identity::RawEvent::ClaimAdded(..))
+                )
+            })
+            .count()
+    };
+
+    // 1. Batches are atomic.
+    let invalid = iter::once((IdentityId::from(42), Claim::NoData, None))
+        .chain(claims.iter().cloned())
+        .collect::<Vec<_>>();
+    assert_noop!(
+        Identity::add_claims_batch(issuer.origin(), invalid),
+        Error::DidMustAlreadyExist
+    );
+
+    // 2. Add many claims, paying the protocol fee for each of them.
+    TestStorage::set_payer_context(Some(issuer.acc()));
+    let balance = Balances::free_balance(issuer.acc());
+    System::reset_events();
+    assert_ok!(Identity::add_claims_batch(issuer.origin(), claims.clone()));
+    assert_eq!(
+        Balances::free_balance(issuer.acc()),
+        balance - 3 * PROTOCOL_OP_BASE_FEE
+    );
+    assert_eq!(count_added(), 3);
+    assert_eq!(identity::IssuedClaims::iter_prefix(issuer.did).count(), 3);
+
+    // 3. Revoke them all at once.
+    let to_revoke = claims
+        .iter()
+        .map(|(target, claim, _)| (*target, claim.clone()))
+        .collect::<Vec<_>>();
+    assert_ok!(Identity::revoke_claims_batch(issuer.origin(), to_revoke));
+    assert_eq!(identity::IssuedClaims::iter_prefix(issuer.did).count(), 0);
+
+    // 4. A relayer submits a bundle signed by the issuer and pays its fees.
+    Timestamp::set_timestamp(100);
+    let bundle = ClaimsBundle {
+        issuer: issuer.did,
+        claims: claims.clone(),
+        nonce: Identity::claims_bundle_nonce(issuer.did),
+        expires_at: 200,
+    };
+    let sign = |ring: AccountKeyring, bundle: &ClaimsBundle<u64>| {
+        MultiSignature::from(ring.sign(&Identity::claims_bundle_payload(bundle)))
+    };
+    let add_signed = |bundle: ClaimsBundle<u64>, signature| {
+        Identity::add_claims_batch_signed(relayer.origin(), bundle, issuer.acc(), signature)
+    };
+    assert_noop!(
+        add_signed(bundle.clone(), sign(AccountKeyring::Eve, &bundle)),
+        Error::InvalidAuthorizationSignature
+    );
+    assert_noop!(
+        add_signed(
+            bundle.clone(),
+            MultiSignature::from(AccountKeyring::Alice.sign(&bundle.encode()))
+        ),
+        Error::InvalidAuthorizationSignature
+    );
+    let add_signed_by_eve = || {
+        Identity::add_claims_batch_signed(
+            relayer.origin(),
+            bundle.clone(),
+            AccountKeyring::Eve.to_account_id(),
+            sign(AccountKeyring::Eve, &bundle),
+        )
+    };
+    assert_noop!(add_signed_by_eve(), Error::NotASigner);
+
+    // Secondary keys must be permissioned to add claims.
+    let eve = Signatory::Account(AccountKeyring::Eve.to_account_id());
+    add_secondary_key(issuer.did, eve.clone());
+    let set_eve_perms = |dispatchable: &str| {
+        let perms = Permissions::from_pallet_permissions(vec![PalletPermissions::new(
+            b"Identity".into(),
+            SubsetRestriction::elem(dispatchable.into()),
+        )]);
+        assert_ok!(Identity::set_permission_to_signer(
+            issuer.origin(),
+            eve.clone(),
+            perms
+        ));
+    };
+    set_eve_perms("revoke_claims_batch");
+    assert_noop!(add_signed_by_eve(), Error::Unauthorized);
+    set_eve_perms("add_claims_batch");
+    assert_ok!(add_signed_by_eve());
+    assert_eq!(Identity::claims_bundle_nonce(issuer.did), 1);
+    assert_eq!(Identity::offchain_authorization_nonce(issuer.did), 0);
+    let bundle = ClaimsBundle {
+        nonce: Identity::claims_bundle_nonce(issuer.did),
+        ..bundle
+    };
+
+    TestStorage::set_payer_context(Some(relayer.acc()));
+    let issuer_balance = Balances::free_balance(issuer.acc());
+    let relayer_balance = Balances::free_balance(relayer.acc());
+    System::reset_events();
+    assert_ok!(add_signed(
+        bundle.clone(),
+        sign(AccountKeyring::Alice, &bundle)
+    ));
+    assert_eq!(count_added(), 3);
+    assert_eq!(Balances::free_balance(issuer.acc()), issuer_balance);
+    assert_eq!(
+        Balances::free_balance(relayer.acc()),
+        relayer_balance - 3 * PROTOCOL_OP_BASE_FEE
+    );
+    assert!(Identity::fetch_claim(
+        charlie.did,
+        ClaimType::Affiliate,
+        issuer.did,
+        Some(scope.clone())
+    )
+    .is_some());
+
+    // 5. Bundles cannot be replayed nor submitted after their expiry.
+    assert_noop!(
+        add_signed(bundle.clone(), sign(AccountKeyring::Alice, &bundle)),
+        Error::InvalidAuthorizationNonce
+    );
+    let bundle = ClaimsBundle {
+        nonce: Identity::claims_bundle_nonce(issuer.did),
+        ..bundle
+    };
+    Timestamp::set_timestamp(200);
+    assert_noop!(
+        add_signed(bundle.clone(), sign(AccountKeyring::Alice, &bundle)),
+        Error::AuthorizationExpired
+    );
+}
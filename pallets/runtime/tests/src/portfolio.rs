@@ -6,12 +6,14 @@ use super::{
 };
 use frame_support::{assert_noop, assert_ok, StorageMap};
 use frame_system::EventRecord;
-use pallet_portfolio::{MovePortfolioItem, RawEvent};
+use pallet_portfolio::{MovePortfolioItem, PortfolioMove, RawEvent};
 use polymesh_common_utilities::balances::Memo;
 use polymesh_common_utilities::portfolio::PortfolioSubTrait;
 use polymesh_primitives::{
-    AuthorizationData, AuthorizationError, PortfolioId, PortfolioName, PortfolioNumber, Signatory,
+    AuthorizationData, AuthorizationError, PortfolioId, PortfolioName, PortfolioNumber,
+    PortfolioPolicy, Signatory, Ticker, VestingKind, VestingSchedule,
 };
+use std::convert::TryFrom;
 use test_client::AccountKeyring;
 
 type Asset = pallet_asset::Module<TestStorage>;
@@ -19,6 +21,7 @@ type Error = pallet_portfolio::Error<TestStorage>;
 type Identity = pallet_identity::Module<TestStorage>;
 type Origin = <TestStorage as frame_system::Config>::Origin;
 type Portfolio = pallet_portfolio::Module<TestStorage>;
+type Timestamp = pallet_timestamp::Module<TestStorage>;
 
 fn create_portfolio() -> (User, PortfolioNumber) {
     let owner = User::new(AccountKeyring::Alice);
@@ -298,6 +301,354 @@ fn do_move_asset_from_portfolio(memo: Option<Memo>) {
     );
 }
 
+#[test]
+fn can_rebalance_portfolios() {
+    ExtBuilder::default()
+        .build()
+        .execute_with(do_rebalance_portfolios);
+}
+
+fn do_rebalance_portfolios() {
+    System::set_block_number(1); // This is needed to enable events.
+
+    let (owner, num) = create_portfolio();
+    let bob = User::new(AccountKeyring::Bob);
+    let (ticker, token) = create_token(owner);
+    let name = PortfolioName::from([43u8].to_vec());
+    let num2 = Portfolio::next_portfolio_number(&owner.did);
+    assert_ok!(Portfolio::create_portfolio(owner.origin(), name));
+    let owner_default = PortfolioId::default_portfolio(owner.did);
+    let owner_user = PortfolioId::user_portfolio(owner.did, num);
+    let owner_user2 = PortfolioId::user_portfolio(owner.did, num2);
+    let bob_default = PortfolioId::default_portfolio(bob.did);
+    let bob_user = PortfolioId::user_portfolio(bob.did, PortfolioNumber(1));
+    assert_ok!(Portfolio::create_portfolio(
+        bob.origin(),
+        PortfolioName::from([44u8].to_vec())
+    ));
+    Portfolio::set_default_portfolio_balance(bob.did, &ticker, 100);
+    let mv = |from, to, amount| PortfolioMove {
+        from,
+        to,
+        ticker,
+        amount,
+        memo: None,
+    };
+
+    // Moves must be between different portfolios of the same identity, in the custody of the caller.
+    assert_noop!(
+        Portfolio::rebalance_portfolios(owner.origin(), vec![mv(owner_default, owner_default, 1)]),
+        Error::DestinationIsSamePortfolio
+    );
+    assert_noop!(
+        Portfolio::rebalance_portfolios(owner.origin(), vec![mv(owner_default, bob_user, 1)]),
+        Error::DifferentIdentityPortfolios
+    );
+    assert_noop!(
+        Portfolio::rebalance_portfolios(owner.origin(), vec![mv(bob_default, bob_user, 1)]),
+        Error::UnauthorizedCustodian
+    );
+
+    // Balances are checked against the net amount moved out of each portfolio.
+    let total = token.total_supply;
+    assert_noop!(
+        Portfolio::rebalance_portfolios(
+            owner.origin(),
+            vec![
+                mv(owner_user, owner_user2, 10),
+                mv(owner_default, owner_user, 5)
+            ]
+        ),
+        Error::InsufficientPortfolioBalance
+    );
+    set_custodian_ok(bob, owner, bob_default);
+    assert_ok!(Portfolio::rebalance_portfolios(
+        owner.origin(),
+        vec![
+            mv(owner_user, owner_user2, 10),
+            mv(owner_default, owner_user, 10),
+            mv(bob_default, bob_user, 100),
+        ]
+    ));
+    assert_last_event!(
+        EventTest::pallet_portfolio(RawEvent::MovedBetweenPortfolios(
+            did,
+            from,
+            to,
+            _,
+            amount,
+            _
+        )),
+        did == &owner.did && from == &bob_default && to == &bob_user && *amount == 100
+    );
+    let balance = |p| Portfolio::portfolio_asset_balances(p, ticker);
+    assert_eq!(balance(owner_default), total - 10);
+    assert_eq!(balance(owner_user), 0);
+    assert_eq!(balance(owner_user2), 10);
+    assert_eq!(balance(bob_default), 0);
+    assert_eq!(balance(bob_user), 100);
+}
+
+#[test]
+fn freezes_restrict_portfolio_moves() {
+    ExtBuilder::default()
+        .build()
+        .execute_with(do_freezes_restrict_portfolio_moves);
+}
+
+fn do_freezes_restrict_portfolio_moves() {
+    let (owner, num) = create_portfolio();
+    let bob = User::new(AccountKeyring::Bob);
+    let (ticker, token) = create_token(owner);
+    let owner_default = PortfolioId::default_portfolio(owner.did);
+    let owner_user = PortfolioId::user_portfolio(owner.did, num);
+    let move_funds = |from, to, amount| {
+        Portfolio::move_portfolio_funds(
+            owner.origin(),
+            from,
+            to,
+            vec![MovePortfolioItem {
+                ticker,
+                amount,
+                memo: None,
+            }],
+        )
+    };
+    let rebalance = |moves: Vec<(PortfolioId, PortfolioId, u128)>| {
+        let moves = moves
+            .into_iter()
+            .map(|(from, to, amount)| PortfolioMove {
+                from,
+                to,
+                ticker,
+                amount,
+                memo: None,
+            })
+            .collect();
+        Portfolio::rebalance_portfolios(owner.origin(), moves)
+    };
+
+    // Funds can't be moved into or out of a frozen portfolio.
+    assert_ok!(Asset::freeze_portfolio(owner.origin(), ticker, owner_user));
+    assert_noop!(
+        move_funds(owner_default, owner_user, 1),
+        Error::PortfolioFrozen
+    );
+    assert_noop!(
+        rebalance(vec![(owner_default, owner_user, 1)]),
+        Error::PortfolioFrozen
+    );
+    assert_ok!(Asset::unfreeze_portfolio(
+        owner.origin(),
+        ticker,
+        owner_user
+    ));
+    assert_ok!(move_funds(owner_default, owner_user, 10));
+    assert_ok!(Asset::freeze_portfolio(owner.origin(), ticker, owner_user));
+    assert_noop!(
+        move_funds(owner_user, owner_default, 1),
+        Error::PortfolioFrozen
+    );
+    assert_noop!(
+        rebalance(vec![(owner_user, owner_default, 1)]),
+        Error::PortfolioFrozen
+    );
+    assert_ok!(Asset::unfreeze_portfolio(
+        owner.origin(),
+        ticker,
+        owner_user
+    ));
+
+    // Funds moved into the custody of someone else count against the frozen balance.
+    set_custodian_ok(owner, bob, owner_user);
+    let frozen = token.total_supply - 20;
+    assert_ok!(Asset::set_frozen_balance(
+        owner.origin(),
+        ticker,
+        owner.did,
+        frozen
+    ));
+    assert_noop!(
+        move_funds(owner_default, owner_user, 21),
+        Error::FrozenBalanceExceeded
+    );
+    assert_noop!(
+        rebalance(vec![
+            (owner_default, owner_user, 15),
+            (owner_default, owner_user, 6)
+        ]),
+        Error::FrozenBalanceExceeded
+    );
+    assert_ok!(rebalance(vec![
+        (owner_default, owner_user, 15),
+        (owner_default, owner_user, 5)
+    ]));
+    assert_eq!(Portfolio::portfolio_asset_balances(owner_user, ticker), 30);
+}
+
+#[test]
+fn portfolio_policies() {
+    ExtBuilder::default()
+        .build()
+        .execute_with(do_portfolio_policies);
+}
+
+fn do_portfolio_policies() {
+    System::set_block_number(1); // This is needed to enable events.
+
+    let (owner, num) = create_portfolio();
+    let bob = User::new(AccountKeyring::Bob);
+    let (ticker, token) = create_token(owner);
+    let other_ticker = Ticker::try_from(&b"OTHER"[..]).unwrap();
+    let owner_default_portfolio = PortfolioId::default_portfolio(owner.did);
+    let owner_user_portfolio = PortfolioId::user_portfolio(owner.did, num);
+    let move_item = |amount| {
+        vec![MovePortfolioItem {
+            ticker,
+            amount,
+            memo: None,
+        }]
+    };
+
+    // Only the custodian can set the policy of a portfolio.
+    let only_other = PortfolioPolicy {
+        allowed_tickers: Some(vec![other_ticker]),
+        affirmation_threshold: None,
+    };
+    assert_noop!(
+        Portfolio::set_portfolio_policy(bob.origin(), owner_user_portfolio, only_other.clone()),
+        Error::UnauthorizedCustodian
+    );
+    let too_many = PortfolioPolicy {
+        allowed_tickers: Some(vec![other_ticker; 101]),
+        affirmation_threshold: None,
+    };
+    assert_noop!(
+        Portfolio::set_portfolio_policy(owner.origin(), owner_user_portfolio, too_many),
+        Error::TooManyAllowedTickers
+    );
+
+    // The user portfolio only accepts `other_ticker`.
+    assert_ok!(Portfolio::set_portfolio_policy(
+        owner.origin(),
+        owner_user_portfolio,
+        only_other.clone()
+    ));
+    assert_last_event!(
+        EventTest::pallet_portfolio(RawEvent::PortfolioPolicySet(did, portfolio, policy)),
+        did == &owner.did && portfolio == &owner_user_portfolio && policy == &only_other
+    );
+    assert_eq!(
+        Portfolio::portfolio_policy(owner_user_portfolio),
+        only_other
+    );
+    assert_noop!(
+        Portfolio::move_portfolio_funds(
+            owner.origin(),
+            owner_default_portfolio,
+            owner_user_portfolio,
+            move_item(1)
+        ),
+        Error::TickerNotAllowedByPolicy
+    );
+    assert!(
+        Portfolio::ensure_portfolio_transfer_validity_granular(
+            &owner_default_portfolio,
+            &owner_user_portfolio,
+            &ticker,
+            &1
+        )
+        .receiver_policy_disallows_ticker
+    );
+
+    // Allowing `ticker` as well permits the move.
+    assert_ok!(Portfolio::set_portfolio_policy(
+        owner.origin(),
+        owner_user_portfolio,
+        PortfolioPolicy {
+            allowed_tickers: Some(vec![other_ticker, ticker]),
+            affirmation_threshold: None,
+        }
+    ));
+    assert_ok!(Portfolio::move_portfolio_funds(
+        owner.origin(),
+        owner_default_portfolio,
+        owner_user_portfolio,
+        move_item(1)
+    ));
+
+    // Transfers above the threshold of the sender require the affirmation of its custodian.
+    let threshold = token.total_supply / 2;
+    assert_ok!(Portfolio::set_portfolio_policy(
+        owner.origin(),
+        owner_default_portfolio,
+        PortfolioPolicy {
+            allowed_tickers: None,
+            affirmation_threshold: Some(threshold),
+        }
+    ));
+    let bob_default_portfolio = PortfolioId::default_portfolio(bob.did);
+    assert_ok!(Portfolio::ensure_portfolio_transfer_validity(
+        &owner_default_portfolio,
+        &bob_default_portfolio,
+        &ticker,
+        &threshold,
+    ));
+    assert_noop!(
+        Portfolio::ensure_portfolio_transfer_validity(
+            &owner_default_portfolio,
+            &bob_default_portfolio,
+            &ticker,
+            &(threshold + 1),
+        ),
+        Error::CustodianAffirmationRequired
+    );
+    assert!(
+        Portfolio::ensure_portfolio_transfer_validity_granular(
+            &owner_default_portfolio,
+            &bob_default_portfolio,
+            &ticker,
+            &(threshold + 1)
+        )
+        .sender_policy_requires_affirmation
+    );
+    // Affirmations are only recorded for senders with an affirmation threshold.
+    assert_noop!(
+        Portfolio::with_custodian_affirmation(&[bob_default_portfolio], || {
+            Portfolio::ensure_portfolio_transfer_validity(
+                &owner_default_portfolio,
+                &bob_default_portfolio,
+                &ticker,
+                &(threshold + 1),
+            )
+        }),
+        Error::CustodianAffirmationRequired
+    );
+    assert_ok!(Portfolio::with_custodian_affirmation(
+        &[owner_default_portfolio],
+        || {
+            assert!(Portfolio::custodian_affirmed());
+            Portfolio::ensure_portfolio_transfer_validity(
+                &owner_default_portfolio,
+                &bob_default_portfolio,
+                &ticker,
+                &(threshold + 1),
+            )
+        }
+    ));
+    assert!(!Portfolio::custodian_affirmed());
+
+    // Setting the default policy removes it.
+    assert_ok!(Portfolio::set_portfolio_policy(
+        owner.origin(),
+        owner_default_portfolio,
+        PortfolioPolicy::default()
+    ));
+    assert!(
+        !<pallet_portfolio::PortfolioPolicies<TestStorage>>::contains_key(owner_default_portfolio)
+    );
+}
+
 #[test]
 fn can_lock_unlock_assets() {
     ExtBuilder::default().build().execute_with(|| {
@@ -424,6 +775,92 @@ fn can_lock_unlock_assets() {
     });
 }
 
+#[test]
+fn vesting_schedules_lock_assets() {
+    ExtBuilder::default().build().execute_with(|| {
+        let (owner, num) = create_portfolio();
+        let (ticker, token) = create_token(owner);
+        let owner_default_portfolio = PortfolioId::default_portfolio(owner.did);
+        let owner_user_portfolio = PortfolioId::user_portfolio(owner.did, num);
+        let move_funds = |amount| {
+            Portfolio::move_portfolio_funds(
+                owner.origin(),
+                owner_default_portfolio,
+                owner_user_portfolio,
+                vec![MovePortfolioItem {
+                    ticker,
+                    amount,
+                    memo: None,
+                }],
+            )
+        };
+        Timestamp::set_timestamp(1_000);
+
+        // Invalid schedules are rejected without issuing tokens.
+        assert_noop!(
+            Asset::issue_with_vesting(
+                owner.origin(),
+                ticker,
+                1_000,
+                VestingKind::Linear {
+                    start: 2_000,
+                    end: 2_000
+                }
+            ),
+            Error::InvalidVestingSchedule
+        );
+
+        // Issue tokens released linearly over 1000 seconds.
+        let kind = VestingKind::Linear {
+            start: 1_000,
+            end: 2_000,
+        };
+        assert_ok!(Asset::issue_with_vesting(
+            owner.origin(),
+            ticker,
+            1_000,
+            kind
+        ));
+        assert_eq!(
+            Portfolio::default_portfolio_balance(owner.did, &ticker),
+            token.total_supply + 1_000,
+        );
+        assert_eq!(
+            Portfolio::vesting_schedules(owner_default_portfolio, ticker),
+            vec![VestingSchedule {
+                amount: 1_000,
+                kind
+            }]
+        );
+        assert_eq!(
+            Portfolio::vesting_locked(&owner_default_portfolio, &ticker),
+            1_000
+        );
+
+        // Only the tokens issued without vesting can be moved.
+        assert_noop!(
+            move_funds(token.total_supply + 1),
+            Error::InsufficientPortfolioBalance
+        );
+        assert_ok!(move_funds(token.total_supply));
+        assert_noop!(move_funds(1), Error::InsufficientPortfolioBalance);
+
+        // Half of the tokens are released halfway through.
+        Timestamp::set_timestamp(1_500);
+        assert_noop!(move_funds(501), Error::InsufficientPortfolioBalance);
+        assert_ok!(move_funds(500));
+
+        // All tokens are released at the end.
+        Timestamp::set_timestamp(2_000);
+        assert_eq!(
+            Portfolio::vesting_locked(&owner_default_portfolio, &ticker),
+            0
+        );
+        assert_ok!(move_funds(500));
+        assert_eq!(Portfolio::default_portfolio_balance(owner.did, &ticker), 0);
+    });
+}
+
 #[test]
 fn can_take_custody_of_portfolios() {
     ExtBuilder::default().build().execute_with(|| {
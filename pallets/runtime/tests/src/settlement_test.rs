@@ -2,7 +2,7 @@ use super::{
     asset_test::max_len_bytes,
     next_block,
     storage::{
-        default_portfolio_vec, make_account, make_account_without_cdd,
+        default_portfolio_vec, fast_forward_blocks, make_account, make_account_without_cdd,
         provide_scope_claim_to_multiple_parties, register_keyring_account, user_portfolio_vec,
         TestStorage, User,
     },
@@ -12,18 +12,20 @@ use codec::Encode;
 use frame_support::{assert_noop, assert_ok, IterableStorageDoubleMap, StorageMap};
 use pallet_asset as asset;
 use pallet_balances as balances;
-use pallet_compliance_manager as compliance_manager;
+use pallet_compliance_manager::{
+    self as compliance_manager, ClaimAttestation, PresentedClaim, MAX_PRESENTED_CLAIM_VALIDITY,
+};
 use pallet_identity as identity;
 use pallet_portfolio::MovePortfolioItem;
 use pallet_scheduler as scheduler;
 use pallet_settlement::{
-    self as settlement, AffirmationStatus, Instruction, InstructionStatus, Leg, LegStatus, Receipt,
-    ReceiptDetails, ReceiptMetadata, SettlementType, VenueDetails, VenueType,
+    self as settlement, AffirmationStatus, Instruction, InstructionStatus, Leg, LegDetails,
+    LegStatus, Receipt, ReceiptDetails, ReceiptMetadata, SettlementType, VenueDetails, VenueType,
 };
 use polymesh_common_utilities::constants::ERC1400_TRANSFER_SUCCESS;
 use polymesh_primitives::{
-    asset::AssetType, AccountId, AuthorizationData, Claim, Condition, ConditionType, IdentityId,
-    PortfolioId, PortfolioName, Signatory, Ticker,
+    asset::AssetType, AccountId, AuthorizationData, Claim, ClaimType, Condition, ConditionType,
+    IdentityId, PortfolioId, PortfolioName, PortfolioPolicy, Scope, Signatory, Ticker,
 };
 use rand::{prelude::*, thread_rng};
 use sp_runtime::AnySignature;
@@ -38,6 +40,7 @@ type Portfolio = pallet_portfolio::Module<TestStorage>;
 type PortfolioError = pallet_portfolio::Error<TestStorage>;
 type Timestamp = pallet_timestamp::Module<TestStorage>;
 type ComplianceManager = compliance_manager::Module<TestStorage>;
+type ComplianceError = compliance_manager::Error<TestStorage>;
 type AssetError = asset::Error<TestStorage>;
 type OffChainSignature = AnySignature;
 type Origin = <TestStorage as frame_system::Config>::Origin;
@@ -3347,6 +3350,677 @@ fn reject_failed_instruction() {
     });
 }
 
+#[test]
+fn netted_instructions_execution() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(|| {
+            let alice = User::new(AccountKeyring::Alice);
+            let bob = User::new(AccountKeyring::Bob);
+            let token_name = b"ACME";
+            let ticker = Ticker::try_from(&token_name[..]).unwrap();
+            let venue_counter = init(token_name, ticker, AccountKeyring::Alice.to_account_id());
+            let eve = AccountKeyring::Eve.to_account_id();
+            let alice_portfolio = PortfolioId::default_portfolio(alice.did);
+            let bob_portfolio = PortfolioId::default_portfolio(bob.did);
+
+            // Provide scope claim to sender and receiver of the transaction.
+            provide_scope_claim_to_multiple_parties(&[alice.did, bob.did], ticker, eve);
+
+            // Give Bob some tokens, so that he is able to affirm a leg.
+            assert_ok!(Asset::base_transfer(
+                alice_portfolio,
+                bob_portfolio,
+                &ticker,
+                1_000
+            ));
+            let alice_init_balance = Asset::balance_of(&ticker, alice.did);
+            let bob_init_balance = Asset::balance_of(&ticker, bob.did);
+
+            let add_instruction = |from, to, amount| {
+                let instruction_id = Settlement::instruction_counter();
+                assert_ok!(Settlement::add_instruction(
+                    alice.origin(),
+                    venue_counter,
+                    SettlementType::SettleOnAffirmation,
+                    None,
+                    None,
+                    vec![Leg {
+                        from,
+                        to,
+                        asset: ticker,
+                        amount
+                    }]
+                ));
+                instruction_id
+            };
+            let instruction1 = add_instruction(alice_portfolio, bob_portfolio, 100);
+            let instruction2 = add_instruction(bob_portfolio, alice_portfolio, 40);
+
+            // Netting requires all affirmations to be received.
+            assert_affirm_instruction_with_one_leg!(alice.origin(), instruction1, alice.did);
+            assert_noop!(
+                Settlement::execute_netted_instructions(
+                    alice.origin(),
+                    venue_counter,
+                    vec![instruction1, instruction2],
+                    2
+                ),
+                Error::InstructionNotAffirmed
+            );
+            assert_affirm_instruction_with_zero_leg!(bob.origin(), instruction1, bob.did);
+            assert_affirm_instruction_with_one_leg!(bob.origin(), instruction2, bob.did);
+            assert_affirm_instruction_with_zero_leg!(alice.origin(), instruction2, alice.did);
+
+            // Only the venue creator can net instructions.
+            assert_noop!(
+                Settlement::execute_netted_instructions(
+                    bob.origin(),
+                    venue_counter,
+                    vec![instruction1, instruction2],
+                    2
+                ),
+                Error::Unauthorized
+            );
+            assert_noop!(
+                Settlement::execute_netted_instructions(
+                    alice.origin(),
+                    venue_counter,
+                    vec![instruction1, instruction2],
+                    1
+                ),
+                Error::LegCountTooSmall
+            );
+            assert_ok!(Settlement::execute_netted_instructions(
+                alice.origin(),
+                venue_counter,
+                vec![instruction1, instruction2],
+                2
+            ));
+
+            // Only the net amount has been transferred and all locks have been released.
+            assert_eq!(
+                Asset::balance_of(&ticker, alice.did),
+                alice_init_balance - 60
+            );
+            assert_eq!(Asset::balance_of(&ticker, bob.did), bob_init_balance + 60);
+            assert_eq!(Portfolio::locked_assets(alice_portfolio, &ticker), 0);
+            assert_eq!(Portfolio::locked_assets(bob_portfolio, &ticker), 0);
+            ensure_instruction_status(instruction1, InstructionStatus::Unknown);
+            ensure_instruction_status(instruction2, InstructionStatus::Unknown);
+
+            // The scheduled executions have been cancelled, so nothing happens on the next block.
+            next_block();
+            assert_eq!(Asset::balance_of(&ticker, bob.did), bob_init_balance + 60);
+        });
+}
+
+#[test]
+fn netted_instructions_failures() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(|| {
+            let alice = User::new(AccountKeyring::Alice);
+            let bob = User::new(AccountKeyring::Bob);
+            let charlie = User::new(AccountKeyring::Charlie);
+            let token_name = b"ACME";
+            let ticker = Ticker::try_from(&token_name[..]).unwrap();
+            let venue_counter = init(token_name, ticker, AccountKeyring::Alice.to_account_id());
+            let eve = AccountKeyring::Eve.to_account_id();
+            let alice_portfolio = PortfolioId::default_portfolio(alice.did);
+            let bob_portfolio = PortfolioId::default_portfolio(bob.did);
+            let charlie_portfolio = PortfolioId::default_portfolio(charlie.did);
+
+            // Charlie has no scope claim, so he can't receive the asset.
+            provide_scope_claim_to_multiple_parties(&[alice.did, bob.did], ticker, eve);
+            assert_ok!(Asset::base_transfer(
+                alice_portfolio,
+                bob_portfolio,
+                &ticker,
+                1_000
+            ));
+            let alice_init_balance = Asset::balance_of(&ticker, alice.did);
+            let bob_init_balance = Asset::balance_of(&ticker, bob.did);
+
+            let add_instruction = |from, to, amount| {
+                let instruction_id = Settlement::instruction_counter();
+                assert_ok!(Settlement::add_instruction(
+                    alice.origin(),
+                    venue_counter,
+                    SettlementType::SettleOnAffirmation,
+                    None,
+                    None,
+                    vec![Leg {
+                        from,
+                        to,
+                        asset: ticker,
+                        amount
+                    }]
+                ));
+                instruction_id
+            };
+            let execute = |instruction_ids| {
+                Settlement::execute_netted_instructions(
+                    alice.origin(),
+                    venue_counter,
+                    instruction_ids,
+                    2,
+                )
+            };
+            let assert_balances = |alice_change, bob_change| {
+                assert_eq!(
+                    Asset::balance_of(&ticker, alice.did),
+                    alice_init_balance - alice_change
+                );
+                assert_eq!(
+                    Asset::balance_of(&ticker, bob.did),
+                    bob_init_balance + bob_change
+                );
+                assert_eq!(Asset::balance_of(&ticker, charlie.did), 0);
+            };
+
+            // Alice -> Bob and Bob -> Charlie net to Alice -> Charlie,
+            // but the leg to Charlie is still checked on its own.
+            let instruction1 = add_instruction(alice_portfolio, bob_portfolio, 100);
+            let instruction2 = add_instruction(bob_portfolio, charlie_portfolio, 100);
+            assert_affirm_instruction_with_one_leg!(alice.origin(), instruction1, alice.did);
+            assert_affirm_instruction_with_zero_leg!(bob.origin(), instruction1, bob.did);
+            assert_affirm_instruction_with_one_leg!(bob.origin(), instruction2, bob.did);
+            assert_affirm_instruction_with_zero_leg!(charlie.origin(), instruction2, charlie.did);
+            assert_eq!(
+                execute(vec![instruction1, instruction2]),
+                Err(Error::InstructionFailed.into())
+            );
+
+            // Only the instruction of the failing leg has failed, and nothing has been transferred.
+            ensure_instruction_status(instruction1, InstructionStatus::Pending);
+            ensure_instruction_status(instruction2, InstructionStatus::Failed);
+            assert_balances(0, 0);
+            assert_eq!(Portfolio::locked_assets(alice_portfolio, &ticker), 100);
+
+            // Legs that net to zero are checked too.
+            let instruction3 = add_instruction(alice_portfolio, bob_portfolio, 50);
+            let instruction4 = add_instruction(bob_portfolio, alice_portfolio, 50);
+            assert_affirm_instruction_with_one_leg!(alice.origin(), instruction3, alice.did);
+            assert_affirm_instruction_with_zero_leg!(bob.origin(), instruction3, bob.did);
+            assert_affirm_instruction_with_one_leg!(bob.origin(), instruction4, bob.did);
+            assert_affirm_instruction_with_zero_leg!(alice.origin(), instruction4, alice.did);
+            assert_ok!(Asset::freeze_portfolio(
+                alice.origin(),
+                ticker,
+                bob_portfolio
+            ));
+            assert_eq!(
+                execute(vec![instruction3, instruction4]),
+                Err(Error::InstructionFailed.into())
+            );
+            ensure_instruction_status(instruction3, InstructionStatus::Failed);
+            ensure_instruction_status(instruction4, InstructionStatus::Failed);
+            assert_ok!(Asset::unfreeze_portfolio(
+                alice.origin(),
+                ticker,
+                bob_portfolio
+            ));
+
+            // Bob's balance is frozen, so his net outflow of 100 fails,
+            // along with the instructions sending from his portfolio.
+            let instruction5 = add_instruction(bob_portfolio, alice_portfolio, 200);
+            assert_affirm_instruction_with_one_leg!(bob.origin(), instruction5, bob.did);
+            assert_affirm_instruction_with_zero_leg!(alice.origin(), instruction5, alice.did);
+            assert_ok!(Asset::set_frozen_balance(
+                alice.origin(),
+                ticker,
+                bob.did,
+                bob_init_balance
+            ));
+            assert_eq!(
+                execute(vec![instruction1, instruction5]),
+                Err(AssetError::InvalidTransfer.into())
+            );
+            ensure_instruction_status(instruction1, InstructionStatus::Pending);
+            ensure_instruction_status(instruction5, InstructionStatus::Failed);
+            assert_balances(0, 0);
+
+            // The remaining instruction can still be netted on its own.
+            assert_ok!(execute(vec![instruction1]));
+            ensure_instruction_status(instruction1, InstructionStatus::Unknown);
+            assert_balances(100, 100);
+        });
+}
+
+#[test]
+fn instruction_expiry() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(|| {
+            let alice = User::new(AccountKeyring::Alice);
+            let bob = User::new(AccountKeyring::Bob);
+            let token_name = b"ACME";
+            let ticker = Ticker::try_from(&token_name[..]).unwrap();
+            let venue_counter = init(token_name, ticker, AccountKeyring::Alice.to_account_id());
+            let eve = AccountKeyring::Eve.to_account_id();
+            let alice_portfolio = PortfolioId::default_portfolio(alice.did);
+            let instruction_counter = Settlement::instruction_counter();
+            let alice_init_balance = Asset::balance_of(&ticker, alice.did);
+            let bob_init_balance = Asset::balance_of(&ticker, bob.did);
+
+            // Provide scope claim to sender and receiver of the transaction.
+            provide_scope_claim_to_multiple_parties(&[alice.did, bob.did], ticker, eve);
+
+            let legs = vec![Leg {
+                from: alice_portfolio,
+                to: PortfolioId::default_portfolio(bob.did),
+                asset: ticker,
+                amount: 100,
+            }];
+            let block_number = System::block_number();
+
+            // The expiry must be in the future and after the settlement block.
+            assert_noop!(
+                Settlement::add_instruction_with_expiry(
+                    alice.origin(),
+                    venue_counter,
+                    SettlementType::SettleOnAffirmation,
+                    None,
+                    None,
+                    legs.clone(),
+                    block_number
+                ),
+                Error::InstructionExpiryInPast
+            );
+            assert_noop!(
+                Settlement::add_instruction_with_expiry(
+                    alice.origin(),
+                    venue_counter,
+                    SettlementType::SettleOnBlock(block_number + 3),
+                    None,
+                    None,
+                    legs.clone(),
+                    block_number + 3
+                ),
+                Error::InstructionDatesInvalid
+            );
+
+            assert_ok!(Settlement::add_and_affirm_instruction_with_expiry(
+                alice.origin(),
+                venue_counter,
+                SettlementType::SettleOnAffirmation,
+                None,
+                None,
+                legs.clone(),
+                vec![alice_portfolio],
+                block_number + 3
+            ));
+            assert_eq!(
+                Settlement::instruction_expiry(instruction_counter),
+                Some(block_number + 3)
+            );
+            assert_eq!(Portfolio::locked_assets(alice_portfolio, &ticker), 100);
+
+            // Bob never affirms, so the instruction expires and Alice's tokens are unlocked.
+            fast_forward_blocks(3);
+            ensure_instruction_status(instruction_counter, InstructionStatus::Expired);
+            assert_eq!(Portfolio::locked_assets(alice_portfolio, &ticker), 0);
+            assert_eq!(Asset::balance_of(&ticker, alice.did), alice_init_balance);
+            assert_eq!(Asset::balance_of(&ticker, bob.did), bob_init_balance);
+
+            // An expired instruction can no longer be affirmed.
+            assert_noop!(
+                Settlement::affirm_instruction(
+                    bob.origin(),
+                    instruction_counter,
+                    vec![PortfolioId::default_portfolio(bob.did)],
+                    0
+                ),
+                Error::InstructionExpired
+            );
+
+            // Rejecting it removes it from storage.
+            assert_ok!(Settlement::reject_instruction(
+                alice.origin(),
+                instruction_counter
+            ));
+            ensure_instruction_status(instruction_counter, InstructionStatus::Unknown);
+            assert_eq!(Settlement::instruction_expiry(instruction_counter), None);
+            assert_eq!(Portfolio::locked_assets(alice_portfolio, &ticker), 0);
+        });
+}
+
+#[test]
+fn instruction_queries() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(|| {
+            let alice = User::new(AccountKeyring::Alice);
+            let bob = User::new(AccountKeyring::Bob);
+            let token_name = b"ACME";
+            let ticker = Ticker::try_from(&token_name[..]).unwrap();
+            let venue_counter = init(token_name, ticker, AccountKeyring::Alice.to_account_id());
+            let eve = AccountKeyring::Eve.to_account_id();
+            let alice_portfolio = PortfolioId::default_portfolio(alice.did);
+            let bob_portfolio = PortfolioId::default_portfolio(bob.did);
+            let instruction_counter = Settlement::instruction_counter();
+            let alice_init_balance = Asset::balance_of(&ticker, alice.did);
+
+            // Provide scope claim to sender and receiver of the transaction.
+            provide_scope_claim_to_multiple_parties(&[alice.did, bob.did], ticker, eve);
+
+            assert_eq!(
+                Settlement::get_instruction_report(instruction_counter),
+                None
+            );
+            let leg = Leg {
+                from: alice_portfolio,
+                to: bob_portfolio,
+                asset: ticker,
+                amount: 100,
+            };
+            assert_ok!(Settlement::add_and_affirm_instruction(
+                alice.origin(),
+                venue_counter,
+                SettlementType::SettleOnAffirmation,
+                None,
+                None,
+                vec![leg.clone()],
+                vec![alice_portfolio]
+            ));
+
+            let report = Settlement::get_instruction_report(instruction_counter).unwrap();
+            assert_eq!(report.instruction.status, InstructionStatus::Pending);
+            assert_eq!(
+                report.legs,
+                vec![LegDetails {
+                    leg_id: 0,
+                    leg,
+                    status: LegStatus::ExecutionPending
+                }]
+            );
+            assert_eq!(report.missing_affirmations, vec![bob_portfolio]);
+            assert_eq!(report.expiry, None);
+            assert_eq!(
+                Settlement::pending_instructions_for_portfolio(alice_portfolio),
+                vec![(instruction_counter, AffirmationStatus::Affirmed)]
+            );
+            assert_eq!(
+                Settlement::pending_instructions_for_portfolio(bob_portfolio),
+                vec![(instruction_counter, AffirmationStatus::Pending)]
+            );
+
+            // The instruction can't be executed until Bob affirms it.
+            let dry_run = Settlement::dry_run_instruction(instruction_counter);
+            assert_eq!(dry_run.missing_affirmations, vec![bob_portfolio]);
+            assert_eq!(dry_run.failed_leg, None);
+            assert!(!dry_run.result);
+
+            assert_affirm_instruction_with_zero_leg!(bob.origin(), instruction_counter, bob.did);
+            assert!(Settlement::get_instruction_report(instruction_counter)
+                .unwrap()
+                .missing_affirmations
+                .is_empty());
+
+            // A frozen asset makes the leg fail.
+            assert_ok!(Asset::freeze(alice.origin(), ticker));
+            let dry_run = Settlement::dry_run_instruction(instruction_counter);
+            assert!(dry_run.legs[0].1.asset_frozen);
+            assert_eq!(dry_run.failed_leg, Some(0));
+            assert!(!dry_run.result);
+
+            assert_ok!(Asset::unfreeze(alice.origin(), ticker));
+            let dry_run = Settlement::dry_run_instruction(instruction_counter);
+            assert_eq!(dry_run.failed_leg, None);
+            assert!(dry_run.result);
+
+            // Dry-runs don't change the state.
+            assert_eq!(Asset::balance_of(&ticker, alice.did), alice_init_balance);
+            assert_eq!(Portfolio::locked_assets(alice_portfolio, &ticker), 100);
+            ensure_instruction_status(instruction_counter, InstructionStatus::Pending);
+
+            next_block();
+            ensure_instruction_status(instruction_counter, InstructionStatus::Unknown);
+            assert_eq!(
+                Asset::balance_of(&ticker, alice.did),
+                alice_init_balance - 100
+            );
+            assert!(Settlement::pending_instructions_for_portfolio(bob_portfolio).is_empty());
+        });
+}
+
+#[test]
+fn affirm_with_presented_claims() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(|| {
+            let alice = User::new(AccountKeyring::Alice);
+            let bob = User::new(AccountKeyring::Bob);
+            let dave = User::new(AccountKeyring::Dave);
+            let token_name = b"ACME";
+            let ticker = Ticker::try_from(&token_name[..]).unwrap();
+            let venue_counter = init(token_name, ticker, AccountKeyring::Alice.to_account_id());
+            let eve = AccountKeyring::Eve.to_account_id();
+            let alice_init_balance = Asset::balance_of(&ticker, alice.did);
+            provide_scope_claim_to_multiple_parties(&[alice.did, bob.did], ticker, eve);
+
+            // Only receivers accredited by Dave can receive the asset.
+            let scope = Scope::Ticker(ticker);
+            assert_ok!(ComplianceManager::reset_asset_compliance(
+                alice.origin(),
+                ticker
+            ));
+            assert_ok!(ComplianceManager::add_compliance_requirement(
+                alice.origin(),
+                ticker,
+                vec![],
+                vec![Condition::from_dids(
+                    ConditionType::IsPresent(Claim::Accredited(scope.clone())),
+                    &[dave.did]
+                )]
+            ));
+
+            let attestation = ClaimAttestation {
+                target: bob.did,
+                issuer: dave.did,
+                claim: Claim::Accredited(scope.clone()),
+                expiry: Timestamp::get() + 1_000,
+            };
+            let present =
+                |ring: AccountKeyring, attestation: ClaimAttestation<u64>| PresentedClaim {
+                    signer: ring.to_account_id(),
+                    signature: ring
+                        .sign(&ComplianceManager::attestation_payload(&attestation))
+                        .into(),
+                    attestation,
+                };
+            let affirm = |instruction_id, claims| {
+                Settlement::affirm_with_claims(
+                    bob.origin(),
+                    instruction_id,
+                    default_portfolio_vec(bob.did),
+                    0,
+                    claims,
+                )
+            };
+
+            // Claims can only be presented with the last affirmation.
+            let instruction_id = Settlement::instruction_counter();
+            assert_ok!(Settlement::add_instruction(
+                alice.origin(),
+                venue_counter,
+                SettlementType::SettleOnAffirmation,
+                None,
+                None,
+                vec![Leg {
+                    from: PortfolioId::default_portfolio(alice.did),
+                    to: PortfolioId::default_portfolio(bob.did),
+                    asset: ticker,
+                    amount: 100
+                }]
+            ));
+            assert_noop!(
+                affirm(
+                    instruction_id,
+                    vec![present(AccountKeyring::Dave, attestation.clone())]
+                ),
+                Error::ClaimsRequireFinalAffirmation
+            );
+
+            let instruction_id = create_instruction(&alice, &bob, venue_counter, ticker, 100);
+            // Without the claim, the transfer is not compliant.
+            assert_noop!(affirm(instruction_id, vec![]), Error::InstructionFailed);
+            // Claims must be signed by a key of their issuer, about the presenter, and unexpired.
+            assert_noop!(
+                affirm(
+                    instruction_id,
+                    vec![present(AccountKeyring::Eve, attestation.clone())]
+                ),
+                ComplianceError::InvalidPresentedClaim
+            );
+            let about_alice = ClaimAttestation {
+                target: alice.did,
+                ..attestation.clone()
+            };
+            assert_noop!(
+                affirm(
+                    instruction_id,
+                    vec![present(AccountKeyring::Dave, about_alice)]
+                ),
+                ComplianceError::InvalidPresentedClaim
+            );
+            let expired = ClaimAttestation {
+                expiry: Timestamp::get(),
+                ..attestation.clone()
+            };
+            assert_noop!(
+                affirm(instruction_id, vec![present(AccountKeyring::Dave, expired)]),
+                ComplianceError::PresentedClaimExpired
+            );
+            let too_long = ClaimAttestation {
+                expiry: Timestamp::get() + MAX_PRESENTED_CLAIM_VALIDITY + 1,
+                ..attestation.clone()
+            };
+            assert_noop!(
+                affirm(
+                    instruction_id,
+                    vec![present(AccountKeyring::Dave, too_long)]
+                ),
+                ComplianceError::PresentedClaimValidityTooLong
+            );
+            // The signed payload is tagged, so a bare encoded attestation is rejected.
+            let untagged = PresentedClaim {
+                signer: AccountKeyring::Dave.to_account_id(),
+                signature: AccountKeyring::Dave.sign(&attestation.encode()).into(),
+                attestation: attestation.clone(),
+            };
+            assert_noop!(
+                affirm(instruction_id, vec![untagged]),
+                ComplianceError::InvalidPresentedClaim
+            );
+
+            // With the claim, the instruction executes right away.
+            assert_ok!(affirm(
+                instruction_id,
+                vec![present(AccountKeyring::Dave, attestation)]
+            ));
+            ensure_instruction_status(instruction_id, InstructionStatus::Unknown);
+            assert_eq!(
+                Asset::balance_of(&ticker, alice.did),
+                alice_init_balance - 100
+            );
+            assert_eq!(Asset::balance_of(&ticker, bob.did), 100);
+
+            // The claim was never stored.
+            assert!(ComplianceManager::presented_claims().is_empty());
+            assert!(!ComplianceManager::claims_presented());
+            assert_eq!(
+                Identity::fetch_claim(bob.did, ClaimType::Accredited, dave.did, Some(scope)),
+                None
+            );
+        });
+}
+
+#[test]
+fn portfolio_policies_in_settlement() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(|| {
+            let alice = User::new(AccountKeyring::Alice);
+            let bob = User::new(AccountKeyring::Bob);
+            let token_name = b"ACME";
+            let ticker = Ticker::try_from(&token_name[..]).unwrap();
+            let other_ticker = Ticker::try_from(&b"OTHER"[..]).unwrap();
+            let venue_counter = init(token_name, ticker, AccountKeyring::Alice.to_account_id());
+            let eve = AccountKeyring::Eve.to_account_id();
+            let alice_init_balance = Asset::balance_of(&ticker, alice.did);
+            provide_scope_claim_to_multiple_parties(&[alice.did, bob.did], ticker, eve);
+
+            let alice_portfolio = PortfolioId::default_portfolio(alice.did);
+            let bob_portfolio = PortfolioId::default_portfolio(bob.did);
+            let instruction_id = create_instruction(&alice, &bob, venue_counter, ticker, 100);
+
+            // Bob's portfolio does not accept the asset, so his affirmation fails early.
+            assert_ok!(Portfolio::set_portfolio_policy(
+                bob.origin(),
+                bob_portfolio,
+                PortfolioPolicy {
+                    allowed_tickers: Some(vec![other_ticker]),
+                    affirmation_threshold: None,
+                }
+            ));
+            assert_noop!(
+                Settlement::affirm_instruction(
+                    bob.origin(),
+                    instruction_id,
+                    default_portfolio_vec(bob.did),
+                    1
+                ),
+                PortfolioError::TickerNotAllowedByPolicy
+            );
+            assert_ok!(Portfolio::set_portfolio_policy(
+                bob.origin(),
+                bob_portfolio,
+                PortfolioPolicy::default()
+            ));
+
+            // Alice's threshold blocks direct transfers, but not affirmed settlements.
+            assert_ok!(Portfolio::set_portfolio_policy(
+                alice.origin(),
+                alice_portfolio,
+                PortfolioPolicy {
+                    allowed_tickers: None,
+                    affirmation_threshold: Some(50),
+                }
+            ));
+            assert_noop!(
+                Portfolio::ensure_portfolio_transfer_validity(
+                    &alice_portfolio,
+                    &bob_portfolio,
+                    &ticker,
+                    &100
+                ),
+                PortfolioError::CustodianAffirmationRequired
+            );
+            assert_ok!(Settlement::affirm_instruction(
+                bob.origin(),
+                instruction_id,
+                default_portfolio_vec(bob.did),
+                1
+            ));
+            next_block();
+            ensure_instruction_status(instruction_id, InstructionStatus::Unknown);
+            assert_eq!(
+                Asset::balance_of(&ticker, alice.did),
+                alice_init_balance - 100
+            );
+            assert_eq!(Asset::balance_of(&ticker, bob.did), 100);
+            assert!(!Portfolio::custodian_affirmed());
+        });
+}
+
 fn create_instruction(
     alice: &User,
     bob: &User,
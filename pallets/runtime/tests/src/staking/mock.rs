@@ -446,6 +446,12 @@ impl AssetSubTrait<Balance> for Test {
     fn scope_id_of(_: &Ticker, _: &IdentityId) -> ScopeId {
         ScopeId::from(0u128)
     }
+    fn is_portfolio_frozen(_: &Ticker, _: &PortfolioId) -> bool {
+        false
+    }
+    fn is_frozen_balance_exceeded(_: &Ticker, _: IdentityId, _: Balance) -> bool {
+        false
+    }
 }
 
 impl MultiSigSubTrait<AccountId> for Test {
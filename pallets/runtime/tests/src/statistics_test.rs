@@ -5,11 +5,12 @@ use super::{
 use frame_support::{assert_noop, assert_ok};
 use pallet_asset::{self as asset, SecurityToken};
 use pallet_compliance_manager as compliance_manager;
-use pallet_statistics::{self as statistics};
+use pallet_statistics::{self as statistics, MAX_VOLUME_PERIOD_UNITS};
 use polymesh_primitives::{
     asset::AssetType,
-    statistics::{HashablePermill, TransferManager},
-    AccountId, IdentityId, PortfolioId, Ticker,
+    calendar::{CalendarPeriod, CalendarUnit},
+    statistics::{HashablePermill, StatType, TransferManager},
+    AccountId, Claim, ClaimType, CountryCode, IdentityId, PortfolioId, Scope, Ticker,
 };
 use sp_arithmetic::Permill;
 use sp_std::convert::TryFrom;
@@ -17,6 +18,8 @@ use test_client::AccountKeyring;
 
 type Origin = <TestStorage as frame_system::Config>::Origin;
 type Asset = asset::Module<TestStorage>;
+type Identity = pallet_identity::Module<TestStorage>;
+type Timestamp = pallet_timestamp::Module<TestStorage>;
 type Statistic = statistics::Module<TestStorage>;
 type ComplianceManager = compliance_manager::Module<TestStorage>;
 type Error = statistics::Error<TestStorage>;
@@ -324,3 +327,298 @@ fn should_verify_tms() {
             do_valid_transfer(ticker, alice_did, char_did, 25_000);
         });
 }
+
+#[test]
+fn should_verify_stat_tms() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(|| {
+            let token_name = b"ACME";
+            let ticker = Ticker::try_from(&token_name[..]).unwrap();
+            let setup_account = |keyring: AccountKeyring| {
+                make_account_with_scope(
+                    keyring.to_account_id(),
+                    ticker,
+                    AccountKeyring::Eve.to_account_id(),
+                )
+                .unwrap()
+            };
+            let (alice_signed, alice_did, _) = setup_account(AccountKeyring::Alice);
+            let (_, bob_did, bob_scope) = setup_account(AccountKeyring::Bob);
+            let (_, char_did, char_scope) = setup_account(AccountKeyring::Charlie);
+            let (_, dave_did, _) = setup_account(AccountKeyring::Dave);
+            let (_, ferdie_did, _) = setup_account(AccountKeyring::Ferdie);
+            create_token(token_name, ticker, AccountKeyring::Alice.to_account_id());
+
+            // Alice issues the jurisdiction and accreditation claims.
+            let add_claim = |target, claim| {
+                assert_ok!(Identity::add_claim(
+                    alice_signed.clone(),
+                    target,
+                    claim,
+                    None
+                ));
+            };
+            let scope = Scope::Ticker(ticker);
+            for (did, cc, accredited) in &[
+                (bob_did, CountryCode::US, true),
+                (char_did, CountryCode::US, true),
+                (dave_did, CountryCode::CA, false),
+                (ferdie_did, CountryCode::CA, false),
+            ] {
+                add_claim(*did, Claim::Jurisdiction(*cc, scope.clone()));
+                if *accredited {
+                    add_claim(*did, Claim::Accredited(scope.clone()));
+                }
+            }
+
+            // At most one investor in the US and one non-accredited investor.
+            let add_tm = |tm| Statistic::add_transfer_manager(alice_signed.clone(), ticker, tm);
+            let us = StatType::Jurisdiction(alice_did, CountryCode::US);
+            let non_accredited = StatType::Claim(alice_did, ClaimType::Accredited, false);
+            assert_ok!(add_tm(TransferManager::JurisdictionTransferManager(
+                alice_did,
+                CountryCode::US,
+                1
+            )));
+            assert_ok!(add_tm(TransferManager::ClaimCountTransferManager(
+                alice_did,
+                ClaimType::Accredited,
+                false,
+                1
+            )));
+            assert_eq!(Statistic::tracked_stats(ticker), vec![us, non_accredited]);
+
+            do_valid_transfer(ticker, alice_did, bob_did, 10);
+            assert_eq!(Statistic::stat_investor_count(ticker, us), 1);
+            // Charlie would be a second investor in the US.
+            ensure_invalid_transfer(ticker, alice_did, char_did, 10);
+
+            do_valid_transfer(ticker, alice_did, dave_did, 10);
+            assert_eq!(Statistic::stat_investor_count(ticker, non_accredited), 1);
+            // Ferdie would be a second non-accredited investor.
+            ensure_invalid_transfer(ticker, alice_did, ferdie_did, 10);
+
+            // Bob leaving the US statistic as Charlie joins it keeps the count.
+            do_valid_transfer(ticker, bob_did, char_did, 10);
+            assert_eq!(Statistic::stat_investor_count(ticker, us), 1);
+            assert!(!Statistic::stat_investor((ticker, us), bob_scope));
+            assert!(Statistic::stat_investor((ticker, us), char_scope));
+
+            // Once distributed, new statistics can't be tracked, but tracked ones can be limited.
+            assert_noop!(
+                add_tm(TransferManager::JurisdictionTransferManager(
+                    alice_did,
+                    CountryCode::CA,
+                    1
+                )),
+                Error::AssetAlreadyDistributed
+            );
+            assert_ok!(add_tm(TransferManager::JurisdictionTransferManager(
+                alice_did,
+                CountryCode::US,
+                2
+            )));
+        });
+}
+
+#[test]
+fn should_verify_hptm() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(|| {
+            let token_name = b"ACME";
+            let ticker = Ticker::try_from(&token_name[..]).unwrap();
+            let setup_account = |keyring: AccountKeyring| {
+                make_account_with_scope(
+                    keyring.to_account_id(),
+                    ticker,
+                    AccountKeyring::Eve.to_account_id(),
+                )
+                .unwrap()
+            };
+            let (alice_signed, alice_did, alice_scope) = setup_account(AccountKeyring::Alice);
+            let (_, bob_did, bob_scope) = setup_account(AccountKeyring::Bob);
+            let (_, char_did, char_scope) = setup_account(AccountKeyring::Charlie);
+            create_token(token_name, ticker, AccountKeyring::Alice.to_account_id());
+
+            let hptm = TransferManager::HoldingPeriodTransferManager(1000);
+            assert_ok!(Statistic::add_transfer_manager(
+                alice_signed.clone(),
+                ticker,
+                hptm.clone()
+            ));
+
+            Timestamp::set_timestamp(5000);
+            // Alice held her tokens before the HPTM was added, so she can distribute right away.
+            do_valid_transfer(ticker, alice_did, bob_did, 10);
+            assert_eq!(Statistic::acquired_at(ticker, bob_scope), Some(5000));
+
+            // Bob has to hold for the whole period.
+            Timestamp::set_timestamp(5999);
+            ensure_invalid_transfer(ticker, bob_did, char_did, 5);
+            Timestamp::set_timestamp(6000);
+            do_valid_transfer(ticker, bob_did, char_did, 5);
+            assert_eq!(Statistic::acquired_at(ticker, char_scope), Some(6000));
+
+            // Acquiring more while holding doesn't restart the period, so dust can't lock Bob.
+            do_valid_transfer(ticker, alice_did, bob_did, 1);
+            assert_eq!(Statistic::acquired_at(ticker, bob_scope), Some(5000));
+            do_valid_transfer(ticker, bob_did, char_did, 5);
+
+            // Charlie started holding just now, unless exempted.
+            ensure_invalid_transfer(ticker, char_did, bob_did, 5);
+            assert_ok!(Statistic::add_exempted_entities(
+                alice_signed.clone(),
+                ticker,
+                hptm,
+                vec![char_scope]
+            ));
+            do_valid_transfer(ticker, char_did, bob_did, 5);
+
+            // Leaving the asset clears the acquisition time.
+            do_valid_transfer(ticker, bob_did, char_did, 6);
+            assert_eq!(Statistic::acquired_at(ticker, bob_scope), None);
+
+            // Issuing to an identity that stopped holding the asset starts a new period.
+            let rest = Asset::balance_of(&ticker, alice_did);
+            do_valid_transfer(ticker, alice_did, char_did, rest);
+            Timestamp::set_timestamp(8000);
+            assert_ok!(Asset::issue(alice_signed, ticker, 100));
+            assert_eq!(Statistic::acquired_at(ticker, alice_scope), Some(8000));
+            ensure_invalid_transfer(ticker, alice_did, bob_did, 10);
+        });
+}
+
+#[test]
+fn should_verify_volume_tms() {
+    ExtBuilder::default()
+        .cdd_providers(vec![AccountKeyring::Eve.to_account_id()])
+        .build()
+        .execute_with(|| {
+            let token_name = b"ACME";
+            let ticker = Ticker::try_from(&token_name[..]).unwrap();
+            let setup_account = |keyring: AccountKeyring| {
+                make_account_with_scope(
+                    keyring.to_account_id(),
+                    ticker,
+                    AccountKeyring::Eve.to_account_id(),
+                )
+                .unwrap()
+            };
+            let (alice_signed, alice_did, alice_scope) = setup_account(AccountKeyring::Alice);
+            let (_, bob_did, bob_scope) = setup_account(AccountKeyring::Bob);
+            let (_, char_did, _) = setup_account(AccountKeyring::Charlie);
+            create_token(token_name, ticker, AccountKeyring::Alice.to_account_id());
+
+            let add_tm = |tm| Statistic::add_transfer_manager(alice_signed.clone(), ticker, tm);
+            let daily = CalendarPeriod {
+                unit: CalendarUnit::Day,
+                amount: 1,
+            };
+            assert_noop!(
+                add_tm(TransferManager::AssetVolumeTransferManager(
+                    CalendarPeriod::default(),
+                    150
+                )),
+                Error::NonRecurringVolumePeriod
+            );
+            let too_long = CalendarPeriod {
+                unit: CalendarUnit::Day,
+                amount: MAX_VOLUME_PERIOD_UNITS + 1,
+            };
+            assert_noop!(
+                add_tm(TransferManager::AssetVolumeTransferManager(too_long, 150)),
+                Error::VolumePeriodTooLong
+            );
+            let ivtm = TransferManager::InvestorVolumeTransferManager(daily, 100);
+            assert_ok!(add_tm(ivtm.clone()));
+            assert_ok!(add_tm(TransferManager::AssetVolumeTransferManager(
+                daily, 150
+            )));
+
+            // Day 1 started one hour ago.
+            let day = 86_400_000;
+            Timestamp::set_timestamp(day + 3_600_000);
+            do_valid_transfer(ticker, alice_did, bob_did, 100);
+            assert_eq!(
+                Statistic::investor_volume((ticker, daily), alice_scope).buckets,
+                vec![(1, 100)]
+            );
+            // Alice reached her daily limit.
+            ensure_invalid_transfer(ticker, alice_did, char_did, 1);
+            // Bob can still send, up to the daily limit of the asset.
+            do_valid_transfer(ticker, bob_did, char_did, 50);
+            assert_eq!(
+                Statistic::asset_volume(ticker, daily).buckets,
+                vec![(1, 150)]
+            );
+            ensure_invalid_transfer(ticker, bob_did, char_did, 1);
+
+            // The granular result points at the limit that was hit.
+            let result = Asset::unsafe_can_transfer_granular(
+                None,
+                PortfolioId::default_portfolio(alice_did),
+                None,
+                PortfolioId::default_portfolio(char_did),
+                &ticker,
+                1,
+            );
+            let failed = result
+                .statistics_result
+                .iter()
+                .filter(|r| !r.result)
+                .map(|r| r.tm.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                failed,
+                vec![
+                    ivtm.clone(),
+                    TransferManager::AssetVolumeTransferManager(daily, 150)
+                ]
+            );
+
+            // Volumes restart on the next day.
+            Timestamp::set_timestamp(2 * day);
+            do_valid_transfer(ticker, alice_did, char_did, 100);
+            ensure_invalid_transfer(ticker, alice_did, char_did, 1);
+
+            // Exempted senders are not limited by the investor volume.
+            assert_ok!(Statistic::add_exempted_entities(
+                alice_signed.clone(),
+                ticker,
+                ivtm,
+                vec![alice_scope]
+            ));
+            do_valid_transfer(ticker, alice_did, char_did, 50);
+
+            // Over a period of several days, the volume of the last days adds up.
+            assert_ok!(Statistic::remove_transfer_manager(
+                alice_signed.clone(),
+                ticker,
+                TransferManager::AssetVolumeTransferManager(daily, 150)
+            ));
+            let two_days = CalendarPeriod {
+                unit: CalendarUnit::Day,
+                amount: 2,
+            };
+            assert_ok!(add_tm(TransferManager::InvestorVolumeTransferManager(
+                two_days, 120
+            )));
+            do_valid_transfer(ticker, alice_did, bob_did, 100);
+            do_valid_transfer(ticker, bob_did, char_did, 100);
+            Timestamp::set_timestamp(3 * day);
+            ensure_invalid_transfer(ticker, bob_did, char_did, 30);
+            do_valid_transfer(ticker, bob_did, char_did, 20);
+
+            // The volume of the oldest day is dropped as it leaves the window.
+            Timestamp::set_timestamp(4 * day);
+            do_valid_transfer(ticker, bob_did, char_did, 30);
+            assert_eq!(
+                Statistic::investor_volume((ticker, two_days), bob_scope).buckets,
+                vec![(3, 20), (4, 30)]
+            );
+        });
+}
@@ -1,17 +1,26 @@
 use super::{
     asset_test::{allow_all_transfers, max_len_bytes},
-    storage::{make_account_with_portfolio, TestStorage, User},
+    storage::{
+        create_investor_uid, make_account_with_portfolio, provide_scope_claim, TestStorage, User,
+    },
     ExtBuilder,
 };
 use pallet_asset as asset;
-use pallet_compliance_manager as compliance_manager;
+use pallet_compliance_manager::{self as compliance_manager, ClaimAttestation, PresentedClaim};
+use pallet_portfolio::{MovePortfolioItem, MAX_VESTING_SCHEDULES};
 use pallet_settlement::{self as settlement, VenueDetails, VenueType};
 use pallet_sto::{
-    self as sto, Fundraiser, FundraiserName, FundraiserStatus, FundraiserTier, PriceTier, MAX_TIERS,
+    self as sto, Fundraiser, FundraiserKind, FundraiserName, FundraiserStatus, FundraiserTier,
+    InvestorRestrictions, PriceTier, BOOK_CLOSING_PERIOD, MAX_ALLOWED_CLAIMS, MAX_TIERS,
+};
+use polymesh_common_utilities::SystematicIssuers::Settlement as SettlementDID;
+use polymesh_primitives::{
+    asset::AssetType, AuthorizationData, Claim, Condition, ConditionType, PortfolioId,
+    PortfolioName, Scope, Signatory, Ticker, VestingKind, VestingSchedule,
 };
-use polymesh_primitives::{asset::AssetType, PortfolioId, Ticker};
 
 use crate::storage::provide_scope_claim_to_multiple_parties;
+use codec::Encode;
 use frame_support::{assert_noop, assert_ok};
 use sp_runtime::DispatchError;
 use sp_std::convert::TryFrom;
@@ -22,6 +31,8 @@ type Asset = asset::Module<TestStorage>;
 type STO = sto::Module<TestStorage>;
 type Error = sto::Error<TestStorage>;
 type EAError = pallet_external_agents::Error<TestStorage>;
+type Identity = pallet_identity::Module<TestStorage>;
+type Portfolio = pallet_portfolio::Module<TestStorage>;
 type PortfolioError = pallet_portfolio::Error<TestStorage>;
 type ComplianceManager = compliance_manager::Module<TestStorage>;
 type Settlement = settlement::Module<TestStorage>;
@@ -74,6 +85,71 @@ fn stop_fundraiser_ext() {
     test(stop_fundraiser);
 }
 
+#[test]
+fn dutch_auction_fundraiser_ext() {
+    test(dutch_auction_fundraiser);
+}
+
+#[test]
+fn pro_rata_fundraiser_ext() {
+    test(pro_rata_fundraiser);
+}
+
+#[test]
+fn book_building_failed_allocation_ext() {
+    test(book_building_failed_allocation);
+}
+
+#[test]
+fn book_building_late_cancel_ext() {
+    test(book_building_late_cancel);
+}
+
+#[test]
+fn investor_restrictions_ext() {
+    test(investor_restrictions);
+}
+
+#[test]
+fn escrowed_fundraiser_release_ext() {
+    test(escrowed_fundraiser_release);
+}
+
+#[test]
+fn escrowed_fundraiser_refund_ext() {
+    test(escrowed_fundraiser_refund);
+}
+
+#[test]
+fn escrowed_fundraiser_failed_release_ext() {
+    test(escrowed_fundraiser_failed_release);
+}
+
+#[test]
+fn escrowed_fundraiser_soft_cap_missed_ext() {
+    test(escrowed_fundraiser_soft_cap_missed);
+}
+
+#[test]
+fn escrowed_fundraiser_refund_after_end_ext() {
+    test(escrowed_fundraiser_refund_after_end);
+}
+
+#[test]
+fn fundraiser_vesting_ext() {
+    test(fundraiser_vesting);
+}
+
+#[test]
+fn fundraiser_vesting_many_contributions_ext() {
+    test(fundraiser_vesting_many_contributions);
+}
+
+#[test]
+fn invest_with_presented_claims_ext() {
+    test(invest_with_presented_claims);
+}
+
 pub fn create_asset(origin: Origin, ticker: Ticker, supply: u128) {
     assert_ok!(Asset::base_create_asset_and_mint(
         origin,
@@ -754,3 +830,1252 @@ fn stop_fundraiser() {
         Error::FundraiserClosed
     );
 }
+
+struct BookBuildingContext {
+    fundraiser_id: u64,
+    end: u64,
+    raise: RaiseContext,
+    charlie: User,
+    charlie_portfolio: PortfolioId,
+}
+
+fn book_building_fundraiser(kind: FundraiserKind) -> BookBuildingContext {
+    let raise = init_raise_context(1_000_000, Some(1_000_000));
+    let (charlie, charlie_portfolio) = make_account_with_portfolio(AccountKeyring::Charlie);
+    let raise_ticker = raise.raise_ticker.unwrap();
+    let eve = AccountKeyring::Eve.to_account_id();
+    provide_scope_claim_to_multiple_parties(&[charlie.did], raise.offering_ticker, eve.clone());
+    provide_scope_claim_to_multiple_parties(&[charlie.did], raise_ticker, eve);
+    allow_all_transfers(raise.offering_ticker, raise.alice);
+    allow_all_transfers(raise_ticker, raise.alice);
+
+    // Fund the investors.
+    for portfolio in &[raise.bob_portfolio, charlie_portfolio] {
+        assert_ok!(Asset::unsafe_transfer(
+            raise.alice_portfolio,
+            *portfolio,
+            &raise_ticker,
+            10_000
+        ));
+    }
+
+    let venue_counter = Settlement::venue_counter();
+    assert_ok!(Settlement::create_venue(
+        raise.alice.origin(),
+        VenueDetails::default(),
+        vec![AccountKeyring::Alice.to_account_id()],
+        VenueType::Sto
+    ));
+
+    let create = |tiers, end| {
+        STO::create_fundraiser_with_kind(
+            raise.alice.origin(),
+            raise.alice_portfolio,
+            raise.offering_ticker,
+            raise.alice_portfolio,
+            raise_ticker,
+            tiers,
+            venue_counter,
+            None,
+            end,
+            0,
+            FundraiserName::default(),
+            kind,
+        )
+    };
+    let tier = PriceTier {
+        total: 1_000,
+        price: 1_000_000,
+    };
+    let end = Timestamp::get() + 10;
+
+    // Book-building fundraisers need a single tier and an end.
+    assert_noop!(
+        create(vec![tier.clone(), tier.clone()], Some(end)),
+        Error::InvalidPriceTiers
+    );
+    assert_noop!(
+        create(vec![tier.clone()], None),
+        Error::InvalidOfferingWindow
+    );
+
+    let fundraiser_id = STO::fundraiser_count(raise.offering_ticker);
+    assert_ok!(create(vec![tier], Some(end)));
+    assert_eq!(
+        STO::fundraiser_kind(raise.offering_ticker, fundraiser_id),
+        kind
+    );
+
+    BookBuildingContext {
+        fundraiser_id,
+        end,
+        raise,
+        charlie,
+        charlie_portfolio,
+    }
+}
+
+fn dutch_auction_fundraiser() {
+    let BookBuildingContext {
+        fundraiser_id,
+        end,
+        raise:
+            RaiseContext {
+                alice,
+                alice_portfolio,
+                bob,
+                bob_portfolio,
+                offering_ticker,
+                raise_ticker,
+            },
+        charlie,
+        charlie_portfolio,
+    } = book_building_fundraiser(FundraiserKind::DutchAuction);
+    let raise_ticker = raise_ticker.unwrap();
+    let alice_init_offering = Asset::balance_of(&offering_ticker, alice.did);
+    let alice_init_raise = Asset::balance_of(&raise_ticker, alice.did);
+
+    let bid = |user: User, portfolio, amount, price| {
+        STO::submit_bid(
+            user.origin(),
+            portfolio,
+            portfolio,
+            offering_ticker,
+            fundraiser_id,
+            amount,
+            price,
+        )
+    };
+
+    // Book-building fundraisers don't accept direct investments.
+    assert_noop!(
+        STO::invest(
+            bob.origin(),
+            bob_portfolio,
+            bob_portfolio,
+            offering_ticker,
+            fundraiser_id,
+            100,
+            None,
+            None
+        ),
+        Error::InvalidFundraiserKind
+    );
+    // Bids below the reserve price are rejected.
+    assert_noop!(
+        bid(bob, bob_portfolio, 100, 999_999),
+        Error::InvalidBidPrice
+    );
+
+    assert_ok!(bid(bob, bob_portfolio, 600, 2_000_000));
+    assert_ok!(bid(charlie, charlie_portfolio, 600, 1_500_000));
+    assert_ok!(bid(bob, bob_portfolio, 100, 1_200_000));
+    assert_eq!(
+        Portfolio::locked_assets(bob_portfolio, &raise_ticker),
+        1_320
+    );
+    assert_eq!(
+        Portfolio::locked_assets(charlie_portfolio, &raise_ticker),
+        900
+    );
+
+    // Only the investor can cancel a bid.
+    assert_noop!(
+        STO::cancel_bid(charlie.origin(), offering_ticker, fundraiser_id, 2),
+        Error::Unauthorized
+    );
+    assert_ok!(STO::cancel_bid(
+        bob.origin(),
+        offering_ticker,
+        fundraiser_id,
+        2
+    ));
+    assert_eq!(
+        Portfolio::locked_assets(bob_portfolio, &raise_ticker),
+        1_200
+    );
+
+    assert_noop!(
+        STO::close_and_allocate(alice.origin(), offering_ticker, fundraiser_id, 2),
+        Error::BiddingWindowOpen
+    );
+
+    Timestamp::set_timestamp(end);
+    assert_noop!(
+        bid(bob, bob_portfolio, 100, 2_000_000),
+        Error::FundraiserExpired
+    );
+    assert_noop!(
+        STO::close_and_allocate(alice.origin(), offering_ticker, fundraiser_id, 1),
+        Error::TooManyBids
+    );
+    assert_ok!(STO::close_and_allocate(
+        alice.origin(),
+        offering_ticker,
+        fundraiser_id,
+        2
+    ));
+
+    // Bob is filled first, Charlie gets the rest, and both pay Charlie's price.
+    assert_eq!(Asset::balance_of(&offering_ticker, bob.did), 600);
+    assert_eq!(Asset::balance_of(&offering_ticker, charlie.did), 400);
+    assert_eq!(
+        Asset::balance_of(&offering_ticker, alice.did),
+        alice_init_offering - 1_000
+    );
+    assert_eq!(Asset::balance_of(&raise_ticker, bob.did), 10_000 - 900);
+    assert_eq!(Asset::balance_of(&raise_ticker, charlie.did), 10_000 - 600);
+    assert_eq!(
+        Asset::balance_of(&raise_ticker, alice.did),
+        alice_init_raise + 1_500
+    );
+    assert_eq!(Portfolio::locked_assets(bob_portfolio, &raise_ticker), 0);
+    assert_eq!(
+        Portfolio::locked_assets(charlie_portfolio, &raise_ticker),
+        0
+    );
+    assert_eq!(
+        Portfolio::locked_assets(alice_portfolio, &offering_ticker),
+        0
+    );
+
+    let fundraiser = STO::fundraisers(offering_ticker, fundraiser_id).unwrap();
+    assert_eq!(fundraiser.status, FundraiserStatus::Closed);
+    assert_eq!(fundraiser.tiers[0].remaining, 0);
+    assert_eq!(STO::bids((offering_ticker, fundraiser_id), 0), None);
+    assert_noop!(
+        STO::close_and_allocate(alice.origin(), offering_ticker, fundraiser_id, 2),
+        Error::FundraiserClosed
+    );
+}
+
+fn pro_rata_fundraiser() {
+    let BookBuildingContext {
+        fundraiser_id,
+        end,
+        raise:
+            RaiseContext {
+                alice,
+                alice_portfolio,
+                bob,
+                bob_portfolio,
+                offering_ticker,
+                raise_ticker,
+            },
+        charlie,
+        charlie_portfolio,
+    } = book_building_fundraiser(FundraiserKind::ProRata);
+    let raise_ticker = raise_ticker.unwrap();
+    let alice_init_offering = Asset::balance_of(&offering_ticker, alice.did);
+
+    let bid = |user: User, portfolio, amount, price| {
+        STO::submit_bid(
+            user.origin(),
+            portfolio,
+            portfolio,
+            offering_ticker,
+            fundraiser_id,
+            amount,
+            price,
+        )
+    };
+
+    // Pro-rata bids must be at the fixed price.
+    assert_noop!(
+        bid(bob, bob_portfolio, 100, 2_000_000),
+        Error::InvalidBidPrice
+    );
+    assert_ok!(bid(bob, bob_portfolio, 900, 1_000_000));
+    assert_ok!(bid(charlie, charlie_portfolio, 300, 1_000_000));
+
+    Timestamp::set_timestamp(end);
+    assert_ok!(STO::close_and_allocate(
+        alice.origin(),
+        offering_ticker,
+        fundraiser_id,
+        2
+    ));
+
+    // The fundraiser is oversubscribed, so both bids are scaled down by 1_000 / 1_200.
+    assert_eq!(Asset::balance_of(&offering_ticker, bob.did), 750);
+    assert_eq!(Asset::balance_of(&offering_ticker, charlie.did), 250);
+    assert_eq!(
+        Asset::balance_of(&offering_ticker, alice.did),
+        alice_init_offering - 1_000
+    );
+    assert_eq!(Asset::balance_of(&raise_ticker, bob.did), 10_000 - 750);
+    assert_eq!(Asset::balance_of(&raise_ticker, charlie.did), 10_000 - 250);
+    assert_eq!(Portfolio::locked_assets(bob_portfolio, &raise_ticker), 0);
+    assert_eq!(
+        Portfolio::locked_assets(charlie_portfolio, &raise_ticker),
+        0
+    );
+    assert_eq!(
+        Portfolio::locked_assets(alice_portfolio, &offering_ticker),
+        0
+    );
+}
+
+fn book_building_failed_allocation() {
+    let BookBuildingContext {
+        fundraiser_id,
+        end,
+        raise:
+            RaiseContext {
+                alice,
+                alice_portfolio,
+                bob,
+                bob_portfolio,
+                offering_ticker,
+                raise_ticker,
+            },
+        charlie,
+        charlie_portfolio,
+    } = book_building_fundraiser(FundraiserKind::ProRata);
+    let raise_ticker = raise_ticker.unwrap();
+    let alice_init_offering = Asset::balance_of(&offering_ticker, alice.did);
+
+    for (user, portfolio, amount) in &[(bob, bob_portfolio, 900), (charlie, charlie_portfolio, 300)]
+    {
+        assert_ok!(STO::submit_bid(
+            user.origin(),
+            *portfolio,
+            *portfolio,
+            offering_ticker,
+            fundraiser_id,
+            *amount,
+            1_000_000,
+        ));
+    }
+
+    // Charlie can no longer receive the offering asset, so his allocation fails to settle.
+    assert_ok!(Asset::freeze_portfolio(
+        alice.origin(),
+        offering_ticker,
+        charlie_portfolio
+    ));
+    Timestamp::set_timestamp(end);
+    assert_ok!(STO::close_and_allocate(
+        alice.origin(),
+        offering_ticker,
+        fundraiser_id,
+        2
+    ));
+
+    // Bob's allocation settles, while Charlie's funds are unlocked and his allocation is left unsold.
+    assert_eq!(Asset::balance_of(&offering_ticker, bob.did), 750);
+    assert_eq!(Asset::balance_of(&offering_ticker, charlie.did), 0);
+    assert_eq!(
+        Asset::balance_of(&offering_ticker, alice.did),
+        alice_init_offering - 750
+    );
+    assert_eq!(Asset::balance_of(&raise_ticker, bob.did), 10_000 - 750);
+    assert_eq!(Asset::balance_of(&raise_ticker, charlie.did), 10_000);
+    assert_eq!(
+        Portfolio::locked_assets(charlie_portfolio, &raise_ticker),
+        0
+    );
+    assert_eq!(
+        Portfolio::locked_assets(alice_portfolio, &offering_ticker),
+        0
+    );
+    let fundraiser = STO::fundraisers(offering_ticker, fundraiser_id).unwrap();
+    assert_eq!(fundraiser.status, FundraiserStatus::Closed);
+    assert_eq!(fundraiser.tiers[0].remaining, 250);
+}
+
+fn book_building_late_cancel() {
+    let BookBuildingContext {
+        fundraiser_id,
+        end,
+        raise:
+            RaiseContext {
+                bob,
+                bob_portfolio,
+                offering_ticker,
+                raise_ticker,
+                ..
+            },
+        ..
+    } = book_building_fundraiser(FundraiserKind::DutchAuction);
+    let raise_ticker = raise_ticker.unwrap();
+
+    assert_ok!(STO::submit_bid(
+        bob.origin(),
+        bob_portfolio,
+        bob_portfolio,
+        offering_ticker,
+        fundraiser_id,
+        100,
+        1_000_000,
+    ));
+    let cancel = || STO::cancel_bid(bob.origin(), offering_ticker, fundraiser_id, 0);
+
+    // Bids can't be cancelled while the book is waiting to be closed...
+    Timestamp::set_timestamp(end);
+    assert_noop!(cancel(), Error::FundraiserExpired);
+    Timestamp::set_timestamp(end + u64::from(BOOK_CLOSING_PERIOD) - 1);
+    assert_noop!(cancel(), Error::FundraiserExpired);
+
+    // ...but can once the book hasn't been closed in time.
+    Timestamp::set_timestamp(end + u64::from(BOOK_CLOSING_PERIOD));
+    assert_ok!(cancel());
+    assert_eq!(Portfolio::locked_assets(bob_portfolio, &raise_ticker), 0);
+    assert_eq!(STO::bids((offering_ticker, fundraiser_id), 0), None);
+}
+
+fn investor_restrictions() {
+    let RaiseContext {
+        alice,
+        alice_portfolio,
+        bob,
+        bob_portfolio,
+        offering_ticker,
+        raise_ticker,
+    } = init_raise_context(1_000_000, Some(1_000_000));
+    let raise_ticker = raise_ticker.unwrap();
+    let eve = AccountKeyring::Eve.to_account_id();
+
+    // Charlie shares Bob's `ScopeId` for the offering asset.
+    let (charlie, charlie_portfolio) = make_account_with_portfolio(AccountKeyring::Charlie);
+    provide_scope_claim(
+        charlie.did,
+        offering_ticker,
+        create_investor_uid(bob.acc()),
+        eve.clone(),
+        None,
+    );
+    provide_scope_claim_to_multiple_parties(&[charlie.did], raise_ticker, eve);
+    allow_all_transfers(offering_ticker, alice);
+    allow_all_transfers(raise_ticker, alice);
+    for portfolio in &[bob_portfolio, charlie_portfolio] {
+        assert_ok!(Asset::unsafe_transfer(
+            alice_portfolio,
+            *portfolio,
+            &raise_ticker,
+            10_000
+        ));
+    }
+
+    let venue_counter = Settlement::venue_counter();
+    assert_ok!(Settlement::create_venue(
+        alice.origin(),
+        VenueDetails::default(),
+        vec![AccountKeyring::Alice.to_account_id()],
+        VenueType::Sto
+    ));
+    let fundraiser_id = STO::fundraiser_count(offering_ticker);
+    assert_ok!(STO::create_fundraiser(
+        alice.origin(),
+        alice_portfolio,
+        offering_ticker,
+        alice_portfolio,
+        raise_ticker,
+        vec![PriceTier {
+            total: 1_000_000u128,
+            price: 1_000_000u128
+        }],
+        venue_counter,
+        None,
+        None,
+        0,
+        FundraiserName::default()
+    ));
+
+    let invest = |user: User, portfolio, amount| {
+        STO::invest(
+            user.origin(),
+            portfolio,
+            portfolio,
+            offering_ticker,
+            fundraiser_id,
+            amount,
+            None,
+            None,
+        )
+    };
+    let allowance = |user: User| STO::remaining_allowance(offering_ticker, fundraiser_id, user.did);
+    let accredited = Claim::Accredited(Scope::Ticker(offering_ticker));
+
+    // Investments are unrestricted by default.
+    assert_eq!(allowance(bob), None);
+
+    assert_noop!(
+        STO::set_investor_restrictions(
+            alice.origin(),
+            offering_ticker,
+            fundraiser_id,
+            InvestorRestrictions {
+                max_investment: None,
+                allowlist: true,
+                allowed_claims: vec![(accredited.clone(), alice.did); MAX_ALLOWED_CLAIMS + 1],
+            }
+        ),
+        Error::TooManyAllowedClaims
+    );
+    assert_ok!(STO::set_investor_restrictions(
+        alice.origin(),
+        offering_ticker,
+        fundraiser_id,
+        InvestorRestrictions {
+            max_investment: Some(1_000),
+            allowlist: true,
+            allowed_claims: vec![(accredited.clone(), alice.did)],
+        }
+    ));
+
+    // Bob can invest once allowlisted, up to the cap.
+    assert_eq!(allowance(bob), Some(0));
+    assert_noop!(invest(bob, bob_portfolio, 100), Error::InvestorNotAllowed);
+    assert_ok!(STO::add_allowed_investors(
+        alice.origin(),
+        offering_ticker,
+        fundraiser_id,
+        vec![bob.did]
+    ));
+    assert_eq!(allowance(bob), Some(1_000));
+    assert_ok!(invest(bob, bob_portfolio, 600));
+    assert_eq!(allowance(bob), Some(400));
+
+    // Charlie can invest once holding an allowed claim, sharing Bob's cap.
+    assert_noop!(
+        invest(charlie, charlie_portfolio, 100),
+        Error::InvestorNotAllowed
+    );
+    assert_ok!(Identity::add_claim(
+        alice.origin(),
+        charlie.did,
+        accredited,
+        None
+    ));
+    assert_eq!(allowance(charlie), Some(400));
+    assert_noop!(
+        invest(charlie, charlie_portfolio, 401),
+        Error::MaxInvestmentExceeded
+    );
+    assert_ok!(invest(charlie, charlie_portfolio, 400));
+    assert_eq!(allowance(bob), Some(0));
+    assert_eq!(allowance(charlie), Some(0));
+    assert_eq!(Asset::balance_of(&offering_ticker, bob.did), 600);
+    assert_eq!(Asset::balance_of(&offering_ticker, charlie.did), 400);
+
+    // Removing Bob from the allowlist stops his investments.
+    assert_ok!(STO::remove_allowed_investors(
+        alice.origin(),
+        offering_ticker,
+        fundraiser_id,
+        vec![bob.did]
+    ));
+    assert!(!STO::allowed_investor(
+        (offering_ticker, fundraiser_id),
+        bob.did
+    ));
+    assert_noop!(invest(bob, bob_portfolio, 1), Error::InvestorNotAllowed);
+
+    // Capped fundraisers need a scope claim to aggregate the investments of an investor.
+    let (dave, dave_portfolio) = make_account_with_portfolio(AccountKeyring::Dave);
+    assert_ok!(STO::add_allowed_investors(
+        alice.origin(),
+        offering_ticker,
+        fundraiser_id,
+        vec![dave.did]
+    ));
+    assert_eq!(allowance(dave), Some(0));
+    assert_noop!(invest(dave, dave_portfolio, 100), Error::MissingScopeClaim);
+}
+
+fn escrowed_fundraiser(soft_cap: u128, end: Option<u64>) -> (u64, RaiseContext) {
+    let raise = init_raise_context(1_000_000, Some(1_000_000));
+    let raise_ticker = raise.raise_ticker.unwrap();
+    allow_all_transfers(raise.offering_ticker, raise.alice);
+    allow_all_transfers(raise_ticker, raise.alice);
+    assert_ok!(Asset::unsafe_transfer(
+        raise.alice_portfolio,
+        raise.bob_portfolio,
+        &raise_ticker,
+        10_000
+    ));
+
+    let venue_counter = Settlement::venue_counter();
+    assert_ok!(Settlement::create_venue(
+        raise.alice.origin(),
+        VenueDetails::default(),
+        vec![AccountKeyring::Alice.to_account_id()],
+        VenueType::Sto
+    ));
+
+    let create = |soft_cap| {
+        STO::create_escrowed_fundraiser(
+            raise.alice.origin(),
+            raise.alice_portfolio,
+            raise.offering_ticker,
+            raise.alice_portfolio,
+            raise_ticker,
+            vec![PriceTier {
+                total: 1_000,
+                price: 1_000_000,
+            }],
+            venue_counter,
+            None,
+            end,
+            0,
+            FundraiserName::default(),
+            soft_cap,
+        )
+    };
+    // The soft cap must be positive and reachable.
+    assert_noop!(create(0), Error::InvalidSoftCap);
+    assert_noop!(create(1_001), Error::InvalidSoftCap);
+
+    let fundraiser_id = STO::fundraiser_count(raise.offering_ticker);
+    assert_ok!(create(soft_cap));
+    assert_eq!(
+        STO::soft_cap(raise.offering_ticker, fundraiser_id),
+        Some(soft_cap)
+    );
+    (fundraiser_id, raise)
+}
+
+fn escrowed_fundraiser_release() {
+    let (
+        fundraiser_id,
+        RaiseContext {
+            alice,
+            alice_portfolio,
+            bob,
+            bob_portfolio,
+            offering_ticker,
+            raise_ticker,
+        },
+    ) = escrowed_fundraiser(500, None);
+    let raise_ticker = raise_ticker.unwrap();
+    let alice_init_raise = Asset::balance_of(&raise_ticker, alice.did);
+
+    let invest = |amount| {
+        STO::invest(
+            bob.origin(),
+            bob_portfolio,
+            bob_portfolio,
+            offering_ticker,
+            fundraiser_id,
+            amount,
+            None,
+            None,
+        )
+    };
+
+    // Escrowed investments can't be funded from a portfolio of another identity in the investor's custody.
+    let alice_num = Portfolio::next_portfolio_number(alice.did);
+    assert_ok!(Portfolio::create_portfolio(
+        alice.origin(),
+        PortfolioName::from(b"Custodied" as &[u8])
+    ));
+    let alice_user_portfolio = PortfolioId::user_portfolio(alice.did, alice_num);
+    let auth_id = Identity::add_auth(
+        alice.did,
+        Signatory::from(bob.did),
+        AuthorizationData::PortfolioCustody(alice_user_portfolio),
+        None,
+    );
+    assert_ok!(Portfolio::accept_portfolio_custody(bob.origin(), auth_id));
+    assert_noop!(
+        STO::invest(
+            bob.origin(),
+            bob_portfolio,
+            alice_user_portfolio,
+            offering_ticker,
+            fundraiser_id,
+            300,
+            None,
+            None,
+        ),
+        Error::FundingPortfolioNotOwned
+    );
+
+    // Investments move the funds to escrow without settling.
+    assert_ok!(invest(300));
+    assert_ok!(invest(300));
+    let escrow_portfolio = STO::escrow_portfolio_of(bob.did).unwrap();
+    assert_eq!(
+        Portfolio::portfolio_custodian(escrow_portfolio),
+        Some(SettlementDID.as_id())
+    );
+    assert_eq!(Asset::balance_of(&offering_ticker, bob.did), 0);
+    assert_eq!(Asset::balance_of(&raise_ticker, bob.did), 10_000);
+    assert_eq!(
+        Portfolio::portfolio_asset_balances(escrow_portfolio, &raise_ticker),
+        600
+    );
+    assert_eq!(
+        Portfolio::portfolio_asset_balances(bob_portfolio, &raise_ticker),
+        10_000 - 600
+    );
+    // The escrowed funds can't be moved by the investor.
+    assert_noop!(
+        Portfolio::move_portfolio_funds(
+            bob.origin(),
+            escrow_portfolio,
+            bob_portfolio,
+            vec![MovePortfolioItem {
+                ticker: raise_ticker,
+                amount: 600,
+                memo: None,
+            }]
+        ),
+        PortfolioError::UnauthorizedCustodian
+    );
+    assert_eq!(STO::total_raised(offering_ticker, fundraiser_id), 600);
+    assert_eq!(
+        STO::contributions((offering_ticker, fundraiser_id), 1)
+            .unwrap()
+            .cost,
+        300
+    );
+
+    assert_noop!(
+        STO::release(alice.origin(), offering_ticker, fundraiser_id, 2),
+        Error::FundraiserNotPendingRelease
+    );
+    assert_noop!(
+        STO::claim_refund(bob.origin(), offering_ticker, fundraiser_id, 0),
+        Error::FundraiserNotRefunding
+    );
+
+    // The soft cap was reached, so only the unsold offering is unlocked.
+    assert_ok!(STO::stop(alice.origin(), offering_ticker, fundraiser_id));
+    let status = || {
+        STO::fundraisers(offering_ticker, fundraiser_id)
+            .unwrap()
+            .status
+    };
+    assert_eq!(status(), FundraiserStatus::PendingRelease);
+    assert_eq!(
+        Portfolio::locked_assets(alice_portfolio, &offering_ticker),
+        600
+    );
+    assert_noop!(invest(100), Error::FundraiserNotLive);
+
+    assert_noop!(
+        STO::release(alice.origin(), offering_ticker, fundraiser_id, 1),
+        Error::TooManyContributions
+    );
+    assert_ok!(STO::release(
+        alice.origin(),
+        offering_ticker,
+        fundraiser_id,
+        2
+    ));
+    assert_eq!(status(), FundraiserStatus::Closed);
+    assert_eq!(Asset::balance_of(&offering_ticker, bob.did), 600);
+    assert_eq!(Asset::balance_of(&raise_ticker, bob.did), 10_000 - 600);
+    assert_eq!(
+        Asset::balance_of(&raise_ticker, alice.did),
+        alice_init_raise + 600
+    );
+    assert_eq!(
+        Portfolio::portfolio_asset_balances(escrow_portfolio, &raise_ticker),
+        0
+    );
+    assert_eq!(
+        Portfolio::locked_assets(alice_portfolio, &offering_ticker),
+        0
+    );
+    assert_eq!(
+        STO::contributions((offering_ticker, fundraiser_id), 0),
+        None
+    );
+}
+
+fn escrowed_fundraiser_refund() {
+    let (
+        fundraiser_id,
+        RaiseContext {
+            alice,
+            alice_portfolio,
+            bob,
+            bob_portfolio,
+            offering_ticker,
+            raise_ticker,
+        },
+    ) = escrowed_fundraiser(500, None);
+    let raise_ticker = raise_ticker.unwrap();
+
+    assert_ok!(STO::invest(
+        bob.origin(),
+        bob_portfolio,
+        bob_portfolio,
+        offering_ticker,
+        fundraiser_id,
+        300,
+        None,
+        None,
+    ));
+
+    // The soft cap was missed, so the whole offering is unlocked.
+    let invested = || {
+        STO::invested_amount(
+            (offering_ticker, fundraiser_id),
+            Asset::scope_id_of(offering_ticker, bob.did),
+        )
+    };
+    assert_eq!(invested(), 300);
+
+    assert_ok!(STO::stop(alice.origin(), offering_ticker, fundraiser_id));
+    assert_eq!(
+        STO::fundraisers(offering_ticker, fundraiser_id)
+            .unwrap()
+            .status,
+        FundraiserStatus::Refunding
+    );
+    assert_eq!(
+        Portfolio::locked_assets(alice_portfolio, &offering_ticker),
+        0
+    );
+    assert_noop!(
+        STO::release(alice.origin(), offering_ticker, fundraiser_id, 1),
+        Error::FundraiserNotPendingRelease
+    );
+
+    // Only the investor can claim the refund.
+    assert_noop!(
+        STO::claim_refund(alice.origin(), offering_ticker, fundraiser_id, 0),
+        Error::Unauthorized
+    );
+    assert_ok!(STO::claim_refund(
+        bob.origin(),
+        offering_ticker,
+        fundraiser_id,
+        0
+    ));
+    assert_eq!(
+        Portfolio::portfolio_asset_balances(bob_portfolio, &raise_ticker),
+        10_000
+    );
+    let escrow_portfolio = STO::escrow_portfolio_of(bob.did).unwrap();
+    assert_eq!(
+        Portfolio::portfolio_asset_balances(escrow_portfolio, &raise_ticker),
+        0
+    );
+    assert_eq!(Asset::balance_of(&raise_ticker, bob.did), 10_000);
+    assert_eq!(Asset::balance_of(&offering_ticker, bob.did), 0);
+    // Refunded investments no longer count towards the investment cap.
+    assert_eq!(invested(), 0);
+    assert_noop!(
+        STO::claim_refund(bob.origin(), offering_ticker, fundraiser_id, 0),
+        Error::ContributionNotFound
+    );
+}
+
+fn escrowed_fundraiser_failed_release() {
+    let (
+        fundraiser_id,
+        RaiseContext {
+            alice,
+            bob,
+            bob_portfolio,
+            offering_ticker,
+            raise_ticker,
+            ..
+        },
+    ) = escrowed_fundraiser(500, None);
+    let raise_ticker = raise_ticker.unwrap();
+    let frozen_portfolio =
+        PortfolioId::user_portfolio(bob.did, Portfolio::next_portfolio_number(bob.did));
+    assert_ok!(Portfolio::create_portfolio(
+        bob.origin(),
+        PortfolioName::from([42u8].to_vec())
+    ));
+
+    let invest = |investment_portfolio, amount| {
+        STO::invest(
+            bob.origin(),
+            investment_portfolio,
+            bob_portfolio,
+            offering_ticker,
+            fundraiser_id,
+            amount,
+            None,
+            None,
+        )
+    };
+    assert_ok!(invest(bob_portfolio, 600));
+    assert_ok!(invest(frozen_portfolio, 300));
+    assert_ok!(STO::stop(alice.origin(), offering_ticker, fundraiser_id));
+
+    // The investment into the frozen portfolio fails to settle and is refunded,
+    // while the other one still reaches the soft cap.
+    assert_ok!(Asset::freeze_portfolio(
+        alice.origin(),
+        offering_ticker,
+        frozen_portfolio
+    ));
+    assert_ok!(STO::release(
+        alice.origin(),
+        offering_ticker,
+        fundraiser_id,
+        2
+    ));
+    assert_eq!(
+        STO::fundraisers(offering_ticker, fundraiser_id)
+            .unwrap()
+            .status,
+        FundraiserStatus::Closed
+    );
+    assert_eq!(Asset::balance_of(&offering_ticker, bob.did), 600);
+    assert_eq!(
+        Portfolio::portfolio_asset_balances(bob_portfolio, &raise_ticker),
+        10_000 - 600
+    );
+    let escrow_portfolio = STO::escrow_portfolio_of(bob.did).unwrap();
+    assert_eq!(
+        Portfolio::portfolio_asset_balances(escrow_portfolio, &raise_ticker),
+        0
+    );
+}
+
+fn escrowed_fundraiser_soft_cap_missed() {
+    let (
+        fundraiser_id,
+        RaiseContext {
+            alice,
+            alice_portfolio,
+            bob,
+            bob_portfolio,
+            offering_ticker,
+            raise_ticker,
+        },
+    ) = escrowed_fundraiser(500, Some(Timestamp::get() + 10));
+    let raise_ticker = raise_ticker.unwrap();
+    let frozen_portfolio =
+        PortfolioId::user_portfolio(bob.did, Portfolio::next_portfolio_number(bob.did));
+    assert_ok!(Portfolio::create_portfolio(
+        bob.origin(),
+        PortfolioName::from([42u8].to_vec())
+    ));
+
+    let invest = |investment_portfolio, amount| {
+        STO::invest(
+            bob.origin(),
+            investment_portfolio,
+            bob_portfolio,
+            offering_ticker,
+            fundraiser_id,
+            amount,
+            None,
+            None,
+        )
+    };
+    assert_ok!(invest(bob_portfolio, 300));
+    assert_ok!(invest(frozen_portfolio, 300));
+    let status = || {
+        STO::fundraisers(offering_ticker, fundraiser_id)
+            .unwrap()
+            .status
+    };
+
+    // The fundraiser reached its end, so releasing stops it first.
+    Timestamp::set_timestamp(Timestamp::get() + 10);
+    assert_eq!(status(), FundraiserStatus::Live);
+    assert_ok!(Asset::freeze_portfolio(
+        alice.origin(),
+        offering_ticker,
+        frozen_portfolio
+    ));
+
+    // Only one investment settles, which falls short of the soft cap,
+    // so the release is reverted and every investment is refunded.
+    assert_ok!(STO::release(
+        alice.origin(),
+        offering_ticker,
+        fundraiser_id,
+        2
+    ));
+    assert_eq!(status(), FundraiserStatus::Refunding);
+    assert_eq!(Asset::balance_of(&offering_ticker, bob.did), 0);
+    assert_eq!(
+        Portfolio::locked_assets(alice_portfolio, &offering_ticker),
+        0
+    );
+    for contribution_id in 0..2 {
+        assert_ok!(STO::claim_refund(
+            bob.origin(),
+            offering_ticker,
+            fundraiser_id,
+            contribution_id
+        ));
+    }
+    assert_eq!(
+        Portfolio::portfolio_asset_balances(bob_portfolio, &raise_ticker),
+        10_000
+    );
+}
+
+fn escrowed_fundraiser_refund_after_end() {
+    let end = Timestamp::get() + 10;
+    let (
+        fundraiser_id,
+        RaiseContext {
+            alice_portfolio,
+            bob,
+            bob_portfolio,
+            offering_ticker,
+            raise_ticker,
+            ..
+        },
+    ) = escrowed_fundraiser(500, Some(end));
+    let raise_ticker = raise_ticker.unwrap();
+
+    assert_ok!(STO::invest(
+        bob.origin(),
+        bob_portfolio,
+        bob_portfolio,
+        offering_ticker,
+        fundraiser_id,
+        300,
+        None,
+        None,
+    ));
+    let refund = || STO::claim_refund(bob.origin(), offering_ticker, fundraiser_id, 0);
+    assert_noop!(refund(), Error::FundraiserNotRefunding);
+
+    // Once the fundraiser reached its end without reaching the soft cap, refunds can be claimed without stopping it.
+    Timestamp::set_timestamp(end);
+    assert_ok!(refund());
+    assert_eq!(
+        STO::fundraisers(offering_ticker, fundraiser_id)
+            .unwrap()
+            .status,
+        FundraiserStatus::Refunding
+    );
+    assert_eq!(
+        Portfolio::portfolio_asset_balances(bob_portfolio, &raise_ticker),
+        10_000
+    );
+    assert_eq!(
+        Portfolio::locked_assets(alice_portfolio, &offering_ticker),
+        0
+    );
+}
+
+fn fundraiser_vesting() {
+    let (
+        fundraiser_id,
+        RaiseContext {
+            alice,
+            bob,
+            bob_portfolio,
+            offering_ticker,
+            ..
+        },
+    ) = escrowed_fundraiser(500, None);
+    let vesting_end = Timestamp::get() + 1_000;
+    let kind = VestingKind::Cliff(vesting_end);
+
+    let set_vesting = |vesting| {
+        STO::set_fundraiser_vesting(alice.origin(), offering_ticker, fundraiser_id, vesting)
+    };
+    assert_noop!(
+        STO::set_fundraiser_vesting(bob.origin(), offering_ticker, fundraiser_id, Some(kind)),
+        EAError::UnauthorizedAgent
+    );
+    assert_noop!(
+        set_vesting(Some(VestingKind::Step {
+            start: 0,
+            period: 0,
+            steps: 1
+        })),
+        Error::InvalidVesting
+    );
+    assert_ok!(set_vesting(Some(kind)));
+    assert_eq!(
+        STO::fundraiser_vesting(offering_ticker, fundraiser_id),
+        Some(kind)
+    );
+
+    assert_ok!(STO::invest(
+        bob.origin(),
+        bob_portfolio,
+        bob_portfolio,
+        offering_ticker,
+        fundraiser_id,
+        600,
+        None,
+        None
+    ));
+    assert_ok!(STO::stop(alice.origin(), offering_ticker, fundraiser_id));
+    assert_ok!(STO::release(
+        alice.origin(),
+        offering_ticker,
+        fundraiser_id,
+        1
+    ));
+
+    // The bought tokens are locked in the investment portfolio until the cliff.
+    assert_eq!(Asset::balance_of(&offering_ticker, bob.did), 600);
+    assert_eq!(
+        Portfolio::vesting_schedules(bob_portfolio, offering_ticker),
+        vec![VestingSchedule { amount: 600, kind }]
+    );
+    let move_funds = || {
+        Portfolio::move_portfolio_funds(
+            bob.origin(),
+            bob_portfolio,
+            PortfolioId::default_portfolio(bob.did),
+            vec![MovePortfolioItem {
+                ticker: offering_ticker,
+                amount: 600,
+                memo: None,
+            }],
+        )
+    };
+    assert_noop!(move_funds(), PortfolioError::InsufficientPortfolioBalance);
+
+    Timestamp::set_timestamp(vesting_end);
+    assert_ok!(move_funds());
+}
+
+fn fundraiser_vesting_many_contributions() {
+    let (
+        fundraiser_id,
+        RaiseContext {
+            alice,
+            bob,
+            bob_portfolio,
+            offering_ticker,
+            ..
+        },
+    ) = escrowed_fundraiser(500, None);
+    let kind = VestingKind::Cliff(Timestamp::get() + 1_000);
+    assert_ok!(STO::set_fundraiser_vesting(
+        alice.origin(),
+        offering_ticker,
+        fundraiser_id,
+        Some(kind)
+    ));
+
+    // More contributions than `MAX_VESTING_SCHEDULES` settle into a single schedule.
+    let contributions = MAX_VESTING_SCHEDULES as u32 + 2;
+    for _ in 0..contributions {
+        assert_ok!(STO::invest(
+            bob.origin(),
+            bob_portfolio,
+            bob_portfolio,
+            offering_ticker,
+            fundraiser_id,
+            50,
+            None,
+            None
+        ));
+    }
+    assert_ok!(STO::stop(alice.origin(), offering_ticker, fundraiser_id));
+    assert_ok!(STO::release(
+        alice.origin(),
+        offering_ticker,
+        fundraiser_id,
+        contributions
+    ));
+
+    let bought = 50 * contributions as u128;
+    assert_eq!(Asset::balance_of(&offering_ticker, bob.did), bought);
+    assert_eq!(
+        Portfolio::vesting_schedules(bob_portfolio, offering_ticker),
+        vec![VestingSchedule {
+            amount: bought,
+            kind
+        }]
+    );
+}
+
+fn invest_with_presented_claims() {
+    let RaiseContext {
+        alice,
+        alice_portfolio,
+        bob,
+        bob_portfolio,
+        offering_ticker,
+        raise_ticker,
+    } = init_raise_context(1_000_000, Some(1_000_000));
+    let raise_ticker = raise_ticker.unwrap();
+    let dave = User::new(AccountKeyring::Dave);
+    allow_all_transfers(raise_ticker, alice);
+    assert_ok!(Asset::unsafe_transfer(
+        alice_portfolio,
+        bob_portfolio,
+        &raise_ticker,
+        10_000
+    ));
+
+    // Only investors accredited by Dave can receive the offering asset.
+    let scope = Scope::Ticker(offering_ticker);
+    assert_ok!(ComplianceManager::add_compliance_requirement(
+        alice.origin(),
+        offering_ticker,
+        vec![],
+        vec![Condition::from_dids(
+            ConditionType::IsPresent(Claim::Accredited(scope.clone())),
+            &[dave.did]
+        )]
+    ));
+
+    let venue_counter = Settlement::venue_counter();
+    assert_ok!(Settlement::create_venue(
+        alice.origin(),
+        VenueDetails::default(),
+        vec![AccountKeyring::Alice.to_account_id()],
+        VenueType::Sto
+    ));
+    let fundraiser_id = STO::fundraiser_count(offering_ticker);
+    assert_ok!(STO::create_fundraiser(
+        alice.origin(),
+        alice_portfolio,
+        offering_ticker,
+        alice_portfolio,
+        raise_ticker,
+        vec![PriceTier {
+            total: 1_000,
+            price: 1_000_000
+        }],
+        venue_counter,
+        None,
+        None,
+        0,
+        FundraiserName::default(),
+    ));
+
+    let attestation = ClaimAttestation {
+        target: bob.did,
+        issuer: dave.did,
+        claim: Claim::Accredited(scope),
+        expiry: Timestamp::get() + 1_000,
+    };
+    let presented = PresentedClaim {
+        signer: AccountKeyring::Dave.to_account_id(),
+        signature: AccountKeyring::Dave
+            .sign(&ComplianceManager::attestation_payload(&attestation))
+            .into(),
+        attestation,
+    };
+    let invest = |claims| {
+        STO::invest_with_claims(
+            bob.origin(),
+            bob_portfolio,
+            bob_portfolio,
+            offering_ticker,
+            fundraiser_id,
+            100,
+            None,
+            claims,
+        )
+    };
+
+    // Without the claim, Bob cannot receive the offering asset.
+    assert_noop!(
+        invest(vec![]),
+        settlement::Error::<TestStorage>::InstructionFailed
+    );
+    assert_ok!(invest(vec![presented]));
+    assert_eq!(Asset::balance_of(&offering_ticker, bob.did), 100);
+    assert_eq!(Asset::balance_of(&raise_ticker, bob.did), 10_000 - 100);
+    assert!(ComplianceManager::presented_claims().is_empty());
+    assert!(!ComplianceManager::claims_presented());
+}
@@ -19,6 +19,9 @@ use pallet_committee as committee;
 use pallet_corporate_actions as corporate_actions;
 use pallet_corporate_actions::ballot as corporate_ballots;
 use pallet_corporate_actions::distribution as capital_distributions;
+use pallet_corporate_actions::fixed_income;
+use pallet_corporate_actions::split as stock_splits;
+use pallet_corporate_actions::tender as tender_offers;
 use pallet_group as group;
 use pallet_identity as identity;
 use pallet_multisig as multisig;
@@ -231,6 +234,9 @@ frame_support::construct_runtime!(
         TestUtils: pallet_test_utils::{Module, Call, Storage, Event<T> } = 50,
         Base: pallet_base::{Module, Call, Event} = 51,
         ExternalAgents: pallet_external_agents::{Module, Call, Storage, Event} = 52,
+        StockSplit: stock_splits::{Module, Call, Storage, Event<T>} = 53,
+        TenderOffer: tender_offers::{Module, Call, Storage, Event<T>} = 54,
+        FixedIncome: fixed_income::{Module, Call, Storage, Event<T>} = 55,
     }
 );
 
@@ -615,6 +615,35 @@ benchmarks! {
     }
 
 
+    add_instruction_with_expiry {
+        let l in 1 .. MAX_LEGS_IN_INSTRUCTION;
+        // Define settlement type
+        let settlement_type = SettlementType::SettleOnBlock(100u32.into());
+        set_block_number::<T>(50);
+        // Emulate the add instruction and get all the necessary arguments.
+        let (legs, venue_id, origin, did , _, _, _ ) = emulate_add_instruction::<T>(l, false).unwrap();
+    }: _(origin, venue_id, settlement_type, Some(99999999u32.into()), Some(99999999u32.into()), legs, 200u32.into())
+    verify {
+        verify_add_instruction::<T>(venue_id, settlement_type).unwrap();
+        assert_eq!(Module::<T>::instruction_expiry(1), Some(200u32.into()), "Settlement: Expiry not set");
+    }
+
+
+    add_and_affirm_instruction_with_expiry {
+        let l in 1 .. MAX_LEGS_IN_INSTRUCTION;
+        // Define settlement type.
+        let settlement_type = SettlementType::SettleOnBlock(100u32.into());
+        set_block_number::<T>(50);
+        // Emulate the add instruction and get all the necessary arguments.
+        let (legs, venue_id, origin, did , portfolios, _, _) = emulate_add_instruction::<T>(l, true).unwrap();
+        let s_portfolios = portfolios.clone();
+    }: _(origin, venue_id, settlement_type, Some(99999999u32.into()), Some(99999999u32.into()), legs, s_portfolios, 200u32.into())
+    verify {
+        verify_add_and_affirm_instruction::<T>(venue_id, settlement_type, portfolios).unwrap();
+        assert_eq!(Module::<T>::instruction_expiry(1), Some(200u32.into()), "Settlement: Expiry not set");
+    }
+
+
     set_venue_filtering {
         // Constant time function. It is only for allow venue filtering.
         let user = UserBuilder::<T>::default().generate_did().build("creator");
@@ -678,7 +707,7 @@ benchmarks! {
         // Emulate the add instruction and get all the necessary arguments.
         let (legs, venue_id, origin, did , portfolios, _, _) = emulate_add_instruction::<T>(l, true).unwrap();
         // Add instruction
-        Module::<T>::base_add_instruction(did, venue_id, SettlementType::SettleOnAffirmation, None, None, legs.clone()).unwrap();
+        Module::<T>::base_add_instruction(did, venue_id, SettlementType::SettleOnAffirmation, None, None, legs.clone(), None).unwrap();
         let instruction_id: u64 = 1;
         // Affirm an instruction
         let portfolios_set = portfolios.clone().into_iter().collect::<BTreeSet<_>>();
@@ -699,7 +728,7 @@ benchmarks! {
         // Emulate the add instruction and get all the necessary arguments.
         let (legs, venue_id, origin, did , portfolios, _, account_id) = emulate_add_instruction::<T>(l, true).unwrap();
         // Add instruction
-        Module::<T>::base_add_instruction(did, venue_id, SettlementType::SettleOnAffirmation, None, None, legs.clone()).unwrap();
+        Module::<T>::base_add_instruction(did, venue_id, SettlementType::SettleOnAffirmation, None, None, legs.clone(), None).unwrap();
         let instruction_id: u64 = 1;
         // Affirm an instruction
         portfolios.clone().into_iter().for_each(|p| {
@@ -723,7 +752,7 @@ benchmarks! {
         // Emulate the add instruction and get all the necessary arguments.
         let (legs, venue_id, origin, did, _, _, account_id) = emulate_add_instruction::<T>(1, true).unwrap();
         // Add instruction
-        Module::<T>::base_add_instruction(did, venue_id, SettlementType::SettleOnAffirmation, None, None, legs.clone()).unwrap();
+        Module::<T>::base_add_instruction(did, venue_id, SettlementType::SettleOnAffirmation, None, None, legs.clone(), None).unwrap();
         let instruction_id = 1;
         let leg_id = 0;
 
@@ -766,7 +795,7 @@ benchmarks! {
         // Emulate the add instruction and get all the necessary arguments.
         let (legs, venue_id, origin, did , s_portfolios, r_portfolios, account_id) = emulate_add_instruction::<T>(1, true).unwrap();
         // Add instruction
-        Module::<T>::base_add_instruction(did, venue_id, SettlementType::SettleOnAffirmation, None, None, legs.clone()).unwrap();
+        Module::<T>::base_add_instruction(did, venue_id, SettlementType::SettleOnAffirmation, None, None, legs.clone(), None).unwrap();
         let instruction_id = 1;
         let ticker = Ticker::generate_into(1u64);
         let receipt = create_receipt_details::<T>(0, legs.first().unwrap().clone());
@@ -791,7 +820,7 @@ benchmarks! {
         // Emulate the add instruction and get all the necessary arguments.
         let (legs, venue_id, origin, did , s_portfolios, r_portfolios, account_id) = emulate_add_instruction::<T>(r, true).unwrap();
         // Add instruction
-        Module::<T>::base_add_instruction(did, venue_id, SettlementType::SettleOnAffirmation, None, None, legs.clone()).unwrap();
+        Module::<T>::base_add_instruction(did, venue_id, SettlementType::SettleOnAffirmation, None, None, legs.clone(), None).unwrap();
         let instruction_id = 1;
         let mut receipt_details = Vec::with_capacity(r as usize);
         legs.clone().into_iter().enumerate().for_each(|(idx, l)| {
@@ -883,6 +912,37 @@ benchmarks! {
         next_block::<T>();
         assert_eq!(Module::<T>::instruction_details(instruction_id).status, InstructionStatus::Failed, "Settlement: reschedule_instruction didn't work");
     }
+
+    execute_netted_instructions {
+        let l in 1 .. MAX_LEGS_IN_INSTRUCTION;
+
+        // Setup affirm instruction (One party (i.e from) already affirms the instruction)
+        let (portfolios_to, from, to, _, legs) = setup_affirm_instruction::<T>(l);
+        let instruction_id = 1; // It will always be `1` as we know there is no other instruction in the storage yet.
+        let venue_id = 1; // Venue is created by `from` during the setup.
+        let first_leg = legs.into_iter().nth(0).unwrap_or_default();
+        let before_transfer_balance = <PortfolioAssetBalances<T>>::get(first_leg.from, first_leg.asset);
+        Module::<T>::affirm_instruction(RawOrigin::Signed(to.account.clone()).into(), instruction_id, portfolios_to, l).unwrap();
+    }: _(RawOrigin::Signed(from.account), venue_id, vec![instruction_id], l)
+    verify {
+        let after_transfer_balance = <PortfolioAssetBalances<T>>::get(first_leg.from, first_leg.asset);
+        assert_eq!(before_transfer_balance - after_transfer_balance, first_leg.amount, "Settlement: Failed to execute netted instructions");
+        assert_eq!(Module::<T>::instruction_details(instruction_id).status, InstructionStatus::Unknown, "Settlement: Instruction not pruned");
+    }
+
+
+    expire_instruction {
+        let l in 1 .. MAX_LEGS_IN_INSTRUCTION;
+
+        // Setup affirm instruction (One party (i.e from) already affirms the instruction)
+        let (_, _, _, _, legs) = setup_affirm_instruction::<T>(l);
+        let instruction_id = 1; // It will always be `1` as we know there is no other instruction in the storage yet.
+        let first_leg = legs.into_iter().nth(0).unwrap_or_default();
+    }: _(RawOrigin::Root, instruction_id, l)
+    verify {
+        assert_eq!(Module::<T>::instruction_details(instruction_id).status, InstructionStatus::Expired, "Settlement: Instruction not expired");
+        assert_eq!(Portfolio::<T>::locked_assets(first_leg.from, first_leg.asset), 0u32.into(), "Settlement: Locks not released");
+    }
 }
 
 pub fn next_block<T: Config + pallet_scheduler::Config>() {
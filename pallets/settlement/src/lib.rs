@@ -27,6 +27,7 @@
 //!
 //! An instruction must be authorized by all the counter parties involved for it to be executed.
 //! An instruction can be set to automatically execute in the next block when all authorizations are received or at a particular block number.
+//! An instruction can also be given an expiry block. If it has not been executed by then, it expires and all tokens locked for it are released.
 //!
 //! Offchain settlements are represented via receipts. If a leg has a receipt attached to it, it will not be executed onchain.
 //! All other legs will be executed onchain during settlement.
@@ -35,7 +36,9 @@
 //!
 //! - `create_venue` - Registers a new venue.
 //! - `add_instruction` - Adds a new instruction.
+//! - `add_instruction_with_expiry` - Adds a new instruction that expires at a given block.
 //! - `affirm_instruction` - Provides affirmation to an existing instruction.
+//! - `affirm_with_claims` - Provides the last affirmation to an instruction, presenting off-chain claims, and executes it.
 //! - `withdraw_affirmation` - Withdraw an existing affirmation to given instruction.
 //! - `reject_instruction` - Rejects an existing instruction.
 //! - `claim_receipt` - Claims a signed receipt.
@@ -43,6 +46,7 @@
 //! - `set_venue_filtering` - Enables or disabled venue filtering for a token.
 //! - `allow_venues` - Allows additional venues to create instructions involving an asset.
 //! - `disallow_venues` - Revokes permission given to venues for creating instructions involving a particular asset.
+//! - `execute_netted_instructions` - Nets the legs of several affirmed instructions of a venue and executes them atomically.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![recursion_limit = "256"]
@@ -64,11 +68,16 @@ use frame_support::{
 };
 use frame_system::{self as system, ensure_root, RawOrigin};
 use pallet_base::ensure_string_limited;
+use pallet_compliance_manager::PresentedClaimsOf;
 use pallet_identity::{self as identity, PermissionedCallOriginData};
+use pallet_portfolio::PortfolioCustodian;
 use polymesh_common_utilities::{
     constants::{
-        queue_priority::SETTLEMENT_INSTRUCTION_EXECUTION_PRIORITY,
-        schedule_name_prefix::SETTLEMENT_INSTRUCTION_EXECUTION,
+        queue_priority::{
+            SETTLEMENT_INSTRUCTION_EXECUTION_PRIORITY, SETTLEMENT_INSTRUCTION_EXPIRY_PRIORITY,
+        },
+        schedule_name_prefix::{SETTLEMENT_INSTRUCTION_EXECUTION, SETTLEMENT_INSTRUCTION_EXPIRY},
+        ERC1400_TRANSFER_SUCCESS,
     },
     traits::{
         asset, identity::Config as IdentityConfig, portfolio::PortfolioSubTrait, CommonConfig,
@@ -77,16 +86,25 @@ use polymesh_common_utilities::{
     SystematicIssuers::Settlement as SettlementDID,
 };
 use polymesh_primitives::{
-    storage_migrate_on, storage_migration_ver, IdentityId, PortfolioId, SecondaryKey, Ticker,
+    asset::GranularCanTransferResult, storage_migrate_on, storage_migration_ver, IdentityId,
+    PortfolioId, SecondaryKey, Ticker,
 };
 use polymesh_primitives_derive::VecU8StrongTyped;
-use sp_runtime::traits::{One, Verify};
-use sp_std::{collections::btree_set::BTreeSet, convert::TryFrom, prelude::*};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::traits::{One, Verify, Zero};
+use sp_std::{
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    convert::TryFrom,
+    prelude::*,
+};
 
 type Identity<T> = identity::Module<T>;
 type System<T> = frame_system::Module<T>;
 type Asset<T> = pallet_asset::Module<T>;
+type Portfolio<T> = pallet_portfolio::Module<T>;
 type ExternalAgents<T> = pallet_external_agents::Module<T>;
+type ComplianceManager<T> = pallet_compliance_manager::Module<T>;
 
 pub trait Config:
     frame_system::Config<Call: From<Call<Self>> + Into<<Self as IdentityConfig>::Proposal>>
@@ -115,6 +133,7 @@ pub trait Config:
 pub struct VenueDetails(Vec<u8>);
 
 /// Status of an instruction
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum InstructionStatus {
     /// Invalid instruction or details pruned
@@ -123,6 +142,8 @@ pub enum InstructionStatus {
     Pending,
     /// Instruction has failed execution
     Failed,
+    /// Instruction has not been executed before its expiry
+    Expired,
 }
 
 impl Default for InstructionStatus {
@@ -151,6 +172,7 @@ impl Default for VenueType {
 }
 
 /// Status of a leg
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LegStatus<AccountId> {
     /// It is waiting for affirmation
@@ -168,6 +190,7 @@ impl<AccountId> Default for LegStatus<AccountId> {
 }
 
 /// Status of an affirmation
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum AffirmationStatus {
     /// Invalid affirmation
@@ -187,6 +210,7 @@ impl Default for AffirmationStatus {
 }
 
 /// Type of settlement
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum SettlementType<BlockNumber> {
     /// Instruction should be settled in the next block as soon as all affirmations are received.
@@ -202,6 +226,7 @@ impl<BlockNumber> Default for SettlementType<BlockNumber> {
 }
 
 /// Details about an instruction
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, Default, Clone, PartialEq, Eq, Debug, PartialOrd, Ord)]
 pub struct Instruction<Moment, BlockNumber> {
     /// Unique instruction id. It is an auto incrementing number
@@ -221,6 +246,7 @@ pub struct Instruction<Moment, BlockNumber> {
 }
 
 /// Details of a leg including the leg id in the instruction
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, Default, Clone, PartialEq, Eq, Debug, PartialOrd, Ord)]
 pub struct Leg<Balance> {
     /// Portfolio of the sender
@@ -304,6 +330,50 @@ pub struct ReceiptDetails<AccountId, OffChainSignature> {
     pub metadata: ReceiptMetadata,
 }
 
+/// A leg of an instruction together with its id and current status.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+pub struct LegDetails<AccountId, Balance> {
+    /// Id of the leg in the instruction
+    pub leg_id: u64,
+    /// The leg itself
+    pub leg: Leg<Balance>,
+    /// Current status of the leg
+    pub status: LegStatus<AccountId>,
+}
+
+/// Details of an instruction, its legs and the affirmations it is still waiting for.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+pub struct InstructionReport<AccountId, Moment, BlockNumber, Balance> {
+    /// Details of the instruction
+    pub instruction: Instruction<Moment, BlockNumber>,
+    /// Legs of the instruction, ordered by leg id
+    pub legs: Vec<LegDetails<AccountId, Balance>>,
+    /// Portfolios that have not affirmed the instruction yet
+    pub missing_affirmations: Vec<PortfolioId>,
+    /// Block at which the instruction expires, if any
+    pub expiry: Option<BlockNumber>,
+}
+
+/// Result of a dry-run execution of an instruction.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+pub struct InstructionExecutionReport {
+    /// Current status of the instruction
+    pub status: InstructionStatus,
+    /// Portfolios that have not affirmed the instruction yet
+    pub missing_affirmations: Vec<PortfolioId>,
+    /// The venue is not allowed to settle one of the assets of the instruction
+    pub unauthorized_venue: bool,
+    /// Transfer checks of each leg to be executed, in execution order
+    pub legs: Vec<(u64, GranularCanTransferResult)>,
+    /// Id of the first leg that would fail
+    pub failed_leg: Option<u64>,
+    /// Final evaluation result
+    pub result: bool,
+}
+
 pub trait WeightInfo {
     fn create_venue(d: u32, u: u32) -> Weight;
     fn update_venue(d: u32) -> Weight;
@@ -320,6 +390,11 @@ pub trait WeightInfo {
     fn execute_scheduled_instruction(l: u32) -> Weight;
     fn reject_instruction() -> Weight;
     fn change_receipt_validity() -> Weight;
+    fn execute_netted_instructions(l: u32) -> Weight;
+    fn add_instruction_with_expiry(u: u32) -> Weight;
+    fn add_and_affirm_instruction_with_expiry(u: u32) -> Weight;
+    fn expire_instruction(l: u32) -> Weight;
+    fn affirm_with_claims(l: u32, c: u32) -> Weight;
 
     // Some multiple paths based extrinsic.
     // TODO: Will be removed once we get the worst case weight.
@@ -383,6 +458,13 @@ decl_event!(
         /// Instruction is rescheduled.
         /// (caller DID, instruction_id)
         InstructionRescheduled(IdentityId, u64),
+        /// Instructions have been netted and executed together.
+        /// (caller DID, venue_id, instruction_ids, net transfers)
+        InstructionsNetted(IdentityId, u64, Vec<u64>, Vec<Leg<Balance>>),
+        /// An expiry has been set for an instruction (did, instruction_id, expiry block)
+        InstructionExpirySet(IdentityId, u64, BlockNumber),
+        /// Instruction has expired and its locked tokens have been released (did, instruction_id)
+        InstructionExpired(IdentityId, u64),
     }
 );
 
@@ -437,6 +519,14 @@ decl_error! {
         LegCountTooSmall,
         /// Instruction status is unknown
         UnknownInstruction,
+        /// Instruction does not belong to the provided venue.
+        InstructionVenueMismatch,
+        /// Instruction has expired.
+        InstructionExpired,
+        /// The provided expiry block number is in the past.
+        InstructionExpiryInPast,
+        /// Claims can only be presented with the affirmation settling a `SettleOnAffirmation` instruction.
+        ClaimsRequireFinalAffirmation,
     }
 }
 
@@ -472,6 +562,8 @@ decl_storage! {
         /// Venues that are allowed to create instructions involving a particular ticker. Oly used if filtering is enabled.
         /// (ticker, venue_id) -> allowed
         VenueAllowList get(fn venue_allow_list): double_map hasher(blake2_128_concat) Ticker, hasher(twox_64_concat) u64 => bool;
+        /// Block at which a pending instruction expires. instruction_id -> expiry
+        InstructionExpiry get(fn instruction_expiry): map hasher(twox_64_concat) u64 => Option<T::BlockNumber>;
         /// Number of venues in the system (It's one more than the actual number)
         VenueCounter get(fn venue_counter) build(|_| 1u64): u64;
         /// Number of instructions in the system (It's one more than the actual number)
@@ -576,7 +668,7 @@ decl_module! {
             legs: Vec<Leg<T::Balance>>
         ) {
             let did = Identity::<T>::ensure_perms(origin)?;
-            Self::base_add_instruction(did, venue_id, settlement_type, trade_date, value_date, legs)?;
+            Self::base_add_instruction(did, venue_id, settlement_type, trade_date, value_date, legs, None)?;
         }
 
         /// Adds and affirms a new instruction.
@@ -605,13 +697,72 @@ decl_module! {
             legs: Vec<Leg<T::Balance>>,
             portfolios: Vec<PortfolioId>
         ) -> DispatchResult {
-            let did = Identity::<T>::ensure_perms(origin.clone())?;
-            with_transaction(|| {
-                let portfolios_set = portfolios.into_iter().collect::<BTreeSet<_>>();
-                let legs_count = legs.iter().filter(|l| portfolios_set.contains(&l.from)).count() as u32;
-                let instruction_id = Self::base_add_instruction(did, venue_id, settlement_type, trade_date, value_date, legs)?;
-                Self::affirm_and_maybe_schedule_instruction(origin, instruction_id, portfolios_set.into_iter(), legs_count)
-            })
+            Self::base_add_and_affirm_instruction(origin, venue_id, settlement_type, trade_date, value_date, legs, portfolios, None)
+        }
+
+        /// Adds a new instruction that expires at the given block unless it has been executed before.
+        ///
+        /// On expiry, the instruction status changes to `Expired`, all tokens locked for it are released,
+        /// and it can no longer be affirmed or executed.
+        ///
+        /// # Arguments
+        /// * `venue_id` - ID of the venue this instruction belongs to.
+        /// * `settlement_type` - Defines if the instruction should be settled
+        ///    in the next block after receiving all affirmations or waiting till a specific block.
+        /// * `trade_date` - Optional date from which people can interact with this instruction.
+        /// * `value_date` - Optional date after which the instruction should be settled (not enforced)
+        /// * `legs` - Legs included in this instruction.
+        /// * `expiry` - Block at which the instruction expires.
+        ///
+        /// # Errors
+        /// * `InstructionExpiryInPast` if `expiry` is not in the future.
+        /// * `InstructionDatesInvalid` if `expiry` is not after the settlement block.
+        #[weight = <T as Config>::WeightInfo::add_instruction_with_expiry(legs.len() as u32)
+        .saturating_add(
+            <T as Config>::WeightInfo::execute_scheduled_instruction(legs.len() as u32)
+        )]
+        pub fn add_instruction_with_expiry(
+            origin,
+            venue_id: u64,
+            settlement_type: SettlementType<T::BlockNumber>,
+            trade_date: Option<T::Moment>,
+            value_date: Option<T::Moment>,
+            legs: Vec<Leg<T::Balance>>,
+            expiry: T::BlockNumber
+        ) {
+            let did = Identity::<T>::ensure_perms(origin)?;
+            Self::base_add_instruction(did, venue_id, settlement_type, trade_date, value_date, legs, Some(expiry))?;
+        }
+
+        /// Adds and affirms a new instruction that expires at the given block unless it has been executed before.
+        ///
+        /// # Arguments
+        /// * `venue_id` - ID of the venue this instruction belongs to.
+        /// * `settlement_type` - Defines if the instruction should be settled
+        ///    in the next block after receiving all affirmations or waiting till a specific block.
+        /// * `trade_date` - Optional date from which people can interact with this instruction.
+        /// * `value_date` - Optional date after which the instruction should be settled (not enforced)
+        /// * `legs` - Legs included in this instruction.
+        /// * `portfolios` - Portfolios that the sender controls and wants to use in this affirmations.
+        /// * `expiry` - Block at which the instruction expires.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::add_and_affirm_instruction_with_expiry(legs.len() as u32)
+        .saturating_add(
+            <T as Config>::WeightInfo::execute_scheduled_instruction(legs.len() as u32)
+        )]
+        pub fn add_and_affirm_instruction_with_expiry(
+            origin,
+            venue_id: u64,
+            settlement_type: SettlementType<T::BlockNumber>,
+            trade_date: Option<T::Moment>,
+            value_date: Option<T::Moment>,
+            legs: Vec<Leg<T::Balance>>,
+            portfolios: Vec<PortfolioId>,
+            expiry: T::BlockNumber
+        ) -> DispatchResult {
+            Self::base_add_and_affirm_instruction(origin, venue_id, settlement_type, trade_date, value_date, legs, portfolios, Some(expiry))
         }
 
         /// Provide affirmation to an existing instruction.
@@ -688,6 +839,53 @@ decl_module! {
             Self::affirm_with_receipts_and_maybe_schedule_instruction(origin, instruction_id, receipt_details, portfolios, max_legs_count)
         }
 
+        /// Provides the last affirmation of a `SettleOnAffirmation` instruction,
+        /// presenting off-chain claims about the caller, and executes the instruction.
+        /// The claims are taken into account by compliance checks during this call only,
+        /// and are never stored on-chain.
+        ///
+        /// # Arguments
+        /// * `instruction_id` - Instruction id to affirm.
+        /// * `portfolios` - Portfolios that the sender controls and wants to affirm this instruction.
+        /// * `max_legs_count` - Number of legs that need to be affirmed.
+        /// * `claims` - Claims, signed off-chain by their issuers, about the caller.
+        ///
+        /// # Errors
+        /// * `ClaimsRequireFinalAffirmation` if the instruction is not `SettleOnAffirmation`,
+        /// or other affirmations are still pending.
+        /// * See `ComplianceManager::with_presented_claims`.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::affirm_with_claims(*max_legs_count, claims.len() as u32)]
+        pub fn affirm_with_claims(
+            origin,
+            instruction_id: u64,
+            portfolios: Vec<PortfolioId>,
+            max_legs_count: u32,
+            claims: PresentedClaimsOf<T>,
+        ) -> DispatchResult {
+            let (did, secondary_key, details) = Self::ensure_origin_perm_and_instruction_validity(origin, instruction_id)?;
+            ensure!(
+                details.settlement_type == SettlementType::SettleOnAffirmation,
+                Error::<T>::ClaimsRequireFinalAffirmation
+            );
+            let portfolios_set = portfolios.into_iter().collect::<BTreeSet<_>>();
+
+            with_transaction(|| {
+                Self::unsafe_affirm_instruction(did, instruction_id, portfolios_set, max_legs_count, secondary_key.as_ref())?;
+                ensure!(
+                    Self::instruction_affirms_pending(instruction_id) == 0,
+                    Error::<T>::ClaimsRequireFinalAffirmation
+                );
+                <ComplianceManager<T>>::with_presented_claims(did, claims, || {
+                    Self::execute_instruction(instruction_id)
+                })?;
+                Self::prune_instruction(instruction_id);
+                Ok(())
+            })
+        }
+
         /// Claims a signed receipt.
         ///
         /// # Arguments
@@ -809,6 +1007,13 @@ decl_module! {
             Self::execute_instruction_retryable(instruction_id)?;
         }
 
+        /// Root callable extrinsic, used as an internal call to expire a settlement instruction.
+        #[weight = <T as Config>::WeightInfo::expire_instruction(*_legs_count)]
+        fn expire_instruction(origin, instruction_id: u64, _legs_count: u32) {
+            ensure_root(origin)?;
+            Self::base_expire_instruction(instruction_id);
+        }
+
         /// Reschedules a failed instruction.
         ///
         /// # Arguments
@@ -835,6 +1040,40 @@ decl_module! {
 
             Self::deposit_event(RawEvent::InstructionRescheduled(did, instruction_id));
         }
+
+        /// Nets the legs of several fully affirmed instructions of a venue and executes them atomically.
+        ///
+        /// Each leg is first checked on its own, as if it were executed separately,
+        /// except for the balance of its sender. The legs of all instructions are then aggregated
+        /// into a net balance change per portfolio and ticker, and only the resulting net transfers
+        /// are executed. Tokens locked while affirming the legs are released before the net transfers
+        /// take place. Legs settled offchain via receipts are skipped.
+        /// Either all instructions are executed or none are.
+        ///
+        /// When a leg fails its checks, its instruction is marked as failed, can be rescheduled,
+        /// and its receipts are unclaimed. When a net transfer fails, so do all the instructions
+        /// sending its asset from its sending portfolio.
+        ///
+        /// # Arguments
+        /// * `venue_id` - ID of the venue that all instructions belong to.
+        /// * `instruction_ids` - Instructions to net and execute.
+        /// * `max_legs_count` - Maximum number of legs across all instructions.
+        ///
+        /// # Errors
+        /// * `InvalidVenue` if `venue_id` does not exist.
+        /// * `Unauthorized` if the caller is not the creator of the venue.
+        /// * `InstructionNotPending` if an instruction is not pending execution.
+        /// * `InstructionVenueMismatch` if an instruction does not belong to `venue_id`.
+        /// * `InstructionNotAffirmed` if an instruction still has pending affirmations.
+        /// * `LegCountTooSmall` if the instructions have more than `max_legs_count` legs in total.
+        /// * `UnauthorizedVenue` if the venue is not allowed to settle one of the tickers.
+        /// * `InstructionFailed` if a leg fails its checks.
+        /// * The error of the first net transfer that fails, e.g., `InsufficientPortfolioBalance`.
+        #[weight = <T as Config>::WeightInfo::execute_netted_instructions(*max_legs_count)]
+        pub fn execute_netted_instructions(origin, venue_id: u64, instruction_ids: Vec<u64>, max_legs_count: u32) -> DispatchResult {
+            let did = Identity::<T>::ensure_perms(origin)?;
+            Self::base_execute_netted_instructions(did, venue_id, instruction_ids, max_legs_count)
+        }
     }
 }
 
@@ -878,6 +1117,7 @@ impl<T: Config> Module<T> {
         trade_date: Option<T::Moment>,
         value_date: Option<T::Moment>,
         legs: Vec<Leg<T::Balance>>,
+        expiry: Option<T::BlockNumber>,
     ) -> Result<u64, DispatchError> {
         // Ensure that the scheduled block number is in the future so that `T::Scheduler::schedule_named`
         // doesn't fail.
@@ -888,6 +1128,17 @@ impl<T: Config> Module<T> {
             );
         }
 
+        // Ensure that the expiry is in the future and that the instruction can be settled before it expires.
+        if let Some(expiry) = expiry {
+            ensure!(
+                expiry > System::<T>::block_number(),
+                Error::<T>::InstructionExpiryInPast
+            );
+            if let SettlementType::SettleOnBlock(block_number) = settlement_type {
+                ensure!(expiry > block_number, Error::<T>::InstructionDatesInvalid);
+            }
+        }
+
         // Check if a venue exists and the sender is the creator of the venue
         let mut venue = Self::venue_info(venue_id).ok_or(Error::<T>::InvalidVenue)?;
         ensure!(venue.creator == did, Error::<T>::Unauthorized);
@@ -946,6 +1197,11 @@ impl<T: Config> Module<T> {
             Self::schedule_instruction(instruction_counter, block_number, legs.len() as u32);
         }
 
+        if let Some(expiry) = expiry {
+            <InstructionExpiry<T>>::insert(instruction_counter, expiry);
+            Self::schedule_instruction_expiry(instruction_counter, expiry, legs.len() as u32);
+        }
+
         <InstructionDetails<T>>::insert(instruction_counter, instruction);
         InstructionAffirmsPending::insert(
             instruction_counter,
@@ -963,9 +1219,82 @@ impl<T: Config> Module<T> {
             value_date,
             legs,
         ));
+        if let Some(expiry) = expiry {
+            Self::deposit_event(RawEvent::InstructionExpirySet(
+                did,
+                instruction_counter,
+                expiry,
+            ));
+        }
         Ok(instruction_counter)
     }
 
+    fn base_add_and_affirm_instruction(
+        origin: <T as frame_system::Config>::Origin,
+        venue_id: u64,
+        settlement_type: SettlementType<T::BlockNumber>,
+        trade_date: Option<T::Moment>,
+        value_date: Option<T::Moment>,
+        legs: Vec<Leg<T::Balance>>,
+        portfolios: Vec<PortfolioId>,
+        expiry: Option<T::BlockNumber>,
+    ) -> DispatchResult {
+        let did = Identity::<T>::ensure_perms(origin.clone())?;
+        with_transaction(|| {
+            let portfolios_set = portfolios.into_iter().collect::<BTreeSet<_>>();
+            let legs_count = legs
+                .iter()
+                .filter(|l| portfolios_set.contains(&l.from))
+                .count() as u32;
+            let instruction_id = Self::base_add_instruction(
+                did,
+                venue_id,
+                settlement_type,
+                trade_date,
+                value_date,
+                legs,
+                expiry,
+            )?;
+            Self::affirm_and_maybe_schedule_instruction(
+                origin,
+                instruction_id,
+                portfolios_set.into_iter(),
+                legs_count,
+            )
+        })
+    }
+
+    /// Expires the instruction with `instruction_id` if it is still pending or failed,
+    /// releasing all tokens locked for it and unclaiming its receipts.
+    ///
+    /// The instruction is kept with the `Expired` status until it is rejected.
+    fn base_expire_instruction(instruction_id: u64) {
+        let details = Self::instruction_details(instruction_id);
+        if !matches!(
+            details.status,
+            InstructionStatus::Pending | InstructionStatus::Failed
+        ) {
+            return;
+        }
+
+        let legs = <InstructionLegs<T>>::iter_prefix(instruction_id).collect::<Vec<_>>();
+        Self::unsafe_unclaim_receipts(instruction_id, &legs);
+        Self::unchecked_release_locks(instruction_id, &legs);
+        // Nothing is locked anymore, so make sure a later rejection doesn't release the locks again.
+        for (leg_id, _) in &legs {
+            <InstructionLegStatus<T>>::insert(instruction_id, leg_id, LegStatus::PendingTokenLock);
+        }
+        let _ =
+            T::Scheduler::cancel_named((SETTLEMENT_INSTRUCTION_EXECUTION, instruction_id).encode());
+        <InstructionDetails<T>>::mutate(instruction_id, |details| {
+            details.status = InstructionStatus::Expired
+        });
+        Self::deposit_event(RawEvent::InstructionExpired(
+            SettlementDID.as_id(),
+            instruction_id,
+        ));
+    }
+
     fn unsafe_withdraw_instruction_affirmation(
         did: IdentityId,
         instruction_id: u64,
@@ -1034,6 +1363,10 @@ impl<T: Config> Module<T> {
             details.status != InstructionStatus::Unknown,
             Error::<T>::UnknownInstruction
         );
+        ensure!(
+            details.status != InstructionStatus::Expired,
+            Error::<T>::InstructionExpired
+        );
         if let (Some(trade_date), Some(value_date)) = (details.trade_date, details.value_date) {
             ensure!(
                 value_date >= trade_date,
@@ -1097,25 +1430,29 @@ impl<T: Config> Module<T> {
             }
         }
 
-        match with_transaction(|| {
-            Self::unchecked_release_locks(instruction_id, &legs);
-
-            for (leg_id, leg_details) in legs.iter().filter(|(leg_id, _)| {
-                let status = Self::instruction_leg_status(instruction_id, leg_id);
-                status == LegStatus::ExecutionPending
-            }) {
-                if <Asset<T>>::base_transfer(
-                    leg_details.from,
-                    leg_details.to,
-                    &leg_details.asset,
-                    leg_details.amount,
-                )
-                .is_err()
-                {
-                    return Err(leg_id);
+        // The legs being executed were affirmed by the custodians of their portfolios.
+        let senders = legs.iter().map(|(_, leg)| &leg.from);
+        match <Portfolio<T>>::with_custodian_affirmation(senders, || {
+            with_transaction(|| {
+                Self::unchecked_release_locks(instruction_id, &legs);
+
+                for (leg_id, leg_details) in legs.iter().filter(|(leg_id, _)| {
+                    let status = Self::instruction_leg_status(instruction_id, leg_id);
+                    status == LegStatus::ExecutionPending
+                }) {
+                    if <Asset<T>>::base_transfer(
+                        leg_details.from,
+                        leg_details.to,
+                        &leg_details.asset,
+                        leg_details.amount,
+                    )
+                    .is_err()
+                    {
+                        return Err(leg_id);
+                    }
                 }
-            }
-            Ok(())
+                Ok(())
+            })
         }) {
             Ok(_) => {
                 Self::deposit_event(RawEvent::InstructionExecuted(
@@ -1142,12 +1479,235 @@ impl<T: Config> Module<T> {
         Ok(instructions_processed)
     }
 
+    fn base_execute_netted_instructions(
+        did: IdentityId,
+        venue_id: u64,
+        instruction_ids: Vec<u64>,
+        max_legs_count: u32,
+    ) -> DispatchResult {
+        // Only the venue creator may net instructions of the venue.
+        let venue = Self::venue_info(venue_id).ok_or(Error::<T>::InvalidVenue)?;
+        ensure!(venue.creator == did, Error::<T>::Unauthorized);
+
+        // Ensure all instructions are ready for execution and collect their legs.
+        let instruction_ids = instruction_ids.into_iter().collect::<BTreeSet<_>>();
+        let mut instructions = Vec::with_capacity(instruction_ids.len());
+        let mut legs_count: u32 = 0;
+        for instruction_id in &instruction_ids {
+            let details = Self::instruction_details(instruction_id);
+            ensure!(
+                details.status == InstructionStatus::Pending,
+                Error::<T>::InstructionNotPending
+            );
+            ensure!(
+                details.venue_id == venue_id,
+                Error::<T>::InstructionVenueMismatch
+            );
+            ensure!(
+                Self::instruction_affirms_pending(instruction_id) == 0,
+                Error::<T>::InstructionNotAffirmed
+            );
+            let legs = <InstructionLegs<T>>::iter_prefix(instruction_id).collect::<Vec<_>>();
+            legs_count = legs_count.saturating_add(u32::try_from(legs.len()).unwrap_or_default());
+            instructions.push((*instruction_id, legs));
+        }
+        ensure!(legs_count <= max_legs_count, Error::<T>::LegCountTooSmall);
+
+        // Verify that the venue still has the required permissions for the tokens involved.
+        let tickers = instructions
+            .iter()
+            .flat_map(|(_, legs)| legs.iter().map(|(_, leg)| leg.asset))
+            .collect::<BTreeSet<_>>();
+        for ticker in &tickers {
+            ensure!(
+                !Self::venue_filtering(ticker) || Self::venue_allow_list(ticker, venue_id),
+                Error::<T>::UnauthorizedVenue
+            );
+        }
+
+        // The legs being executed were affirmed by the custodians of their portfolios.
+        let senders = instructions
+            .iter()
+            .flat_map(|(_, legs)| legs.iter().map(|(_, leg)| &leg.from));
+        let transfers =
+            <Portfolio<T>>::with_custodian_affirmation(senders, || -> Result<_, DispatchError> {
+                // Each leg is verified on its own, between the parties that actually traded,
+                // so that compliance and transfer managers also apply to legs that net out.
+                let mut failed = false;
+                for (instruction_id, legs) in &instructions {
+                    let failed_leg = Self::pending_legs(*instruction_id, legs).find(|(_, leg)| {
+                        <Asset<T>>::netted_transfer_status(&leg.asset, leg.from, leg.to, leg.amount)
+                            != Ok(ERC1400_TRANSFER_SUCCESS)
+                    });
+                    if let Some((leg_id, _)) = failed_leg {
+                        Self::fail_netted_instruction(*instruction_id, legs, Some(*leg_id));
+                        failed = true;
+                    }
+                }
+                ensure!(!failed, Error::<T>::InstructionFailed);
+
+                let transfers = Self::net_legs(
+                    instructions
+                        .iter()
+                        .flat_map(|(instruction_id, legs)| {
+                            Self::pending_legs(*instruction_id, legs)
+                        })
+                        .map(|(_, leg)| leg),
+                );
+                let result = with_transaction(|| {
+                    for (instruction_id, legs) in &instructions {
+                        Self::unchecked_release_locks(*instruction_id, legs);
+                    }
+                    for transfer in &transfers {
+                        <Asset<T>>::unsafe_net_transfer(
+                            transfer.from,
+                            transfer.to,
+                            &transfer.asset,
+                            transfer.amount,
+                        )
+                        .map_err(|error| (transfer, error))?;
+                    }
+                    Ok(())
+                });
+                if let Err((transfer, error)) = result {
+                    // The instructions sending the asset out of a portfolio that can't cover its net outflow fail.
+                    for (instruction_id, legs) in &instructions {
+                        if Self::pending_legs(*instruction_id, legs).any(|(_, leg)| {
+                            leg.from == transfer.from && leg.asset == transfer.asset
+                        }) {
+                            Self::fail_netted_instruction(*instruction_id, legs, None);
+                        }
+                    }
+                    return Err(error);
+                }
+                Ok(transfers)
+            })?;
+
+        for instruction_id in &instruction_ids {
+            let _ = T::Scheduler::cancel_named(
+                (SETTLEMENT_INSTRUCTION_EXECUTION, instruction_id).encode(),
+            );
+            Self::prune_instruction(*instruction_id);
+            Self::deposit_event(RawEvent::InstructionExecuted(
+                SettlementDID.as_id(),
+                *instruction_id,
+            ));
+        }
+        Self::deposit_event(RawEvent::InstructionsNetted(
+            did,
+            venue_id,
+            instruction_ids.into_iter().collect(),
+            transfers,
+        ));
+        Ok(())
+    }
+
+    /// Returns the legs of an instruction that are settled onchain,
+    /// i.e., without the legs settled offchain through a claimed receipt.
+    fn pending_legs<'a>(
+        instruction_id: u64,
+        legs: &'a [(u64, Leg<T::Balance>)],
+    ) -> impl Iterator<Item = &'a (u64, Leg<T::Balance>)> + 'a {
+        legs.iter().filter(move |(leg_id, _)| {
+            Self::instruction_leg_status(instruction_id, leg_id) == LegStatus::ExecutionPending
+        })
+    }
+
+    /// Marks an instruction that failed to be netted as failed, so that it can be rescheduled,
+    /// and unclaims its receipts so that they can be reused.
+    /// `leg_id` is the leg that failed its checks, if the instruction failed because of a single leg.
+    fn fail_netted_instruction(
+        instruction_id: u64,
+        legs: &Vec<(u64, Leg<T::Balance>)>,
+        leg_id: Option<u64>,
+    ) {
+        <InstructionDetails<T>>::mutate(instruction_id, |details| {
+            details.status = InstructionStatus::Failed
+        });
+        if let Some(leg_id) = leg_id {
+            Self::deposit_event(RawEvent::LegFailedExecution(
+                SettlementDID.as_id(),
+                instruction_id,
+                leg_id,
+            ));
+        }
+        Self::deposit_event(RawEvent::InstructionFailed(
+            SettlementDID.as_id(),
+            instruction_id,
+        ));
+        Self::unsafe_unclaim_receipts(instruction_id, legs);
+    }
+
+    /// Computes the net transfers that settle all of `legs`.
+    ///
+    /// The legs are aggregated into a net balance change per ticker and portfolio.
+    /// Portfolios with a net outflow are then matched, in order, against portfolios with a net inflow of the same ticker.
+    /// As every leg moves the same amount out of one portfolio and into another,
+    /// the total outflow of a ticker always equals its total inflow.
+    fn net_legs<'a>(legs: impl Iterator<Item = &'a Leg<T::Balance>>) -> Vec<Leg<T::Balance>> {
+        // (ticker, portfolio) -> (incoming, outgoing)
+        let mut flows = BTreeMap::<(Ticker, PortfolioId), (T::Balance, T::Balance)>::new();
+        for leg in legs {
+            let (_, outgoing) = flows
+                .entry((leg.asset, leg.from))
+                .or_insert_with(|| (Zero::zero(), Zero::zero()));
+            *outgoing = outgoing.saturating_add(leg.amount);
+            let (incoming, _) = flows
+                .entry((leg.asset, leg.to))
+                .or_insert_with(|| (Zero::zero(), Zero::zero()));
+            *incoming = incoming.saturating_add(leg.amount);
+        }
+
+        // Both lists are sorted by ticker due to the ordering of `flows`.
+        let mut senders = Vec::new();
+        let mut receivers = Vec::new();
+        for ((ticker, portfolio), (incoming, outgoing)) in flows {
+            if outgoing > incoming {
+                senders.push((ticker, portfolio, outgoing - incoming));
+            } else if incoming > outgoing {
+                receivers.push((ticker, portfolio, incoming - outgoing));
+            }
+        }
+
+        let mut transfers = Vec::new();
+        let mut receivers = receivers.into_iter().peekable();
+        for (ticker, from, mut remaining) in senders {
+            while remaining > Zero::zero() {
+                let (receiver_ticker, to, wanted) = match receivers.peek_mut() {
+                    Some(receiver) => receiver,
+                    None => break,
+                };
+                if *receiver_ticker != ticker {
+                    break;
+                }
+                let amount = remaining.min(*wanted);
+                transfers.push(Leg {
+                    from,
+                    to: *to,
+                    asset: ticker,
+                    amount,
+                });
+                remaining = remaining - amount;
+                *wanted = *wanted - amount;
+                if wanted.is_zero() {
+                    receivers.next();
+                }
+            }
+        }
+        transfers
+    }
+
     fn prune_instruction(instruction_id: u64) {
         let legs = <InstructionLegs<T>>::drain_prefix(instruction_id).collect::<Vec<_>>();
         <InstructionDetails<T>>::remove(instruction_id);
         <InstructionLegStatus<T>>::remove_prefix(instruction_id);
         InstructionAffirmsPending::remove(instruction_id);
         AffirmsReceived::remove_prefix(instruction_id);
+        if <InstructionExpiry<T>>::take(instruction_id).is_some() {
+            let _ = T::Scheduler::cancel_named(
+                (SETTLEMENT_INSTRUCTION_EXPIRY, instruction_id).encode(),
+            );
+        }
 
         // We remove duplicates in memory before triggering storage actions
         let mut counter_parties = Vec::with_capacity(legs.len() * 2);
@@ -1180,6 +1740,7 @@ impl<T: Config> Module<T> {
 
         let (total_leg_count, filtered_legs) =
             Self::filtered_legs(instruction_id, &portfolios, max_legs_count)?;
+        Self::ensure_policies_allow_legs(instruction_id, &portfolios)?;
         with_transaction(|| {
             for (leg_id, leg_details) in filtered_legs {
                 if let Err(_) = Self::lock_via_leg(&leg_details) {
@@ -1346,6 +1907,23 @@ impl<T: Config> Module<T> {
         }
     }
 
+    /// Schedule expiry of given instruction at given block number.
+    fn schedule_instruction_expiry(instruction_id: u64, expiry: T::BlockNumber, legs_count: u32) {
+        let call = Call::<T>::expire_instruction(instruction_id, legs_count).into();
+        if let Err(_) = T::Scheduler::schedule_named(
+            (SETTLEMENT_INSTRUCTION_EXPIRY, instruction_id).encode(),
+            DispatchTime::At(expiry),
+            None,
+            SETTLEMENT_INSTRUCTION_EXPIRY_PRIORITY,
+            RawOrigin::Root.into(),
+            call,
+        ) {
+            Self::deposit_event(RawEvent::SchedulingFailed(
+                Error::<T>::FailedToSchedule.into(),
+            ));
+        }
+    }
+
     pub fn base_affirm_with_receipts(
         origin: <T as frame_system::Config>::Origin,
         instruction_id: u64,
@@ -1409,6 +1987,7 @@ impl<T: Config> Module<T> {
 
         let (total_leg_count, filtered_legs) =
             Self::filtered_legs(instruction_id, &portfolios_set, max_legs_count)?;
+        Self::ensure_policies_allow_legs(instruction_id, &portfolios_set)?;
         // Lock tokens that do not have a receipt attached to their leg.
         with_transaction(|| {
             for (leg_id, leg_details) in filtered_legs {
@@ -1584,6 +2163,45 @@ impl<T: Config> Module<T> {
         })
     }
 
+    /// Executes the instruction if all its affirmations have been received.
+    ///
+    /// NB - Use this function only in the STO pallet to settle allocations affirmed on behalf of investors.
+    pub fn execute_affirmed_instruction(instruction_id: u64) -> DispatchResult {
+        Self::execute_settle_on_affirmation_instruction(
+            instruction_id,
+            Self::instruction_affirms_pending(instruction_id),
+            Self::instruction_details(instruction_id).settlement_type,
+        )
+    }
+
+    /// Rescales, e.g., for a stock split, the amounts of `ticker` sent from `portfolio`
+    /// in the instructions pending execution.
+    ///
+    /// Returns the amount of `ticker` locked in `portfolio` by the affirmed legs,
+    /// before and after rescaling.
+    pub fn rescale_pending_legs(
+        portfolio: PortfolioId,
+        ticker: Ticker,
+        rescale: impl Fn(T::Balance) -> Result<T::Balance, DispatchError>,
+    ) -> Result<(T::Balance, T::Balance), DispatchError> {
+        let mut locked = (T::Balance::zero(), T::Balance::zero());
+        for (instruction_id, _) in UserAffirmations::iter_prefix(portfolio) {
+            for (leg_id, leg) in <InstructionLegs<T>>::iter_prefix(instruction_id) {
+                if leg.from == portfolio && leg.asset == ticker {
+                    let amount = rescale(leg.amount)?;
+                    if Self::instruction_leg_status(instruction_id, leg_id)
+                        == LegStatus::ExecutionPending
+                    {
+                        locked.0 += leg.amount;
+                        locked.1 += amount;
+                    }
+                    <InstructionLegs<T>>::insert(instruction_id, leg_id, Leg { amount, ..leg });
+                }
+            }
+        }
+        Ok(locked)
+    }
+
     fn execute_settle_on_affirmation_instruction(
         instruction_id: u64,
         affirms_pending: u64,
@@ -1622,6 +2240,137 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+    /// Returns the details of `instruction_id` along with the status of its legs
+    /// and the portfolios that still have to affirm it.
+    pub fn get_instruction_report(
+        instruction_id: u64,
+    ) -> Option<InstructionReport<T::AccountId, T::Moment, T::BlockNumber, T::Balance>> {
+        let instruction = Self::instruction_details(instruction_id);
+        if instruction.status == InstructionStatus::Unknown {
+            return None;
+        }
+        let mut legs = <InstructionLegs<T>>::iter_prefix(instruction_id)
+            .map(|(leg_id, leg)| LegDetails {
+                leg_id,
+                leg,
+                status: Self::instruction_leg_status(instruction_id, leg_id),
+            })
+            .collect::<Vec<_>>();
+        legs.sort_by_key(|leg| leg.leg_id);
+        Some(InstructionReport {
+            missing_affirmations: Self::missing_affirmations(instruction_id),
+            expiry: Self::instruction_expiry(instruction_id),
+            instruction,
+            legs,
+        })
+    }
+
+    /// Returns the instructions awaiting execution that involve `portfolio`,
+    /// together with the affirmation status of `portfolio` for each of them.
+    pub fn pending_instructions_for_portfolio(
+        portfolio: PortfolioId,
+    ) -> Vec<(u64, AffirmationStatus)> {
+        let mut pending = UserAffirmations::iter_prefix(portfolio)
+            .filter(|(instruction_id, _)| {
+                matches!(
+                    Self::instruction_details(instruction_id).status,
+                    InstructionStatus::Pending | InstructionStatus::Failed
+                )
+            })
+            .collect::<Vec<_>>();
+        pending.sort_by_key(|(instruction_id, _)| *instruction_id);
+        pending
+    }
+
+    /// Simulates the execution of `instruction_id` without changing any state.
+    ///
+    /// Every leg that would be executed is checked in execution order, applying
+    /// the transfers of the previous legs, so the first failing leg can be reported.
+    pub fn dry_run_instruction(instruction_id: u64) -> InstructionExecutionReport {
+        let details = Self::instruction_details(instruction_id);
+        let missing_affirmations = Self::missing_affirmations(instruction_id);
+        let mut legs = <InstructionLegs<T>>::iter_prefix(instruction_id).collect::<Vec<_>>();
+        legs.sort_by_key(|leg| leg.0);
+        let unauthorized_venue = legs
+            .iter()
+            .map(|(_, leg)| leg.asset)
+            .collect::<BTreeSet<_>>()
+            .iter()
+            .any(|ticker| {
+                Self::venue_filtering(ticker) && !Self::venue_allow_list(ticker, details.venue_id)
+            });
+
+        let mut leg_results = Vec::with_capacity(legs.len());
+        let mut failed_leg = None;
+        let senders = legs.iter().map(|(_, leg)| &leg.from);
+        let _ = <Portfolio<T>>::with_custodian_affirmation(senders, || {
+            with_transaction(|| -> DispatchResult {
+                Self::unchecked_release_locks(instruction_id, &legs);
+                for (leg_id, leg) in legs.iter().filter(|(leg_id, _)| {
+                    !matches!(
+                        Self::instruction_leg_status(instruction_id, leg_id),
+                        LegStatus::ExecutionToBeSkipped(..)
+                    )
+                }) {
+                    let result = <Asset<T>>::unsafe_can_transfer_granular(
+                        PortfolioCustodian::get(&leg.from),
+                        leg.from,
+                        PortfolioCustodian::get(&leg.to),
+                        leg.to,
+                        &leg.asset,
+                        leg.amount,
+                    );
+                    // Apply the transfer, so that the following legs are checked against the updated balances.
+                    let failed = !result.result
+                        || <Asset<T>>::base_transfer(leg.from, leg.to, &leg.asset, leg.amount)
+                            .is_err();
+                    if failed && failed_leg.is_none() {
+                        failed_leg = Some(*leg_id);
+                    }
+                    leg_results.push((*leg_id, result));
+                }
+                // Discard every change made by the simulation.
+                Err(Error::<T>::InstructionFailed.into())
+            })
+        });
+
+        InstructionExecutionReport {
+            result: details.status == InstructionStatus::Pending
+                && missing_affirmations.is_empty()
+                && !unauthorized_venue
+                && failed_leg.is_none(),
+            status: details.status,
+            missing_affirmations,
+            unauthorized_venue,
+            legs: leg_results,
+            failed_leg,
+        }
+    }
+
+    /// Returns the portfolios that have not affirmed `instruction_id` yet.
+    fn missing_affirmations(instruction_id: u64) -> Vec<PortfolioId> {
+        let mut portfolios = <InstructionLegs<T>>::iter_prefix_values(instruction_id)
+            .flat_map(|leg| vec![leg.from, leg.to])
+            .collect::<BTreeSet<_>>();
+        portfolios.retain(|portfolio| {
+            Self::user_affirmations(portfolio, instruction_id) == AffirmationStatus::Pending
+        });
+        portfolios.into_iter().collect()
+    }
+
+    /// Ensures that the policies of the receiving portfolios allow the legs of `instruction_id`
+    /// involving `portfolios`, so that affirmations fail early rather than at execution.
+    ///
+    /// NB - Call it after `filtered_legs`, so that affirmations of too many legs fail before this check.
+    fn ensure_policies_allow_legs(
+        instruction_id: u64,
+        portfolios: &BTreeSet<PortfolioId>,
+    ) -> DispatchResult {
+        <InstructionLegs<T>>::iter_prefix(instruction_id)
+            .filter(|(_, leg)| portfolios.contains(&leg.from) || portfolios.contains(&leg.to))
+            .try_for_each(|(_, leg)| <Portfolio<T>>::ensure_receivable(&leg.to, &leg.asset))
+    }
+
     /// Returns total number of legs of an `instruction_id` and vector of legs where sender is in the `portfolios` set.
     /// Also, ensures that the number of filtered legs is under the limit.
     fn filtered_legs(
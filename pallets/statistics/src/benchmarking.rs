@@ -101,13 +101,16 @@ benchmarks! {
         InvestorCountPerAsset::insert(ticker, 1337);
     }: {
         // This will trigger the worse case (exemption)
+        let party = |balance: u32| TransferParty {
+            did: owner_did,
+            scope_id: owner_did,
+            balance: balance.into(),
+        };
         Module::<T>::verify_tm_restrictions(
             &ticker,
-            owner_did,
-            owner_did,
+            party(200),
+            party(0),
             100u32.into(),
-            200u32.into(),
-            0u32.into(),
             500u32.into(),
         ).unwrap();
     }
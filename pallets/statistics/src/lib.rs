@@ -22,12 +22,32 @@ use frame_support::{
 };
 pub use polymesh_common_utilities::traits::statistics::{Config, Event, WeightInfo};
 use polymesh_primitives::{
-    statistics::{Counter, Percentage, TransferManager, TransferManagerResult},
-    ScopeId, Ticker,
+    calendar::CalendarPeriod,
+    statistics::{
+        Counter, Percentage, StatType, TransferManager, TransferManagerResult, VolumeWindow,
+    },
+    Balance, Claim, ClaimType, IdentityId, Moment, ScopeId, Ticker,
 };
+use sp_runtime::traits::{CheckedAdd, SaturatedConversion, Saturating};
 use sp_std::vec::Vec;
 
+/// Maximum number of calendar units in the period of a volume transfer manager,
+/// which bounds the number of buckets tracking the volume within its rolling window.
+pub const MAX_VOLUME_PERIOD_UNITS: u64 = 100;
+
 type ExternalAgents<T> = pallet_external_agents::Module<T>;
+type Identity<T> = pallet_identity::Module<T>;
+
+/// An investor taking part in a transfer, as seen by the transfer managers.
+#[derive(Clone, Copy, Debug)]
+pub struct TransferParty<Balance> {
+    /// The identity sending or receiving the tokens.
+    pub did: IdentityId,
+    /// The scope of `did` for the asset.
+    pub scope_id: ScopeId,
+    /// The aggregate balance of `scope_id`.
+    pub balance: Balance,
+}
 
 decl_storage! {
     trait Store for Module<T: Config> as statistics {
@@ -44,6 +64,33 @@ decl_storage! {
                 hasher(blake2_128_concat) ScopeId
             =>
                 bool;
+        /// Holder statistics tracked for an Asset.
+        /// A statistic is tracked while some transfer manager limits it,
+        /// and for as long as it cannot be dropped without losing count of an investor.
+        pub TrackedStats get(fn tracked_stats): map hasher(blake2_128_concat) Ticker => Vec<StatType>;
+        /// Number of current investors in an asset that are counted in a tracked statistic.
+        pub InvestorCountPerStat get(fn stat_investor_count):
+            double_map hasher(blake2_128_concat) Ticker, hasher(blake2_128_concat) StatType => Counter;
+        /// Investors counted in a tracked statistic of an asset.
+        /// An investor is counted when they become one and stays counted until they stop being one,
+        /// even if their claims change in between.
+        pub StatInvestors get(fn stat_investor):
+            double_map hasher(blake2_128_concat) (Ticker, StatType), hasher(identity) ScopeId => bool;
+        /// When an investor started holding an asset, through a transfer or an issuance.
+        /// It is kept while the investor's balance stays above zero, so later acquisitions don't reset it.
+        /// It is only recorded while the asset has an active HPTM.
+        pub AcquiredAt get(fn acquired_at):
+            double_map hasher(blake2_128_concat) Ticker, hasher(identity) ScopeId => Option<Moment>;
+        /// Volume sent by an investor of an asset within the rolling window of a period.
+        /// It is only recorded while the asset has an active IVTM for that period.
+        pub InvestorVolume get(fn investor_volume):
+            double_map hasher(blake2_128_concat) (Ticker, CalendarPeriod), hasher(identity) ScopeId
+                => VolumeWindow<T::Balance>;
+        /// Volume of an asset transferred within the rolling window of a period.
+        /// It is only recorded while the asset has an active AVTM for that period.
+        pub AssetVolume get(fn asset_volume):
+            double_map hasher(blake2_128_concat) Ticker, hasher(blake2_128_concat) CalendarPeriod
+                => VolumeWindow<T::Balance>;
     }
 }
 
@@ -58,6 +105,12 @@ decl_module! {
 
         /// Adds a new transfer manager.
         ///
+        /// A transfer manager limiting a holder statistic, i.e., a JTM or CCTM,
+        /// must be added before the asset is distributed to more than one investor,
+        /// as the investors already holding the asset cannot be counted in the statistic retroactively.
+        /// The statistic stays tracked when the transfer manager is removed afterwards,
+        /// so that it can be added again later.
+        ///
         /// # Arguments
         /// * `origin` It contains the secondary key of the caller (i.e who signed the transaction to execute this function).
         /// * `ticker` ticker for which the transfer managers are being updated.
@@ -67,6 +120,11 @@ decl_module! {
         /// * `Unauthorized` if `origin` is not the owner of the ticker.
         /// * `DuplicateTransferManager` if `new_transfer_manager` is already enabled for the ticker.
         /// * `TransferManagersLimitReached` if the `ticker` already has max TMs attached
+        /// * `AssetAlreadyDistributed` if `new_transfer_manager` limits a holder statistic
+        ///    that is not tracked yet and the asset has more than one investor.
+        /// * `NonRecurringVolumePeriod` if `new_transfer_manager` limits volume over a non-recurring period.
+        /// * `VolumePeriodTooLong` if `new_transfer_manager` limits volume over a period
+        ///    of more than `MAX_VOLUME_PERIOD_UNITS` units.
         ///
         /// # Permissions
         /// * Asset
@@ -76,6 +134,13 @@ decl_module! {
             ActiveTransferManagers::try_mutate(&ticker, |transfer_managers| {
                 ensure!((transfer_managers.len() as u32) < T::MaxTransferManagersPerAsset::get(), Error::<T>::TransferManagersLimitReached);
                 ensure!(!transfer_managers.contains(&new_transfer_manager), Error::<T>::DuplicateTransferManager);
+                if let Some(period) = Self::volume_period(&new_transfer_manager) {
+                    ensure!(period.to_recurring().is_some(), Error::<T>::NonRecurringVolumePeriod);
+                    ensure!(period.amount <= MAX_VOLUME_PERIOD_UNITS, Error::<T>::VolumePeriodTooLong);
+                }
+                if let Some(stat) = new_transfer_manager.stat_type() {
+                    Self::track_stat(&ticker, stat, transfer_managers)?;
+                }
                 transfer_managers.push(new_transfer_manager.clone());
                 Ok(()) as DispatchResult
             })?;
@@ -104,6 +169,13 @@ decl_module! {
                 ensure!(before != transfer_managers.len(), Error::<T>::TransferManagerMissing);
                 Ok(()) as DispatchResult
             })?;
+            if let Some(stat) = transfer_manager.stat_type() {
+                // Stop tracking the statistic if nothing limits it and no investor can be lost.
+                if Self::investor_count(&ticker) <= 1 && !Self::is_stat_limited(&ticker, stat) {
+                    TrackedStats::mutate(&ticker, |stats| stats.retain(|s| *s != stat));
+                    Self::untrack_stat(&ticker, stat);
+                }
+            }
             Self::deposit_event(Event::TransferManagerRemoved(did, ticker, transfer_manager));
         }
 
@@ -159,106 +231,200 @@ impl<T: Config> Module<T> {
     /// It updates our statistics after transfer execution.
     /// The following counters could be updated:
     ///     - *Investor count per asset*.
+    ///     - *Investor count per tracked holder statistic*.
+    ///     - *Acquisition time of a receiver starting to hold the asset*, if an HPTM is active.
+    ///     - *Volume sent by the sender and of the asset*, if an IVTM or AVTM is active.
     ///
+    /// The balances of `from` and `to` are their aggregate balances after the transfer.
+    /// Mints have no `from` and redemptions have no `to`.
     pub fn update_transfer_stats(
         ticker: &Ticker,
-        updated_from_balance: Option<T::Balance>,
-        updated_to_balance: Option<T::Balance>,
+        from: Option<TransferParty<T::Balance>>,
+        to: Option<TransferParty<T::Balance>>,
         amount: T::Balance,
     ) {
+        if amount == 0u128.into() {
+            return;
+        }
+
         // 1. Unique investor count per asset.
-        if amount != 0u128.into() {
-            let counter = Self::investor_count(ticker);
-            let mut new_counter = counter;
+        let counter = Self::investor_count(ticker);
+        let mut new_counter = counter;
 
-            if let Some(from_balance) = updated_from_balance {
-                if from_balance == 0u128.into() {
-                    new_counter = new_counter.checked_sub(1).unwrap_or(new_counter);
+        let leaving = from.filter(|from| from.balance == 0u128.into());
+        let joining = to.filter(|to| to.balance == amount);
+
+        if leaving.is_some() {
+            new_counter = new_counter.checked_sub(1).unwrap_or(new_counter);
+        }
+        if joining.is_some() {
+            new_counter = new_counter.checked_add(1).unwrap_or(new_counter);
+        }
+
+        // Only updates extrinsics if counter has been changed.
+        if new_counter != counter {
+            InvestorCountPerAsset::insert(ticker, new_counter)
+        }
+
+        // 2. Investor count per tracked holder statistic.
+        let stats = Self::tracked_stats(ticker);
+        if let Some(from) = leaving {
+            for stat in &stats {
+                if StatInvestors::take((*ticker, *stat), from.scope_id) {
+                    InvestorCountPerStat::mutate(ticker, stat, |count| {
+                        *count = count.saturating_sub(1)
+                    });
                 }
             }
+            AcquiredAt::remove(ticker, from.scope_id);
+        }
+        let tms = Self::transfer_managers(ticker);
+        if let Some(to) = joining {
+            for stat in stats.into_iter().filter(|s| Self::is_in_stat(to.did, *s)) {
+                StatInvestors::insert((*ticker, stat), to.scope_id, true);
+                InvestorCountPerStat::mutate(ticker, stat, |count| {
+                    *count = count.saturating_add(1)
+                });
+            }
 
-            if let Some(to_balance) = updated_to_balance {
-                if to_balance == amount {
-                    new_counter = new_counter.checked_add(1).unwrap_or(new_counter);
-                }
+            // 3. Acquisition time, for holding periods, as of when the receiver started holding the asset.
+            let has_hptm = tms
+                .iter()
+                .any(|tm| matches!(tm, TransferManager::HoldingPeriodTransferManager(..)));
+            if has_hptm {
+                AcquiredAt::insert(ticker, to.scope_id, Self::now());
             }
+        }
 
-            // Only updates extrinsics if counter has been changed.
-            if new_counter != counter {
-                InvestorCountPerAsset::insert(ticker, new_counter)
+        if let (Some(from), Some(_)) = (from, to) {
+            // 4. Volume per investor and per asset, once per limited period.
+            let mut periods = tms
+                .iter()
+                .filter_map(|tm| {
+                    let per_investor =
+                        matches!(tm, TransferManager::InvestorVolumeTransferManager(..));
+                    Some((per_investor, Self::volume_period(tm)?))
+                })
+                .collect::<Vec<_>>();
+            periods.sort();
+            periods.dedup();
+            for (per_investor, period) in periods {
+                let (current, oldest) = match Self::window_units(period) {
+                    Some(units) => units,
+                    None => continue,
+                };
+                let add = |window: &mut VolumeWindow<T::Balance>| {
+                    window.buckets.retain(|(unit, _)| *unit >= oldest);
+                    match window.buckets.last_mut() {
+                        Some((unit, volume)) if *unit == current => {
+                            *volume = volume.saturating_add(amount)
+                        }
+                        _ => window.buckets.push((current, amount)),
+                    }
+                };
+                if per_investor {
+                    InvestorVolume::<T>::mutate((*ticker, period), from.scope_id, add);
+                } else {
+                    AssetVolume::<T>::mutate(ticker, period, add);
+                }
             }
         }
     }
 
-    /// Verify transfer restrictions for a transfer
+    /// Verify transfer restrictions for a transfer.
+    ///
+    /// The balances of `sender` and `receiver` are their aggregate balances before the transfer.
     pub fn verify_tm_restrictions(
         ticker: &Ticker,
-        sender: ScopeId,
-        receiver: ScopeId,
+        sender: TransferParty<T::Balance>,
+        receiver: TransferParty<T::Balance>,
         value: T::Balance,
-        sender_balance: T::Balance,
-        receiver_balance: T::Balance,
         total_supply: T::Balance,
     ) -> DispatchResult {
         Self::transfer_managers(ticker)
             .into_iter()
-            .try_for_each(|tm| match tm {
-                TransferManager::CountTransferManager(max_count) => Self::ensure_ctm(
-                    ticker,
-                    sender,
-                    value,
-                    sender_balance,
-                    receiver_balance,
-                    max_count,
-                ),
-                TransferManager::PercentageTransferManager(max_percentage) => Self::ensure_ptm(
-                    ticker,
-                    receiver,
-                    value,
-                    receiver_balance,
-                    total_supply,
-                    max_percentage,
-                ),
-            })
+            .try_for_each(|tm| Self::verify_tm(ticker, tm, sender, receiver, value, total_supply))
     }
 
+    /// Verify each transfer restriction for a transfer, reporting the result of each one.
+    ///
+    /// The balances of `sender` and `receiver` are their aggregate balances before the transfer.
     pub fn verify_tm_restrictions_granular(
         ticker: &Ticker,
-        sender: ScopeId,
-        receiver: ScopeId,
+        sender: TransferParty<T::Balance>,
+        receiver: TransferParty<T::Balance>,
         value: T::Balance,
-        sender_balance: T::Balance,
-        receiver_balance: T::Balance,
         total_supply: T::Balance,
     ) -> Vec<TransferManagerResult> {
         Self::transfer_managers(ticker)
             .into_iter()
             .map(|tm| TransferManagerResult {
-                result: match &tm {
-                    TransferManager::CountTransferManager(max_count) => Self::ensure_ctm(
-                        ticker,
-                        sender,
-                        value,
-                        sender_balance,
-                        receiver_balance,
-                        *max_count,
-                    )
+                result: Self::verify_tm(ticker, tm.clone(), sender, receiver, value, total_supply)
                     .is_ok(),
-                    TransferManager::PercentageTransferManager(max_percentage) => Self::ensure_ptm(
-                        ticker,
-                        receiver,
-                        value,
-                        receiver_balance,
-                        total_supply,
-                        max_percentage.clone(),
-                    )
-                    .is_ok(),
-                },
                 tm,
             })
             .collect()
     }
 
+    fn verify_tm(
+        ticker: &Ticker,
+        tm: TransferManager,
+        sender: TransferParty<T::Balance>,
+        receiver: TransferParty<T::Balance>,
+        value: T::Balance,
+        total_supply: T::Balance,
+    ) -> DispatchResult {
+        match tm {
+            TransferManager::CountTransferManager(max_count) => Self::ensure_ctm(
+                ticker,
+                sender.scope_id,
+                value,
+                sender.balance,
+                receiver.balance,
+                max_count,
+            ),
+            TransferManager::PercentageTransferManager(max_percentage) => Self::ensure_ptm(
+                ticker,
+                receiver.scope_id,
+                value,
+                receiver.balance,
+                total_supply,
+                max_percentage,
+            ),
+            TransferManager::JurisdictionTransferManager(.., max_count)
+            | TransferManager::ClaimCountTransferManager(.., max_count) => {
+                Self::ensure_stat_tm(ticker, tm, sender, receiver, value, max_count)
+            }
+            TransferManager::HoldingPeriodTransferManager(period) => {
+                Self::ensure_hptm(ticker, sender.scope_id, period)
+            }
+            TransferManager::InvestorVolumeTransferManager(period, max_volume) => {
+                let volume = <InvestorVolume<T>>::get((*ticker, period), sender.scope_id);
+                Self::ensure_vtm(
+                    ticker,
+                    tm,
+                    sender.scope_id,
+                    value,
+                    volume,
+                    period,
+                    max_volume,
+                )
+            }
+            TransferManager::AssetVolumeTransferManager(period, max_volume) => {
+                let volume = <AssetVolume<T>>::get(ticker, period);
+                Self::ensure_vtm(
+                    ticker,
+                    tm,
+                    sender.scope_id,
+                    value,
+                    volume,
+                    period,
+                    max_volume,
+                )
+            }
+        }
+    }
+
     fn ensure_ctm(
         ticker: &Ticker,
         sender: ScopeId,
@@ -307,6 +473,158 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+    fn ensure_stat_tm(
+        ticker: &Ticker,
+        tm: TransferManager,
+        sender: TransferParty<T::Balance>,
+        receiver: TransferParty<T::Balance>,
+        value: T::Balance,
+        max_count: Counter,
+    ) -> DispatchResult {
+        let stat = match tm.stat_type() {
+            Some(stat) => stat,
+            None => return Ok(()),
+        };
+        // The count is unchanged if the sender leaves the statistic as the receiver joins it.
+        let replaces_sender =
+            || sender.balance == value && Self::stat_investor((*ticker, stat), sender.scope_id);
+        ensure!(
+            receiver.balance > 0u32.into()
+                || Self::stat_investor_count(ticker, stat) < max_count
+                || replaces_sender()
+                || !Self::is_in_stat(receiver.did, stat)
+                || Self::entity_exempt((*ticker, tm), sender.scope_id),
+            Error::<T>::InvalidTransfer
+        );
+        Ok(())
+    }
+
+    fn ensure_hptm(ticker: &Ticker, sender: ScopeId, period: Moment) -> DispatchResult {
+        let now = Self::now();
+        ensure!(
+            Self::acquired_at(ticker, sender).map_or(true, |at| at.saturating_add(period) <= now)
+                || Self::entity_exempt(
+                    (
+                        *ticker,
+                        TransferManager::HoldingPeriodTransferManager(period)
+                    ),
+                    sender
+                ),
+            Error::<T>::InvalidTransfer
+        );
+        Ok(())
+    }
+
+    fn ensure_vtm(
+        ticker: &Ticker,
+        tm: TransferManager,
+        sender: ScopeId,
+        value: T::Balance,
+        window: VolumeWindow<T::Balance>,
+        period: CalendarPeriod,
+        max_volume: Balance,
+    ) -> DispatchResult {
+        let volume = Self::window_units(period).map_or(0u32.into(), |(_, oldest)| {
+            Self::volume_in_window(&window, oldest)
+        });
+        ensure!(
+            volume
+                .checked_add(&value)
+                .map_or(false, |volume| volume <= max_volume.into())
+                || Self::entity_exempt((*ticker, tm), sender),
+            Error::<T>::InvalidTransfer
+        );
+        Ok(())
+    }
+
+    /// Returns the period over which `tm` limits volume, if any.
+    fn volume_period(tm: &TransferManager) -> Option<CalendarPeriod> {
+        match tm {
+            TransferManager::InvestorVolumeTransferManager(period, _)
+            | TransferManager::AssetVolumeTransferManager(period, _) => Some(*period),
+            _ => None,
+        }
+    }
+
+    /// Returns the indices of the current unit of `period` and of the oldest unit in its rolling window.
+    /// Units are aligned to the Unix epoch, e.g., days start at midnight UTC
+    /// and months on the first day of each month.
+    fn window_units(period: CalendarPeriod) -> Option<(u64, u64)> {
+        let current = period.unit.index_at(Self::now())?;
+        let oldest = current.saturating_sub(period.amount.saturating_sub(1));
+        Some((current, oldest))
+    }
+
+    /// Returns the volume of the buckets of `window` from the unit `oldest` onwards.
+    fn volume_in_window(window: &VolumeWindow<T::Balance>, oldest: u64) -> T::Balance {
+        window
+            .buckets
+            .iter()
+            .filter(|(unit, _)| *unit >= oldest)
+            .fold(0u32.into(), |total: T::Balance, (_, volume)| {
+                total.saturating_add(*volume)
+            })
+    }
+
+    fn now() -> Moment {
+        <pallet_timestamp::Module<T>>::get().saturated_into::<Moment>()
+    }
+
+    /// Returns whether an investor with identity `did` should be counted in `stat`.
+    fn is_in_stat(did: IdentityId, stat: StatType) -> bool {
+        match stat {
+            StatType::Jurisdiction(issuer, cc) => {
+                Identity::<T>::fetch_claims_by_issuer(did, ClaimType::Jurisdiction, issuer).any(
+                    |id_claim| matches!(id_claim.claim, Claim::Jurisdiction(code, _) if code == cc),
+                )
+            }
+            StatType::Claim(issuer, claim_type, has_claim) => {
+                Identity::<T>::fetch_claims_by_issuer(did, claim_type, issuer)
+                    .next()
+                    .is_some()
+                    == has_claim
+            }
+        }
+    }
+
+    /// Returns whether some active transfer manager of `ticker` limits `stat`.
+    fn is_stat_limited(ticker: &Ticker, stat: StatType) -> bool {
+        Self::transfer_managers(ticker)
+            .iter()
+            .any(|tm| tm.stat_type() == Some(stat))
+    }
+
+    /// Starts tracking `stat` for `ticker`, unless it is tracked already.
+    /// As the current investors cannot be counted, this is only allowed before distribution.
+    /// Statistics that `active` transfer managers no longer limit are dropped at the same time.
+    fn track_stat(ticker: &Ticker, stat: StatType, active: &[TransferManager]) -> DispatchResult {
+        let mut stats = Self::tracked_stats(ticker);
+        if stats.contains(&stat) {
+            return Ok(());
+        }
+        ensure!(
+            Self::investor_count(ticker) <= 1,
+            Error::<T>::AssetAlreadyDistributed
+        );
+
+        let (kept, dropped) = stats
+            .drain(..)
+            .partition::<Vec<_>, _>(|s| active.iter().any(|tm| tm.stat_type() == Some(*s)));
+        for s in dropped {
+            Self::untrack_stat(ticker, s);
+        }
+        stats = kept;
+        stats.push(stat);
+        TrackedStats::insert(ticker, stats);
+        Ok(())
+    }
+
+    /// Clears the counters of `stat` for `ticker`.
+    fn untrack_stat(ticker: &Ticker, stat: StatType) {
+        InvestorCountPerStat::remove(ticker, stat);
+        StatInvestors::remove_prefix((*ticker, stat));
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     pub fn set_investor_count(ticker: &Ticker, count: Counter) {
         InvestorCountPerAsset::insert(ticker, count);
@@ -323,6 +641,13 @@ decl_error! {
         /// Transfer not allowed
         InvalidTransfer,
         /// The limit of transfer managers allowed for an asset has been reached
-        TransferManagersLimitReached
+        TransferManagersLimitReached,
+        /// The holder statistic limited by the transfer manager can only start being tracked
+        /// while the asset has at most one investor.
+        AssetAlreadyDistributed,
+        /// Volume can only be limited over recurring periods.
+        NonRecurringVolumePeriod,
+        /// Volume can only be limited over periods of at most `MAX_VOLUME_PERIOD_UNITS` units.
+        VolumePeriodTooLong,
     }
 }
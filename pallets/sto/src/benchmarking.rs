@@ -10,10 +10,13 @@ use polymesh_common_utilities::{
     benchs::{make_asset, AccountIdOf, User, UserBuilder},
     TestUtilsFn,
 };
-use polymesh_primitives::TrustedIssuer;
+use polymesh_primitives::{Scope, TrustedIssuer};
 
 const OFFERING_TICKER: Ticker = Ticker::repeating(b'A');
 const RAISE_TICKER: Ticker = Ticker::repeating(b'B');
+const MAX_BIDS: u32 = 100;
+const MAX_ALLOWED_INVESTORS: u32 = 100;
+const MAX_CONTRIBUTIONS: u32 = 100;
 
 pub type Asset<T> = pallet_asset::Module<T>;
 pub type ComplianceManager<T> = pallet_compliance_manager::Module<T>;
@@ -126,6 +129,103 @@ fn setup_fundraiser<T: Config + TestUtilsFn<AccountIdOf<T>>>(
     Ok((alice, bob))
 }
 
+fn setup_book_building_fundraiser<T: Config + TestUtilsFn<AccountIdOf<T>>>(
+    kind: FundraiserKind,
+    bids: u32,
+) -> Result<(UserWithPortfolio<T>, UserWithPortfolio<T>), DispatchError> {
+    let alice = user::<T>("alice");
+    let bob = user::<T>("bob");
+
+    create_assets_and_compliance::<T>(&alice.user, &bob.user, OFFERING_TICKER, RAISE_TICKER, 0, 0)
+        .unwrap();
+
+    let venue_id = create_venue(&alice.user).unwrap();
+
+    <Sto<T>>::create_fundraiser_with_kind(
+        alice.user.origin().into(),
+        alice.portfolio,
+        OFFERING_TICKER,
+        alice.portfolio,
+        RAISE_TICKER,
+        vec![PriceTier {
+            total: 1_000_000u32.into(),
+            price: 1_000_000u32.into(),
+        }],
+        venue_id,
+        None,
+        Some(101u32.into()),
+        0u32.into(),
+        vec![].into(),
+        kind,
+    )
+    .unwrap();
+
+    for i in 0..bids {
+        <Sto<T>>::submit_bid(
+            bob.user.origin().into(),
+            bob.portfolio,
+            bob.portfolio,
+            OFFERING_TICKER,
+            0,
+            1_000u32.into(),
+            (1_000_000u32 + i).into(),
+        )
+        .unwrap();
+    }
+
+    Ok((alice, bob))
+}
+
+fn setup_escrowed_fundraiser<T: Config + TestUtilsFn<AccountIdOf<T>>>(
+    soft_cap: u32,
+    contributions: u32,
+) -> Result<(UserWithPortfolio<T>, UserWithPortfolio<T>), DispatchError> {
+    let alice = user::<T>("alice");
+    let bob = user::<T>("bob");
+
+    create_assets_and_compliance::<T>(&alice.user, &bob.user, OFFERING_TICKER, RAISE_TICKER, 0, 0)
+        .unwrap();
+
+    let venue_id = create_venue(&alice.user).unwrap();
+
+    <Sto<T>>::create_escrowed_fundraiser(
+        alice.user.origin().into(),
+        alice.portfolio,
+        OFFERING_TICKER,
+        alice.portfolio,
+        RAISE_TICKER,
+        vec![PriceTier {
+            total: 1_000_000u32.into(),
+            price: 1_000_000u32.into(),
+        }],
+        venue_id,
+        None,
+        Some(101u32.into()),
+        0u32.into(),
+        vec![].into(),
+        soft_cap.into(),
+    )
+    .unwrap();
+
+    for _ in 0..contributions {
+        <Sto<T>>::invest(
+            bob.user.origin().into(),
+            bob.portfolio,
+            bob.portfolio,
+            OFFERING_TICKER,
+            0,
+            1_000u32.into(),
+            None,
+            None,
+        )
+        .unwrap();
+    }
+
+    <Sto<T>>::stop(alice.user.origin().into(), OFFERING_TICKER, 0).unwrap();
+
+    Ok((alice, bob))
+}
+
 fn user<T: Config + TestUtilsFn<AccountIdOf<T>>>(name: &'static str) -> UserWithPortfolio<T> {
     let user = <UserBuilder<T>>::default().generate_did().build(name);
     let portfolio = PortfolioId::default_portfolio(user.did());
@@ -210,4 +310,94 @@ benchmarks! {
     verify {
         assert!(<Fundraisers<T>>::get(OFFERING_TICKER, 0).unwrap().is_closed(), "stop");
     }
+
+    submit_bid {
+        let (_, bob) = setup_book_building_fundraiser::<T>(FundraiserKind::DutchAuction, 0).unwrap();
+    }: _(bob.user.origin(), bob.portfolio, bob.portfolio, OFFERING_TICKER, 0, 1_000u32.into(), 1_000_000u32.into())
+    verify {
+        assert!(<Bids<T>>::get((OFFERING_TICKER, 0), 0).is_some(), "submit_bid");
+    }
+
+    cancel_bid {
+        let (_, bob) = setup_book_building_fundraiser::<T>(FundraiserKind::DutchAuction, 1).unwrap();
+    }: _(bob.user.origin(), OFFERING_TICKER, 0, 0)
+    verify {
+        assert!(<Bids<T>>::get((OFFERING_TICKER, 0), 0).is_none(), "cancel_bid");
+    }
+
+    close_and_allocate {
+        // Number of bids
+        let b in 1 .. MAX_BIDS;
+
+        let (alice, bob) = setup_book_building_fundraiser::<T>(FundraiserKind::DutchAuction, b).unwrap();
+        <Timestamp<T>>::set_timestamp(101u32.into());
+    }: _(alice.user.origin(), OFFERING_TICKER, 0, b)
+    verify {
+        assert!(<Asset<T>>::balance_of(&OFFERING_TICKER, bob.user.did()) > 0u32.into(), "close_and_allocate");
+    }
+
+    set_investor_restrictions {
+        // Number of allowed claims
+        let c in 0 .. MAX_ALLOWED_CLAIMS as u32;
+
+        let (alice, _) = setup_fundraiser::<T>(0, 1, 0).unwrap();
+        let restrictions = InvestorRestrictions {
+            max_investment: Some(1_000u32.into()),
+            allowlist: true,
+            allowed_claims: (0..c)
+                .map(|_| (Claim::Accredited(Scope::Ticker(OFFERING_TICKER)), alice.user.did()))
+                .collect(),
+        };
+    }: _(alice.user.origin(), OFFERING_TICKER, 0, restrictions.clone())
+    verify {
+        assert!(<InvestorRestrictionsOf<T>>::get(OFFERING_TICKER, 0) == restrictions, "set_investor_restrictions");
+    }
+
+    add_allowed_investors {
+        // Number of investors
+        let i in 1 .. MAX_ALLOWED_INVESTORS;
+
+        let (alice, _) = setup_fundraiser::<T>(0, 1, 0).unwrap();
+        let investors = (0..i).map(|i| IdentityId::from(i as u128)).collect::<Vec<_>>();
+    }: _(alice.user.origin(), OFFERING_TICKER, 0, investors)
+    verify {
+        assert!(AllowedInvestors::get((OFFERING_TICKER, 0), IdentityId::from(0u128)), "add_allowed_investors");
+    }
+
+    remove_allowed_investors {
+        // Number of investors
+        let i in 1 .. MAX_ALLOWED_INVESTORS;
+
+        let (alice, _) = setup_fundraiser::<T>(0, 1, 0).unwrap();
+        let investors = (0..i).map(|i| IdentityId::from(i as u128)).collect::<Vec<_>>();
+        <Sto<T>>::add_allowed_investors(alice.user.origin().into(), OFFERING_TICKER, 0, investors.clone()).unwrap();
+    }: _(alice.user.origin(), OFFERING_TICKER, 0, investors)
+    verify {
+        assert!(!AllowedInvestors::get((OFFERING_TICKER, 0), IdentityId::from(0u128)), "remove_allowed_investors");
+    }
+
+    release {
+        // Number of escrowed investments
+        let c in 1 .. MAX_CONTRIBUTIONS;
+
+        let (alice, bob) = setup_escrowed_fundraiser::<T>(1, c).unwrap();
+    }: _(alice.user.origin(), OFFERING_TICKER, 0, c)
+    verify {
+        assert!(<Asset<T>>::balance_of(&OFFERING_TICKER, bob.user.did()) > 0u32.into(), "release");
+    }
+
+    claim_refund {
+        let (_, bob) = setup_escrowed_fundraiser::<T>(1_000_000, 1).unwrap();
+    }: _(bob.user.origin(), OFFERING_TICKER, 0, 0)
+    verify {
+        assert!(<Contributions<T>>::get((OFFERING_TICKER, 0), 0).is_none(), "claim_refund");
+    }
+
+    set_fundraiser_vesting {
+        let (alice, _) = setup_fundraiser::<T>(0, 1, 0).unwrap();
+        let vesting = Some(VestingKind::Linear { start: 0, end: 1_000 });
+    }: _(alice.user.origin(), OFFERING_TICKER, 0, vesting)
+    verify {
+        assert!(FundraiserVesting::get(OFFERING_TICKER, 0) == vesting, "set_fundraiser_vesting");
+    }
 }
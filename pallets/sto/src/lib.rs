@@ -11,10 +11,45 @@
 //! Fundraisers allow a single payment asset, known as the raising asset.
 //! Investors can invest through on-chain balance or off-chain receipts.
 //!
+//! Besides first-come first-served fixed price fundraisers, book-building fundraisers
+//! collect bids during their window, locking the raising funds of each bid.
+//! When closed, they allocate the offering either at a uniform clearing price (Dutch auction)
+//! or pro-rata when oversubscribed, and settle each allocation in its own instruction,
+//! unlocking the funds of the bids whose allocation fails to settle.
+//! Bids can be cancelled if the fundraiser isn't closed within `BOOK_CLOSING_PERIOD` of the end of bidding.
+//!
+//! Fundraisers can optionally cap the amount each investor spends, aggregated by `ScopeId`
+//! so that an investor can't bypass the cap through several identities,
+//! and restrict investments to allowlisted identities or to identities holding given claims.
+//! Investors need a scope claim for the offering asset to invest in a capped fundraiser,
+//! and refunded or cancelled investments no longer count towards the cap.
+//! The remaining allowance of an investor is exposed by the `sto_remainingAllowance` RPC.
+//!
+//! Escrowed fundraisers have a soft cap: investments move the raising funds to an escrow portfolio
+//! of the investor, held by the settlement module, until the fundraiser is stopped or reaches its end.
+//! If the total raised reached the soft cap, each investment is then released in its own settlement,
+//! refunding the investments that fail to settle, otherwise every investor can claim a refund.
+//! A release whose settled investments fall short of the soft cap refunds every investment instead.
+//!
+//! Fundraisers can attach a vesting schedule to the offering tokens bought by investors,
+//! which locks them in the investment portfolio until they are released.
+//!
 //! ## Dispatchable Functions
 //!
 //! - `create_fundraiser` - Create a new fundraiser.
+//! - `create_fundraiser_with_kind` - Create a new fundraiser with the given allocation mode.
+//! - `create_escrowed_fundraiser` - Create a new fundraiser with a soft cap and escrowed investments.
 //! - `invest` - Invest in a fundraiser.
+//! - `invest_with_claims` - Invest in a fundraiser, presenting off-chain claims to compliance.
+//! - `submit_bid` - Bid in a book-building fundraiser.
+//! - `cancel_bid` - Cancel a bid, unlocking its funds.
+//! - `close_and_allocate` - Close a book-building fundraiser and settle its allocations.
+//! - `set_investor_restrictions` - Set the investment cap and allowlist of a fundraiser.
+//! - `add_allowed_investors` - Add identities to the allowlist of a fundraiser.
+//! - `remove_allowed_investors` - Remove identities from the allowlist of a fundraiser.
+//! - `release` - Settle the escrowed investments of a fundraiser that reached its soft cap.
+//! - `claim_refund` - Claim a refund of an escrowed investment in a fundraiser that missed its soft cap.
+//! - `set_fundraiser_vesting` - Set the vesting schedule of the tokens bought in a fundraiser.
 //! - `freeze_fundraiser` - Freeze a fundraiser.
 //! - `unfreeze_fundraiser` - Unfreeze a fundraiser.
 //! - `modify_fundraiser_window` - Modify the time window a fundraiser is active.
@@ -29,26 +64,55 @@ pub mod benchmarking;
 use codec::{Decode, Encode};
 use core::mem;
 use frame_support::{
-    decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::{DispatchError, DispatchResult},
+    ensure,
+    traits::Get,
+    IterableStorageDoubleMap, StorageDoubleMap,
 };
+use pallet_compliance_manager::PresentedClaimsOf;
 use pallet_identity::PermissionedCallOriginData;
 use pallet_settlement::{
     self as settlement, Leg, ReceiptDetails, SettlementType, VenueInfo, VenueType,
 };
 use polymesh_common_utilities::{
+    constants::currency::ONE_UNIT,
     portfolio::PortfolioSubTrait,
     traits::{identity, portfolio},
     with_transaction, CommonConfig,
+    SystematicIssuers::Settlement as SettlementDID,
 };
 use polymesh_primitives_derive::VecU8StrongTyped;
 
 use frame_support::weights::Weight;
-use polymesh_primitives::{EventDid, IdentityId, PortfolioId, Ticker};
+use polymesh_primitives::{
+    Claim, EventDid, IdentityId, PortfolioId, PortfolioName, PortfolioPolicy, ScopeId, Ticker,
+    VestingKind, VestingSchedule,
+};
 use sp_runtime::traits::{CheckedAdd, CheckedDiv, CheckedMul, Saturating};
-use sp_std::{collections::btree_set::BTreeSet, prelude::*};
+use sp_std::{
+    cmp::{min, Reverse},
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    iter,
+    prelude::*,
+};
 
 pub const MAX_TIERS: usize = 10;
+pub const MAX_ALLOWED_CLAIMS: usize = 10;
+
+/// Period, in milliseconds, after the end of the bidding window of a book-building fundraiser
+/// after which bids can be cancelled if the fundraiser still hasn't been closed.
+pub const BOOK_CLOSING_PERIOD: u32 = 7 * 24 * 60 * 60 * 1000;
 
+/// Name of the portfolios holding the escrowed investments of each investor.
+const ESCROW_PORTFOLIO_NAME: &[u8] = b"STO escrow";
+
+/// Prices are entered as a multiple of 1_000_000,
+/// i.e. a price of 1 unit is 1_000_000 and a price of 1.5 units is 1_500_000.
+const PRICE_DIVISOR: u32 = 1_000_000;
+
+type Asset<T> = pallet_asset::Module<T>;
+type ComplianceManager<T> = pallet_compliance_manager::Module<T>;
 type ExternalAgents<T> = pallet_external_agents::Module<T>;
 type Identity<T> = pallet_identity::Module<T>;
 type Portfolio<T> = pallet_portfolio::Module<T>;
@@ -66,6 +130,12 @@ pub enum FundraiserStatus {
     Closed,
     /// Fundraiser has been stopped before expiry.
     ClosedEarly,
+    /// Escrowed fundraiser has been stopped after reaching its soft cap.
+    /// Investments are waiting to be released.
+    PendingRelease,
+    /// Escrowed fundraiser has been stopped without reaching its soft cap.
+    /// Investors can claim refunds.
+    Refunding,
 }
 
 impl Default for FundraiserStatus {
@@ -74,6 +144,73 @@ impl Default for FundraiserStatus {
     }
 }
 
+/// Allocation mode of a Fundraiser.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, PartialOrd, Ord, Debug)]
+pub enum FundraiserKind {
+    /// Investments are filled on `invest`, first-come first-served, at the tier prices.
+    FixedPrice,
+    /// Investors bid a price during the window.
+    /// On close, the highest bids are filled and all of them pay the lowest accepted price.
+    DutchAuction,
+    /// Investors bid at the fixed price during the window.
+    /// On close, all bids are scaled down proportionally if the fundraiser is oversubscribed.
+    ProRata,
+}
+
+impl Default for FundraiserKind {
+    fn default() -> Self {
+        Self::FixedPrice
+    }
+}
+
+/// A bid in a book-building fundraiser.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Bid<Balance> {
+    /// The investor that placed the bid.
+    pub investor: IdentityId,
+    /// Portfolio that the offering asset will be deposited in.
+    pub investment_portfolio: PortfolioId,
+    /// Portfolio funding the bid.
+    pub funding_portfolio: PortfolioId,
+    /// Amount of the offering asset bid for.
+    pub amount: Balance,
+    /// Maximum price per unit of the offering asset.
+    pub price: Balance,
+    /// Amount of the raising asset locked in `funding_portfolio`.
+    pub locked: Balance,
+}
+
+/// Restrictions on the investors of a Fundraiser.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct InvestorRestrictions<Balance> {
+    /// Maximum amount of the raising asset that each investor can spend,
+    /// aggregated over all identities sharing the investor's `ScopeId` for the offering asset.
+    pub max_investment: Option<Balance>,
+    /// Whether only allowlisted identities, or identities holding one of `allowed_claims`, can invest.
+    pub allowlist: bool,
+    /// Claims that allow an identity to invest when `allowlist` is set.
+    /// Each claim must be held, unexpired, with the same type and scope, from the paired issuer.
+    pub allowed_claims: Vec<(Claim, IdentityId)>,
+}
+
+/// An investment held in escrow, or an allocation to settle.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Contribution<Balance> {
+    /// The investor that made the investment.
+    pub investor: IdentityId,
+    /// Portfolio that the offering asset will be deposited in.
+    pub investment_portfolio: PortfolioId,
+    /// Portfolio funding the investment.
+    pub funding_portfolio: PortfolioId,
+    /// Amount of the offering asset purchased.
+    pub amount: Balance,
+    /// Cost of the purchase in the raising asset.
+    pub cost: Balance,
+}
+
 /// Details about the Fundraiser.
 #[derive(Encode, Decode, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "std", derive(Debug))]
@@ -106,7 +243,10 @@ pub struct Fundraiser<Balance, Moment> {
 
 impl<Balance, Moment> Fundraiser<Balance, Moment> {
     pub fn is_closed(&self) -> bool {
-        self.status == FundraiserStatus::Closed || self.status == FundraiserStatus::ClosedEarly
+        match self.status {
+            FundraiserStatus::Live | FundraiserStatus::Frozen => false,
+            _ => true,
+        }
     }
 }
 
@@ -151,10 +291,20 @@ pub struct FundraiserName(Vec<u8>);
 pub trait WeightInfo {
     fn create_fundraiser(i: u32) -> Weight;
     fn invest() -> Weight;
+    fn invest_with_claims(c: u32) -> Weight;
     fn freeze_fundraiser() -> Weight;
     fn unfreeze_fundraiser() -> Weight;
     fn modify_fundraiser_window() -> Weight;
     fn stop() -> Weight;
+    fn submit_bid() -> Weight;
+    fn cancel_bid() -> Weight;
+    fn close_and_allocate(b: u32) -> Weight;
+    fn set_investor_restrictions(c: u32) -> Weight;
+    fn add_allowed_investors(i: u32) -> Weight;
+    fn remove_allowed_investors(i: u32) -> Weight;
+    fn release(c: u32) -> Weight;
+    fn claim_refund() -> Weight;
+    fn set_fundraiser_vesting() -> Weight;
 }
 
 pub trait Config:
@@ -201,6 +351,50 @@ decl_event!(
         /// A fundraiser has been stopped.
         /// (primary issuance agent, fundraiser id)
         FundraiserClosed(IdentityId, u64),
+        /// The allocation mode of a fundraiser has been set.
+        /// (primary issuance agent, fundraiser id, allocation mode)
+        FundraiserKindSet(IdentityId, u64, FundraiserKind),
+        /// An investor placed a bid in a book-building fundraiser.
+        /// (investor, fundraiser id, bid id, offering_token_amount, price)
+        BidSubmitted(IdentityId, u64, u64, Balance, Balance),
+        /// A bid has been cancelled and its funds unlocked.
+        /// (investor, fundraiser id, bid id)
+        BidCancelled(IdentityId, u64, u64),
+        /// The bids of a book-building fundraiser have been allocated and settled.
+        /// (primary issuance agent, fundraiser id, clearing price, total offering_token_amount allocated)
+        FundraiserAllocated(IdentityId, u64, Balance, Balance),
+        /// The investor restrictions of a fundraiser have been set.
+        /// (primary issuance agent, fundraiser id, restrictions)
+        InvestorRestrictionsSet(IdentityId, u64, InvestorRestrictions<Balance>),
+        /// Identities have been added to the allowlist of a fundraiser.
+        /// (primary issuance agent, fundraiser id, investors)
+        AllowedInvestorsAdded(IdentityId, u64, Vec<IdentityId>),
+        /// Identities have been removed from the allowlist of a fundraiser.
+        /// (primary issuance agent, fundraiser id, investors)
+        AllowedInvestorsRemoved(IdentityId, u64, Vec<IdentityId>),
+        /// The soft cap of an escrowed fundraiser has been set.
+        /// (primary issuance agent, fundraiser id, soft cap)
+        SoftCapSet(IdentityId, u64, Balance),
+        /// An investment has been placed in escrow.
+        /// (investor, fundraiser id, contribution id)
+        ContributionEscrowed(IdentityId, u64, u64),
+        /// The escrowed investments of a fundraiser have been settled.
+        /// (primary issuance agent, fundraiser id, total raised)
+        FundsReleased(IdentityId, u64, Balance),
+        /// The escrowed investments that settled in a release fell short of the soft cap,
+        /// so the release was reverted and the fundraiser refunds every investment.
+        /// (primary issuance agent, fundraiser id, total settled)
+        SoftCapMissed(IdentityId, u64, Balance),
+        /// An escrowed investment has been refunded.
+        /// (investor, fundraiser id, contribution id, raise_token_amount)
+        RefundClaimed(IdentityId, u64, u64, Balance),
+        /// The vesting schedule of the tokens bought in a fundraiser has been set.
+        /// (primary issuance agent, fundraiser id, vesting)
+        FundraiserVestingSet(IdentityId, u64, Option<VestingKind>),
+        /// An allocation or escrowed investment failed to settle, e.g., because the investor
+        /// no longer complies with the rules of the offering asset, and its funds were released.
+        /// (investor, fundraiser id, bid or contribution id)
+        InvestmentSettlementFailed(IdentityId, u64, u64),
     }
 );
 
@@ -230,7 +424,46 @@ decl_error! {
         /// Price of the investment exceeded the max price.
         MaxPriceExceeded,
         /// Investment amount is lower than minimum investment amount.
-        InvestmentAmountTooLow
+        InvestmentAmountTooLow,
+        /// The operation is not supported by the allocation mode of the fundraiser.
+        InvalidFundraiserKind,
+        /// The bid price is below the reserve price of a Dutch auction,
+        /// or differs from the price of a pro-rata fundraiser.
+        InvalidBidPrice,
+        /// Bid not found.
+        BidNotFound,
+        /// The bidding window of the fundraiser has not ended yet.
+        BiddingWindowOpen,
+        /// The fundraiser has more bids than the provided maximum.
+        TooManyBids,
+        /// The investor is not on the allowlist of the fundraiser and holds none of its allowed claims.
+        InvestorNotAllowed,
+        /// The investment exceeds the remaining allowance of the investor.
+        MaxInvestmentExceeded,
+        /// The investor has no scope claim for the offering asset, which capped fundraisers require.
+        MissingScopeClaim,
+        /// More than `MAX_ALLOWED_CLAIMS` allowed claims were provided.
+        TooManyAllowedClaims,
+        /// The soft cap is zero or more than the fundraiser can raise.
+        InvalidSoftCap,
+        /// Off-chain receipts can't fund escrowed investments.
+        ReceiptsNotSupported,
+        /// Off-chain claims can't be presented for escrowed investments.
+        PresentedClaimsNotSupported,
+        /// Escrowed investments must be funded from a portfolio of the investor.
+        FundingPortfolioNotOwned,
+        /// The fundraiser is not waiting for its investments to be released.
+        FundraiserNotPendingRelease,
+        /// The fundraiser is not refunding its investments.
+        FundraiserNotRefunding,
+        /// Contribution not found.
+        ContributionNotFound,
+        /// The fundraiser has more contributions than the provided maximum.
+        TooManyContributions,
+        /// The release parameters of the vesting schedule are invalid.
+        InvalidVesting,
+        /// The escrowed investments that settled fell short of the soft cap.
+        SoftCapNotReached
     }
 }
 
@@ -244,6 +477,42 @@ decl_storage! {
         /// Name for the Fundraiser. It is only used offchain.
         /// (ticker, fundraiser_id) -> Fundraiser name
         FundraiserNames get(fn fundraiser_name): double_map hasher(blake2_128_concat) Ticker, hasher(twox_64_concat) u64 => FundraiserName;
+        /// Allocation mode of the fundraiser.
+        /// (ticker, fundraiser_id) -> FundraiserKind
+        FundraiserKinds get(fn fundraiser_kind): double_map hasher(blake2_128_concat) Ticker, hasher(twox_64_concat) u64 => FundraiserKind;
+        /// Bids of book-building fundraisers, removed when they are cancelled or allocated.
+        /// (ticker, fundraiser_id) -> bid_id -> Bid
+        Bids get(fn bids): double_map hasher(blake2_128_concat) (Ticker, u64), hasher(twox_64_concat) u64 => Option<Bid<T::Balance>>;
+        /// Total bids placed in a fundraiser.
+        /// (ticker, fundraiser_id) -> bid count
+        BidCount get(fn bid_count): map hasher(blake2_128_concat) (Ticker, u64) => u64;
+        /// Investment cap and allowlist of the fundraiser.
+        /// (ticker, fundraiser_id) -> InvestorRestrictions
+        InvestorRestrictionsOf get(fn investor_restrictions): double_map hasher(blake2_128_concat) Ticker, hasher(twox_64_concat) u64 => InvestorRestrictions<T::Balance>;
+        /// Identities allowlisted in the fundraiser.
+        /// (ticker, fundraiser_id) -> investor -> allowed
+        AllowedInvestors get(fn allowed_investor): double_map hasher(blake2_128_concat) (Ticker, u64), hasher(identity) IdentityId => bool;
+        /// Amount of the raising asset spent in the fundraiser, or locked in its bids, per `ScopeId`.
+        /// (ticker, fundraiser_id) -> scope_id -> amount
+        InvestedAmount get(fn invested_amount): double_map hasher(blake2_128_concat) (Ticker, u64), hasher(identity) ScopeId => T::Balance;
+        /// Soft cap of escrowed fundraisers, in the raising asset.
+        /// (ticker, fundraiser_id) -> soft cap
+        SoftCaps get(fn soft_cap): double_map hasher(blake2_128_concat) Ticker, hasher(twox_64_concat) u64 => Option<T::Balance>;
+        /// Total amount of the raising asset invested in escrowed fundraisers.
+        /// (ticker, fundraiser_id) -> total raised
+        TotalRaised get(fn total_raised): double_map hasher(blake2_128_concat) Ticker, hasher(twox_64_concat) u64 => T::Balance;
+        /// Investments held in escrow, removed when they are released or refunded.
+        /// (ticker, fundraiser_id) -> contribution_id -> Contribution
+        Contributions get(fn contributions): double_map hasher(blake2_128_concat) (Ticker, u64), hasher(twox_64_concat) u64 => Option<Contribution<T::Balance>>;
+        /// Total investments placed in escrow in a fundraiser.
+        /// (ticker, fundraiser_id) -> contribution count
+        ContributionCount get(fn contribution_count): map hasher(blake2_128_concat) (Ticker, u64) => u64;
+        /// Vesting schedule attached to the offering tokens bought in a fundraiser.
+        /// (ticker, fundraiser_id) -> VestingKind
+        FundraiserVesting get(fn fundraiser_vesting): double_map hasher(blake2_128_concat) Ticker, hasher(twox_64_concat) u64 => Option<VestingKind>;
+        /// Portfolio of each investor holding its escrowed investments, in the custody of the settlement module.
+        /// investor -> escrow portfolio
+        EscrowPortfolios get(fn escrow_portfolio_of): map hasher(identity) IdentityId => Option<PortfolioId>;
     }
 }
 
@@ -282,63 +551,138 @@ decl_module! {
             end: Option<T::Moment>,
             minimum_investment: T::Balance,
             fundraiser_name: FundraiserName
-        ) {
-            pallet_base::ensure_string_limited::<T>(&fundraiser_name)?;
-
-            let PermissionedCallOriginData {
-                primary_did: did,
-                secondary_key,
-                ..
-            } = <ExternalAgents<T>>::ensure_agent_asset_perms(origin, offering_asset)?;
-
-            VenueInfo::get(venue_id)
-                .filter(|v| v.creator == did && v.venue_type == VenueType::Sto)
-                .ok_or(Error::<T>::InvalidVenue)?;
-
-            <Portfolio<T>>::ensure_portfolio_custody_and_permission(raising_portfolio, did, secondary_key.as_ref())?;
-            <Portfolio<T>>::ensure_portfolio_custody_and_permission(offering_portfolio, did, secondary_key.as_ref())?;
-
-            ensure!(
-                tiers.len() > 0 && tiers.len() <= MAX_TIERS && tiers.iter().all(|t| t.total > 0u32.into()),
-                Error::<T>::InvalidPriceTiers
-            );
-
-            let offering_amount: T::Balance = tiers
-                .iter()
-                .map(|t| t.total)
-                .try_fold(0u32.into(), |total: T::Balance, x| total.checked_add(&x))
-                .ok_or(Error::<T>::InvalidPriceTiers)?;
-
-            let start = start.unwrap_or_else(Timestamp::<T>::get);
-            if let Some(end) = end {
-                ensure!(start < end, Error::<T>::InvalidOfferingWindow);
-            }
-
-            <Portfolio<T>>::lock_tokens(&offering_portfolio, &offering_asset, &offering_amount)?;
-
-            let fundraiser = Fundraiser {
-                creator: did,
+        ) -> DispatchResult {
+            Self::base_create_fundraiser(
+                origin,
                 offering_portfolio,
                 offering_asset,
                 raising_portfolio,
                 raising_asset,
-                tiers: tiers.into_iter().map(Into::into).collect(),
+                tiers,
                 venue_id,
                 start,
                 end,
-                status: FundraiserStatus::Live,
-                minimum_investment
-            };
+                minimum_investment,
+                fundraiser_name,
+                FundraiserKind::FixedPrice,
+                None,
+            )
+        }
 
-            let id = FundraiserCount::mutate(offering_asset, |id| mem::replace(id, *id + 1));
-            <Fundraisers<T>>::insert(offering_asset, id, fundraiser.clone());
-            FundraiserNames::insert(offering_asset, id, fundraiser_name.clone());
+        /// Create a new fundraiser with the given allocation mode.
+        ///
+        /// Book-building fundraisers (`DutchAuction` and `ProRata`) must have a single price tier,
+        /// whose price is the reserve price of a Dutch auction or the price of a pro-rata fundraiser,
+        /// and an end time, at which bidding stops and `close_and_allocate` can be called.
+        ///
+        /// * `offering_portfolio` - Portfolio containing the `offering_asset`.
+        /// * `offering_asset` - Asset being offered.
+        /// * `raising_portfolio` - Portfolio containing the `raising_asset`.
+        /// * `raising_asset` - Asset being exchanged for `offering_asset` on investment.
+        /// * `tiers` - Price tiers to charge investors on investment.
+        /// * `venue_id` - Venue to handle settlement.
+        /// * `start` - Fundraiser start time, if `None` the fundraiser will start immediately.
+        /// * `end` - Fundraiser end time, if `None` the fundraiser will never expire.
+        /// * `minimum_investment` - Minimum amount of `raising_asset` that an investor needs to spend to invest in this raise.
+        /// * `fundraiser_name` - Fundraiser name, only used in the UIs.
+        /// * `kind` - Allocation mode of the fundraiser.
+        ///
+        /// # Permissions
+        /// * Asset
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::create_fundraiser(tiers.len() as u32)
+            .saturating_add(T::DbWeight::get().writes(1))]
+        pub fn create_fundraiser_with_kind(
+            origin,
+            offering_portfolio: PortfolioId,
+            offering_asset: Ticker,
+            raising_portfolio: PortfolioId,
+            raising_asset: Ticker,
+            tiers: Vec<PriceTier<T::Balance>>,
+            venue_id: u64,
+            start: Option<T::Moment>,
+            end: Option<T::Moment>,
+            minimum_investment: T::Balance,
+            fundraiser_name: FundraiserName,
+            kind: FundraiserKind
+        ) -> DispatchResult {
+            Self::base_create_fundraiser(
+                origin,
+                offering_portfolio,
+                offering_asset,
+                raising_portfolio,
+                raising_asset,
+                tiers,
+                venue_id,
+                start,
+                end,
+                minimum_investment,
+                fundraiser_name,
+                kind,
+                None,
+            )
+        }
 
-            Self::deposit_event(RawEvent::FundraiserCreated(did, id, fundraiser_name, fundraiser));
+        /// Create a new fundraiser whose investments are held in escrow until it is stopped.
+        ///
+        /// Investments move the raising funds from the funding portfolio of the investor
+        /// to its escrow portfolio, in the custody of the settlement module.
+        /// Once stopped, the investments are released if the total raised reached `soft_cap`,
+        /// otherwise investors can claim refunds.
+        ///
+        /// * `offering_portfolio` - Portfolio containing the `offering_asset`.
+        /// * `offering_asset` - Asset being offered.
+        /// * `raising_portfolio` - Portfolio containing the `raising_asset`.
+        /// * `raising_asset` - Asset being exchanged for `offering_asset` on investment.
+        /// * `tiers` - Price tiers to charge investors on investment.
+        /// * `venue_id` - Venue to handle settlement.
+        /// * `start` - Fundraiser start time, if `None` the fundraiser will start immediately.
+        /// * `end` - Fundraiser end time, if `None` the fundraiser will never expire.
+        /// * `minimum_investment` - Minimum amount of `raising_asset` that an investor needs to spend to invest in this raise.
+        /// * `fundraiser_name` - Fundraiser name, only used in the UIs.
+        /// * `soft_cap` - Minimum amount of `raising_asset` to raise for investments to be released.
+        ///
+        /// # Permissions
+        /// * Asset
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::create_fundraiser(tiers.len() as u32)
+            .saturating_add(T::DbWeight::get().writes(1))]
+        pub fn create_escrowed_fundraiser(
+            origin,
+            offering_portfolio: PortfolioId,
+            offering_asset: Ticker,
+            raising_portfolio: PortfolioId,
+            raising_asset: Ticker,
+            tiers: Vec<PriceTier<T::Balance>>,
+            venue_id: u64,
+            start: Option<T::Moment>,
+            end: Option<T::Moment>,
+            minimum_investment: T::Balance,
+            fundraiser_name: FundraiserName,
+            soft_cap: T::Balance
+        ) -> DispatchResult {
+            Self::base_create_fundraiser(
+                origin,
+                offering_portfolio,
+                offering_asset,
+                raising_portfolio,
+                raising_asset,
+                tiers,
+                venue_id,
+                start,
+                end,
+                minimum_investment,
+                fundraiser_name,
+                FundraiserKind::FixedPrice,
+                Some(soft_cap),
+            )
         }
 
         /// Invest in a fundraiser.
         ///
+        /// Investments in escrowed fundraisers move the cost from `funding_portfolio`, which must belong to the caller,
+        /// to the caller's escrow portfolio until the fundraiser is stopped, and can't be funded by `receipt`.
+        ///
         /// * `investment_portfolio` - Portfolio that `offering_asset` will be deposited in.
         /// * `funding_portfolio` - Portfolio that will fund the investment.
         /// * `offering_asset` - Asset to invest in.
@@ -372,6 +716,10 @@ decl_module! {
             let mut fundraiser = <Fundraisers<T>>::get(offering_asset, fundraiser_id).ok_or(Error::<T>::FundraiserNotFound)?;
 
             ensure!(fundraiser.status == FundraiserStatus::Live, Error::<T>::FundraiserNotLive);
+            ensure!(
+                Self::fundraiser_kind(offering_asset, fundraiser_id) == FundraiserKind::FixedPrice,
+                Error::<T>::InvalidFundraiserKind
+            );
 
             let now = Timestamp::<T>::get();
             ensure!(
@@ -386,10 +734,7 @@ decl_module! {
             // Blended price must be <= to max_price or the investment will fail.
             let mut cost = T::Balance::from(0u32);
 
-            // Price is entered as a multiple of 1_000_000
-            // i.e. a price of 1 unit is 1_000_000
-            // a price of 1.5 units is 1_500_00
-            let price_divisor = T::Balance::from(1_000_000u32);
+            let price_divisor = T::Balance::from(PRICE_DIVISOR);
             // Individual purchases from each tier that accumulate to fulfil the investment amount.
             // Tuple of (tier_id, amount to purchase from that tier).
             let mut purchases = Vec::new();
@@ -425,58 +770,130 @@ decl_module! {
                 max_price.map(|max_price| cost <= max_price.saturating_mul(purchase_amount) / price_divisor).unwrap_or(true),
                 Error::<T>::MaxPriceExceeded
             );
+            let scope_id = Self::ensure_investment_allowed(offering_asset, fundraiser_id, did, cost)?;
 
-            let legs = vec![
-                Leg {
-                    from: fundraiser.offering_portfolio,
-                    to: investment_portfolio,
-                    asset: fundraiser.offering_asset,
-                    amount: purchase_amount
-                },
-                Leg {
-                    from: funding_portfolio,
-                    to: fundraiser.raising_portfolio,
-                    asset: fundraiser.raising_asset,
-                    amount: cost
-                }
-            ];
+            if Self::soft_cap(offering_asset, fundraiser_id).is_some() {
+                // Escrowed investments hold the funds in escrow and keep the offering tokens locked until released.
+                ensure!(receipt.is_none(), Error::<T>::ReceiptsNotSupported);
+                // The funds move between portfolios of the investor, so its balance is unchanged.
+                ensure!(funding_portfolio.did == did, Error::<T>::FundingPortfolioNotOwned);
+                <Portfolio<T>>::ensure_sufficient_balance(&funding_portfolio, &fundraiser.raising_asset, &cost)?;
+                let escrow_portfolio = Self::escrow_portfolio(did);
+                <Portfolio<T>>::ensure_moves_unfrozen(iter::once((funding_portfolio, escrow_portfolio, fundraiser.raising_asset, cost)))?;
+                <Portfolio<T>>::unchecked_transfer_portfolio_balance(&funding_portfolio, &escrow_portfolio, &fundraiser.raising_asset, cost);
 
-            with_transaction(|| {
-                <Portfolio<T>>::unlock_tokens(&fundraiser.offering_portfolio, &fundraiser.offering_asset, &purchase_amount)?;
+                let key = (offering_asset, fundraiser_id);
+                let contribution_id = ContributionCount::mutate(key, |id| mem::replace(id, *id + 1));
+                <Contributions<T>>::insert(key, contribution_id, Contribution {
+                    investor: did,
+                    investment_portfolio,
+                    funding_portfolio,
+                    amount: purchase_amount,
+                    cost,
+                });
+                <TotalRaised<T>>::mutate(offering_asset, fundraiser_id, |raised| *raised += cost);
+                Self::deposit_event(RawEvent::ContributionEscrowed(did, fundraiser_id, contribution_id));
+            } else {
+                let legs = vec![
+                    Leg {
+                        from: fundraiser.offering_portfolio,
+                        to: investment_portfolio,
+                        asset: fundraiser.offering_asset,
+                        amount: purchase_amount
+                    },
+                    Leg {
+                        from: funding_portfolio,
+                        to: fundraiser.raising_portfolio,
+                        asset: fundraiser.raising_asset,
+                        amount: cost
+                    }
+                ];
 
-                let instruction_id = Settlement::<T>::base_add_instruction(
-                    fundraiser.creator,
-                    fundraiser.venue_id,
-                    SettlementType::SettleOnAffirmation,
-                    None,
-                    None,
-                    legs
-                )?;
-
-                let portfolios = [fundraiser.offering_portfolio, fundraiser.raising_portfolio].iter().copied().collect::<BTreeSet<_>>();
-                Settlement::<T>::unsafe_affirm_instruction(fundraiser.creator, instruction_id, portfolios, 1, None)?;
-
-                let portfolios = vec![investment_portfolio, funding_portfolio];
-                match receipt {
-                    Some(receipt) => Settlement::<T>::affirm_with_receipts_and_execute_instruction(
-                        origin,
-                        instruction_id,
-                        vec![receipt],
-                        portfolios,
-                        2
-                    ),
-                    None => Settlement::<T>::affirm_and_execute_instruction(origin, instruction_id, portfolios, 1),
-                }
-            })?;
+                with_transaction(|| {
+                    <Portfolio<T>>::unlock_tokens(&fundraiser.offering_portfolio, &fundraiser.offering_asset, &purchase_amount)?;
+
+                    let instruction_id = Settlement::<T>::base_add_instruction(
+                        fundraiser.creator,
+                        fundraiser.venue_id,
+                        SettlementType::SettleOnAffirmation,
+                        None,
+                        None,
+                        legs,
+                        None
+                    )?;
+
+                    let portfolios = [fundraiser.offering_portfolio, fundraiser.raising_portfolio].iter().copied().collect::<BTreeSet<_>>();
+                    Settlement::<T>::unsafe_affirm_instruction(fundraiser.creator, instruction_id, portfolios, 1, None)?;
+
+                    let portfolios = vec![investment_portfolio, funding_portfolio];
+                    match receipt {
+                        Some(receipt) => Settlement::<T>::affirm_with_receipts_and_execute_instruction(
+                            origin,
+                            instruction_id,
+                            vec![receipt],
+                            portfolios,
+                            2
+                        ),
+                        None => Settlement::<T>::affirm_and_execute_instruction(origin, instruction_id, portfolios, 1),
+                    }?;
+
+                    let vesting = Self::fundraiser_vesting(offering_asset, fundraiser_id);
+                    Self::add_vesting(vesting, did, investment_portfolio, offering_asset, purchase_amount)
+                })?;
+            }
 
             for (id, amount) in purchases {
                 fundraiser.tiers[id].remaining -= amount;
             }
+            <InvestedAmount<T>>::mutate((offering_asset, fundraiser_id), scope_id, |invested| *invested += cost);
 
             Self::deposit_event(RawEvent::Invested(did, fundraiser_id, offering_asset, fundraiser.raising_asset, purchase_amount, cost));
             <Fundraisers<T>>::insert(offering_asset, fundraiser_id, fundraiser);
         }
 
+        /// Invest in a fundraiser, presenting off-chain claims about the caller.
+        /// The claims are taken into account by compliance checks during this call only,
+        /// and are never stored on-chain.
+        ///
+        /// * `claims` - Claims, signed off-chain by their issuers, about the caller.
+        /// * See `invest` for the other arguments.
+        ///
+        /// # Errors
+        /// * `PresentedClaimsNotSupported` if the fundraiser escrows investments.
+        /// * See `ComplianceManager::with_presented_claims`.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::invest_with_claims(claims.len() as u32)]
+        pub fn invest_with_claims(
+            origin,
+            investment_portfolio: PortfolioId,
+            funding_portfolio: PortfolioId,
+            offering_asset: Ticker,
+            fundraiser_id: u64,
+            purchase_amount: T::Balance,
+            max_price: Option<T::Balance>,
+            claims: PresentedClaimsOf<T>
+        ) -> DispatchResult {
+            let did = Identity::<T>::ensure_perms(origin.clone())?;
+            ensure!(
+                Self::soft_cap(offering_asset, fundraiser_id).is_none(),
+                Error::<T>::PresentedClaimsNotSupported
+            );
+            <ComplianceManager<T>>::with_presented_claims(did, claims, || {
+                Self::invest(
+                    origin,
+                    investment_portfolio,
+                    funding_portfolio,
+                    offering_asset,
+                    fundraiser_id,
+                    purchase_amount,
+                    max_price,
+                    None,
+                )
+            })
+        }
+
         /// Freeze a fundraiser.
         ///
         /// * `offering_asset` - Asset to freeze.
@@ -523,6 +940,10 @@ decl_module! {
                 if let Some(end) = end {
                     ensure!(start < end, Error::<T>::InvalidOfferingWindow);
                 }
+                // Book-building fundraisers need an end to their bidding window.
+                if Self::fundraiser_kind(offering_asset, fundraiser_id) != FundraiserKind::FixedPrice {
+                    ensure!(end.is_some(), Error::<T>::InvalidOfferingWindow);
+                }
                 Self::deposit_event(RawEvent::FundraiserWindowModified(did, fundraiser_id, fundraiser.start, fundraiser.end, start, end));
                 fundraiser.start = start;
                 fundraiser.end = end;
@@ -532,6 +953,9 @@ decl_module! {
 
         /// Stop a fundraiser.
         ///
+        /// An escrowed fundraiser waits for its investments to be released if it reached its soft cap,
+        /// otherwise it refunds them and unlocks all of the offering.
+        ///
         /// * `offering_asset` - Asset to stop.
         /// * `fundraiser_id` - ID of the fundraiser to stop.
         ///
@@ -548,24 +972,1038 @@ decl_module! {
             }
 
             ensure!(!fundraiser.is_closed(), Error::<T>::FundraiserClosed);
+            Self::base_stop(did, offering_asset, fundraiser_id, &mut fundraiser)?;
+        }
+
+        /// Bid in a book-building fundraiser, locking the raising funds of the bid.
+        ///
+        /// * `investment_portfolio` - Portfolio that `offering_asset` will be deposited in.
+        /// * `funding_portfolio` - Portfolio that will fund the bid.
+        /// * `offering_asset` - Asset to bid for.
+        /// * `fundraiser_id` - ID of the fundraiser to bid in.
+        /// * `amount` - Amount of `offering_asset` to bid for.
+        /// * `price` - Maximum price to pay per unit of `offering_asset`.
+        ///    It must be at least the reserve price of a Dutch auction and equal to the price of a pro-rata fundraiser.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::submit_bid()]
+        pub fn submit_bid(
+            origin,
+            investment_portfolio: PortfolioId,
+            funding_portfolio: PortfolioId,
+            offering_asset: Ticker,
+            fundraiser_id: u64,
+            amount: T::Balance,
+            price: T::Balance
+        ) {
+            let PermissionedCallOriginData {
+                primary_did: did,
+                secondary_key,
+                ..
+            } = Identity::<T>::ensure_origin_call_permissions(origin)?;
+
+            <Portfolio<T>>::ensure_portfolio_custody_and_permission(investment_portfolio, did, secondary_key.as_ref())?;
+            <Portfolio<T>>::ensure_portfolio_custody_and_permission(funding_portfolio, did, secondary_key.as_ref())?;
+
+            let fundraiser = <Fundraisers<T>>::get(offering_asset, fundraiser_id).ok_or(Error::<T>::FundraiserNotFound)?;
+            ensure!(fundraiser.status == FundraiserStatus::Live, Error::<T>::FundraiserNotLive);
+            let kind = Self::fundraiser_kind(offering_asset, fundraiser_id);
+            ensure!(kind != FundraiserKind::FixedPrice, Error::<T>::InvalidFundraiserKind);
+
+            let now = Timestamp::<T>::get();
+            ensure!(
+                fundraiser.start <= now && fundraiser.end.filter(|e| now >= *e).is_none(),
+                Error::<T>::FundraiserExpired
+            );
+
+            // Book-building fundraisers have a single tier.
+            let tier = &fundraiser.tiers[0];
+            ensure!(amount > 0u32.into(), Error::<T>::InvestmentAmountTooLow);
+            ensure!(amount <= tier.total, Error::<T>::InsufficientTokensRemaining);
+            ensure!(
+                match kind {
+                    FundraiserKind::DutchAuction => price >= tier.price,
+                    _ => price == tier.price,
+                },
+                Error::<T>::InvalidBidPrice
+            );
+
+            let locked = Self::cost(amount, price)?;
+            ensure!(locked >= fundraiser.minimum_investment, Error::<T>::InvestmentAmountTooLow);
+            let scope_id = Self::ensure_investment_allowed(offering_asset, fundraiser_id, did, locked)?;
+            <Portfolio<T>>::lock_tokens(&funding_portfolio, &fundraiser.raising_asset, &locked)?;
+
+            let key = (offering_asset, fundraiser_id);
+            // Locked funds count towards the investment cap until the bid is cancelled or allocated.
+            <InvestedAmount<T>>::mutate(key, scope_id, |invested| *invested += locked);
+            let bid_id = BidCount::mutate(key, |id| mem::replace(id, *id + 1));
+            <Bids<T>>::insert(key, bid_id, Bid {
+                investor: did,
+                investment_portfolio,
+                funding_portfolio,
+                amount,
+                price,
+                locked,
+            });
+            Self::deposit_event(RawEvent::BidSubmitted(did, fundraiser_id, bid_id, amount, price));
+        }
+
+        /// Cancel a bid, unlocking its funds.
+        ///
+        /// Bids can be cancelled until the end of the bidding window,
+        /// at any time after the fundraiser has been stopped without allocation,
+        /// or once `BOOK_CLOSING_PERIOD` has elapsed since the end of the bidding window
+        /// without the fundraiser being closed.
+        ///
+        /// * `offering_asset` - Asset of the fundraiser.
+        /// * `fundraiser_id` - ID of the fundraiser.
+        /// * `bid_id` - ID of the bid to cancel.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::cancel_bid()]
+        pub fn cancel_bid(origin, offering_asset: Ticker, fundraiser_id: u64, bid_id: u64) {
+            let PermissionedCallOriginData {
+                primary_did: did,
+                secondary_key,
+                ..
+            } = Identity::<T>::ensure_origin_call_permissions(origin)?;
+
+            let key = (offering_asset, fundraiser_id);
+            let bid = <Bids<T>>::get(key, bid_id).ok_or(Error::<T>::BidNotFound)?;
+            ensure!(bid.investor == did, Error::<T>::Unauthorized);
+            <Portfolio<T>>::ensure_portfolio_custody_and_permission(bid.funding_portfolio, did, secondary_key.as_ref())?;
+
+            let fundraiser = <Fundraisers<T>>::get(offering_asset, fundraiser_id).ok_or(Error::<T>::FundraiserNotFound)?;
+            let now = Timestamp::<T>::get();
+            ensure!(
+                fundraiser.is_closed() || fundraiser.end.map_or(true, |end| {
+                    now < end || now >= end.saturating_add(BOOK_CLOSING_PERIOD.into())
+                }),
+                Error::<T>::FundraiserExpired
+            );
+
+            <Portfolio<T>>::unlock_tokens(&bid.funding_portfolio, &fundraiser.raising_asset, &bid.locked)?;
+            <Bids<T>>::remove(key, bid_id);
+            Self::refund_invested_amount(offering_asset, fundraiser_id, bid.investor, bid.locked);
+            Self::deposit_event(RawEvent::BidCancelled(did, fundraiser_id, bid_id));
+        }
 
-            let remaining_amount: T::Balance = fundraiser.tiers
+        /// Close a book-building fundraiser once its bidding window has ended,
+        /// allocate the offering to the bids and settle each allocation in its own instruction.
+        ///
+        /// A Dutch auction fills the highest bids first, with ties filled in bid order,
+        /// and every filled bid pays the lowest price among them.
+        /// A pro-rata fundraiser fills every bid in full or, when oversubscribed,
+        /// scales down every bid proportionally to the amount offered,
+        /// rounded down to whole units for indivisible assets, leaving the remainder unsold.
+        /// Funds locked for unfilled amounts and unsold offering tokens are unlocked.
+        ///
+        /// An allocation that fails to settle, e.g., because its investor lost its CDD claim
+        /// or no longer complies with the rules of the offering asset, is skipped:
+        /// the funds of its bid are unlocked and its offering tokens are left unsold.
+        ///
+        /// * `offering_asset` - Asset of the fundraiser.
+        /// * `fundraiser_id` - ID of the fundraiser to close.
+        /// * `max_bids` - Maximum number of bids in the fundraiser.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::close_and_allocate(*max_bids)]
+        pub fn close_and_allocate(origin, offering_asset: Ticker, fundraiser_id: u64, max_bids: u32) {
+            let mut fundraiser = <Fundraisers<T>>::get(offering_asset, fundraiser_id)
+                .ok_or(Error::<T>::FundraiserNotFound)?;
+
+            let did = <ExternalAgents<T>>::ensure_asset_perms(origin, &offering_asset)?.primary_did;
+            if fundraiser.creator != did {
+                 <ExternalAgents<T>>::ensure_agent_permissioned(offering_asset, did)?;
+            }
+
+            ensure!(!fundraiser.is_closed(), Error::<T>::FundraiserClosed);
+            let kind = Self::fundraiser_kind(offering_asset, fundraiser_id);
+            ensure!(kind != FundraiserKind::FixedPrice, Error::<T>::InvalidFundraiserKind);
+            ensure!(
+                fundraiser.end.filter(|e| Timestamp::<T>::get() >= *e).is_some(),
+                Error::<T>::BiddingWindowOpen
+            );
+
+            let key = (offering_asset, fundraiser_id);
+            let bids = <Bids<T>>::iter_prefix(key).collect::<Vec<_>>();
+            ensure!(bids.len() <= max_bids as usize, Error::<T>::TooManyBids);
+
+            let divisible = <Asset<T>>::token_details(offering_asset).divisible;
+            let (price, allocations) = Self::allocate(kind, &fundraiser.tiers[0], bids, divisible)?;
+            let contributions = allocations
                 .iter()
-                .map(|t| t.remaining)
-                .fold(0u32.into(), |remaining, x| remaining + x);
+                .filter(|(_, _, amount)| *amount > 0u32.into())
+                .map(|(bid_id, bid, amount)| Ok((*bid_id, Contribution {
+                    investor: bid.investor,
+                    investment_portfolio: bid.investment_portfolio,
+                    funding_portfolio: bid.funding_portfolio,
+                    amount: *amount,
+                    cost: Self::cost(*amount, price)?,
+                })))
+                .collect::<Result<Vec<_>, DispatchError>>()?;
 
-            <Portfolio<T>>::unlock_tokens(&fundraiser.offering_portfolio, &fundraiser.offering_asset, &remaining_amount)?;
-            fundraiser.status = match fundraiser.end {
-                Some(end) if end > Timestamp::<T>::get() => FundraiserStatus::ClosedEarly,
-                _ => FundraiserStatus::Closed,
-            };
+            let failed = with_transaction(|| -> Result<_, DispatchError> {
+                <Portfolio<T>>::unlock_tokens(&fundraiser.offering_portfolio, &fundraiser.offering_asset, &fundraiser.tiers[0].remaining)?;
+                for (_, bid, _) in &allocations {
+                    <Portfolio<T>>::unlock_tokens(&bid.funding_portfolio, &fundraiser.raising_asset, &bid.locked)?;
+                }
+                // Investors consented to the settlement of their allocations when bidding.
+                let vesting = Self::fundraiser_vesting(offering_asset, fundraiser_id);
+                Ok(Self::settle_contributions(&fundraiser, &contributions, vesting, false))
+            })?;
+
+            // Replace the funds locked by each bid with the cost of its settled allocation.
+            for (_, bid, _) in &allocations {
+                Self::refund_invested_amount(offering_asset, fundraiser_id, bid.investor, bid.locked);
+            }
+            let mut allocated = T::Balance::from(0u32);
+            for (bid_id, contribution) in &contributions {
+                if failed.contains(bid_id) {
+                    Self::deposit_event(RawEvent::InvestmentSettlementFailed(contribution.investor, fundraiser_id, *bid_id));
+                } else {
+                    <InvestedAmount<T>>::mutate(key, Self::scope_id_of(offering_asset, contribution.investor), |invested| {
+                        *invested = invested.saturating_add(contribution.cost)
+                    });
+                    allocated += contribution.amount;
+                }
+            }
+            <Bids<T>>::remove_prefix(key);
+            fundraiser.tiers[0].remaining -= allocated;
+            fundraiser.status = FundraiserStatus::Closed;
             <Fundraisers<T>>::insert(offering_asset, fundraiser_id, fundraiser);
+            Self::deposit_event(RawEvent::FundraiserAllocated(did, fundraiser_id, price, allocated));
             Self::deposit_event(RawEvent::FundraiserClosed(did, fundraiser_id));
         }
+
+        /// Set the investment cap and allowlist of a fundraiser.
+        ///
+        /// The cap applies to new investments and bids only,
+        /// so past investments can exceed a lowered cap.
+        ///
+        /// * `offering_asset` - Asset of the fundraiser.
+        /// * `fundraiser_id` - ID of the fundraiser.
+        /// * `restrictions` - New investor restrictions of the fundraiser.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::set_investor_restrictions(restrictions.allowed_claims.len() as u32)]
+        pub fn set_investor_restrictions(
+            origin,
+            offering_asset: Ticker,
+            fundraiser_id: u64,
+            restrictions: InvestorRestrictions<T::Balance>
+        ) {
+            let did = Self::ensure_fundraiser_perms(origin, offering_asset, fundraiser_id)?;
+            ensure!(
+                restrictions.allowed_claims.len() <= MAX_ALLOWED_CLAIMS,
+                Error::<T>::TooManyAllowedClaims
+            );
+            <InvestorRestrictionsOf<T>>::insert(offering_asset, fundraiser_id, restrictions.clone());
+            Self::deposit_event(RawEvent::InvestorRestrictionsSet(did, fundraiser_id, restrictions));
+        }
+
+        /// Add identities to the allowlist of a fundraiser.
+        ///
+        /// * `offering_asset` - Asset of the fundraiser.
+        /// * `fundraiser_id` - ID of the fundraiser.
+        /// * `investors` - Identities to allow.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::add_allowed_investors(investors.len() as u32)]
+        pub fn add_allowed_investors(origin, offering_asset: Ticker, fundraiser_id: u64, investors: Vec<IdentityId>) {
+            let did = Self::ensure_fundraiser_perms(origin, offering_asset, fundraiser_id)?;
+            let key = (offering_asset, fundraiser_id);
+            for investor in &investors {
+                AllowedInvestors::insert(key, investor, true);
+            }
+            Self::deposit_event(RawEvent::AllowedInvestorsAdded(did, fundraiser_id, investors));
+        }
+
+        /// Remove identities from the allowlist of a fundraiser.
+        ///
+        /// * `offering_asset` - Asset of the fundraiser.
+        /// * `fundraiser_id` - ID of the fundraiser.
+        /// * `investors` - Identities to remove.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::remove_allowed_investors(investors.len() as u32)]
+        pub fn remove_allowed_investors(origin, offering_asset: Ticker, fundraiser_id: u64, investors: Vec<IdentityId>) {
+            let did = Self::ensure_fundraiser_perms(origin, offering_asset, fundraiser_id)?;
+            let key = (offering_asset, fundraiser_id);
+            for investor in &investors {
+                AllowedInvestors::remove(key, investor);
+            }
+            Self::deposit_event(RawEvent::AllowedInvestorsRemoved(did, fundraiser_id, investors));
+        }
+
+        /// Settle the escrowed investments of a stopped fundraiser that reached its soft cap,
+        /// each in its own instruction, and close the fundraiser.
+        ///
+        /// An escrowed fundraiser that reached its end is stopped first.
+        /// An investment that fails to settle, e.g., because its investor lost its CDD claim
+        /// or no longer complies with the rules of the offering asset, is refunded.
+        /// If the settled investments fall short of the soft cap, the release is reverted
+        /// and the fundraiser refunds every investment instead.
+        ///
+        /// * `offering_asset` - Asset of the fundraiser.
+        /// * `fundraiser_id` - ID of the fundraiser to release.
+        /// * `max_contributions` - Maximum number of escrowed investments in the fundraiser.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::release(*max_contributions)]
+        pub fn release(origin, offering_asset: Ticker, fundraiser_id: u64, max_contributions: u32) {
+            let mut fundraiser = <Fundraisers<T>>::get(offering_asset, fundraiser_id)
+                .ok_or(Error::<T>::FundraiserNotFound)?;
+
+            let did = <ExternalAgents<T>>::ensure_asset_perms(origin, &offering_asset)?.primary_did;
+            if fundraiser.creator != did {
+                 <ExternalAgents<T>>::ensure_agent_permissioned(offering_asset, did)?;
+            }
+
+            Self::stop_if_ended(did, offering_asset, fundraiser_id, &mut fundraiser)?;
+            ensure!(fundraiser.status == FundraiserStatus::PendingRelease, Error::<T>::FundraiserNotPendingRelease);
+
+            let key = (offering_asset, fundraiser_id);
+            let contributions = <Contributions<T>>::iter_prefix(key).collect::<Vec<_>>();
+            ensure!(contributions.len() <= max_contributions as usize, Error::<T>::TooManyContributions);
+            let sold = contributions
+                .iter()
+                .fold(T::Balance::from(0u32), |sold, (_, contribution)| sold + contribution.amount);
+            let soft_cap = Self::soft_cap(offering_asset, fundraiser_id).unwrap_or_default();
+
+            let released = with_transaction(|| -> Result<_, DispatchError> {
+                <Portfolio<T>>::unlock_tokens(&fundraiser.offering_portfolio, &fundraiser.offering_asset, &sold)?;
+                // Investors consented to the settlement of their investments when investing.
+                let vesting = Self::fundraiser_vesting(offering_asset, fundraiser_id);
+                let failed = Self::settle_contributions(&fundraiser, &contributions, vesting, true);
+                let raised = contributions
+                    .iter()
+                    .filter(|(id, _)| !failed.contains(id))
+                    .fold(T::Balance::from(0u32), |raised, (_, contribution)| raised + contribution.cost);
+                ensure!(raised >= soft_cap, Error::<T>::SoftCapNotReached);
+                Ok((failed, raised))
+            });
+
+            match released {
+                Ok((failed, raised)) => {
+                    for (contribution_id, contribution) in &contributions {
+                        if failed.contains(contribution_id) {
+                            Self::refund_contribution(offering_asset, fundraiser_id, &fundraiser, contribution);
+                            Self::deposit_event(RawEvent::InvestmentSettlementFailed(contribution.investor, fundraiser_id, *contribution_id));
+                        }
+                    }
+                    <Contributions<T>>::remove_prefix(key);
+                    fundraiser.status = FundraiserStatus::Closed;
+                    <Fundraisers<T>>::insert(offering_asset, fundraiser_id, fundraiser);
+                    Self::deposit_event(RawEvent::FundsReleased(did, fundraiser_id, raised));
+                }
+                Err(e) if e == DispatchError::from(Error::<T>::SoftCapNotReached) => {
+                    // Nothing was sold, so the offering is unlocked and every investment can be refunded.
+                    <Portfolio<T>>::unlock_tokens(&fundraiser.offering_portfolio, &fundraiser.offering_asset, &sold)?;
+                    let raised = contributions
+                        .iter()
+                        .fold(T::Balance::from(0u32), |raised, (_, contribution)| raised + contribution.cost);
+                    fundraiser.status = FundraiserStatus::Refunding;
+                    <Fundraisers<T>>::insert(offering_asset, fundraiser_id, fundraiser);
+                    Self::deposit_event(RawEvent::SoftCapMissed(did, fundraiser_id, raised));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        /// Claim a refund of an escrowed investment in a fundraiser that missed its soft cap,
+        /// moving its funds from escrow back to the funding portfolio,
+        /// or to the default portfolio of the investor if the funding portfolio was deleted.
+        ///
+        /// An escrowed fundraiser that reached its end is stopped first.
+        ///
+        /// * `offering_asset` - Asset of the fundraiser.
+        /// * `fundraiser_id` - ID of the fundraiser.
+        /// * `contribution_id` - ID of the escrowed investment to refund.
+        ///
+        /// # Permissions
+        /// * Portfolio
+        #[weight = <T as Config>::WeightInfo::claim_refund()]
+        pub fn claim_refund(origin, offering_asset: Ticker, fundraiser_id: u64, contribution_id: u64) {
+            let PermissionedCallOriginData {
+                primary_did: did,
+                secondary_key,
+                ..
+            } = Identity::<T>::ensure_origin_call_permissions(origin)?;
+
+            let mut fundraiser = <Fundraisers<T>>::get(offering_asset, fundraiser_id).ok_or(Error::<T>::FundraiserNotFound)?;
+            Self::stop_if_ended(did, offering_asset, fundraiser_id, &mut fundraiser)?;
+            ensure!(fundraiser.status == FundraiserStatus::Refunding, Error::<T>::FundraiserNotRefunding);
+
+            let key = (offering_asset, fundraiser_id);
+            let contribution = <Contributions<T>>::get(key, contribution_id).ok_or(Error::<T>::ContributionNotFound)?;
+            ensure!(contribution.investor == did, Error::<T>::Unauthorized);
+            let refund_portfolio = Self::refund_portfolio(&contribution);
+            <Portfolio<T>>::ensure_portfolio_custody_and_permission(refund_portfolio, did, secondary_key.as_ref())?;
+
+            Self::refund_contribution(offering_asset, fundraiser_id, &fundraiser, &contribution);
+            <Contributions<T>>::remove(key, contribution_id);
+            Self::deposit_event(RawEvent::RefundClaimed(did, fundraiser_id, contribution_id, contribution.cost));
+        }
+
+        /// Set the vesting schedule of the offering tokens bought in a fundraiser.
+        ///
+        /// The schedule applies to tokens settled from now on, locking them in the investment portfolio.
+        ///
+        /// * `offering_asset` - Asset of the fundraiser.
+        /// * `fundraiser_id` - ID of the fundraiser.
+        /// * `vesting` - How the bought tokens are released, or `None` to release them at once.
+        ///
+        /// # Permissions
+        /// * Asset
+        #[weight = <T as Config>::WeightInfo::set_fundraiser_vesting()]
+        pub fn set_fundraiser_vesting(origin, offering_asset: Ticker, fundraiser_id: u64, vesting: Option<VestingKind>) {
+            let did = Self::ensure_fundraiser_perms(origin, offering_asset, fundraiser_id)?;
+            ensure!(vesting.map_or(true, |v| v.is_valid()), Error::<T>::InvalidVesting);
+            FundraiserVesting::mutate(offering_asset, fundraiser_id, |v| *v = vesting);
+            Self::deposit_event(RawEvent::FundraiserVestingSet(did, fundraiser_id, vesting));
+        }
     }
 }
 
 impl<T: Config> Module<T> {
+    fn base_create_fundraiser(
+        origin: T::Origin,
+        offering_portfolio: PortfolioId,
+        offering_asset: Ticker,
+        raising_portfolio: PortfolioId,
+        raising_asset: Ticker,
+        tiers: Vec<PriceTier<T::Balance>>,
+        venue_id: u64,
+        start: Option<T::Moment>,
+        end: Option<T::Moment>,
+        minimum_investment: T::Balance,
+        fundraiser_name: FundraiserName,
+        kind: FundraiserKind,
+        soft_cap: Option<T::Balance>,
+    ) -> DispatchResult {
+        pallet_base::ensure_string_limited::<T>(&fundraiser_name)?;
+
+        let PermissionedCallOriginData {
+            primary_did: did,
+            secondary_key,
+            ..
+        } = <ExternalAgents<T>>::ensure_agent_asset_perms(origin, offering_asset)?;
+
+        VenueInfo::get(venue_id)
+            .filter(|v| v.creator == did && v.venue_type == VenueType::Sto)
+            .ok_or(Error::<T>::InvalidVenue)?;
+
+        <Portfolio<T>>::ensure_portfolio_custody_and_permission(
+            raising_portfolio,
+            did,
+            secondary_key.as_ref(),
+        )?;
+        <Portfolio<T>>::ensure_portfolio_custody_and_permission(
+            offering_portfolio,
+            did,
+            secondary_key.as_ref(),
+        )?;
+
+        ensure!(
+            tiers.len() > 0
+                && tiers.len() <= MAX_TIERS
+                && tiers.iter().all(|t| t.total > 0u32.into()),
+            Error::<T>::InvalidPriceTiers
+        );
+        // Book-building fundraisers allocate at a single price, once bidding has ended.
+        if kind != FundraiserKind::FixedPrice {
+            ensure!(tiers.len() == 1, Error::<T>::InvalidPriceTiers);
+            ensure!(end.is_some(), Error::<T>::InvalidOfferingWindow);
+        }
+
+        let offering_amount: T::Balance = tiers
+            .iter()
+            .map(|t| t.total)
+            .try_fold(0u32.into(), |total: T::Balance, x| total.checked_add(&x))
+            .ok_or(Error::<T>::InvalidPriceTiers)?;
+
+        let start = start.unwrap_or_else(Timestamp::<T>::get);
+        if let Some(end) = end {
+            ensure!(start < end, Error::<T>::InvalidOfferingWindow);
+        }
+
+        if let Some(soft_cap) = soft_cap {
+            // Maximum amount of the raising asset that the fundraiser can raise.
+            let hard_cap = tiers.iter().try_fold(
+                0u32.into(),
+                |total: T::Balance, t| -> Result<_, DispatchError> {
+                    Ok(Self::cost(t.total, t.price)?
+                        .checked_add(&total)
+                        .ok_or(Error::<T>::Overflow)?)
+                },
+            )?;
+            ensure!(
+                soft_cap > 0u32.into() && soft_cap <= hard_cap,
+                Error::<T>::InvalidSoftCap
+            );
+        }
+
+        <Portfolio<T>>::lock_tokens(&offering_portfolio, &offering_asset, &offering_amount)?;
+
+        let fundraiser = Fundraiser {
+            creator: did,
+            offering_portfolio,
+            offering_asset,
+            raising_portfolio,
+            raising_asset,
+            tiers: tiers.into_iter().map(Into::into).collect(),
+            venue_id,
+            start,
+            end,
+            status: FundraiserStatus::Live,
+            minimum_investment,
+        };
+
+        let id = FundraiserCount::mutate(offering_asset, |id| mem::replace(id, *id + 1));
+        <Fundraisers<T>>::insert(offering_asset, id, fundraiser.clone());
+        FundraiserNames::insert(offering_asset, id, fundraiser_name.clone());
+        FundraiserKinds::insert(offering_asset, id, kind);
+        if let Some(soft_cap) = soft_cap {
+            <SoftCaps<T>>::insert(offering_asset, id, soft_cap);
+        }
+
+        Self::deposit_event(RawEvent::FundraiserCreated(
+            did,
+            id,
+            fundraiser_name,
+            fundraiser,
+        ));
+        if kind != FundraiserKind::FixedPrice {
+            Self::deposit_event(RawEvent::FundraiserKindSet(did, id, kind));
+        }
+        if let Some(soft_cap) = soft_cap {
+            Self::deposit_event(RawEvent::SoftCapSet(did, id, soft_cap));
+        }
+        Ok(())
+    }
+
+    /// Returns the amount of the raising asset that `investor` can still spend in the fundraiser,
+    /// or `None` if investments in the fundraiser are not capped.
+    ///
+    /// Investors that are not allowed to invest in the fundraiser,
+    /// or that have no scope claim for the offering asset while investments are capped, have no allowance.
+    pub fn remaining_allowance(
+        offering_asset: Ticker,
+        fundraiser_id: u64,
+        investor: IdentityId,
+    ) -> Option<T::Balance> {
+        let restrictions = Self::investor_restrictions(offering_asset, fundraiser_id);
+        if !Self::is_allowed_investor(offering_asset, fundraiser_id, &restrictions, investor) {
+            return Some(0u32.into());
+        }
+        restrictions.max_investment.map(|max| {
+            if !pallet_asset::ScopeIdOf::contains_key(offering_asset, investor) {
+                return 0u32.into();
+            }
+            let scope_id = Self::scope_id_of(offering_asset, investor);
+            max.saturating_sub(Self::invested_amount(
+                (offering_asset, fundraiser_id),
+                scope_id,
+            ))
+        })
+    }
+
+    /// Ensures that `investor` can spend `amount` more in the fundraiser.
+    /// Returns the `ScopeId` that the investment counts towards.
+    fn ensure_investment_allowed(
+        offering_asset: Ticker,
+        fundraiser_id: u64,
+        investor: IdentityId,
+        amount: T::Balance,
+    ) -> Result<ScopeId, DispatchError> {
+        let restrictions = Self::investor_restrictions(offering_asset, fundraiser_id);
+        ensure!(
+            Self::is_allowed_investor(offering_asset, fundraiser_id, &restrictions, investor),
+            Error::<T>::InvestorNotAllowed
+        );
+        let scope_id = Self::scope_id_of(offering_asset, investor);
+        if let Some(max) = restrictions.max_investment {
+            // Without a scope claim, the cap could be bypassed through several identities.
+            ensure!(
+                pallet_asset::ScopeIdOf::contains_key(offering_asset, investor),
+                Error::<T>::MissingScopeClaim
+            );
+            let invested = Self::invested_amount((offering_asset, fundraiser_id), scope_id)
+                .checked_add(&amount)
+                .ok_or(Error::<T>::Overflow)?;
+            ensure!(invested <= max, Error::<T>::MaxInvestmentExceeded);
+        }
+        Ok(scope_id)
+    }
+
+    fn is_allowed_investor(
+        offering_asset: Ticker,
+        fundraiser_id: u64,
+        restrictions: &InvestorRestrictions<T::Balance>,
+        investor: IdentityId,
+    ) -> bool {
+        !restrictions.allowlist
+            || Self::allowed_investor((offering_asset, fundraiser_id), investor)
+            || restrictions.allowed_claims.iter().any(|(claim, issuer)| {
+                Identity::<T>::fetch_claim(
+                    investor,
+                    claim.claim_type(),
+                    *issuer,
+                    claim.as_scope().cloned(),
+                )
+                .is_some()
+            })
+    }
+
+    /// Stops `fundraiser`, unlocking its unsold offering.
+    ///
+    /// An escrowed fundraiser waits for its investments to be released if it reached its soft cap,
+    /// otherwise it refunds them and unlocks all of its offering.
+    fn base_stop(
+        did: IdentityId,
+        offering_asset: Ticker,
+        fundraiser_id: u64,
+        fundraiser: &mut Fundraiser<T::Balance, T::Moment>,
+    ) -> DispatchResult {
+        fundraiser.status = match Self::soft_cap(offering_asset, fundraiser_id) {
+            Some(soft_cap) if Self::total_raised(offering_asset, fundraiser_id) >= soft_cap => {
+                FundraiserStatus::PendingRelease
+            }
+            Some(_) => FundraiserStatus::Refunding,
+            None => match fundraiser.end {
+                Some(end) if end > Timestamp::<T>::get() => FundraiserStatus::ClosedEarly,
+                _ => FundraiserStatus::Closed,
+            },
+        };
+
+        // A refunding fundraiser sells nothing, so its whole offering is unlocked,
+        // including what was sold into escrow.
+        let mut unlock_amount = fundraiser
+            .tiers
+            .iter()
+            .fold(T::Balance::from(0u32), |remaining, t| {
+                remaining + t.remaining
+            });
+        if fundraiser.status == FundraiserStatus::Refunding {
+            unlock_amount = <Contributions<T>>::iter_prefix_values((offering_asset, fundraiser_id))
+                .fold(unlock_amount, |unlock, contribution| {
+                    unlock + contribution.amount
+                });
+        }
+
+        <Portfolio<T>>::unlock_tokens(
+            &fundraiser.offering_portfolio,
+            &fundraiser.offering_asset,
+            &unlock_amount,
+        )?;
+        <Fundraisers<T>>::insert(offering_asset, fundraiser_id, fundraiser.clone());
+        Self::deposit_event(RawEvent::FundraiserClosed(did, fundraiser_id));
+        Ok(())
+    }
+
+    /// Stops an escrowed `fundraiser` that is still open after its end, as `stop` would.
+    fn stop_if_ended(
+        did: IdentityId,
+        offering_asset: Ticker,
+        fundraiser_id: u64,
+        fundraiser: &mut Fundraiser<T::Balance, T::Moment>,
+    ) -> DispatchResult {
+        let ended = fundraiser
+            .end
+            .map_or(false, |end| Timestamp::<T>::get() >= end);
+        if ended
+            && !fundraiser.is_closed()
+            && Self::soft_cap(offering_asset, fundraiser_id).is_some()
+        {
+            Self::base_stop(did, offering_asset, fundraiser_id, fundraiser)?;
+        }
+        Ok(())
+    }
+
+    /// Rescales, e.g., for a stock split, the fundraisers offering `offering_asset`,
+    /// along with their bids and escrowed investments,
+    /// so that they sell the same share of the asset for the same funds.
+    ///
+    /// `rescale` rescales amounts of `offering_asset`, while `rescale_price` rescales prices per unit of it.
+    /// Returns the amounts of `offering_asset` locked by the fundraisers in each offering portfolio,
+    /// before and after rescaling.
+    pub fn rescale_offerings(
+        offering_asset: Ticker,
+        rescale: impl Fn(T::Balance) -> Result<T::Balance, DispatchError>,
+        rescale_price: impl Fn(T::Balance) -> Result<T::Balance, DispatchError>,
+    ) -> Result<BTreeMap<PortfolioId, (T::Balance, T::Balance)>, DispatchError> {
+        let zero = T::Balance::from(0u32);
+        let mut locks = BTreeMap::new();
+        for (fundraiser_id, mut fundraiser) in
+            <Fundraisers<T>>::iter_prefix(offering_asset).collect::<Vec<_>>()
+        {
+            // Open fundraisers lock their unsold offering, and escrowed ones also lock what they sold until released.
+            let open = !fundraiser.is_closed();
+            let escrowing = open || fundraiser.status == FundraiserStatus::PendingRelease;
+            let (mut old, mut new) = (zero, zero);
+
+            for tier in fundraiser.tiers.iter_mut() {
+                let remaining = rescale(tier.remaining)?;
+                if open {
+                    old += tier.remaining;
+                    new += remaining;
+                }
+                tier.total = rescale(tier.total)?;
+                tier.remaining = remaining;
+                tier.price = rescale_price(tier.price)?;
+            }
+
+            let key = (offering_asset, fundraiser_id);
+            for (contribution_id, contribution) in
+                <Contributions<T>>::iter_prefix(key).collect::<Vec<_>>()
+            {
+                let amount = rescale(contribution.amount)?;
+                if escrowing {
+                    old += contribution.amount;
+                    new += amount;
+                }
+                <Contributions<T>>::insert(
+                    key,
+                    contribution_id,
+                    Contribution {
+                        amount,
+                        ..contribution
+                    },
+                );
+            }
+
+            // The funds locked for a bid still cover its rescaled amount at its rescaled price.
+            for (bid_id, bid) in <Bids<T>>::iter_prefix(key).collect::<Vec<_>>() {
+                let amount = rescale(bid.amount)?;
+                let price = rescale_price(bid.price)?;
+                <Bids<T>>::insert(
+                    key,
+                    bid_id,
+                    Bid {
+                        amount,
+                        price,
+                        ..bid
+                    },
+                );
+            }
+
+            if old > zero {
+                let lock = locks
+                    .entry(fundraiser.offering_portfolio)
+                    .or_insert((zero, zero));
+                lock.0 += old;
+                lock.1 += new;
+            }
+            <Fundraisers<T>>::insert(offering_asset, fundraiser_id, fundraiser);
+        }
+        Ok(locks)
+    }
+
+    /// Returns whether a fundraiser raising `raising_asset` is open, or still holds escrowed investments,
+    /// i.e., whether its prices, bids or investments are in amounts of `raising_asset`.
+    pub fn raises_funds_in(raising_asset: Ticker) -> bool {
+        <Fundraisers<T>>::iter().any(|(offering_asset, fundraiser_id, fundraiser)| {
+            fundraiser.raising_asset == raising_asset
+                && (!fundraiser.is_closed()
+                    || <Contributions<T>>::iter_prefix((offering_asset, fundraiser_id))
+                        .next()
+                        .is_some())
+        })
+    }
+
+    /// Returns the escrow portfolio of `investor`, creating it on its first escrowed investment.
+    ///
+    /// The portfolio is in the custody of the settlement module and can't receive assets through transfers,
+    /// so only this pallet moves funds in and out of it.
+    fn escrow_portfolio(investor: IdentityId) -> PortfolioId {
+        Self::escrow_portfolio_of(investor).unwrap_or_else(|| {
+            let portfolio = <Portfolio<T>>::create_custodied_portfolio(
+                investor,
+                PortfolioName::from(ESCROW_PORTFOLIO_NAME),
+                SettlementDID.as_id(),
+                PortfolioPolicy {
+                    allowed_tickers: Some(Vec::new()),
+                    affirmation_threshold: None,
+                },
+            );
+            EscrowPortfolios::insert(investor, portfolio);
+            portfolio
+        })
+    }
+
+    /// Returns the portfolio that the funds of an escrowed `contribution` are refunded to:
+    /// its funding portfolio, or the default portfolio of the investor if it was deleted.
+    fn refund_portfolio(contribution: &Contribution<T::Balance>) -> PortfolioId {
+        if <Portfolio<T>>::ensure_portfolio_validity(&contribution.funding_portfolio).is_ok() {
+            contribution.funding_portfolio
+        } else {
+            PortfolioId::default_portfolio(contribution.investor)
+        }
+    }
+
+    /// Moves the funds of an escrowed `contribution` to `fundraiser` back to the investor.
+    fn refund_contribution(
+        offering_asset: Ticker,
+        fundraiser_id: u64,
+        fundraiser: &Fundraiser<T::Balance, T::Moment>,
+        contribution: &Contribution<T::Balance>,
+    ) {
+        <Portfolio<T>>::unchecked_transfer_portfolio_balance(
+            &Self::escrow_portfolio(contribution.investor),
+            &Self::refund_portfolio(contribution),
+            &fundraiser.raising_asset,
+            contribution.cost,
+        );
+        Self::refund_invested_amount(
+            offering_asset,
+            fundraiser_id,
+            contribution.investor,
+            contribution.cost,
+        );
+    }
+
+    /// Removes `amount` from the investments of `investor` that count towards the cap of the fundraiser.
+    fn refund_invested_amount(
+        offering_asset: Ticker,
+        fundraiser_id: u64,
+        investor: IdentityId,
+        amount: T::Balance,
+    ) {
+        <InvestedAmount<T>>::mutate(
+            (offering_asset, fundraiser_id),
+            Self::scope_id_of(offering_asset, investor),
+            |invested| *invested = invested.saturating_sub(amount),
+        );
+    }
+
+    /// Returns the `ScopeId` of `did` for `ticker`, or `did` itself if it has none.
+    ///
+    /// Investors without a scope claim can only invest in uncapped fundraisers.
+    fn scope_id_of(ticker: Ticker, did: IdentityId) -> ScopeId {
+        if pallet_asset::ScopeIdOf::contains_key(ticker, did) {
+            Asset::<T>::scope_id_of(ticker, did)
+        } else {
+            did
+        }
+    }
+
+    /// Ensures that `origin` has asset permissions for `offering_asset`
+    /// and that the fundraiser exists and isn't closed.
+    fn ensure_fundraiser_perms(
+        origin: T::Origin,
+        offering_asset: Ticker,
+        fundraiser_id: u64,
+    ) -> Result<IdentityId, DispatchError> {
+        let did = <ExternalAgents<T>>::ensure_perms(origin, offering_asset)?;
+        let fundraiser = <Fundraisers<T>>::get(offering_asset, fundraiser_id)
+            .ok_or(Error::<T>::FundraiserNotFound)?;
+        ensure!(!fundraiser.is_closed(), Error::<T>::FundraiserClosed);
+        Ok(did)
+    }
+
+    /// Cost in the raising asset of `amount` offering tokens at `price`.
+    fn cost(amount: T::Balance, price: T::Balance) -> Result<T::Balance, DispatchError> {
+        amount
+            .checked_mul(&price)
+            .and_then(|cost| cost.checked_div(&PRICE_DIVISOR.into()))
+            .ok_or_else(|| Error::<T>::Overflow.into())
+    }
+
+    /// Allocates the offering of `tier` to `bids` according to `kind`.
+    ///
+    /// Returns the price paid by every filled bid and the amount allocated to each bid, with its id,
+    /// including the unfilled ones, whose allocation is zero.
+    /// Pro-rata allocations of indivisible assets are rounded down to whole units.
+    fn allocate(
+        kind: FundraiserKind,
+        tier: &FundraiserTier<T::Balance>,
+        mut bids: Vec<(u64, Bid<T::Balance>)>,
+        divisible: bool,
+    ) -> Result<(T::Balance, Vec<(u64, Bid<T::Balance>, T::Balance)>), DispatchError> {
+        let zero = T::Balance::from(0u32);
+        match kind {
+            FundraiserKind::DutchAuction => {
+                // Highest prices first, earliest bids first on ties.
+                bids.sort_by_key(|(bid_id, bid)| (Reverse(bid.price), *bid_id));
+                let mut remaining = tier.remaining;
+                let mut price = tier.price;
+                let allocations = bids
+                    .into_iter()
+                    .map(|(bid_id, bid)| {
+                        let amount = min(bid.amount, remaining);
+                        if amount > zero {
+                            remaining -= amount;
+                            price = bid.price;
+                        }
+                        (bid_id, bid, amount)
+                    })
+                    .collect();
+                Ok((price, allocations))
+            }
+            _ => {
+                let demand = bids
+                    .iter()
+                    .try_fold(zero, |total, (_, bid)| total.checked_add(&bid.amount))
+                    .ok_or(Error::<T>::Overflow)?;
+                let allocations = bids
+                    .into_iter()
+                    .map(|(bid_id, bid)| {
+                        let amount = if demand <= tier.remaining {
+                            bid.amount
+                        } else {
+                            let amount = bid
+                                .amount
+                                .checked_mul(&tier.remaining)
+                                .ok_or(Error::<T>::Overflow)?
+                                / demand;
+                            if divisible {
+                                amount
+                            } else {
+                                amount - amount % ONE_UNIT.into()
+                            }
+                        };
+                        Ok((bid_id, bid, amount))
+                    })
+                    .collect::<Result<_, DispatchError>>()?;
+                Ok((tier.price, allocations))
+            }
+        }
+    }
+
+    /// Attaches `vesting`, if any, to `amount` of `offering_asset` bought by `investor`.
+    fn add_vesting(
+        vesting: Option<VestingKind>,
+        investor: IdentityId,
+        investment_portfolio: PortfolioId,
+        offering_asset: Ticker,
+        amount: T::Balance,
+    ) -> DispatchResult {
+        match vesting {
+            Some(kind) => <Portfolio<T>>::add_vesting_schedule(
+                investor,
+                investment_portfolio,
+                offering_asset,
+                VestingSchedule { amount, kind },
+            ),
+            None => Ok(()),
+        }
+    }
+
+    /// Settles each of `contributions` to `fundraiser` in its own instruction,
+    /// affirmed on behalf of its investor, and attaches `vesting` to the bought tokens.
+    /// The funds of `escrowed` contributions are sent from the escrow portfolio of the investor.
+    ///
+    /// A contribution that fails to settle, e.g., because its investor lost its CDD claim
+    /// or no longer complies with the rules of the offering asset, is skipped without side effects.
+    /// Returns the ids of the contributions that failed to settle.
+    ///
+    /// The funds and offering tokens to transfer must be unlocked beforehand.
+    fn settle_contributions(
+        fundraiser: &Fundraiser<T::Balance, T::Moment>,
+        contributions: &[(u64, Contribution<T::Balance>)],
+        vesting: Option<VestingKind>,
+        escrowed: bool,
+    ) -> BTreeSet<u64> {
+        contributions
+            .iter()
+            .filter(|(_, contribution)| {
+                with_transaction(|| {
+                    Self::settle_contribution(fundraiser, contribution, escrowed)?;
+                    Self::add_vesting(
+                        vesting,
+                        contribution.investor,
+                        contribution.investment_portfolio,
+                        fundraiser.offering_asset,
+                        contribution.amount,
+                    )
+                })
+                .is_err()
+            })
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Settles `contribution` to `fundraiser` in an instruction affirmed on behalf of its investor,
+    /// and of the settlement module for the escrow portfolio of an `escrowed` contribution.
+    fn settle_contribution(
+        fundraiser: &Fundraiser<T::Balance, T::Moment>,
+        contribution: &Contribution<T::Balance>,
+        escrowed: bool,
+    ) -> DispatchResult {
+        let funding_portfolio = if escrowed {
+            Self::escrow_portfolio(contribution.investor)
+        } else {
+            contribution.funding_portfolio
+        };
+        let legs = vec![
+            Leg {
+                from: fundraiser.offering_portfolio,
+                to: contribution.investment_portfolio,
+                asset: fundraiser.offering_asset,
+                amount: contribution.amount,
+            },
+            Leg {
+                from: funding_portfolio,
+                to: fundraiser.raising_portfolio,
+                asset: fundraiser.raising_asset,
+                amount: contribution.cost,
+            },
+        ];
+        let instruction_id = Settlement::<T>::base_add_instruction(
+            fundraiser.creator,
+            fundraiser.venue_id,
+            SettlementType::SettleOnAffirmation,
+            None,
+            None,
+            legs,
+            None,
+        )?;
+
+        let portfolios = [fundraiser.offering_portfolio, fundraiser.raising_portfolio]
+            .iter()
+            .copied()
+            .collect::<BTreeSet<_>>();
+        Settlement::<T>::unsafe_affirm_instruction(
+            fundraiser.creator,
+            instruction_id,
+            portfolios,
+            1,
+            None,
+        )?;
+        let (investor_portfolios, investor_legs) = if escrowed {
+            Settlement::<T>::unsafe_affirm_instruction(
+                SettlementDID.as_id(),
+                instruction_id,
+                iter::once(funding_portfolio).collect(),
+                1,
+                None,
+            )?;
+            (iter::once(contribution.investment_portfolio).collect(), 0)
+        } else {
+            let portfolios = [contribution.investment_portfolio, funding_portfolio]
+                .iter()
+                .copied()
+                .collect::<BTreeSet<_>>();
+            (portfolios, 1)
+        };
+        Settlement::<T>::unsafe_affirm_instruction(
+            contribution.investor,
+            instruction_id,
+            investor_portfolios,
+            investor_legs,
+            None,
+        )?;
+        Settlement::<T>::execute_affirmed_instruction(instruction_id)
+    }
+
     fn set_frozen(
         origin: T::Origin,
         offering_asset: Ticker,
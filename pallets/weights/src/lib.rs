@@ -28,6 +28,7 @@ pub mod pallet_compliance_manager;
 pub mod pallet_corporate_actions;
 pub mod pallet_corporate_ballot;
 pub mod pallet_external_agents;
+pub mod pallet_fixed_income;
 pub mod pallet_grandpa;
 pub mod pallet_group;
 pub mod pallet_identity;
@@ -43,6 +44,8 @@ pub mod pallet_settlement;
 pub mod pallet_staking;
 pub mod pallet_statistics;
 pub mod pallet_sto;
+pub mod pallet_stock_split;
+pub mod pallet_tender_offer;
 pub mod pallet_test_utils;
 pub mod pallet_timestamp;
 pub mod pallet_treasury;
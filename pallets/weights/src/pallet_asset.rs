@@ -40,6 +40,31 @@ impl pallet_asset::WeightInfo for WeightInfo {
             .saturating_add(DbWeight::get().reads(8 as Weight))
             .saturating_add(DbWeight::get().writes(1 as Weight))
     }
+    fn freeze_identity() -> Weight {
+        (131_472_000 as Weight)
+            .saturating_add(DbWeight::get().reads(8 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn unfreeze_identity() -> Weight {
+        (128_906_000 as Weight)
+            .saturating_add(DbWeight::get().reads(8 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn freeze_portfolio() -> Weight {
+        (132_115_000 as Weight)
+            .saturating_add(DbWeight::get().reads(8 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn unfreeze_portfolio() -> Weight {
+        (129_374_000 as Weight)
+            .saturating_add(DbWeight::get().reads(8 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn set_frozen_balance() -> Weight {
+        (118_653_000 as Weight)
+            .saturating_add(DbWeight::get().reads(7 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
     fn rename_asset(n: u32) -> Weight {
         (130_878_000 as Weight)
             .saturating_add((8_000 as Weight).saturating_mul(n as Weight))
@@ -51,6 +76,11 @@ impl pallet_asset::WeightInfo for WeightInfo {
             .saturating_add(DbWeight::get().reads(18 as Weight))
             .saturating_add(DbWeight::get().writes(5 as Weight))
     }
+    fn issue_with_vesting() -> Weight {
+        (291_562_000 as Weight)
+            .saturating_add(DbWeight::get().reads(20 as Weight))
+            .saturating_add(DbWeight::get().writes(6 as Weight))
+    }
     fn redeem() -> Weight {
         (264_217_000 as Weight)
             .saturating_add(DbWeight::get().reads(16 as Weight))
@@ -116,9 +146,74 @@ impl pallet_asset::WeightInfo for WeightInfo {
             .saturating_add(DbWeight::get().reads(3 as Weight))
             .saturating_add(DbWeight::get().writes(3 as Weight))
     }
+    fn force_transfer() -> Weight {
+        (364_815_000 as Weight)
+            .saturating_add(DbWeight::get().reads(25 as Weight))
+            .saturating_add(DbWeight::get().writes(12 as Weight))
+    }
+    fn execute_forced_transfer() -> Weight {
+        (352_190_000 as Weight)
+            .saturating_add(DbWeight::get().reads(24 as Weight))
+            .saturating_add(DbWeight::get().writes(11 as Weight))
+    }
+    fn contest_forced_transfer() -> Weight {
+        (96_734_000 as Weight)
+            .saturating_add(DbWeight::get().reads(5 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn cancel_forced_transfer() -> Weight {
+        (118_402_000 as Weight)
+            .saturating_add(DbWeight::get().reads(10 as Weight))
+            .saturating_add(DbWeight::get().writes(3 as Weight))
+    }
     fn controller_transfer() -> Weight {
         (339_297_000 as Weight)
             .saturating_add(DbWeight::get().reads(19 as Weight))
             .saturating_add(DbWeight::get().writes(8 as Weight))
     }
+    fn register_asset_metadata_local_type(n: u32, s: u32) -> Weight {
+        (97_415_000 as Weight)
+            .saturating_add((9_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add((4_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(DbWeight::get().reads(10 as Weight))
+            .saturating_add(DbWeight::get().writes(4 as Weight))
+    }
+    fn register_asset_metadata_global_type(n: u32, s: u32) -> Weight {
+        (41_203_000 as Weight)
+            .saturating_add((9_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add((4_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(DbWeight::get().reads(2 as Weight))
+            .saturating_add(DbWeight::get().writes(4 as Weight))
+    }
+    fn set_asset_metadata(v: u32) -> Weight {
+        (104_672_000 as Weight)
+            .saturating_add((5_000 as Weight).saturating_mul(v as Weight))
+            .saturating_add(DbWeight::get().reads(10 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn set_asset_metadata_details() -> Weight {
+        (96_118_000 as Weight)
+            .saturating_add(DbWeight::get().reads(10 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn link_asset_class() -> Weight {
+        (131_842_000 as Weight)
+            .saturating_add(DbWeight::get().reads(17 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+    }
+    fn unlink_asset_class() -> Weight {
+        (127_560_000 as Weight)
+            .saturating_add(DbWeight::get().reads(9 as Weight))
+            .saturating_add(DbWeight::get().writes(20 as Weight))
+    }
+    fn set_class_conversion() -> Weight {
+        (124_215_000 as Weight)
+            .saturating_add(DbWeight::get().reads(19 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn convert_class() -> Weight {
+        (451_836_000 as Weight)
+            .saturating_add(DbWeight::get().reads(40 as Weight))
+            .saturating_add(DbWeight::get().writes(18 as Weight))
+    }
 }
@@ -36,4 +36,15 @@ impl pallet_corporate_actions::distribution::WeightInfo for WeightInfo {
             .saturating_add(DbWeight::get().reads(11 as Weight))
             .saturating_add(DbWeight::get().writes(2 as Weight))
     }
+    fn set_scrip_option() -> Weight {
+        (121_346_000 as Weight)
+            .saturating_add(DbWeight::get().reads(9 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn elect_scrip(t: u32) -> Weight {
+        (98_702_000 as Weight)
+            .saturating_add((421_000 as Weight).saturating_mul(t as Weight))
+            .saturating_add(DbWeight::get().reads(7 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
 }
@@ -36,6 +36,14 @@ impl pallet_corporate_actions::ballot::WeightInfo for WeightInfo {
             .saturating_add(DbWeight::get().reads(10 as Weight))
             .saturating_add(DbWeight::get().writes(1 as Weight))
     }
+    fn set_class_weights(c: u32) -> Weight {
+        (171_208_000 as Weight)
+            .saturating_add((88_342_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(DbWeight::get().reads(10 as Weight))
+            .saturating_add(DbWeight::get().reads((4 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+            .saturating_add(DbWeight::get().writes((5 as Weight).saturating_mul(c as Weight)))
+    }
     fn remove_ballot() -> Weight {
         (175_364_000 as Weight)
             .saturating_add(DbWeight::get().reads(10 as Weight))
@@ -0,0 +1,36 @@
+//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 2.0.1
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use polymesh_runtime_common::{RocksDbWeight as DbWeight, Weight};
+
+pub struct WeightInfo;
+impl pallet_corporate_actions::fixed_income::WeightInfo for WeightInfo {
+    fn set_up_bond() -> Weight {
+        (331_207_000 as Weight)
+            .saturating_add(DbWeight::get().reads(23 as Weight))
+            .saturating_add(DbWeight::get().writes(13 as Weight))
+    }
+    fn process_bond() -> Weight {
+        (246_918_000 as Weight)
+            .saturating_add(DbWeight::get().reads(24 as Weight))
+            .saturating_add(DbWeight::get().writes(9 as Weight))
+    }
+    fn redeem_holder(p: u32) -> Weight {
+        (148_297_000 as Weight)
+            .saturating_add((41_562_000 as Weight).saturating_mul(p as Weight))
+            .saturating_add(DbWeight::get().reads(9 as Weight))
+            .saturating_add(DbWeight::get().reads((5 as Weight).saturating_mul(p as Weight)))
+            .saturating_add(DbWeight::get().writes(5 as Weight))
+            .saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(p as Weight)))
+    }
+    fn redeem_pending(p: u32) -> Weight {
+        (187_654_000 as Weight)
+            .saturating_add((41_803_000 as Weight).saturating_mul(p as Weight))
+            .saturating_add(DbWeight::get().reads(14 as Weight))
+            .saturating_add(DbWeight::get().reads((5 as Weight).saturating_mul(p as Weight)))
+            .saturating_add(DbWeight::get().writes(6 as Weight))
+            .saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(p as Weight)))
+    }
+}
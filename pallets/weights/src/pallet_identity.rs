@@ -57,18 +57,70 @@ impl pallet_identity::WeightInfo for WeightInfo {
     }
     fn add_claim() -> Weight {
         (171_882_000 as Weight)
-            .saturating_add(DbWeight::get().reads(11 as Weight))
-            .saturating_add(DbWeight::get().writes(1 as Weight))
+            .saturating_add(DbWeight::get().reads(12 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
     }
     fn revoke_claim() -> Weight {
         (159_965_000 as Weight)
             .saturating_add(DbWeight::get().reads(8 as Weight))
-            .saturating_add(DbWeight::get().writes(1 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
     }
     fn revoke_claim_by_index() -> Weight {
         (140_936_000 as Weight)
             .saturating_add(DbWeight::get().reads(9 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+    }
+    fn set_claim_issuer_profile(t: u32, j: u32) -> Weight {
+        (52_118_000 as Weight)
+            .saturating_add((1_021_000 as Weight).saturating_mul(t as Weight))
+            .saturating_add((312_000 as Weight).saturating_mul(j as Weight))
+            .saturating_add(DbWeight::get().reads(5 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn remove_claim_issuer_profile() -> Weight {
+        (44_903_000 as Weight)
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn suspend_claim_issuer() -> Weight {
+        (27_640_000 as Weight)
+            .saturating_add(DbWeight::get().reads(1 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn resume_claim_issuer() -> Weight {
+        (27_112_000 as Weight)
+            .saturating_add(DbWeight::get().reads(1 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn revoke_issued_claims(c: u32) -> Weight {
+        (61_255_000 as Weight)
+            .saturating_add((48_913_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(DbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+    }
+    fn add_claims_batch(c: u32) -> Weight {
+        (78_433_000 as Weight)
+            .saturating_add((52_671_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(DbWeight::get().reads(9 as Weight))
+            .saturating_add(DbWeight::get().reads((3 as Weight).saturating_mul(c as Weight)))
             .saturating_add(DbWeight::get().writes(1 as Weight))
+            .saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+    }
+    fn revoke_claims_batch(c: u32) -> Weight {
+        (54_120_000 as Weight)
+            .saturating_add((41_387_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+    }
+    fn add_claims_batch_signed(c: u32) -> Weight {
+        (203_581_000 as Weight)
+            .saturating_add((52_904_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(DbWeight::get().reads(11 as Weight))
+            .saturating_add(DbWeight::get().reads((3 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+            .saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
     }
     fn set_permission_to_signer() -> Weight {
         (117_012_000 as Weight)
@@ -20,8 +20,8 @@ impl pallet_portfolio::WeightInfo for WeightInfo {
     fn move_portfolio_funds(a: u32) -> Weight {
         (117_433_000 as Weight)
             .saturating_add((62_083_000 as Weight).saturating_mul(a as Weight))
-            .saturating_add(DbWeight::get().reads(8 as Weight))
-            .saturating_add(DbWeight::get().reads((3 as Weight).saturating_mul(a as Weight)))
+            .saturating_add(DbWeight::get().reads(10 as Weight))
+            .saturating_add(DbWeight::get().reads((7 as Weight).saturating_mul(a as Weight)))
             .saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(a as Weight)))
     }
     fn rename_portfolio(i: u32) -> Weight {
@@ -40,4 +40,17 @@ impl pallet_portfolio::WeightInfo for WeightInfo {
             .saturating_add(DbWeight::get().reads(8 as Weight))
             .saturating_add(DbWeight::get().writes(5 as Weight))
     }
+    fn set_portfolio_policy(t: u32) -> Weight {
+        (84_315_000 as Weight)
+            .saturating_add((1_262_000 as Weight).saturating_mul(t as Weight))
+            .saturating_add(DbWeight::get().reads(7 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn rebalance_portfolios(m: u32) -> Weight {
+        (96_218_000 as Weight)
+            .saturating_add((81_347_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(DbWeight::get().reads((13 as Weight).saturating_mul(m as Weight)))
+            .saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(m as Weight)))
+    }
 }
@@ -135,4 +135,46 @@ impl pallet_settlement::WeightInfo for WeightInfo {
             .saturating_add(DbWeight::get().writes(4 as Weight))
             .saturating_add(DbWeight::get().writes((14 as Weight).saturating_mul(l as Weight)))
     }
+    fn execute_netted_instructions(l: u32) -> Weight {
+        (187_542_000 as Weight)
+            .saturating_add((2_671_904_000 as Weight).saturating_mul(l as Weight))
+            .saturating_add(DbWeight::get().reads(12 as Weight))
+            .saturating_add(DbWeight::get().reads((77 as Weight).saturating_mul(l as Weight)))
+            .saturating_add(DbWeight::get().writes(5 as Weight))
+            .saturating_add(DbWeight::get().writes((14 as Weight).saturating_mul(l as Weight)))
+    }
+    fn add_instruction_with_expiry(l: u32) -> Weight {
+        (215_364_000 as Weight)
+            .saturating_add((80_315_000 as Weight).saturating_mul(l as Weight))
+            .saturating_add(DbWeight::get().reads(5 as Weight))
+            .saturating_add(DbWeight::get().reads((3 as Weight).saturating_mul(l as Weight)))
+            .saturating_add(DbWeight::get().writes(7 as Weight))
+            .saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(l as Weight)))
+    }
+    fn add_and_affirm_instruction_with_expiry(l: u32) -> Weight {
+        (318_455_000 as Weight)
+            .saturating_add((150_102_000 as Weight).saturating_mul(l as Weight))
+            .saturating_add(DbWeight::get().reads(13 as Weight))
+            .saturating_add(DbWeight::get().reads((5 as Weight).saturating_mul(l as Weight)))
+            .saturating_add(DbWeight::get().writes(8 as Weight))
+            .saturating_add(DbWeight::get().writes((6 as Weight).saturating_mul(l as Weight)))
+    }
+    fn expire_instruction(l: u32) -> Weight {
+        (98_517_000 as Weight)
+            .saturating_add((41_260_000 as Weight).saturating_mul(l as Weight))
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().reads((3 as Weight).saturating_mul(l as Weight)))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+            .saturating_add(DbWeight::get().writes((3 as Weight).saturating_mul(l as Weight)))
+    }
+    fn affirm_with_claims(l: u32, c: u32) -> Weight {
+        (161_962_000 as Weight)
+            .saturating_add((2_718_394_000 as Weight).saturating_mul(l as Weight))
+            .saturating_add((62_485_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(DbWeight::get().reads(15 as Weight))
+            .saturating_add(DbWeight::get().reads((79 as Weight).saturating_mul(l as Weight)))
+            .saturating_add(DbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(DbWeight::get().writes(9 as Weight))
+            .saturating_add(DbWeight::get().writes((16 as Weight).saturating_mul(l as Weight)))
+    }
 }
@@ -9,13 +9,13 @@ pub struct WeightInfo;
 impl pallet_statistics::WeightInfo for WeightInfo {
     fn add_transfer_manager() -> Weight {
         (140_560_000 as Weight)
-            .saturating_add(DbWeight::get().reads(8 as Weight))
-            .saturating_add(DbWeight::get().writes(1 as Weight))
+            .saturating_add(DbWeight::get().reads(10 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
     }
     fn remove_transfer_manager() -> Weight {
         (142_988_000 as Weight)
-            .saturating_add(DbWeight::get().reads(8 as Weight))
-            .saturating_add(DbWeight::get().writes(1 as Weight))
+            .saturating_add(DbWeight::get().reads(10 as Weight))
+            .saturating_add(DbWeight::get().writes(3 as Weight))
     }
     fn add_exempted_entities(i: u32) -> Weight {
         (195_817_000 as Weight)
@@ -14,9 +14,16 @@ impl pallet_sto::WeightInfo for WeightInfo {
             .saturating_add(DbWeight::get().writes(4 as Weight))
     }
     fn invest() -> Weight {
-        (6_049_179_000 as Weight)
-            .saturating_add(DbWeight::get().reads(159 as Weight))
-            .saturating_add(DbWeight::get().writes(30 as Weight))
+        (6_093_412_000 as Weight)
+            .saturating_add(DbWeight::get().reads(163 as Weight))
+            .saturating_add(DbWeight::get().writes(35 as Weight))
+    }
+    fn invest_with_claims(c: u32) -> Weight {
+        (6_093_412_000 as Weight)
+            .saturating_add((62_485_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(DbWeight::get().reads(163 as Weight))
+            .saturating_add(DbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(DbWeight::get().writes(33 as Weight))
     }
     fn freeze_fundraiser() -> Weight {
         (165_192_000 as Weight)
@@ -38,4 +45,58 @@ impl pallet_sto::WeightInfo for WeightInfo {
             .saturating_add(DbWeight::get().reads(10 as Weight))
             .saturating_add(DbWeight::get().writes(2 as Weight))
     }
+    fn submit_bid() -> Weight {
+        (263_871_000 as Weight)
+            .saturating_add(DbWeight::get().reads(17 as Weight))
+            .saturating_add(DbWeight::get().writes(4 as Weight))
+    }
+    fn cancel_bid() -> Weight {
+        (184_215_000 as Weight)
+            .saturating_add(DbWeight::get().reads(10 as Weight))
+            .saturating_add(DbWeight::get().writes(3 as Weight))
+    }
+    fn close_and_allocate(b: u32) -> Weight {
+        (612_845_000 as Weight)
+            .saturating_add((2_947_316_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(DbWeight::get().reads(17 as Weight))
+            .saturating_add(DbWeight::get().reads((80 as Weight).saturating_mul(b as Weight)))
+            .saturating_add(DbWeight::get().writes(9 as Weight))
+            .saturating_add(DbWeight::get().writes((17 as Weight).saturating_mul(b as Weight)))
+    }
+    fn set_investor_restrictions(c: u32) -> Weight {
+        (158_724_000 as Weight)
+            .saturating_add((1_206_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(DbWeight::get().reads(9 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn add_allowed_investors(i: u32) -> Weight {
+        (151_390_000 as Weight)
+            .saturating_add((4_118_000 as Weight).saturating_mul(i as Weight))
+            .saturating_add(DbWeight::get().reads(9 as Weight))
+            .saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(i as Weight)))
+    }
+    fn remove_allowed_investors(i: u32) -> Weight {
+        (149_867_000 as Weight)
+            .saturating_add((3_964_000 as Weight).saturating_mul(i as Weight))
+            .saturating_add(DbWeight::get().reads(9 as Weight))
+            .saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(i as Weight)))
+    }
+    fn release(c: u32) -> Weight {
+        (597_203_000 as Weight)
+            .saturating_add((2_861_544_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(DbWeight::get().reads(18 as Weight))
+            .saturating_add(DbWeight::get().reads((79 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(DbWeight::get().writes(10 as Weight))
+            .saturating_add(DbWeight::get().writes((19 as Weight).saturating_mul(c as Weight)))
+    }
+    fn claim_refund() -> Weight {
+        (176_942_000 as Weight)
+            .saturating_add(DbWeight::get().reads(14 as Weight))
+            .saturating_add(DbWeight::get().writes(7 as Weight))
+    }
+    fn set_fundraiser_vesting() -> Weight {
+        (146_218_000 as Weight)
+            .saturating_add(DbWeight::get().reads(9 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
 }
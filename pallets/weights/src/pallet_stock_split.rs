@@ -0,0 +1,33 @@
+//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 2.0.1
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use polymesh_runtime_common::{RocksDbWeight as DbWeight, Weight};
+
+pub struct WeightInfo;
+impl pallet_corporate_actions::split::WeightInfo for WeightInfo {
+    fn attach_split() -> Weight {
+        (132_418_000 as Weight)
+            .saturating_add(DbWeight::get().reads(11 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn remove_split() -> Weight {
+        (84_215_000 as Weight)
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn execute_split(h: u32) -> Weight {
+        (156_904_000 as Weight)
+            .saturating_add((187_362_000 as Weight).saturating_mul(h as Weight))
+            .saturating_add(DbWeight::get().reads(13 as Weight))
+            .saturating_add(DbWeight::get().reads((16 as Weight).saturating_mul(h as Weight)))
+            .saturating_add(DbWeight::get().writes(3 as Weight))
+            .saturating_add(DbWeight::get().writes((11 as Weight).saturating_mul(h as Weight)))
+    }
+    fn claim_cash_in_lieu() -> Weight {
+        (326_871_000 as Weight)
+            .saturating_add(DbWeight::get().reads(24 as Weight))
+            .saturating_add(DbWeight::get().writes(6 as Weight))
+    }
+}
@@ -0,0 +1,33 @@
+//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 2.0.1
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use polymesh_runtime_common::{RocksDbWeight as DbWeight, Weight};
+
+pub struct WeightInfo;
+impl pallet_corporate_actions::tender::WeightInfo for WeightInfo {
+    fn launch_tender_offer() -> Weight {
+        (238_470_000 as Weight)
+            .saturating_add(DbWeight::get().reads(15 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+    }
+    fn tender() -> Weight {
+        (171_533_000 as Weight)
+            .saturating_add(DbWeight::get().reads(13 as Weight))
+            .saturating_add(DbWeight::get().writes(3 as Weight))
+    }
+    fn remove_tender_offer() -> Weight {
+        (152_896_000 as Weight)
+            .saturating_add(DbWeight::get().reads(9 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+    }
+    fn settle_tender_offer(t: u32) -> Weight {
+        (174_208_000 as Weight)
+            .saturating_add((1_182_640_000 as Weight).saturating_mul(t as Weight))
+            .saturating_add(DbWeight::get().reads(9 as Weight))
+            .saturating_add(DbWeight::get().reads((58 as Weight).saturating_mul(t as Weight)))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+            .saturating_add(DbWeight::get().writes((22 as Weight).saturating_mul(t as Weight)))
+    }
+}
@@ -16,6 +16,7 @@
 use crate::compliance_manager::AssetComplianceResult;
 use crate::identity_id::PortfolioValidityResult;
 use crate::statistics::TransferManagerResult;
+use crate::Moment;
 use codec::{Decode, Encode};
 use polymesh_primitives_derive::VecU8StrongTyped;
 #[cfg(feature = "std")]
@@ -74,6 +75,59 @@ impl Default for AssetType {
     }
 }
 
+/// The reason for a forced transfer of an asset by its agents.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForcedTransferReason {
+    /// Recovery of the assets of a holder who lost access to their keys.
+    LostKeyRecovery,
+    /// Enforcement of a court order.
+    CourtOrder,
+    /// Enforcement of a decision of a regulator.
+    RegulatoryAction,
+    /// Correction of an erroneous transfer.
+    ErroneousTransfer,
+    /// Any other reason, detailed in the evidence document.
+    Other,
+}
+
+impl Default for ForcedTransferReason {
+    fn default() -> Self {
+        Self::Other
+    }
+}
+
+/// The terms on which holders may convert one class of an asset family into another.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ClassConversion {
+    /// The number of shares of the class converted into...
+    pub numerator: u32,
+    /// ...for this number of shares of the class converted from.
+    pub denominator: u32,
+    /// When conversions open, if not already.
+    pub start: Option<Moment>,
+    /// When conversions close, if ever.
+    pub end: Option<Moment>,
+}
+
+impl ClassConversion {
+    /// Returns whether the ratio is non-zero and finite, and the window is non-empty.
+    pub fn is_valid(&self) -> bool {
+        self.numerator > 0
+            && self.denominator > 0
+            && self
+                .start
+                .zip(self.end)
+                .map_or(true, |(start, end)| start < end)
+    }
+
+    /// Returns whether conversions are open at `now`.
+    pub fn is_open(&self, now: Moment) -> bool {
+        self.start.map_or(true, |start| start <= now) && self.end.map_or(true, |end| now < end)
+    }
+}
+
 /// A wrapper for a funding round name.
 #[derive(
     Decode, Encode, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Default, VecU8StrongTyped,
@@ -104,6 +158,18 @@ pub struct GranularCanTransferResult {
     pub portfolio_validity_result: PortfolioValidityResult,
     /// Asset is frozen.
     pub asset_frozen: bool,
+    /// Sender identity is frozen for the asset.
+    pub sender_identity_frozen: bool,
+    /// Receiver identity is frozen for the asset.
+    pub receiver_identity_frozen: bool,
+    /// Sender portfolio is frozen for the asset.
+    pub sender_portfolio_frozen: bool,
+    /// Receiver portfolio is frozen for the asset.
+    pub receiver_portfolio_frozen: bool,
+    /// Sender would be left with less than its frozen balance.
+    pub sender_frozen_balance_exceeded: bool,
+    /// Asset has reached its maturity date.
+    pub asset_matured: bool,
     /// Result of statistics check.
     pub statistics_result: Vec<TransferManagerResult>,
     /// Result of compliance check.
@@ -0,0 +1,204 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymathNetwork/Polymesh).
+// Copyright (c) 2020 Polymath
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{Moment, Ticker};
+use codec::{Decode, Encode};
+use polymesh_primitives_derive::VecU8StrongTyped;
+use sp_arithmetic::Perbill;
+#[cfg(feature = "std")]
+use sp_runtime::{Deserialize, Serialize};
+use sp_std::prelude::Vec;
+
+/// A wrapper for the name of an asset metadata key.
+#[derive(
+    Decode, Encode, Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord, VecU8StrongTyped,
+)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AssetMetadataName(pub Vec<u8>);
+
+/// The ID of a metadata key defined by governance, shared by every asset.
+#[derive(Decode, Encode, Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AssetMetadataGlobalKey(pub u64);
+
+/// The ID of a metadata key defined by the agents of a single asset.
+#[derive(Decode, Encode, Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AssetMetadataLocalKey(pub u64);
+
+/// A metadata key, either global or local to an asset.
+#[derive(Decode, Encode, Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum AssetMetadataKey {
+    /// A key defined by governance.
+    Global(AssetMetadataGlobalKey),
+    /// A key defined by the agents of the asset.
+    Local(AssetMetadataLocalKey),
+}
+
+/// The type of the values of a metadata key.
+#[derive(Decode, Encode, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum AssetMetadataValueType {
+    /// Arbitrary bytes.
+    Bytes,
+    /// UTF-8 encoded text.
+    Text,
+    /// A boolean.
+    Bool,
+    /// An unsigned integer, e.g., a par value in the smallest units of a currency.
+    Integer,
+    /// A percentage, e.g., a coupon rate.
+    Percentage,
+    /// A moment in time, e.g., a maturity date.
+    Moment,
+    /// The ticker of an asset, e.g., a currency.
+    Ticker,
+}
+
+/// The specification of a metadata key, against which its values are checked.
+#[derive(Decode, Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AssetMetadataSpec {
+    /// The type of the values of the key.
+    pub value_type: AssetMetadataValueType,
+    /// A human readable description of the key.
+    pub description: Option<Vec<u8>>,
+    /// A URL to a more detailed specification of the key, e.g., an ISIN standard.
+    pub url: Option<Vec<u8>>,
+}
+
+impl AssetMetadataSpec {
+    /// Returns the total length of the description and the URL.
+    pub fn bytes_len(&self) -> usize {
+        self.description.as_ref().map_or(0, |d| d.len()) + self.url.as_ref().map_or(0, |u| u.len())
+    }
+}
+
+/// A typed metadata value.
+#[derive(Decode, Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum AssetMetadataValue {
+    /// Arbitrary bytes.
+    Bytes(Vec<u8>),
+    /// UTF-8 encoded text.
+    Text(Vec<u8>),
+    /// A boolean.
+    Bool(bool),
+    /// An unsigned integer.
+    Integer(u128),
+    /// A percentage.
+    Percentage(Perbill),
+    /// A moment in time.
+    Moment(Moment),
+    /// The ticker of an asset.
+    Ticker(Ticker),
+}
+
+impl AssetMetadataValue {
+    /// Returns the type of the value.
+    pub fn value_type(&self) -> AssetMetadataValueType {
+        match self {
+            Self::Bytes(_) => AssetMetadataValueType::Bytes,
+            Self::Text(_) => AssetMetadataValueType::Text,
+            Self::Bool(_) => AssetMetadataValueType::Bool,
+            Self::Integer(_) => AssetMetadataValueType::Integer,
+            Self::Percentage(_) => AssetMetadataValueType::Percentage,
+            Self::Moment(_) => AssetMetadataValueType::Moment,
+            Self::Ticker(_) => AssetMetadataValueType::Ticker,
+        }
+    }
+
+    /// Returns the variable length part of the value, if any.
+    pub fn bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Bytes(bytes) | Self::Text(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the value is well-formed, i.e., text is valid UTF-8.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::Text(text) => core::str::from_utf8(text).is_ok(),
+            _ => true,
+        }
+    }
+}
+
+/// Whether a metadata value can be changed.
+#[derive(Decode, Encode, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum AssetMetadataLockStatus<Moment> {
+    /// The value can be changed.
+    Unlocked,
+    /// The value can never be changed again.
+    Locked,
+    /// The value can not be changed until the given moment.
+    LockedUntil(Moment),
+}
+
+impl<Moment> Default for AssetMetadataLockStatus<Moment> {
+    fn default() -> Self {
+        Self::Unlocked
+    }
+}
+
+impl<Moment: PartialOrd> AssetMetadataLockStatus<Moment> {
+    /// Returns `true` if the value can not be changed at `now`.
+    pub fn is_locked(&self, now: &Moment) -> bool {
+        match self {
+            Self::Unlocked => false,
+            Self::Locked => true,
+            Self::LockedUntil(until) => now < until,
+        }
+    }
+}
+
+/// The expiry and lock status of a metadata value.
+#[derive(Decode, Encode, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AssetMetadataValueDetail<Moment> {
+    /// When the value expires, if ever. Expired values are no longer reported.
+    pub expire: Option<Moment>,
+    /// Whether the value can be changed.
+    pub lock_status: AssetMetadataLockStatus<Moment>,
+}
+
+/// A metadata value of an asset, along with its details and version.
+#[derive(Decode, Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AssetMetadataEntry<Moment> {
+    /// The value.
+    pub value: AssetMetadataValue,
+    /// The expiry and lock status of the value.
+    pub detail: AssetMetadataValueDetail<Moment>,
+    /// The number of times the value was set, starting at 1.
+    pub version: u32,
+}
+
+/// A metadata value of an asset with the name and specification of its key, as reported by RPC.
+#[derive(Decode, Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AssetMetadataItem<Moment> {
+    /// The key.
+    pub key: AssetMetadataKey,
+    /// The name of the key.
+    pub name: AssetMetadataName,
+    /// The specification of the key.
+    pub spec: AssetMetadataSpec,
+    /// The unexpired value of the key.
+    pub entry: AssetMetadataEntry<Moment>,
+}
@@ -13,7 +13,7 @@ pub struct CheckpointId(pub u64);
 
 /// Calendar units for timing recurring operations.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum CalendarUnit {
     /// A unit of one second.
     Second,
@@ -67,6 +67,24 @@ impl CalendarUnit {
             Self::Year => Some(VariableCalendarUnit::Year),
         }
     }
+
+    /// Returns the index, counting from the Unix epoch, of the unit containing the given time
+    /// in milli-seconds Unix time, e.g., the number of whole days or calendar months since the epoch.
+    pub fn index_at(self, now_as_ms_utc: Moment) -> Option<u64> {
+        let now_as_secs_utc = now_as_ms_utc / 1000;
+        match self.to_variable_unit() {
+            None => Some(now_as_secs_utc / self.seconds_in()),
+            Some(variable_unit) => {
+                let date_now =
+                    NaiveDateTime::from_timestamp(i64::try_from(now_as_secs_utc).ok()?, 0).date();
+                let years = u64::try_from(date_now.year() - 1970).ok()?;
+                Some(match variable_unit {
+                    VariableCalendarUnit::Month => years * 12 + u64::from(date_now.month0()),
+                    VariableCalendarUnit::Year => years,
+                })
+            }
+        }
+    }
 }
 
 /// Calendar units that have variable length.
@@ -87,7 +105,7 @@ pub enum FixedOrVariableCalendarUnit {
 
 /// A simple period which is a multiple of a `CalendarUnit`.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-#[derive(Encode, Decode, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Encode, Decode, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CalendarPeriod {
     /// The base calendar unit.
     pub unit: CalendarUnit,
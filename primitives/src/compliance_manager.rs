@@ -13,7 +13,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use crate::Condition;
+use crate::{Condition, IdentityId};
 use codec::{Decode, Encode};
 #[cfg(feature = "std")]
 use sp_runtime::{Deserialize, Serialize};
@@ -35,14 +35,10 @@ pub struct ComplianceRequirement {
 impl ComplianceRequirement {
     /// Dedup `ClaimType`s in `TrustedFor::Specific`.
     pub fn dedup(&mut self) {
-        let dedup_condition = |conds: &mut [Condition]| {
-            conds
-                .iter_mut()
-                .flat_map(|c| &mut c.issuers)
-                .for_each(|issuer| issuer.dedup())
-        };
-        dedup_condition(&mut self.sender_conditions);
-        dedup_condition(&mut self.receiver_conditions);
+        self.sender_conditions
+            .iter_mut()
+            .chain(self.receiver_conditions.iter_mut())
+            .for_each(Condition::dedup);
     }
 
     /// Returns an iterator for all conditions in this requirement.
@@ -87,6 +83,8 @@ pub struct ConditionResult {
     pub condition: Condition,
     /// Result of evaluation
     pub result: bool,
+    /// Results of the conditions nested in `condition`, in order, if it is composite.
+    pub nested: Vec<ConditionResult>,
 }
 
 impl From<Condition> for ConditionResult {
@@ -94,6 +92,7 @@ impl From<Condition> for ConditionResult {
         Self {
             condition,
             result: true,
+            nested: Vec::new(),
         }
     }
 }
@@ -133,3 +132,27 @@ impl From<AssetCompliance> for AssetComplianceResult {
         }
     }
 }
+
+/// Evaluation of a set of compliance requirements against a single holder of an asset.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Hash)]
+pub struct HolderComplianceResult {
+    /// Identity of the holder.
+    pub did: IdentityId,
+    /// Whether the sender conditions of any requirement are satisfied by the holder.
+    pub sender: bool,
+    /// Whether the receiver conditions of any requirement are satisfied by the holder.
+    pub receiver: bool,
+    /// Requirements, each evaluated with the holder as both sender and receiver.
+    pub requirements: Vec<ComplianceRequirementResult>,
+}
+
+/// A page of a compliance simulation over the holders of an asset.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, Hash)]
+pub struct ComplianceSimulationResult {
+    /// Holders in the page that would fail as either sender or receiver.
+    pub failures: Vec<HolderComplianceResult>,
+    /// Index of the first holder of the next page, if there is one.
+    pub next: Option<u32>,
+}
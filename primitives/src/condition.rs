@@ -16,13 +16,24 @@
 use crate as polymesh_primitives;
 use crate::{
     migrate::{Empty, Migrate},
-    Claim, ClaimType, IdentityId,
+    AttributeKind, AttributeValue, Claim, ClaimType, IdentityId, Moment, Scope,
 };
-use codec::{Decode, Encode};
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use codec::{Compact, Decode, Encode, Input};
 use polymesh_primitives_derive::Migrate;
 #[cfg(feature = "std")]
 use sp_runtime::{Deserialize, Serialize};
-use sp_std::prelude::*;
+use sp_std::{
+    borrow::Cow,
+    convert::{TryFrom, TryInto},
+    prelude::*,
+};
+
+const MS_IN_DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// Maximum number of composite conditions that can be nested in one another.
+/// Deeper conditions fail to decode, which bounds the recursion of decoding them.
+pub const MAX_CONDITION_DEPTH: u32 = 8;
 
 /// Defines a static / dynamic identity.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -34,10 +45,62 @@ pub enum TargetIdentity {
     Specific(IdentityId),
 }
 
+/// A predicate on the value of a typed attribute claim.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum AttributePredicate {
+    /// The value is strictly greater than the given one.
+    GreaterThan(AttributeValue),
+    /// The value is strictly less than the given one.
+    LessThan(AttributeValue),
+    /// The value is equal to the given one.
+    EqualTo(AttributeValue),
+    /// The value is a date within the given number of days before now.
+    WithinDays(u32),
+    /// The value is a date at least the given number of calendar years before now.
+    AtLeastYearsAgo(u32),
+}
+
+impl AttributePredicate {
+    /// Returns whether `value` satisfies this predicate at time `now`.
+    /// Values and predicate operands of different variants never satisfy the predicate.
+    pub fn holds(&self, value: &AttributeValue, now: Moment) -> bool {
+        use AttributeValue::{Date, Number};
+        match (self, value) {
+            (Self::GreaterThan(Number(x)), Number(v)) => v > x,
+            (Self::GreaterThan(Date(x)), Date(v)) => v > x,
+            (Self::LessThan(Number(x)), Number(v)) => v < x,
+            (Self::LessThan(Date(x)), Date(v)) => v < x,
+            (Self::EqualTo(x), v) => x == v,
+            (Self::WithinDays(days), Date(v)) => {
+                *v <= now && now - v <= u64::from(*days).saturating_mul(MS_IN_DAY)
+            }
+            (Self::AtLeastYearsAgo(years), Date(v)) => {
+                years_before(now, *years).map_or(false, |bound| *v <= bound)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns the moment `years` calendar years before `now`, if it is representable.
+/// A 29th of February maps to the 28th of February in non-leap years.
+fn years_before(now: Moment, years: u32) -> Option<Moment> {
+    let now_secs = i64::try_from(now / 1000).ok()?;
+    let now = NaiveDateTime::from_timestamp(now_secs, 0);
+    let year = now.year().checked_sub(years.try_into().ok()?)?;
+    let date = NaiveDate::from_ymd_opt(year, now.month(), now.day())
+        .or_else(|| NaiveDate::from_ymd_opt(year, now.month(), now.day() - 1))?;
+    let secs = date.and_time(now.time()).timestamp();
+    u64::try_from(secs).ok()?.checked_mul(1000)
+}
+
 /// It defines the type of condition supported, and the filter information we will use to evaluate as a
 /// predicate.
+///
+/// Decoding fails for conditions nesting more than `MAX_CONDITION_DEPTH` composite conditions.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, Hash)]
+#[derive(Encode, Clone, PartialEq, Eq, Debug, Hash)]
 pub enum ConditionType {
     /// Condition to ensure that claim filter produces one claim.
     IsPresent(Claim),
@@ -49,6 +112,15 @@ pub enum ConditionType {
     IsNoneOf(Vec<Claim>),
     /// Condition to ensure that the sender/receiver is a particular identity or primary issuance agent
     IsIdentity(TargetIdentity),
+    /// Condition to ensure that all of the nested conditions hold.
+    All(Vec<Condition>),
+    /// Condition to ensure that at least one of the nested conditions holds.
+    Any(Vec<Condition>),
+    /// Condition to ensure that the nested condition does not hold.
+    Not(Box<Condition>),
+    /// Condition to ensure that an attribute claim of the given kind and scope
+    /// has a value satisfying the predicate.
+    HasAttribute(AttributeKind, Scope, AttributePredicate),
 }
 
 impl ConditionType {
@@ -56,12 +128,71 @@ impl ConditionType {
         match self {
             ConditionType::IsIdentity(..)
             | ConditionType::IsPresent(..)
-            | ConditionType::IsAbsent(..) => 1,
+            | ConditionType::IsAbsent(..)
+            | ConditionType::HasAttribute(..) => 1,
             ConditionType::IsNoneOf(ref claims) | ConditionType::IsAnyOf(ref claims) => {
                 claims.len()
             }
+            ConditionType::All(..) | ConditionType::Any(..) | ConditionType::Not(..) => {
+                self.nested().iter().fold(1, |c, cond| {
+                    c.saturating_add(cond.condition_type.complexity())
+                })
+            }
+        }
+    }
+
+    /// Returns the conditions nested in this one, if it is composite.
+    pub fn nested(&self) -> &[Condition] {
+        match self {
+            ConditionType::All(conds) | ConditionType::Any(conds) => conds,
+            ConditionType::Not(cond) => sp_std::slice::from_ref(&**cond),
+            _ => &[],
         }
     }
+
+    /// Returns whether this condition is composed of nested ones.
+    pub fn is_composite(&self) -> bool {
+        matches!(
+            self,
+            ConditionType::All(..) | ConditionType::Any(..) | ConditionType::Not(..)
+        )
+    }
+
+    /// Decodes a condition type in which at most `depth` composite conditions can be nested.
+    fn decode_with_depth<I: Input>(input: &mut I, depth: u32) -> Result<Self, codec::Error> {
+        // The variants are decoded as `derive(Decode)` would, in the order they are declared.
+        let variant = input.read_byte()?;
+        let nested_depth = || {
+            depth
+                .checked_sub(1)
+                .ok_or_else(|| codec::Error::from("Condition nested too deeply"))
+        };
+        Ok(match variant {
+            0 => ConditionType::IsPresent(Decode::decode(input)?),
+            1 => ConditionType::IsAbsent(Decode::decode(input)?),
+            2 => ConditionType::IsAnyOf(Decode::decode(input)?),
+            3 => ConditionType::IsNoneOf(Decode::decode(input)?),
+            4 => ConditionType::IsIdentity(Decode::decode(input)?),
+            5 => ConditionType::All(Condition::decode_vec_with_depth(input, nested_depth()?)?),
+            6 => ConditionType::Any(Condition::decode_vec_with_depth(input, nested_depth()?)?),
+            7 => ConditionType::Not(Box::new(Condition::decode_with_depth(
+                input,
+                nested_depth()?,
+            )?)),
+            8 => ConditionType::HasAttribute(
+                Decode::decode(input)?,
+                Decode::decode(input)?,
+                Decode::decode(input)?,
+            ),
+            _ => return Err("Invalid ConditionType variant".into()),
+        })
+    }
+}
+
+impl Decode for ConditionType {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+        Self::decode_with_depth(input, MAX_CONDITION_DEPTH)
+    }
 }
 
 /// Denotes the set of `ClaimType`s for which an issuer is trusted.
@@ -131,7 +262,7 @@ impl Migrate for TrustedIssuerOld {
 
 /// Type of claim requirements that a condition can have
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, Migrate, Hash)]
+#[derive(Encode, Clone, PartialEq, Eq, Debug, Migrate, Hash)]
 pub struct Condition {
     /// Type of condition.
     pub condition_type: ConditionType,
@@ -161,6 +292,78 @@ impl Condition {
     pub fn complexity(&self) -> (usize, usize) {
         (self.condition_type.complexity(), self.issuers.len())
     }
+
+    /// Returns the worst case number of claim reads needed to evaluate this condition,
+    /// when `default_issuer_count` issuers are trusted by default.
+    /// Each composite condition counts as one read, bounding the size of the tree.
+    pub fn claim_reads(&self, default_issuer_count: usize) -> usize {
+        let issuer_count = match self.issuers.len() {
+            0 => default_issuer_count,
+            n => n,
+        };
+        if self.condition_type.is_composite() {
+            self.condition_type.nested().iter().fold(1, |reads, cond| {
+                reads.saturating_add(cond.claim_reads(issuer_count))
+            })
+        } else {
+            self.condition_type
+                .complexity()
+                .saturating_mul(issuer_count)
+        }
+    }
+
+    /// Returns `nested`, a condition nested in this one,
+    /// trusting the issuers of this condition if it does not specify any.
+    pub fn inherit<'a>(&self, nested: &'a Condition) -> Cow<'a, Condition> {
+        if nested.issuers.is_empty() && !self.issuers.is_empty() {
+            Cow::Owned(Condition::new(
+                nested.condition_type.clone(),
+                self.issuers.clone(),
+            ))
+        } else {
+            Cow::Borrowed(nested)
+        }
+    }
+
+    /// Decodes a condition in which at most `depth` composite conditions can be nested.
+    fn decode_with_depth<I: Input>(input: &mut I, depth: u32) -> Result<Self, codec::Error> {
+        Ok(Self {
+            condition_type: ConditionType::decode_with_depth(input, depth)?,
+            issuers: Decode::decode(input)?,
+        })
+    }
+
+    /// Decodes a vector of conditions in which at most `depth` composite conditions can be nested.
+    fn decode_vec_with_depth<I: Input>(
+        input: &mut I,
+        depth: u32,
+    ) -> Result<Vec<Self>, codec::Error> {
+        let len = <Compact<u32>>::decode(input)?.0;
+        // Don't preallocate `len` conditions, as it isn't known to be backed by the input.
+        let mut conds = Vec::new();
+        for _ in 0..len {
+            conds.push(Self::decode_with_depth(input, depth)?);
+        }
+        Ok(conds)
+    }
+
+    /// Deduplicate any `ClaimType`s in `TrustedFor::Specific`, including in nested conditions.
+    pub fn dedup(&mut self) {
+        self.issuers.iter_mut().for_each(|issuer| issuer.dedup());
+        match &mut self.condition_type {
+            ConditionType::All(conds) | ConditionType::Any(conds) => {
+                conds.iter_mut().for_each(Condition::dedup)
+            }
+            ConditionType::Not(cond) => cond.dedup(),
+            _ => {}
+        }
+    }
+}
+
+impl Decode for Condition {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+        Self::decode_with_depth(input, MAX_CONDITION_DEPTH)
+    }
 }
 
 impl From<ConditionType> for Condition {
@@ -168,3 +371,29 @@ impl From<ConditionType> for Condition {
         Condition::new(condition_type, Vec::new())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nest(depth: u32) -> Condition {
+        (0..depth).fold(
+            ConditionType::IsIdentity(TargetIdentity::ExternalAgent).into(),
+            |cond, i| match i % 3 {
+                0 => ConditionType::Not(Box::new(cond)).into(),
+                1 => ConditionType::All(vec![cond]).into(),
+                _ => ConditionType::Any(vec![cond]).into(),
+            },
+        )
+    }
+
+    #[test]
+    fn decode_bounds_nesting_depth() {
+        let cond = nest(MAX_CONDITION_DEPTH);
+        assert_eq!(Condition::decode(&mut &cond.encode()[..]).ok(), Some(cond));
+
+        let too_deep = nest(MAX_CONDITION_DEPTH + 1).encode();
+        assert!(Condition::decode(&mut &too_deep[..]).is_err());
+        assert!(ConditionType::decode(&mut &too_deep[..]).is_err());
+    }
+}
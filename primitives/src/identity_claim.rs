@@ -16,6 +16,7 @@
 use crate::{identity_id::IdentityId, CddId, Moment, Ticker};
 
 use codec::{Decode, Encode};
+use polymesh_primitives_derive::VecU8StrongTyped;
 #[cfg(feature = "std")]
 use sp_runtime::{Deserialize, Serialize};
 use sp_std::{convert::From, prelude::*};
@@ -66,6 +67,30 @@ impl Scope {
     }
 }
 
+/// Kind of a typed attribute of an investor.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, Debug, PartialOrd, Ord, Hash)]
+pub enum AttributeKind {
+    /// Net worth of the investor, either as an amount or as the index of a band.
+    NetWorth,
+    /// Annual income of the investor, either as an amount or as the index of a band.
+    AnnualIncome,
+    /// Date of birth of the investor.
+    DateOfBirth,
+    /// Date on which the investor was accredited.
+    AccreditationDate,
+}
+
+/// Value of a typed attribute of an investor.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum AttributeValue {
+    /// A number, e.g., an amount or the index of a band.
+    Number(u128),
+    /// A date, in milliseconds Unix time.
+    Date(Moment),
+}
+
 /// All possible claims in polymesh
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, Hash)]
@@ -103,6 +128,8 @@ pub enum Claim {
     /// on the `ScopeId` instead of the investor's `IdentityId`, as `ScopeId` is unique at the
     /// investor entity level for a given scope (will always be a `Ticker`).
     InvestorUniquenessV2(CddId),
+    /// User has a typed attribute with the given value.
+    Attribute(AttributeKind, AttributeValue, Scope),
 }
 
 impl Default for Claim {
@@ -127,6 +154,7 @@ impl Claim {
             Claim::InvestorUniqueness(..) => ClaimType::InvestorUniqueness,
             Claim::InvestorUniquenessV2(..) => ClaimType::InvestorUniquenessV2,
             Claim::NoData => ClaimType::NoType,
+            Claim::Attribute(kind, ..) => ClaimType::Attribute(*kind),
         }
     }
 
@@ -139,6 +167,7 @@ impl Claim {
             | Claim::SellLockup(scope)
             | Claim::KnowYourCustomer(scope)
             | Claim::Jurisdiction(.., scope)
+            | Claim::Attribute(.., scope)
             | Claim::Exempted(scope)
             | Claim::Blocked(scope)
             | Claim::InvestorUniqueness(scope, ..) => Some(scope),
@@ -182,6 +211,36 @@ pub enum ClaimType {
     NoType,
     /// New Investor uniqueness claim.
     InvestorUniquenessV2,
+    /// User has a typed attribute of the given kind.
+    Attribute(AttributeKind),
+}
+
+/// A wrapper for the name of a claim issuer.
+#[derive(
+    Decode, Encode, Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord, VecU8StrongTyped,
+)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ClaimIssuerName(pub Vec<u8>);
+
+/// A wrapper for the URI of a claim issuer, e.g., its website.
+#[derive(
+    Decode, Encode, Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord, VecU8StrongTyped,
+)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ClaimIssuerUri(pub Vec<u8>);
+
+/// The profile a claim issuer publishes in the claim issuer registry.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, Default, Hash)]
+pub struct ClaimIssuerProfile {
+    /// Name of the issuer.
+    pub name: ClaimIssuerName,
+    /// URI with further information about the issuer.
+    pub uri: ClaimIssuerUri,
+    /// Claim types the issuer issues.
+    pub claim_types: Vec<ClaimType>,
+    /// Jurisdictions the issuer operates in.
+    pub jurisdictions: Vec<CountryCode>,
 }
 
 impl Default for ClaimType {
@@ -13,7 +13,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use crate::{cdd_id::InvestorUid, AccountId, EventOnly, SecondaryKey};
+use crate::{cdd_id::InvestorUid, AccountId, EventOnly, SecondaryKey, Ticker};
 use codec::{Decode, Encode};
 use core::fmt::{Display, Formatter};
 use core::str;
@@ -303,6 +303,33 @@ impl PortfolioId {
     }
 }
 
+/// Restrictions set by the custodian of a portfolio on the transfers involving it.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Decode, Encode, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PortfolioPolicy<Balance> {
+    /// If `Some`, the portfolio can only receive the assets in the list.
+    pub allowed_tickers: Option<Vec<Ticker>>,
+    /// If `Some`, outgoing transfers of more than this amount
+    /// must be part of a settlement instruction affirmed by the custodian.
+    pub affirmation_threshold: Option<Balance>,
+}
+
+impl<Balance: PartialOrd> PortfolioPolicy<Balance> {
+    /// Returns `true` if the portfolio can receive `ticker`.
+    pub fn allows_ticker(&self, ticker: &Ticker) -> bool {
+        self.allowed_tickers
+            .as_ref()
+            .map_or(true, |tickers| tickers.contains(ticker))
+    }
+
+    /// Returns `true` if sending `amount` from the portfolio requires the affirmation of its custodian.
+    pub fn requires_affirmation(&self, amount: &Balance) -> bool {
+        self.affirmation_threshold
+            .as_ref()
+            .map_or(false, |threshold| amount > threshold)
+    }
+}
+
 /// Result of a portfolio validity check.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Decode, Encode, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -315,6 +342,10 @@ pub struct PortfolioValidityResult {
     pub receiver_portfolio_does_not_exist: bool,
     /// Sender does not have sufficient balance.
     pub sender_insufficient_balance: bool,
+    /// The policy of the receiver portfolio does not allow the asset.
+    pub receiver_policy_disallows_ticker: bool,
+    /// The policy of the sender portfolio requires the transfer to be affirmed by its custodian.
+    pub sender_policy_requires_affirmation: bool,
     /// Final evaluation result.
     pub result: bool,
 }
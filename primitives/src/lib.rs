@@ -164,6 +164,7 @@ pub use event_only::EventOnly;
 pub mod identity_id;
 pub use identity_id::{
     EventDid, IdentityId, PortfolioId, PortfolioKind, PortfolioName, PortfolioNumber,
+    PortfolioPolicy,
 };
 
 /// Identity information.
@@ -184,7 +185,10 @@ pub use investor_zkproof_data::InvestorZKProofData;
 /// Claim information.
 /// Each claim is associated with this kind of record.
 pub mod identity_claim;
-pub use identity_claim::{Claim, ClaimType, IdentityClaim, Scope, ScopeId};
+pub use identity_claim::{
+    AttributeKind, AttributeValue, Claim, ClaimIssuerName, ClaimIssuerProfile, ClaimIssuerUri,
+    ClaimType, IdentityClaim, Scope, ScopeId,
+};
 
 // Defining and enumerating jurisdictions.
 pub mod jurisdiction;
@@ -231,7 +235,10 @@ pub use document::{Document, DocumentId, DocumentName, DocumentUri};
 
 /// Rules for claims.
 pub mod condition;
-pub use condition::{Condition, ConditionType, TargetIdentity, TrustedFor, TrustedIssuer};
+pub use condition::{
+    AttributePredicate, Condition, ConditionType, TargetIdentity, TrustedFor, TrustedIssuer,
+    MAX_CONDITION_DEPTH,
+};
 
 /// Predicate calculation for Claims.
 pub mod proposition;
@@ -249,12 +256,19 @@ pub mod crypto;
 /// Asset type definitions.
 pub mod asset;
 
+/// Asset metadata type definitions.
+pub mod asset_metadata;
+
 /// Statistics type definitions.
 pub mod statistics;
 
 /// Compliance manager type definitions.
 pub mod compliance_manager;
 
+/// Vesting schedules of portfolio balances.
+pub mod vesting;
+pub use vesting::{VestingKind, VestingSchedule};
+
 /// Host functions.
 pub mod host_functions;
 
@@ -1,6 +1,6 @@
 use crate::{
     proposition::{Context, Proposition},
-    Claim, IdentityId,
+    AttributeKind, AttributePredicate, Claim, IdentityId, Scope,
 };
 use codec::{Decode, Encode};
 
@@ -163,6 +163,35 @@ impl<C: Iterator<Item = Claim>> Proposition<C> for AnyProposition<'_> {
     }
 }
 
+// AttributeProposition
+// =========================================================
+
+/// Proposition that checks if an attribute claim of a certain kind and scope
+/// satisfying a predicate exists in context.
+#[derive(Clone)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct AttributeProposition<'a> {
+    /// Kind of the attribute.
+    pub kind: AttributeKind,
+    /// Scope of the attribute claim.
+    pub scope: &'a Scope,
+    /// Predicate the value of the attribute has to satisfy.
+    pub predicate: &'a AttributePredicate,
+}
+
+impl<C: Iterator<Item = Claim>> Proposition<C> for AttributeProposition<'_> {
+    /// Evaluate proposition against `context`.
+    fn evaluate(&self, mut context: Context<C>) -> bool {
+        let now = context.now;
+        context.claims.any(|ctx_claim| match ctx_claim {
+            Claim::Attribute(kind, value, ref scope) => {
+                kind == self.kind && scope == self.scope && self.predicate.holds(&value, now)
+            }
+            _ => false,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -186,6 +215,7 @@ mod tests {
         Context {
             claims: claims.into_iter(),
             id: <_>::default(),
+            now: <_>::default(),
         }
     }
 
@@ -298,6 +328,7 @@ mod tests {
             Context {
                 id: identity1,
                 claims: vec![].into_iter(),
+                now: 0,
             },
             |context: Context<_>| context.id == identity1,
         ));
@@ -306,6 +337,7 @@ mod tests {
             Context {
                 id: identity1,
                 claims: vec![].into_iter(),
+                now: 0,
             },
             |_| false,
         ));
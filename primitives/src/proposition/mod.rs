@@ -13,7 +13,10 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use crate::{Claim, Condition, ConditionType, IdentityId, TargetIdentity};
+use crate::{
+    AttributeKind, AttributePredicate, Claim, Condition, ConditionType, IdentityId, Moment, Scope,
+    TargetIdentity,
+};
 use codec::{Decode, Encode};
 
 use sp_std::prelude::*;
@@ -28,6 +31,8 @@ pub struct Context<C> {
     /// It could be the sender DID during the evaluation of sender's conditions or
     /// the receiver DID on a receiver's condition evaluation.
     pub id: IdentityId,
+    /// Time of the evaluation, used by date predicates.
+    pub now: Moment,
 }
 
 // Proposition Trait
@@ -78,8 +83,8 @@ impl<C, F: Fn(Context<C>) -> bool> Proposition<C> for F {
 /// Base and simple propositions
 pub mod base;
 pub use base::{
-    AndProposition, AnyProposition, ExistentialProposition, IsIdentityProposition, NotProposition,
-    OrProposition,
+    AndProposition, AnyProposition, AttributeProposition, ExistentialProposition,
+    IsIdentityProposition, NotProposition, OrProposition,
 };
 
 // Helper functions
@@ -97,6 +102,21 @@ pub fn any(claims: &[Claim]) -> AnyProposition<'_> {
     AnyProposition { claims }
 }
 
+/// It creates a proposition to evaluate if an attribute claim of `kind` and `scope`
+/// satisfying `predicate` is found in the context.
+#[inline]
+pub fn attribute<'a>(
+    kind: AttributeKind,
+    scope: &'a Scope,
+    predicate: &'a AttributePredicate,
+) -> AttributeProposition<'a> {
+    AttributeProposition {
+        kind,
+        scope,
+        predicate,
+    }
+}
+
 /// It create a negate proposition of `proposition`.
 #[inline]
 pub fn not<P: Proposition<C>, C>(proposition: P) -> NotProposition<P> {
@@ -118,5 +138,11 @@ pub fn run<C: Iterator<Item = Claim>, E: Proposition<C>>(
             IsIdentityProposition { identity: *id }.evaluate(context)
         }
         ConditionType::IsIdentity(TargetIdentity::ExternalAgent) => ea_prop.evaluate(context),
+        ConditionType::HasAttribute(kind, scope, predicate) => {
+            attribute(*kind, scope, predicate).evaluate(context)
+        }
+        // Each nested condition has its own context, so composite conditions
+        // must be evaluated by the caller, one nested condition at a time.
+        ConditionType::All(..) | ConditionType::Any(..) | ConditionType::Not(..) => false,
     }
 }
@@ -13,6 +13,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use crate::{calendar::CalendarPeriod, Balance, ClaimType, CountryCode, IdentityId, Moment};
 use codec::{Decode, Encode};
 #[cfg(feature = "std")]
 use sp_runtime::{Deserialize, Serialize};
@@ -31,6 +32,59 @@ pub enum TransferManager {
     CountTransferManager(Counter),
     /// PTM limits the percentage of token owned by a single Identity.
     PercentageTransferManager(Percentage),
+    /// JTM limits the number of investors with a `Claim::Jurisdiction` in the given country,
+    /// issued by the given identity.
+    /// It can only be added before the token is distributed to more than one investor.
+    JurisdictionTransferManager(IdentityId, CountryCode, Counter),
+    /// CCTM limits the number of investors that have (`true`) or lack (`false`) a valid claim
+    /// of the given type issued by the given identity, e.g., at most 35 non-accredited investors.
+    /// It can only be added before the token is distributed to more than one investor.
+    ClaimCountTransferManager(IdentityId, ClaimType, bool, Counter),
+    /// HPTM forbids investors from sending tokens until the given number of milliseconds
+    /// has passed since they started holding the token, i.e., since their balance was last zero.
+    HoldingPeriodTransferManager(Moment),
+    /// IVTM limits the volume each investor can send within the rolling window of the period.
+    InvestorVolumeTransferManager(CalendarPeriod, Balance),
+    /// AVTM limits the volume of the token transferred within the rolling window of the period.
+    AssetVolumeTransferManager(CalendarPeriod, Balance),
+}
+
+impl TransferManager {
+    /// Returns the holder statistic that this transfer manager limits, if any.
+    pub fn stat_type(&self) -> Option<StatType> {
+        match self {
+            Self::JurisdictionTransferManager(issuer, cc, _) => {
+                Some(StatType::Jurisdiction(*issuer, *cc))
+            }
+            Self::ClaimCountTransferManager(issuer, ct, has, _) => {
+                Some(StatType::Claim(*issuer, *ct, *has))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A subset of the investors of an asset whose size is tracked for transfer managers.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Decode, Encode, Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StatType {
+    /// Investors with a `Claim::Jurisdiction` for the country, issued by the identity.
+    Jurisdiction(IdentityId, CountryCode),
+    /// Investors with (`true`) or without (`false`) a claim of the type, issued by the identity.
+    Claim(IdentityId, ClaimType, bool),
+}
+
+/// Volume transferred within the rolling window of a period.
+///
+/// The window spans the current calendar unit of the period, e.g., the current day,
+/// and as many units before it as the period has, minus one, e.g., the previous 29 days for 30 days.
+/// Its volume is tracked in buckets of one unit, dropped as they leave the window.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Decode, Encode, Clone, Debug, Default, PartialEq, Eq)]
+pub struct VolumeWindow<Balance> {
+    /// The volume transferred in each unit of the window with some volume,
+    /// keyed by the index of the unit counting from the Unix epoch, oldest first.
+    pub buckets: Vec<(u64, Balance)>,
 }
 
 /// Result of a transfer manager check.
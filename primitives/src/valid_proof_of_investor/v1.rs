@@ -67,12 +67,14 @@ mod tests {
         let context = Context {
             claims: vec![].into_iter(),
             id: investor_id,
+            now: 0,
         };
         assert_eq!(proposition.evaluate(context), false);
 
         let context = Context {
             claims: vec![Claim::Affiliate(Scope::Ticker(asset_ticker))].into_iter(),
             id: investor_id,
+            now: 0,
         };
         assert_eq!(proposition.evaluate(context), true);
 
@@ -0,0 +1,141 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymathNetwork/Polymesh).
+// Copyright (c) 2020 Polymath
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::Moment;
+use codec::{Decode, Encode};
+use sp_arithmetic::{traits::AtLeast32BitUnsigned, Perbill};
+#[cfg(feature = "std")]
+use sp_runtime::{Deserialize, Serialize};
+
+/// How the tokens of a vesting schedule are released over time.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VestingKind {
+    /// All tokens are locked until the given moment, then released at once.
+    Cliff(Moment),
+    /// Tokens are released linearly from `start` to `end`.
+    Linear { start: Moment, end: Moment },
+    /// Tokens are released in `steps` equal tranches, one every `period` milliseconds,
+    /// the first one at `start + period`.
+    Step {
+        start: Moment,
+        period: Moment,
+        steps: u32,
+    },
+}
+
+impl Default for VestingKind {
+    fn default() -> Self {
+        Self::Cliff(0)
+    }
+}
+
+impl VestingKind {
+    /// Returns whether the parameters of the release are sane.
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Self::Cliff(_) => true,
+            Self::Linear { start, end } => start < end,
+            Self::Step { period, steps, .. } => period > 0 && steps > 0,
+        }
+    }
+
+    /// Returns the fraction of the tokens released at `now`.
+    pub fn released_at(&self, now: Moment) -> Perbill {
+        match *self {
+            Self::Cliff(at) if now < at => Perbill::zero(),
+            Self::Cliff(_) => Perbill::one(),
+            Self::Linear { start, .. } if now <= start => Perbill::zero(),
+            Self::Linear { end, .. } if now >= end => Perbill::one(),
+            Self::Linear { start, end } => {
+                Perbill::from_rational_approximation(now - start, end - start)
+            }
+            Self::Step {
+                start,
+                period,
+                steps,
+            } => {
+                let steps = u64::from(steps.max(1));
+                let elapsed = now.saturating_sub(start) / period.max(1);
+                Perbill::from_rational_approximation(elapsed.min(steps), steps)
+            }
+        }
+    }
+
+    /// Returns the moment after which all tokens are released.
+    pub fn end(&self) -> Moment {
+        match *self {
+            Self::Cliff(at) => at,
+            Self::Linear { end, .. } => end,
+            Self::Step {
+                start,
+                period,
+                steps,
+            } => start.saturating_add(period.saturating_mul(steps.into())),
+        }
+    }
+}
+
+/// A schedule restricting the transfer of part of a portfolio's balance of an asset.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq)]
+pub struct VestingSchedule<Balance> {
+    /// Amount of tokens subject to the schedule.
+    pub amount: Balance,
+    /// How the tokens are released.
+    pub kind: VestingKind,
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy> VestingSchedule<Balance> {
+    /// Returns the amount of tokens that are still locked at `now`.
+    pub fn locked_at(&self, now: Moment) -> Balance {
+        self.amount
+            .saturating_sub(self.kind.released_at(now) * self.amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locked_at_test() {
+        let schedule = |kind| VestingSchedule {
+            amount: 1_000u128,
+            kind,
+        };
+
+        let cliff = schedule(VestingKind::Cliff(10));
+        assert_eq!(cliff.locked_at(9), 1_000);
+        assert_eq!(cliff.locked_at(10), 0);
+
+        let linear = schedule(VestingKind::Linear { start: 10, end: 20 });
+        assert_eq!(linear.locked_at(10), 1_000);
+        assert_eq!(linear.locked_at(15), 500);
+        assert_eq!(linear.locked_at(20), 0);
+
+        let step = schedule(VestingKind::Step {
+            start: 10,
+            period: 5,
+            steps: 4,
+        });
+        assert_eq!(step.locked_at(14), 1_000);
+        assert_eq!(step.locked_at(15), 750);
+        assert_eq!(step.locked_at(29), 500);
+        assert_eq!(step.locked_at(30), 0);
+        assert_eq!(step.locked_at(100), 0);
+        assert_eq!(step.kind.end(), 30);
+    }
+}
@@ -16,7 +16,10 @@
 //! Runtime API definition for Identity module.
 
 use codec::Codec;
-use polymesh_primitives::{Balance, IdentityId, PortfolioId, Ticker};
+use polymesh_primitives::{
+    asset_metadata::{AssetMetadataEntry, AssetMetadataItem, AssetMetadataKey},
+    Balance, IdentityId, Moment, PortfolioId, Ticker,
+};
 use sp_std::vec::Vec;
 
 /// The maximum number of DIDs allowed in a `balance_at` RPC query.
@@ -75,5 +78,28 @@ sp_api::decl_runtime_apis! {
             ticker: &Ticker,
             value: Balance
         ) -> polymesh_primitives::asset::GranularCanTransferResult;
+
+        /// Returns the unexpired metadata values of `ticker`,
+        /// along with the names and specifications of their keys.
+        ///
+        /// # Example
+        ///
+        /// ```ignore
+        ///  curl
+        ///    -H "Content-Type: application/json"
+        ///    -d {
+        ///        "id":1, "jsonrpc":"2.0",
+        ///        "method": "asset_metadata",
+        ///        "params":["0x010000000000000000000000"]
+        ///       }
+        ///    http://localhost:9933 | python3 -m json.tool
+        /// ```
+        fn asset_metadata(ticker: Ticker) -> Vec<AssetMetadataItem<Moment>>;
+
+        /// Returns the unexpired value of the metadata `key` of `ticker`, if any.
+        fn asset_metadata_value(
+            ticker: Ticker,
+            key: AssetMetadataKey
+        ) -> Option<AssetMetadataEntry<Moment>>;
     }
 }
@@ -17,7 +17,13 @@
 
 use codec::Codec;
 use frame_support::traits::Currency;
-use polymesh_primitives::{compliance_manager::AssetComplianceResult, IdentityId, Ticker};
+use polymesh_primitives::{
+    compliance_manager::{
+        AssetComplianceResult, ComplianceRequirement, ComplianceSimulationResult,
+    },
+    IdentityId, Ticker,
+};
+use sp_std::vec::Vec;
 
 pub trait Trait: frame_system::Config {
     type Currency: Currency<Self::AccountId>;
@@ -57,5 +63,33 @@ sp_api::decl_runtime_apis! {
             from_did: Option<IdentityId>,
             to_did: Option<IdentityId>,
         ) -> AssetComplianceResult;
+
+         /// Evaluates the candidate `requirements` against the current holders of `ticker`,
+         /// without changing state, skipping the first `start` holders and taking at most `count`.
+         /// Reports the holders that would fail as sender or receiver, along with the failing conditions.
+         ///
+         /// # Example
+         ///
+         /// ```ignore
+         ///  curl
+         ///    -H "Content-Type: application/json"
+         ///    -d {
+         ///        "id":1, "jsonrpc":"2.0",
+         ///        "method": "compliance_simulateCompliance",
+         ///        "params":[
+         ///            "0x010000000000000000000000",
+         ///            [{ "sender_conditions": [], "receiver_conditions": [], "id": 1 }],
+         ///            0,
+         ///            100
+         ///            ]
+         ///       }
+         ///    http://localhost:9933 | python3 -m json.tool
+         /// ```
+        fn simulate_compliance(
+            ticker: Ticker,
+            requirements: Vec<ComplianceRequirement>,
+            start: u32,
+            count: u32,
+        ) -> ComplianceSimulationResult;
     }
 }
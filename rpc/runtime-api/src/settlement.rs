@@ -0,0 +1,43 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymathNetwork/Polymesh).
+// Copyright (c) 2020 Polymath
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API definition for Settlement module.
+
+use codec::Codec;
+use pallet_settlement::{AffirmationStatus, InstructionExecutionReport, InstructionReport};
+use polymesh_primitives::{Balance, BlockNumber, Moment, PortfolioId};
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+
+    /// The API to query settlement instructions.
+    pub trait SettlementApi<AccountId>
+    where
+        AccountId: Codec,
+    {
+        /// Returns the details, legs and missing affirmations of an instruction,
+        /// or `None` if the instruction doesn't exist.
+        fn get_instruction(
+            instruction_id: u64
+        ) -> Option<InstructionReport<AccountId, Moment, BlockNumber, Balance>>;
+
+        /// Returns the instructions awaiting execution that involve `portfolio`
+        /// and the affirmation status of `portfolio` for each of them.
+        fn pending_for_portfolio(portfolio: PortfolioId) -> Vec<(u64, AffirmationStatus)>;
+
+        /// Simulates the execution of an instruction and reports which of its legs would fail.
+        fn can_execute(instruction_id: u64) -> InstructionExecutionReport;
+    }
+}
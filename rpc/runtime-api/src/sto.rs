@@ -0,0 +1,33 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymathNetwork/Polymesh).
+// Copyright (c) 2020 Polymath
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API definition for STO module.
+
+use polymesh_primitives::{Balance, IdentityId, Ticker};
+
+sp_api::decl_runtime_apis! {
+
+    /// The API to query fundraisers.
+    pub trait StoApi {
+        /// Returns the amount of the raising asset that `investor` can still spend
+        /// in the fundraiser `fundraiser_id` of `offering_asset`,
+        /// or `None` if investments in the fundraiser are not capped.
+        fn remaining_allowance(
+            offering_asset: Ticker,
+            fundraiser_id: u64,
+            investor: IdentityId
+        ) -> Option<Balance>;
+    }
+}
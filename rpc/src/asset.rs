@@ -22,6 +22,10 @@ use jsonrpc_derive::rpc;
 use codec::Codec;
 use jsonrpc_core::Error;
 use polymesh_primitives::asset::GranularCanTransferResult;
+use polymesh_primitives::asset_metadata::{
+    AssetMetadataEntry, AssetMetadataItem, AssetMetadataKey,
+};
+use polymesh_primitives::Moment;
 use sp_api::{ApiRef, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_rpc::number;
@@ -55,6 +59,21 @@ pub trait AssetApi<BlockHash, AccountId> {
         value: number::NumberOrHex,
         at: Option<BlockHash>,
     ) -> Result<GranularCanTransferResult>;
+
+    #[rpc(name = "asset_metadata")]
+    fn asset_metadata(
+        &self,
+        ticker: Ticker,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<AssetMetadataItem<Moment>>>;
+
+    #[rpc(name = "asset_metadataValue")]
+    fn asset_metadata_value(
+        &self,
+        ticker: Ticker,
+        key: AssetMetadataKey,
+        at: Option<BlockHash>,
+    ) -> Result<Option<AssetMetadataEntry<Moment>>>;
 }
 
 /// An implementation of asset specific RPC methods.
@@ -147,4 +166,32 @@ where
             "Unable to check transfer"
         )
     }
+
+    fn asset_metadata(
+        &self,
+        ticker: Ticker,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<AssetMetadataItem<Moment>>> {
+        rpc_forward_call!(
+            self,
+            at,
+            |api: ApiRef<<C as ProvideRuntimeApi<Block>>::Api>, at| api.asset_metadata(at, ticker),
+            "Unable to fetch asset metadata"
+        )
+    }
+
+    fn asset_metadata_value(
+        &self,
+        ticker: Ticker,
+        key: AssetMetadataKey,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<AssetMetadataEntry<Moment>>> {
+        rpc_forward_call!(
+            self,
+            at,
+            |api: ApiRef<<C as ProvideRuntimeApi<Block>>::Api>, at| api
+                .asset_metadata_value(at, ticker, key),
+            "Unable to fetch asset metadata value"
+        )
+    }
 }
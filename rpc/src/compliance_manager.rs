@@ -19,7 +19,12 @@ use codec::Codec;
 use frame_support::traits::Currency;
 use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
 use jsonrpc_derive::rpc;
-use polymesh_primitives::{compliance_manager::AssetComplianceResult, IdentityId, Ticker};
+use polymesh_primitives::{
+    compliance_manager::{
+        AssetComplianceResult, ComplianceRequirement, ComplianceSimulationResult,
+    },
+    IdentityId, Ticker,
+};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_runtime::{generic::BlockId, traits::Block as BlockT};
@@ -42,6 +47,16 @@ pub trait ComplianceManagerApi<BlockHash, AccountId, T> {
         to_did: Option<IdentityId>,
         at: Option<BlockHash>,
     ) -> Result<AssetComplianceResult>;
+
+    #[rpc(name = "compliance_simulateCompliance")]
+    fn simulate_compliance(
+        &self,
+        ticker: Ticker,
+        requirements: Vec<ComplianceRequirement>,
+        start: u32,
+        count: u32,
+        at: Option<BlockHash>,
+    ) -> Result<ComplianceSimulationResult>;
 }
 
 /// An implementation of Compliance manager specific RPC methods.
@@ -90,4 +105,25 @@ where
                 data: Some(format!("{:?}", e).into()),
             })
     }
+
+    fn simulate_compliance(
+        &self,
+        ticker: Ticker,
+        requirements: Vec<ComplianceRequirement>,
+        start: u32,
+        count: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<ComplianceSimulationResult> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+                // If the block hash is not supplied assume the best block.
+                self.client.info().best_hash));
+
+        api.simulate_compliance(&at, ticker, requirements, start, count)
+            .map_err(|e| RpcError {
+                code: ErrorCode::ServerError(1),
+                message: "Unable to simulate compliance over the asset holders.".into(),
+                data: Some(format!("{:?}", e).into()),
+            })
+    }
 }
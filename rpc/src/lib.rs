@@ -42,4 +42,6 @@ pub mod asset;
 pub mod compliance_manager;
 pub mod identity;
 pub mod pips;
+pub mod settlement;
+pub mod sto;
 pub mod transaction_payment;
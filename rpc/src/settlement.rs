@@ -0,0 +1,122 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymathNetwork/Polymesh).
+// Copyright (c) 2020 Polymath
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+pub use node_rpc_runtime_api::settlement::SettlementApi as SettlementRuntimeApi;
+use pallet_settlement::{AffirmationStatus, InstructionExecutionReport, InstructionReport};
+use polymesh_primitives::{Balance, BlockNumber, Moment, PortfolioId};
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::{ApiRef, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use std::sync::Arc;
+
+/// Settlement RPC methods.
+#[rpc]
+pub trait SettlementApi<BlockHash, AccountId> {
+    /// Retrieves the details, legs and missing affirmations of the instruction `instruction_id`.
+    #[rpc(name = "settlement_getInstruction")]
+    fn get_instruction(
+        &self,
+        instruction_id: u64,
+        at: Option<BlockHash>,
+    ) -> Result<Option<InstructionReport<AccountId, Moment, BlockNumber, Balance>>>;
+
+    /// Retrieves the instructions awaiting execution that involve `portfolio`.
+    #[rpc(name = "settlement_pendingForPortfolio")]
+    fn pending_for_portfolio(
+        &self,
+        portfolio: PortfolioId,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<(u64, AffirmationStatus)>>;
+
+    /// Simulates the execution of the instruction `instruction_id`.
+    #[rpc(name = "settlement_canExecute")]
+    fn can_execute(
+        &self,
+        instruction_id: u64,
+        at: Option<BlockHash>,
+    ) -> Result<InstructionExecutionReport>;
+}
+
+/// An implementation of settlement specific RPC methods.
+pub struct Settlement<T, U> {
+    client: Arc<T>,
+    _marker: std::marker::PhantomData<U>,
+}
+
+impl<T, U> Settlement<T, U> {
+    /// Create new `Settlement` with the given reference to the client.
+    pub fn new(client: Arc<T>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<C, Block, AccountId> SettlementApi<<Block as BlockT>::Hash, AccountId> for Settlement<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static,
+    C: ProvideRuntimeApi<Block>,
+    C: HeaderBackend<Block>,
+    C::Api: SettlementRuntimeApi<Block, AccountId>,
+    AccountId: Codec,
+{
+    fn get_instruction(
+        &self,
+        instruction_id: u64,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<InstructionReport<AccountId, Moment, BlockNumber, Balance>>> {
+        rpc_forward_call!(
+            self,
+            at,
+            |api: ApiRef<<C as ProvideRuntimeApi<Block>>::Api>, at| api
+                .get_instruction(at, instruction_id),
+            "Unable to fetch instruction"
+        )
+    }
+
+    fn pending_for_portfolio(
+        &self,
+        portfolio: PortfolioId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<(u64, AffirmationStatus)>> {
+        rpc_forward_call!(
+            self,
+            at,
+            |api: ApiRef<<C as ProvideRuntimeApi<Block>>::Api>, at| api
+                .pending_for_portfolio(at, portfolio),
+            "Unable to fetch pending instructions"
+        )
+    }
+
+    fn can_execute(
+        &self,
+        instruction_id: u64,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<InstructionExecutionReport> {
+        rpc_forward_call!(
+            self,
+            at,
+            |api: ApiRef<<C as ProvideRuntimeApi<Block>>::Api>, at| api
+                .can_execute(at, instruction_id),
+            "Unable to simulate instruction execution"
+        )
+    }
+}
@@ -0,0 +1,84 @@
+// This file is part of the Polymesh distribution (https://github.com/PolymathNetwork/Polymesh).
+// Copyright (c) 2020 Polymath
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+pub use node_rpc_runtime_api::sto::StoApi as StoRuntimeApi;
+use polymesh_primitives::{Balance, IdentityId, Ticker};
+
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::{ApiRef, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use std::sync::Arc;
+
+/// STO RPC methods.
+#[rpc]
+pub trait StoApi<BlockHash> {
+    /// Retrieves the amount of the raising asset that `investor` can still spend in a fundraiser,
+    /// or `None` if investments in the fundraiser are not capped.
+    #[rpc(name = "sto_remainingAllowance")]
+    fn remaining_allowance(
+        &self,
+        offering_asset: Ticker,
+        fundraiser_id: u64,
+        investor: IdentityId,
+        at: Option<BlockHash>,
+    ) -> Result<Option<Balance>>;
+}
+
+/// An implementation of STO specific RPC methods.
+pub struct Sto<T, U> {
+    client: Arc<T>,
+    _marker: std::marker::PhantomData<U>,
+}
+
+impl<T, U> Sto<T, U> {
+    /// Create new `Sto` with the given reference to the client.
+    pub fn new(client: Arc<T>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<C, Block> StoApi<<Block as BlockT>::Hash> for Sto<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static,
+    C: ProvideRuntimeApi<Block>,
+    C: HeaderBackend<Block>,
+    C::Api: StoRuntimeApi<Block>,
+{
+    fn remaining_allowance(
+        &self,
+        offering_asset: Ticker,
+        fundraiser_id: u64,
+        investor: IdentityId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<Balance>> {
+        rpc_forward_call!(
+            self,
+            at,
+            |api: ApiRef<<C as ProvideRuntimeApi<Block>>::Api>, at| api.remaining_allowance(
+                at,
+                offering_asset,
+                fundraiser_id,
+                investor
+            ),
+            "Unable to fetch remaining allowance"
+        )
+    }
+}
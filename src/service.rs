@@ -115,6 +115,8 @@ pub trait RuntimeApiCollection<Extrinsic: RuntimeExtrinsic>:
     + node_rpc_runtime_api::asset::AssetApi<Block, AccountId>
     + pallet_group_rpc_runtime_api::GroupApi<Block>
     + node_rpc_runtime_api::compliance_manager::ComplianceManagerApi<Block, AccountId, Balance>
+    + node_rpc_runtime_api::settlement::SettlementApi<Block, AccountId>
+    + node_rpc_runtime_api::sto::StoApi<Block>
 where
     <Self as sp_api::ApiExt<Block>>::StateBackend: sp_api::StateBackend<BlakeTwo256>,
 {
@@ -148,7 +150,9 @@ where
         > + pallet_protocol_fee_rpc_runtime_api::ProtocolFeeApi<Block>
         + node_rpc_runtime_api::asset::AssetApi<Block, AccountId>
         + pallet_group_rpc_runtime_api::GroupApi<Block>
-        + node_rpc_runtime_api::compliance_manager::ComplianceManagerApi<Block, AccountId, Balance>,
+        + node_rpc_runtime_api::compliance_manager::ComplianceManagerApi<Block, AccountId, Balance>
+        + node_rpc_runtime_api::settlement::SettlementApi<Block, AccountId>
+        + node_rpc_runtime_api::sto::StoApi<Block>,
     <Self as sp_api::ApiExt<Block>>::StateBackend: sp_api::StateBackend<BlakeTwo256>,
 {
 }